@@ -0,0 +1,171 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::fmt::Write;
+use wavefront::{Obj, ParseOptions};
+
+/// Generate an OBJ containing a `size` x `size` grid of quads, with texture coordinates and normals.
+fn grid(size: usize) -> String {
+    let mut src = String::new();
+    for y in 0..=size {
+        for x in 0..=size {
+            writeln!(src, "v {} {} 0", x, y).unwrap();
+            writeln!(src, "vt {} {}", x as f32 / size as f32, y as f32 / size as f32).unwrap();
+        }
+    }
+    writeln!(src, "vn 0 0 1").unwrap();
+    for y in 0..size {
+        for x in 0..size {
+            let i = y * (size + 1) + x + 1;
+            let j = i + size + 1;
+            writeln!(src, "f {0}/{0}/1 {1}/{1}/1 {2}/{2}/1 {3}/{3}/1", i, i + 1, j + 1, j).unwrap();
+        }
+    }
+    src
+}
+
+/// Generate an OBJ containing `faces` triangles, each selecting several of `groups` distinct groups.
+fn grouped(faces: usize, groups: usize) -> String {
+    let mut src = String::from("v 0 0 0\nv 1 0 0\nv 0 1 0\n");
+    for i in 0..faces {
+        writeln!(src, "g group{} group{} group{}", i % groups, (i * 7) % groups, (i * 13) % groups).unwrap();
+        for _ in 0..4 {
+            writeln!(src, "f 1 2 3").unwrap();
+        }
+    }
+    src
+}
+
+/// Generate an OBJ containing `count` of each kind of vertex attribute, with full-precision components.
+fn attributes(count: usize) -> String {
+    let mut src = String::new();
+    for i in 0..count {
+        let x = (i as f32 * 0.618034).sin() * 1000.0;
+        writeln!(src, "v {} {} {:e}", x, x * 0.5, -x).unwrap();
+        writeln!(src, "vt {} {}", x.fract(), (x * 3.0).fract()).unwrap();
+        writeln!(src, "vn {} {} {}", x.cos(), x.sin(), 0.0).unwrap();
+    }
+    src
+}
+
+fn parse(c: &mut Criterion) {
+    let src = grid(300);
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+    group.sample_size(20);
+    group.bench_function("from_lines", |b| b.iter(|| Obj::from_lines(src.lines()).unwrap()));
+    group.bench_function("from_buf_reader", |b| b.iter(|| Obj::from_buf_reader(src.as_bytes()).unwrap()));
+    #[cfg(feature = "rayon")]
+    group.bench_function("from_str_parallel", |b| b.iter(|| Obj::from_str_parallel(&src).unwrap()));
+    group.finish();
+}
+
+/// Compare with and without `--features fast-float` to measure the effect of the faster float parser.
+fn parse_attributes(c: &mut Criterion) {
+    let src = attributes(200_000);
+    let mut group = c.benchmark_group("parse_attributes");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+    group.sample_size(20);
+    let name = if cfg!(feature = "fast-float") { "fast-float" } else { "std" };
+    group.bench_function(name, |b| b.iter(|| Obj::from_lines(src.lines()).unwrap()));
+    group.finish();
+}
+
+fn parse_preallocate(c: &mut Criterion) {
+    let src = grid(300);
+    let mut group = c.benchmark_group("parse_preallocate");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+    group.sample_size(20);
+    for preallocate in [false, true] {
+        let options = ParseOptions::new().preallocate(preallocate);
+        group.bench_function(if preallocate { "preallocate" } else { "grow" }, |b| {
+            b.iter(|| Obj::from_str_with(&src, options.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn parse_skip(c: &mut Criterion) {
+    let src = grid(300);
+    let mut group = c.benchmark_group("parse_skip");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+    group.sample_size(20);
+    for (name, options) in [
+        ("none", ParseOptions::new()),
+        ("uvs", ParseOptions::new().skip_uvs(true)),
+        ("uvs_normals", ParseOptions::new().skip_uvs(true).skip_normals(true)),
+    ] {
+        group.bench_function(name, |b| b.iter(|| Obj::from_str_with(&src, options.clone()).unwrap()));
+    }
+    group.finish();
+}
+
+fn parse_file(c: &mut Criterion) {
+    let src = grid(600);
+    let path = std::env::temp_dir().join(format!("wavefront-bench-{}.obj", std::process::id()));
+    std::fs::write(&path, &src).unwrap();
+    let mut group = c.benchmark_group("parse_file");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+    group.sample_size(10);
+    group.bench_function("from_file", |b| b.iter(|| Obj::from_file(&path).unwrap()));
+    #[cfg(feature = "mmap")]
+    group.bench_function("from_file_mmap", |b| b.iter(|| unsafe { Obj::from_file_mmap(&path) }.unwrap()));
+    group.finish();
+    std::fs::remove_file(&path).unwrap();
+}
+
+fn parse_grouped(c: &mut Criterion) {
+    let src = grouped(50_000, 5_000);
+    let mut group = c.benchmark_group("parse_grouped");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+    group.sample_size(20);
+    group.bench_function("from_lines", |b| b.iter(|| Obj::from_lines(src.lines()).unwrap()));
+    group.bench_function("from_str_borrowed", |b| b.iter(|| Obj::from_str_borrowed(&src).unwrap().objects().len()));
+    group.finish();
+}
+
+fn iterate_triangles(c: &mut Criterion) {
+    let mut obj = Obj::from_str_with(&grid(300), ParseOptions::new()).unwrap();
+    let mut group = c.benchmark_group("iterate_triangles");
+    group.sample_size(20);
+    let sum = |obj: &Obj| (0..100)
+        .map(|_| obj.triangles().map(|[a, b, c]| a.position()[0] + b.position()[1] + c.position()[2]).sum::<f32>())
+        .sum::<f32>();
+    group.bench_function("polygons", |b| b.iter(|| sum(&obj)));
+    obj.cache_triangles();
+    group.bench_function("cached", |b| b.iter(|| sum(&obj)));
+    group.finish();
+}
+
+/// Compare loading a binary cache with parsing the text it was made from.
+#[cfg(feature = "cache")]
+fn load_cache(c: &mut Criterion) {
+    let src = grid(600);
+    let mut cache = Vec::new();
+    Obj::from_str_with(&src, ParseOptions::new()).unwrap().to_cache(&mut cache).unwrap();
+    let mut group = c.benchmark_group("load_cache");
+    group.sample_size(10);
+    group.bench_function("from_lines", |b| b.iter(|| Obj::from_lines(src.lines()).unwrap()));
+    group.bench_function("from_cache", |b| b.iter(|| Obj::from_cache(&cache[..]).unwrap()));
+    group.finish();
+}
+
+#[cfg(not(feature = "cache"))]
+fn load_cache(_: &mut Criterion) {}
+
+fn clone(c: &mut Criterion) {
+    let obj = Obj::from_str_with(&grid(600), ParseOptions::new()).unwrap();
+    c.bench_function("clone", |b| b.iter(|| obj.clone()));
+}
+
+criterion_group!(
+    benches,
+    parse,
+    parse_attributes,
+    parse_preallocate,
+    parse_skip,
+    parse_file,
+    parse_grouped,
+    iterate_triangles,
+    load_cache,
+    clone,
+);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+use crate::{parse::{Parser, Reporter}, Error, Float, GenericObj, ParseOptions};
+use std::{fmt, io};
+
+/// A push parser that builds an OBJ from data that arrives in arbitrarily sized pieces, such as from a socket.
+///
+/// Most users will want to use the [`ObjAccumulator`] alias, which stores attributes as `f32`.
+///
+/// Data is given to the parser with [`GenericObjAccumulator::feed`] as it arrives. Each complete line is parsed
+/// immediately, while an incomplete line at the end of a piece is kept until the rest of it arrives, so statements
+/// (and `\r\n` line endings) may be split between pieces at any point. The result is identical to that of
+/// [`GenericObj::from_reader`] given the same data.
+///
+/// ```
+/// use wavefront::ObjAccumulator;
+///
+/// let mut acc = ObjAccumulator::new();
+/// acc.feed(b"v 0 0 0\nv 1 0 0\nv 0 1").unwrap();
+/// acc.feed(b" 0\nf 1 2 3\n").unwrap();
+/// let obj = acc.finish().unwrap();
+/// assert_eq!(obj.polygons().len(), 1);
+/// ```
+pub struct GenericObjAccumulator<T> {
+    parser: Parser<T>,
+    reporter: Reporter,
+    // The start of a line whose end has not yet arrived
+    pending: Vec<u8>,
+    line_num: usize,
+}
+
+/// A push parser that stores attributes as `f32`. See [`GenericObjAccumulator`] for more information.
+pub type ObjAccumulator = GenericObjAccumulator<f32>;
+
+impl<T: Float> GenericObjAccumulator<T> {
+    /// Create a new push parser.
+    pub fn new() -> Self {
+        Self::with_options(ParseOptions::default())
+    }
+
+    /// Create a new push parser, using the given [`ParseOptions`].
+    ///
+    /// The length of the data is not known in advance, so [`ParseOptions::preallocate`] has no effect and progress
+    /// reports have no total.
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self {
+            parser: Parser::new(&options),
+            reporter: Reporter::new(&options),
+            pending: Vec::new(),
+            line_num: 0,
+        }
+    }
+
+    /// Parse the next piece of the OBJ.
+    ///
+    /// Errors refer to line numbers counted from the start of the first piece. Once an error has been produced the
+    /// parser should be discarded, since the line that caused it has been dropped.
+    pub fn feed(&mut self, mut bytes: &[u8]) -> Result<(), Error> {
+        while let Some(end) = bytes.iter().position(|b| *b == b'\n') {
+            // Lines that arrive whole are parsed without being copied
+            let line = if self.pending.is_empty() {
+                &bytes[..end]
+            } else {
+                self.pending.extend_from_slice(&bytes[..end]);
+                &self.pending
+            };
+            self.line_num += 1;
+            let result = parse_line(&mut self.parser, &mut self.reporter, self.line_num, line);
+            self.pending.clear();
+            result?;
+            bytes = &bytes[end + 1..];
+        }
+        self.pending.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Finish parsing, producing the OBJ.
+    ///
+    /// Any data after the last line break is parsed as the final line.
+    pub fn finish(mut self) -> Result<GenericObj<T>, Error> {
+        if !self.pending.is_empty() {
+            self.line_num += 1;
+            parse_line(&mut self.parser, &mut self.reporter, self.line_num, &self.pending)?;
+        }
+        let obj = self.parser.finish()?;
+        self.reporter.finish();
+        Ok(obj)
+    }
+}
+
+impl<T> fmt::Debug for GenericObjAccumulator<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenericObjAccumulator")
+            .field("lines", &self.line_num)
+            .field("pending", &self.pending.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Float> Default for GenericObjAccumulator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Parse a line without its `\n`. A trailing `\r` is left in place: it is whitespace to the parser, and is counted
+// by progress reports.
+fn parse_line<T: Float>(parser: &mut Parser<T>, reporter: &mut Reporter, line_num: usize, line: &[u8]) -> Result<(), Error> {
+    let line = core::str::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    reporter.statement(line_num, line)?;
+    parser.parse_line(line_num, line)?;
+    reporter.line(line_num, line)
+}
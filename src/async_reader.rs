@@ -0,0 +1,52 @@
+use crate::{parse::{check_cancelled, Parser}, Error, Float, GenericObj, ParseOptions};
+use std::future::Future;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+impl<T: Float> GenericObj<T> {
+    /// Read an OBJ from an asynchronous reader (something implementing [`tokio::io::AsyncRead`]).
+    ///
+    /// The reader is buffered internally and the OBJ is parsed one line at a time as it arrives, in the same way as
+    /// [`GenericObj::from_reader`]. Parsing can be cancelled by dropping the returned future.
+    pub async fn from_async_reader<R: AsyncRead + Unpin>(reader: R) -> Result<Self, Error> {
+        Self::from_async_reader_with(reader, ParseOptions::default()).await
+    }
+
+    /// Read an OBJ from an asynchronous reader (something implementing [`tokio::io::AsyncRead`]), using the given
+    /// [`ParseOptions`].
+    ///
+    /// The reader can only be read once, so [`ParseOptions::preallocate`] has no effect. [`ParseOptions::progress`]
+    /// and [`ParseOptions::on_unknown`] are not supported, since their callbacks need not be [`Send`] and holding
+    /// them would prevent the future from being sent between threads: setting either produces an
+    /// [`Error::UnsupportedOption`] rather than silently ignoring it. [`ParseOptions::cancel_flag`] is supported.
+    pub fn from_async_reader_with<R: AsyncRead + Unpin>(
+        reader: R,
+        options: ParseOptions,
+    ) -> impl Future<Output=Result<Self, Error>> {
+        let unsupported = if options.progress.is_some() {
+            Some("progress")
+        } else if options.on_unknown.is_some() {
+            Some("on_unknown")
+        } else {
+            None
+        };
+        // The options are not moved into the future, so that it is `Send` whenever the reader is
+        let parser = Parser::new(&options);
+        let cancel_flag = options.cancel_flag.clone();
+        async move {
+            if let Some(option) = unsupported {
+                return Err(Error::UnsupportedOption(option));
+            }
+            let mut reader = BufReader::new(reader);
+            let mut parser = parser;
+            let mut line = String::new();
+            let mut line_num = 0;
+            while reader.read_line(&mut line).await? > 0 {
+                line_num += 1;
+                parser.parse_line(line_num, &line)?;
+                check_cancelled(cancel_flag.as_deref(), line_num)?;
+                line.clear();
+            }
+            parser.finish()
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    math::{self, GridCoord},
+    util, Buffers, Error, Float, GenericObj, NameIndex, VertexData, VertexIndices, VertexRange,
+};
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::{convert::TryFrom, num::NonZeroU32};
+use hashbrown::HashMap;
+
+/// A builder that assembles an [`Obj`](crate::Obj) from the attributes of each corner of its polygons.
+///
+/// Generated meshes usually have data for every corner of every polygon, rather than separate lists of attributes and
+/// the indices into them. The builder takes care of that bookkeeping: each position, texture coordinate and normal is
+/// looked up among those seen so far and reused if it has been, so shared corners become shared attributes. By
+/// default, attributes are only reused if they're identical (bit-for-bit); see [`ObjBuilder::dedup_epsilon`] for
+/// merging nearby values as well. Attributes appear in the order that they're first used.
+///
+/// ```
+/// use wavefront::{ObjBuilder, VertexData};
+///
+/// let corner = |x, y| VertexData { position: [x, y, 0.0], uv: Some([x, y, 0.0]), normal: None };
+/// let mut builder = ObjBuilder::new();
+/// builder.object("quad").unwrap();
+/// builder.triangle([corner(0.0, 0.0), corner(1.0, 0.0), corner(1.0, 1.0)]);
+/// builder.triangle([corner(0.0, 0.0), corner(1.0, 1.0), corner(0.0, 1.0)]);
+///
+/// let obj = builder.build();
+/// assert_eq!(obj.positions().len(), 4);
+/// assert_eq!(obj.object("quad").unwrap().polygons().count(), 2);
+/// ```
+#[derive(Clone)]
+pub struct ObjBuilder<T = f32> {
+    positions: Vec<[T; 3]>,
+    uvs: Vec<[T; 3]>,
+    normals: Vec<[T; 3]>,
+    vertices: Vec<VertexIndices>,
+    polygons: Vec<VertexRange>,
+    objects: HashMap<String, HashMap<String, Vec<usize>>>,
+    object: String,
+    groups: Vec<String>,
+
+    epsilon: Option<T>,
+    // The index of each attribute, keyed by its bit pattern (or its cell, see `ObjBuilder::dedup_epsilon`)
+    lookup: [HashMap<[GridCoord; 3], NonZeroU32>; 3],
+}
+
+impl<T: Float> Default for ObjBuilder<T> {
+    fn default() -> Self {
+        Self {
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            normals: Vec::new(),
+            vertices: Vec::new(),
+            polygons: Vec::new(),
+            objects: HashMap::new(),
+            object: String::new(),
+            groups: Vec::new(),
+            epsilon: None,
+            lookup: Default::default(),
+        }
+    }
+}
+
+impl<T: Float> ObjBuilder<T> {
+    /// Create a new, empty builder. Polygons belong to the unnamed object and group until others are selected.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merge attributes that fall into the same cell of a grid with cells of the given size, rather than only those
+    /// that are identical (default: `None`).
+    ///
+    /// The first attribute to fall into each cell is kept and used by every later corner in that cell. Attributes
+    /// just either side of a cell boundary aren't merged, however close they are, so this is intended for cleaning up
+    /// rounding noise rather than welding. Components that are infinite, NaN, or too large to be placed in a cell
+    /// are only merged with identical ones. Only attributes added after this is set are affected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the epsilon isn't positive and finite.
+    pub fn dedup_epsilon(mut self, epsilon: Option<T>) -> Self {
+        if let Some(epsilon) = epsilon {
+            assert!(epsilon > T::ZERO && epsilon.is_finite(), "Deduplication epsilon must be positive and finite");
+        }
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Select the object to which subsequent polygons belong, and select the default group within it.
+    ///
+    /// Selecting an object that has been used before continues adding to it. An [`Error::InvalidName`] is produced if
+    /// the name contains characters that the parser doesn't accept (see [`util::name_is_valid`]).
+    pub fn object(&mut self, name: &str) -> Result<&mut Self, Error> {
+        if !util::name_is_valid(name) {
+            return Err(Error::InvalidName(String::from(name)));
+        }
+        self.object = String::from(name);
+        self.groups.clear();
+        Ok(self)
+    }
+
+    /// Select the groups, within the current object, to which subsequent polygons belong. No names selects the
+    /// default group.
+    ///
+    /// An [`Error::InvalidName`] is produced if any of the names is empty or contains characters that the parser
+    /// doesn't accept, in which case the selection is unchanged.
+    pub fn group(&mut self, names: &[&str]) -> Result<&mut Self, Error> {
+        if let Some(name) = names.iter().find(|name| name.is_empty() || !util::name_is_valid(name)) {
+            return Err(Error::InvalidName(String::from(*name)));
+        }
+        self.groups = names.iter().map(|name| String::from(*name)).collect();
+        self.groups.sort_unstable();
+        self.groups.dedup();
+        Ok(self)
+    }
+
+    /// Add a triangle with the given corners to the currently selected object and groups.
+    ///
+    /// See [`ObjBuilder::polygon`] for more information.
+    pub fn triangle(&mut self, corners: [VertexData<T>; 3]) -> &mut Self {
+        self.polygon(&corners)
+    }
+
+    /// Add a polygon with the given corners to the currently selected object and groups.
+    ///
+    /// The attributes of each corner reuse existing attributes where possible (see [`ObjBuilder`]). As with the `f`
+    /// statements of an OBJ, polygons may have any number of corners, but those with fewer than three have no
+    /// triangles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OBJ would contain more than `u32::MAX` of any kind of attribute.
+    pub fn polygon(&mut self, corners: &[VertexData<T>]) -> &mut Self {
+        let start = self.vertices.len();
+        for corner in corners {
+            let vertex = (
+                self.attribute(0, corner.position),
+                corner.uv.map(|uv| self.attribute(1, uv)),
+                corner.normal.map(|normal| self.attribute(2, normal)),
+            );
+            self.vertices.push(vertex);
+        }
+        self.polygons.push(VertexRange { start, end: self.vertices.len() });
+
+        let poly = self.polygons.len() - 1;
+        let groups = self.objects.entry(self.object.clone()).or_default();
+        if self.groups.is_empty() {
+            groups.entry(String::new()).or_default().push(poly);
+        } else {
+            for name in &self.groups {
+                groups.entry(name.clone()).or_default().push(poly);
+            }
+        }
+        self
+    }
+
+    /// Returns the number of polygons added so far.
+    pub fn len(&self) -> usize {
+        self.polygons.len()
+    }
+
+    /// Returns `true` if no polygons have been added.
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    /// Finish building, producing the OBJ.
+    pub fn build(self) -> GenericObj<T> {
+        GenericObj {
+            buffers: Buffers {
+                positions: Arc::new(self.positions),
+                uvs: Arc::new(self.uvs),
+                normals: Arc::new(self.normals),
+                vertices: Arc::new(self.vertices),
+                polygons: Arc::new(self.polygons),
+                lines: None,
+                comments: None,
+                report: Arc::default(),
+                materials: None,
+                material_names: Arc::default(),
+            },
+            names: Arc::new(NameIndex::new(&self.objects)),
+            objects: Arc::new(self.objects),
+            triangles: None,
+        }
+    }
+
+    // Find or add an attribute of the given kind (`0` for positions, `1` for texture coordinates and `2` for normals)
+    fn attribute(&mut self, kind: usize, attribute: [T; 3]) -> NonZeroU32 {
+        let key = match self.epsilon {
+            Some(epsilon) => math::grid_cell(attribute, epsilon),
+            None => attribute.map(|x| GridCoord::Exact(x.to_f64().to_bits())),
+        };
+        let attrs = match kind {
+            0 => &mut self.positions,
+            1 => &mut self.uvs,
+            _ => &mut self.normals,
+        };
+        *self.lookup[kind].entry(key).or_insert_with(|| {
+            attrs.push(attribute);
+            u32::try_from(attrs.len())
+                .ok()
+                .and_then(NonZeroU32::new)
+                .expect("Too many attributes for a built OBJ")
+        })
+    }
+}
+
@@ -0,0 +1,480 @@
+use crate::{
+    math,
+    query::{intersect_triangle, TriangleHit},
+    Float, GenericObj, Index, RayHit, SurfacePoint, Vertex,
+};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Nodes with this many triangles or fewer are not split any further.
+const MAX_LEAF_LEN: usize = 4;
+
+/// A bounding volume hierarchy over the triangles of an [`Obj`](crate::Obj), produced by [`GenericObj::build_bvh`].
+///
+/// This accelerates raycasts and proximity queries on large models, which would otherwise need to visit every
+/// triangle. Triangles are identified by their index in the order produced by [`GenericObj::triangles`], and the
+/// results of every query are identical to those of a brute-force search over that list, including how ties are
+/// broken.
+pub struct Bvh<'a, T = f32> {
+    triangles: Vec<BvhTriangle<'a, T>>,
+    // The object and group names of each polygon, see `GenericObj::polygon_owners`
+    owners: Vec<(&'a str, &'a str)>,
+    nodes: Vec<Node<T>>,
+    // Triangle indices, arranged such that the triangles of each leaf are contiguous
+    order: Vec<Index>,
+}
+
+struct BvhTriangle<'a, T> {
+    vertices: [Vertex<'a, T>; 3],
+    polygon: Index,
+}
+
+struct Node<T> {
+    min: [T; 3],
+    max: [T; 3],
+    kind: NodeKind,
+}
+
+enum NodeKind {
+    // A range of `Bvh::order`
+    Leaf { start: usize, end: usize },
+    Branch { left: usize, right: usize },
+}
+
+/// The closest point on the surface of an [`Obj`](crate::Obj) to a query point, produced by [`Bvh::closest_point`].
+#[derive(Copy, Clone, Debug)]
+pub struct ClosestHit<'a, T: Float = f32> {
+    /// The closest point on the surface.
+    pub point: [T; 3],
+    /// The euclidean distance between the query point and [`ClosestHit::point`].
+    pub distance: T,
+    /// The barycentric coordinates of [`ClosestHit::point`] within [`ClosestHit::triangle`], such that the point is
+    /// the weighted sum of the positions of its vertices.
+    pub barycentric: [T; 3],
+    /// The triangle containing the point, as produced by [`Polygon::triangles`](crate::Polygon::triangles).
+    pub triangle: [Vertex<'a, T>; 3],
+    /// The index of the triangle containing the point, in the order produced by [`GenericObj::triangles`].
+    pub triangle_index: Index,
+    /// The index of the polygon containing the point (see [`Polygon::index`](crate::Polygon::index)).
+    pub polygon: Index,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Build a [`Bvh`] over the triangles of this [`Obj`](crate::Obj), for fast raycasts and proximity queries.
+    ///
+    /// The hierarchy is built by splitting the triangles at the median of their centroids along the longest axis of
+    /// their bounds, and is fully determined by the OBJ. Degenerate triangles are handled like any other.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// let bvh = obj.build_bvh();
+    /// let hit = bvh.raycast([0.5, 0.5, -1.0], [0.0, 0.0, 1.0], false).unwrap();
+    /// assert_eq!(hit.distance, 1.0);
+    /// ```
+    pub fn build_bvh(&self) -> Bvh<'_, T> {
+        let triangles = self
+            .polygons()
+            .flat_map(|poly| poly.triangles().map(move |vertices| BvhTriangle { vertices, polygon: poly.index() }))
+            .collect::<Vec<_>>();
+        let centroids = triangles
+            .iter()
+            .map(|tri| {
+                let [a, b, c] = tri.vertices.map(|v| v.position());
+                let third = T::ONE / T::from_f32(3.0);
+                math::scale(math::add(math::add(a, b), c), third)
+            })
+            .collect::<Vec<_>>();
+
+        let mut bvh = Bvh {
+            owners: self.polygon_owners(),
+            nodes: Vec::new(),
+            order: (0..triangles.len()).collect(),
+            triangles,
+        };
+        if !bvh.triangles.is_empty() {
+            bvh.build(&centroids, 0, bvh.order.len());
+        }
+        bvh
+    }
+}
+
+impl<'a, T: Float> Bvh<'a, T> {
+    /// Build the subtree containing the triangles in `order[start..end]`, returning the index of its root node.
+    fn build(&mut self, centroids: &[[T; 3]], start: usize, end: usize) -> usize {
+        let (min, max) = self.order[start..end]
+            .iter()
+            .flat_map(|&i| self.triangles[i].vertices.map(|v| v.position()))
+            .fold((None, None), |(min, max): (Option<[T; 3]>, Option<[T; 3]>), p| {
+                (Some(min.map_or(p, |min| component_min(min, p))), Some(max.map_or(p, |max| component_max(max, p))))
+            });
+        let (min, max) = pad(min.unwrap(), max.unwrap());
+        let node = self.nodes.len();
+        self.nodes.push(Node { min, max, kind: NodeKind::Leaf { start, end } });
+        if end - start <= MAX_LEAF_LEN {
+            return node;
+        }
+
+        // Split along the axis in which the centroids are most spread out
+        let (cmin, cmax) = self.order[start..end]
+            .iter()
+            .map(|&i| centroids[i])
+            .fold((centroids[self.order[start]], centroids[self.order[start]]), |(min, max), c| {
+                (component_min(min, c), component_max(max, c))
+            });
+        let extent = math::sub(cmax, cmin);
+        // Triangles that all share a centroid can't be told apart, so there's no point in splitting them
+        if !(extent[0] > T::ZERO || extent[1] > T::ZERO || extent[2] > T::ZERO) {
+            return node;
+        }
+        let axis = (0..3).fold(0, |best, axis| if extent[axis] > extent[best] { axis } else { best });
+
+        // Break ties by index so that the hierarchy is deterministic
+        let mid = start + (end - start) / 2;
+        self.order[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            centroids[a][axis].partial_cmp(&centroids[b][axis]).unwrap_or(Ordering::Equal).then(a.cmp(&b))
+        });
+        let left = self.build(centroids, start, mid);
+        let right = self.build(centroids, mid, end);
+        self.nodes[node].kind = NodeKind::Branch { left, right };
+        node
+    }
+
+    /// Returns the number of triangles in this [`Bvh`].
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Returns `true` if this [`Bvh`] contains no triangles.
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Returns the triangle with the given index, in the order produced by [`GenericObj::triangles`].
+    pub fn triangle(&self, index: Index) -> Option<[Vertex<'a, T>; 3]> {
+        self.triangles.get(index).map(|tri| tri.vertices)
+    }
+
+    /// Find the closest intersection between a ray and the triangles in this [`Bvh`], if any.
+    ///
+    /// The result is identical to that of [`GenericObj::raycast`].
+    pub fn raycast(&self, origin: [T; 3], dir: [T; 3], cull_backfaces: bool) -> Option<RayHit<'a, T>> {
+        let mut closest: Option<(TriangleHit<'a, T>, Index)> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            let limit = closest.as_ref().map(|(hit, _)| hit.distance);
+            if !ray_hits_box(origin, dir, node.min, node.max, limit) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf { start, end } => {
+                    for &i in &self.order[start..end] {
+                        let Some(hit) = intersect_triangle(origin, dir, self.triangles[i].vertices, cull_backfaces)
+                        else { continue };
+                        // Prefer the earliest triangle at a given distance, as a brute-force search would
+                        let better = closest.as_ref().map_or(true, |(closest, j)| {
+                            hit.distance < closest.distance || (hit.distance == closest.distance && i < *j)
+                        });
+                        if better {
+                            closest = Some((hit, i));
+                        }
+                    }
+                },
+                NodeKind::Branch { left, right } => {
+                    stack.push(right);
+                    stack.push(left);
+                },
+            }
+        }
+        closest.map(|(hit, i)| {
+            let polygon = self.triangles[i].polygon;
+            hit.into_ray_hit(polygon, self.owners[polygon])
+        })
+    }
+
+    /// Returns the indices of the triangles that intersect the given axis-aligned box, in ascending order.
+    ///
+    /// Triangles touching the boundary of the box are included.
+    pub fn intersect_aabb(&self, min: [T; 3], max: [T; 3]) -> impl Iterator<Item=Index> + '_ {
+        let mut found = Vec::new();
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if (0..3).any(|i| node.min[i] > max[i] || node.max[i] < min[i]) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf { start, end } => found.extend(self.order[start..end]
+                    .iter()
+                    .copied()
+                    .filter(|&i| triangle_hits_box(self.triangles[i].vertices.map(|v| v.position()), min, max))),
+                NodeKind::Branch { left, right } => {
+                    stack.push(right);
+                    stack.push(left);
+                },
+            }
+        }
+        found.sort_unstable();
+        found.into_iter()
+    }
+
+    /// Find the closest point to `p` on the triangles in this [`Bvh`], or `None` if it contains no triangles.
+    ///
+    /// If several triangles are equally close, the one that comes first is chosen.
+    pub fn closest_point(&self, p: [T; 3]) -> Option<ClosestHit<'a, T>> {
+        let (dist_sq, i, (point, barycentric)) = self.nearest(p, |i, closest| {
+            let (point, barycentric) = closest_on_triangle(p, self.triangles[i].vertices.map(|v| v.position()));
+            let d = math::sub(point, p);
+            offer(closest, math::dot(d, d), i, (point, barycentric));
+        })?;
+        Some(ClosestHit {
+            point,
+            distance: dist_sq.sqrt(),
+            barycentric,
+            triangle: self.triangles[i].vertices,
+            triangle_index: i,
+            polygon: self.triangles[i].polygon,
+        })
+    }
+
+    /// Find the closest point to `p` on the triangles in this [`Bvh`], or `None` if it contains no triangles.
+    ///
+    /// The result is identical to that of [`GenericObj::nearest_surface_point`]. See also [`Bvh::closest_point`].
+    pub fn nearest_surface_point(&self, p: [T; 3]) -> Option<SurfacePoint<T>> {
+        self.closest_point(p).map(|hit| SurfacePoint {
+            position: hit.point,
+            distance: hit.distance,
+            barycentric: hit.barycentric,
+            triangle: hit.triangle_index,
+            polygon: hit.polygon,
+        })
+    }
+
+    /// Find the position closest to `p` among the vertices of the triangles in this [`Bvh`], returning its index in
+    /// [`GenericObj::positions`] and its distance from `p`.
+    ///
+    /// Unlike [`GenericObj::nearest_position`], positions that aren't used by any triangle are not considered.
+    /// Otherwise, the result is identical, with ties going to the lowest index.
+    pub fn nearest_position(&self, p: [T; 3]) -> Option<(Index, T)> {
+        let (dist_sq, i, ()) = self.nearest(p, |i, closest| {
+            for v in self.triangles[i].vertices {
+                let d = math::sub(v.position(), p);
+                offer(closest, math::dot(d, d), v.position_index(), ());
+            }
+        })?;
+        Some((i, dist_sq.sqrt()))
+    }
+
+    /// Walk the hierarchy outwards from `p`, calling `measure` with each triangle that might contain something closer
+    /// than the closest thing found so far.
+    fn nearest<R>(&self, p: [T; 3], mut measure: impl FnMut(Index, &mut Option<(T, Index, R)>)) -> Option<(T, Index, R)> {
+        // The squared distance to the closest thing so far, the index by which ties are broken, and anything else
+        let mut closest: Option<(T, Index, R)> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if closest.as_ref().is_some_and(|(dist_sq, ..)| box_distance_sq(p, node.min, node.max) > *dist_sq) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf { start, end } => for &i in &self.order[start..end] {
+                    measure(i, &mut closest);
+                },
+                NodeKind::Branch { left, right } => {
+                    // Visit the nearer child first, so that the other is more likely to be pruned
+                    let dist = |node: usize| box_distance_sq(p, self.nodes[node].min, self.nodes[node].max);
+                    if dist(left) <= dist(right) {
+                        stack.push(right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                },
+            }
+        }
+        closest
+    }
+}
+
+/// Replace `closest` with a candidate if it's closer, or equally close with a lower index.
+fn offer<T: Float, R>(closest: &mut Option<(T, Index, R)>, dist_sq: T, index: Index, candidate: R) {
+    let better = closest.as_ref().map_or(true, |&(closest, j, _)| dist_sq < closest || (dist_sq == closest && index < j));
+    if better {
+        *closest = Some((dist_sq, index, candidate));
+    }
+}
+
+fn component_min<T: Float>(a: [T; 3], b: [T; 3]) -> [T; 3] {
+    [0, 1, 2].map(|i| if b[i] < a[i] { b[i] } else { a[i] })
+}
+
+fn component_max<T: Float>(a: [T; 3], b: [T; 3]) -> [T; 3] {
+    [0, 1, 2].map(|i| if b[i] > a[i] { b[i] } else { a[i] })
+}
+
+/// The relative amount by which bounding boxes and distance limits are padded, so that a triangle can't be missed
+/// because of rounding in the ray/box and ray/triangle tests disagreeing.
+fn tolerance<T: Float>() -> T {
+    T::EPSILON * T::from_f32(64.0)
+}
+
+/// Grow a bounding box slightly, relative to its size and distance from the origin.
+fn pad<T: Float>(min: [T; 3], max: [T; 3]) -> ([T; 3], [T; 3]) {
+    let margin = (0..3)
+        .map(|i| (max[i] - min[i]) + min[i].abs() + max[i].abs())
+        .fold(T::ZERO, |a, b| if b > a { b } else { a })
+        * tolerance();
+    (min.map(|x| x - margin), max.map(|x| x + margin))
+}
+
+/// Returns `true` if the ray passes through the box before travelling `limit` multiples of its direction, inclusive.
+fn ray_hits_box<T: Float>(origin: [T; 3], dir: [T; 3], min: [T; 3], max: [T; 3], limit: Option<T>) -> bool {
+    let mut near = T::ZERO;
+    let mut far = limit.map(|limit| limit + limit.abs() * tolerance());
+    for i in 0..3 {
+        if dir[i] == T::ZERO {
+            // Parallel to the slab, so the origin must lie within it
+            if origin[i] < min[i] || origin[i] > max[i] {
+                return false;
+            }
+            continue;
+        }
+        let (mut t0, mut t1) = ((min[i] - origin[i]) / dir[i], (max[i] - origin[i]) / dir[i]);
+        if t0 > t1 {
+            core::mem::swap(&mut t0, &mut t1);
+        }
+        if t0 > near {
+            near = t0;
+        }
+        if far.map_or(true, |far| t1 < far) {
+            far = Some(t1);
+        }
+        if far.is_some_and(|far| near > far) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns the squared distance from `p` to the closest point in the box.
+fn box_distance_sq<T: Float>(p: [T; 3], min: [T; 3], max: [T; 3]) -> T {
+    (0..3)
+        .map(|i| if p[i] < min[i] { min[i] - p[i] } else if p[i] > max[i] { p[i] - max[i] } else { T::ZERO })
+        .map(|d| d * d)
+        .fold(T::ZERO, |a, b| a + b)
+}
+
+/// Separating axis test between a triangle and an axis-aligned box.
+fn triangle_hits_box<T: Float>(triangle: [[T; 3]; 3], min: [T; 3], max: [T; 3]) -> bool {
+    let half = T::ONE / T::from_f32(2.0);
+    let center = math::scale(math::add(min, max), half);
+    let extent = math::scale(math::sub(max, min), half);
+    let [a, b, c] = triangle.map(|p| math::sub(p, center));
+    let edges = [math::sub(b, a), math::sub(c, b), math::sub(a, c)];
+    let unit = |i| {
+        let mut axis = [T::ZERO; 3];
+        axis[i] = T::ONE;
+        axis
+    };
+    let separated = |axis: [T; 3]| {
+        let [pa, pb, pc] = [a, b, c].map(|p| math::dot(p, axis));
+        let r = extent[0] * axis[0].abs() + extent[1] * axis[1].abs() + extent[2] * axis[2].abs();
+        let lo = if pa < pb { pa } else { pb };
+        let lo = if pc < lo { pc } else { lo };
+        let hi = if pa > pb { pa } else { pb };
+        let hi = if pc > hi { pc } else { hi };
+        lo > r || hi < -r
+    };
+    // Degenerate axes project everything onto zero, so they never separate anything
+    !((0..3).any(|i| separated(unit(i)))
+        || separated(math::cross(edges[0], edges[1]))
+        || (0..3).any(|i| edges.iter().any(|&edge| separated(math::cross(unit(i), edge)))))
+}
+
+/// Returns the closest point to `p` on a triangle, and its barycentric coordinates within it.
+///
+/// See Ericson, *Real-Time Collision Detection*, section 5.1.5.
+pub(crate) fn closest_on_triangle<T: Float>(p: [T; 3], [a, b, c]: [[T; 3]; 3]) -> ([T; 3], [T; 3]) {
+    let (o, l) = (T::ZERO, T::ONE);
+    let at = |[u, v, w]: [T; 3]| {
+        (math::add(math::add(math::scale(a, u), math::scale(b, v)), math::scale(c, w)), [u, v, w])
+    };
+    let ab = math::sub(b, a);
+    let ac = math::sub(c, a);
+    let ap = math::sub(p, a);
+    let d1 = math::dot(ab, ap);
+    let d2 = math::dot(ac, ap);
+    if d1 <= o && d2 <= o {
+        return (a, [l, o, o]);
+    }
+    let bp = math::sub(p, b);
+    let d3 = math::dot(ab, bp);
+    let d4 = math::dot(ac, bp);
+    if d3 >= o && d4 <= d3 {
+        return (b, [o, l, o]);
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= o && d1 >= o && d3 <= o {
+        let v = d1 / (d1 - d3);
+        return at([l - v, v, o]);
+    }
+    let cp = math::sub(p, c);
+    let d5 = math::dot(ab, cp);
+    let d6 = math::dot(ac, cp);
+    if d6 >= o && d5 <= d6 {
+        return (c, [o, o, l]);
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= o && d2 >= o && d6 <= o {
+        let w = d2 / (d2 - d6);
+        return at([l - w, o, w]);
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= o && d4 - d3 >= o && d5 - d6 >= o {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return at([o, l - w, w]);
+    }
+    let denom = va + vb + vc;
+    if denom > o {
+        let v = vb / denom;
+        let w = vc / denom;
+        return at([l - v - w, v, w]);
+    }
+
+    // The triangle is degenerate, so the closest point lies on one of its edges
+    let dist_sq = |(point, _): &([T; 3], [T; 3])| {
+        let d = math::sub(*point, p);
+        math::dot(d, d)
+    };
+    let edge = |from: [T; 3], to: [T; 3]| {
+        let e = math::sub(to, from);
+        let len_sq = math::dot(e, e);
+        let t = if len_sq > o { math::dot(math::sub(p, from), e) / len_sq } else { o };
+        if t < o { o } else if t > l { l } else { t }
+    };
+    let t = edge(a, b);
+    let mut closest = at([l - t, t, o]);
+    let t = edge(b, c);
+    let candidate = at([o, l - t, t]);
+    if dist_sq(&candidate) < dist_sq(&closest) {
+        closest = candidate;
+    }
+    let t = edge(c, a);
+    let candidate = at([t, o, l - t]);
+    if dist_sq(&candidate) < dist_sq(&closest) {
+        closest = candidate;
+    }
+    closest
+}
@@ -0,0 +1,285 @@
+use crate::{Buffers, Error, Float, GenericObj, NameIndex, VertexRange};
+use alloc::sync::Arc;
+use core::{convert::{TryFrom, TryInto}, mem::size_of, num::NonZeroU32};
+use hashbrown::HashMap;
+use std::io::{self, Read, Write};
+
+/// The bytes that every cache begins with.
+const MAGIC: [u8; 8] = *b"WFOBJ\0\xca\xc4";
+
+/// The version of the cache layout, which must be increased whenever the layout changes.
+pub(crate) const CACHE_VERSION: u32 = 2;
+
+/// The largest number of elements reserved for a section of a cache before it has been read.
+const MAX_RESERVE: usize = 1 << 24;
+
+/// The number of elements read from a section at a time.
+const BLOCK_LEN: usize = 4096;
+
+impl<T: Float> GenericObj<T> {
+    /// Write a binary snapshot of this OBJ, which can be loaded much faster than the text it was parsed from with
+    /// [`GenericObj::from_cache`].
+    ///
+    /// The cache stores the attribute, vertex and polygon buffers as raw little-endian numbers, along with the names
+    /// of objects and groups, the materials of polygons (see [`Polygon::material`](crate::Polygon::material)), source
+    /// lines (see [`ParseOptions::track_lines`](crate::ParseOptions::track_lines)) and whether triangles were cached.
+    /// Comments and the [`ParseReport`](crate::ParseReport) are not stored. It begins with a magic number and a
+    /// version, such that caches written by an incompatible version of this crate are rejected rather than misread.
+    /// Wrap the writer in a [`std::io::BufWriter`] if it isn't already buffered.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj = Obj::from_file("tests/ship.obj").unwrap();
+    /// let mut cache = Vec::new();
+    /// obj.to_cache(&mut cache).unwrap();
+    /// let loaded = Obj::from_cache(&cache[..]).unwrap();
+    /// assert_eq!(loaded.positions(), obj.positions());
+    /// ```
+    pub fn to_cache<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        let buffers = &self.buffers;
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+        header.extend_from_slice(&(size_of::<T>() as u32).to_le_bytes());
+        header.push(buffers.lines.is_some() as u8);
+        header.push(self.triangles.is_some() as u8);
+        writer.write_all(&header)?;
+
+        for attributes in [&buffers.positions, &buffers.uvs, &buffers.normals] {
+            write_section(&mut writer, attributes.len(), attributes.len() * 3 * size_of::<T>(), |bytes| {
+                for x in attributes.iter().flatten() {
+                    if size_of::<T>() == 4 {
+                        bytes.extend_from_slice(&x.to_f32().to_le_bytes());
+                    } else {
+                        bytes.extend_from_slice(&x.to_f64().to_le_bytes());
+                    }
+                }
+            })?;
+        }
+        write_section(&mut writer, buffers.vertices.len(), buffers.vertices.len() * 12, |bytes| {
+            // Indices are 1-based, leaving `0` to mean that an attribute is absent
+            for (pos, uv, norm) in buffers.vertices.iter() {
+                for idx in [Some(*pos), *uv, *norm] {
+                    bytes.extend_from_slice(&idx.map_or(0, NonZeroU32::get).to_le_bytes());
+                }
+            }
+        })?;
+        write_section(&mut writer, buffers.polygons.len(), buffers.polygons.len() * 16, |bytes| {
+            for range in buffers.polygons.iter() {
+                bytes.extend_from_slice(&(range.start as u64).to_le_bytes());
+                bytes.extend_from_slice(&(range.end as u64).to_le_bytes());
+            }
+        })?;
+        if let Some(lines) = &buffers.lines {
+            write_section(&mut writer, lines.len(), lines.len() * 8, |bytes| {
+                lines.iter().for_each(|line| bytes.extend_from_slice(&(*line as u64).to_le_bytes()));
+            })?;
+        }
+
+        write_section(&mut writer, self.objects.len(), 0, |bytes| {
+            for (object, groups) in self.objects.iter() {
+                write_name(bytes, object);
+                bytes.extend_from_slice(&(groups.len() as u64).to_le_bytes());
+                for (group, polys) in groups {
+                    write_name(bytes, group);
+                    bytes.extend_from_slice(&(polys.len() as u64).to_le_bytes());
+                    polys.iter().for_each(|poly| bytes.extend_from_slice(&(*poly as u64).to_le_bytes()));
+                }
+            }
+        })?;
+
+        write_section(&mut writer, buffers.material_names.len(), 0, |bytes| {
+            buffers.material_names.iter().for_each(|name| write_name(bytes, name));
+        })?;
+        // Polygons without a material are `0`, and an OBJ without materials has no entries at all
+        let materials = buffers.materials.as_deref().map_or(&[][..], Vec::as_slice);
+        write_section(&mut writer, materials.len(), materials.len() * 4, |bytes| {
+            for material in materials {
+                bytes.extend_from_slice(&material.map_or(0, NonZeroU32::get).to_le_bytes());
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Load an OBJ from a binary snapshot written by [`GenericObj::to_cache`].
+    ///
+    /// An [`Error::UnsupportedCacheVersion`] is produced if the cache was written by an incompatible version of this
+    /// crate, and an [`Error::InvalidCache`] if the data is not a cache or is corrupt: every index is checked, so an
+    /// invalid cache can never produce an invalid OBJ. A cache written with `f64` attributes may be loaded with `f32`
+    /// attributes (and vice versa), in which case they are converted.
+    pub fn from_cache<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut header = [0; 18];
+        reader.read_exact(&mut header).map_err(invalid_if_eof)?;
+        if header[..8] != MAGIC {
+            return Err(Error::InvalidCache);
+        }
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != CACHE_VERSION {
+            return Err(Error::UnsupportedCacheVersion(version));
+        }
+        let width = match u32::from_le_bytes(header[12..16].try_into().unwrap()) {
+            width @ (4 | 8) => width as usize,
+            _ => return Err(Error::InvalidCache),
+        };
+        let (has_lines, has_triangles) = (flag(header[16])?, flag(header[17])?);
+
+        let mut attributes = || {
+            let mut attributes = Vec::new();
+            read_section(&mut reader, 3 * width, &mut attributes, |x| if width == 4 {
+                [0, 4, 8].map(|i| T::from_f32(f32::from_le_bytes(x[i..i + 4].try_into().unwrap())))
+            } else {
+                [0, 8, 16].map(|i| T::from_f64(f64::from_le_bytes(x[i..i + 8].try_into().unwrap())))
+            })?;
+            Ok::<_, Error>(attributes)
+        };
+        let (positions, uvs, normals) = (attributes()?, attributes()?, attributes()?);
+
+        // Indices are checked all at once, which is much faster than stopping at the first invalid one
+        let lengths = [positions.len(), uvs.len(), normals.len()].map(|len| len.min(u32::MAX as usize) as u32);
+        let (mut vertices, mut valid) = (Vec::new(), true);
+        read_section(&mut reader, 12, &mut vertices, |vertex| {
+            let [pos, uv, norm] = [0, 4, 8].map(|i| u32::from_le_bytes(vertex[i..i + 4].try_into().unwrap()));
+            valid &= pos != 0 && pos <= lengths[0] && uv <= lengths[1] && norm <= lengths[2];
+            (NonZeroU32::new(pos.max(1)).unwrap(), NonZeroU32::new(uv), NonZeroU32::new(norm))
+        })?;
+
+        let mut polygons = Vec::new();
+        read_section(&mut reader, 16, &mut polygons, |range| {
+            let [start, end] = [0, 8].map(|i| read_usize(&range[i..i + 8]));
+            valid &= start <= end && end <= vertices.len();
+            VertexRange { start, end }
+        })?;
+
+        let lines = if has_lines {
+            let mut lines = Vec::new();
+            read_section(&mut reader, 8, &mut lines, read_usize)?;
+            valid &= lines.len() == polygons.len();
+            Some(Arc::new(lines))
+        } else {
+            None
+        };
+
+        let objects_len = read_len(&mut reader)?;
+        let mut objects = HashMap::with_capacity(objects_len.min(BLOCK_LEN));
+        for _ in 0..objects_len {
+            let object = read_name(&mut reader)?;
+            let groups_len = read_len(&mut reader)?;
+            let mut groups = HashMap::with_capacity(groups_len.min(BLOCK_LEN));
+            for _ in 0..groups_len {
+                let group = read_name(&mut reader)?;
+                let mut polys = Vec::new();
+                read_section(&mut reader, 8, &mut polys, |poly| {
+                    let poly = read_usize(poly);
+                    valid &= poly < polygons.len();
+                    poly
+                })?;
+                groups.insert(group, polys);
+            }
+            objects.insert(object, groups);
+        }
+
+        let names_len = read_len(&mut reader)?;
+        let mut material_names = Vec::with_capacity(names_len.min(BLOCK_LEN));
+        for _ in 0..names_len {
+            material_names.push(read_name(&mut reader)?);
+        }
+        let names_len = names_len.min(u32::MAX as usize) as u32;
+        let mut materials = Vec::new();
+        read_section(&mut reader, 4, &mut materials, |material| {
+            let material = u32::from_le_bytes(material.try_into().unwrap());
+            valid &= material <= names_len;
+            NonZeroU32::new(material)
+        })?;
+        valid &= materials.is_empty() || materials.len() == polygons.len();
+        if !valid {
+            return Err(Error::InvalidCache);
+        }
+
+        let mut obj = GenericObj {
+            buffers: Buffers {
+                positions: Arc::new(positions),
+                uvs: Arc::new(uvs),
+                normals: Arc::new(normals),
+                vertices: Arc::new(vertices),
+                polygons: Arc::new(polygons),
+                lines,
+                comments: None,
+                report: Arc::default(),
+                materials: if materials.is_empty() { None } else { Some(Arc::new(materials)) },
+                material_names: Arc::new(material_names),
+            },
+            names: Arc::new(NameIndex::new(&objects)),
+            objects: Arc::new(objects),
+            triangles: None,
+        };
+        if has_triangles {
+            obj.cache_triangles();
+        }
+        Ok(obj)
+    }
+}
+
+// Write the number of elements in a section, followed by its contents
+fn write_section<W: Write>(writer: &mut W, count: usize, capacity: usize, contents: impl FnOnce(&mut Vec<u8>)) -> io::Result<()> {
+    let mut bytes = Vec::with_capacity(8 + capacity);
+    bytes.extend_from_slice(&(count as u64).to_le_bytes());
+    contents(&mut bytes);
+    writer.write_all(&bytes)
+}
+
+fn write_name(bytes: &mut Vec<u8>, name: &str) {
+    bytes.extend_from_slice(&(name.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(name.as_bytes());
+}
+
+// Read the number of elements in a section, followed by the given number of bytes for each of them. The contents are
+// read in blocks, and at most `MAX_RESERVE` elements are reserved up front, so that a corrupt count can't cause a huge
+// allocation.
+fn read_section<R: Read, E>(
+    reader: &mut R,
+    stride: usize,
+    elements: &mut Vec<E>,
+    mut element: impl FnMut(&[u8]) -> E,
+) -> Result<(), Error> {
+    let count = read_len(reader)?;
+    elements.reserve(count.min(MAX_RESERVE));
+    let mut block = vec![0; stride * BLOCK_LEN];
+    let mut remaining = count;
+    while remaining > 0 {
+        let len = remaining.min(BLOCK_LEN);
+        let block = &mut block[..stride * len];
+        reader.read_exact(block).map_err(invalid_if_eof)?;
+        elements.extend(block.chunks_exact(stride).map(&mut element));
+        remaining -= len;
+    }
+    Ok(())
+}
+
+fn read_len<R: Read>(reader: &mut R) -> Result<usize, Error> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes).map_err(invalid_if_eof)?;
+    usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| Error::InvalidCache)
+}
+
+fn read_usize(bytes: &[u8]) -> usize {
+    u64::from_le_bytes(bytes.try_into().unwrap()) as usize
+}
+
+fn read_name<R: Read>(reader: &mut R) -> Result<String, Error> {
+    let mut name = Vec::new();
+    read_section(reader, 1, &mut name, |byte| byte[0])?;
+    String::from_utf8(name).map_err(|_| Error::InvalidCache)
+}
+
+fn flag(byte: u8) -> Result<bool, Error> {
+    match byte {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(Error::InvalidCache),
+    }
+}
+
+// A cache that ends early is corrupt, rather than the reader having failed
+fn invalid_if_eof(e: io::Error) -> Error {
+    if e.kind() == io::ErrorKind::UnexpectedEof { Error::InvalidCache } else { Error::Io(e) }
+}
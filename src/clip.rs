@@ -0,0 +1,227 @@
+use crate::{math, Float, GenericObj, NameIndex, Plane, VertexIndices, VertexRange};
+use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use core::{convert::TryFrom, num::NonZeroU32};
+use hashbrown::{HashMap, HashSet};
+
+/// The name of the group, in the unnamed object, that caps produced by [`GenericObj::clipped`] are placed in.
+const CAP_GROUP: &str = "cap";
+
+/// A side of a [`Plane`], used by [`GenericObj::clipped`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The side that the normal of the plane points towards.
+    Front,
+    /// The side that the normal of the plane points away from.
+    Back,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Create a new OBJ containing only the parts of this OBJ on the given side of a plane.
+    ///
+    /// Polygons entirely on the other side are removed, and polygons crossing the plane are cut along it, keeping
+    /// their object and group membership. Vertices created on the plane are shared between the polygons on either
+    /// side of each cut edge, so a welded mesh stays welded. Texture coordinates and normals are interpolated along
+    /// cut edges (normals are then renormalised) where both ends of an edge have them. Geometry lying exactly on the
+    /// plane is kept. Attributes are compacted, so only those still in use remain.
+    ///
+    /// If `cap` is `true`, the holes left along the plane are closed with polygons facing away from the kept side,
+    /// placed in the `cap` group of the unnamed object. This is a best effort intended for closed meshes: each cut
+    /// loop becomes a single polygon, which may be concave, and loops that don't close up are left open. Loops around
+    /// holes in the cross-section are wound the other way, so the volume of the result (see
+    /// [`GenericObj::volume`]) is still correct.
+    ///
+    /// ```
+    /// use wavefront::{Obj, Plane, Side};
+    ///
+    /// let obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// let half = obj.clipped(Plane::new([0.0, 0.0, 1.0], 0.5), Side::Back, true);
+    /// assert!((half.volume() - 0.5).abs() < 1e-6);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result would contain more than `u32::MAX` of any kind of attribute.
+    pub fn clipped(&self, plane: Plane<T>, keep: Side, cap: bool) -> Self {
+        let sign = match keep {
+            Side::Front => T::ONE,
+            Side::Back => -T::ONE,
+        };
+        // The distance of every original position into the kept side
+        let depths = self.buffers.positions.iter().map(|p| plane.signed_distance(*p) * sign).collect::<Vec<_>>();
+        let depth = |v: &VertexIndices| depths[v.0.get() as usize - 1];
+
+        let mut obj = self.clone();
+        let mut positions = self.buffers.positions.to_vec();
+        let mut uvs = self.buffers.uvs.to_vec();
+        let mut normals = self.buffers.normals.to_vec();
+        let mut cuts = Cuts::default();
+
+        let mut vertices = Vec::with_capacity(self.buffers.vertices.len());
+        let mut polygons = Vec::with_capacity(self.buffers.polygons.len());
+        let mut new_index = Vec::with_capacity(self.buffers.polygons.len());
+        for range in self.buffers.polygons.iter() {
+            let poly = &self.buffers.vertices[range.start..range.end];
+            let start = vertices.len();
+            if poly.iter().all(|v| depth(v) >= T::ZERO) {
+                vertices.extend_from_slice(poly);
+            } else if poly.iter().any(|v| depth(v) > T::ZERO) {
+                // Sutherland-Hodgman clipping against a single plane
+                for (i, a) in poly.iter().enumerate() {
+                    let b = &poly[(i + 1) % poly.len()];
+                    let (da, db) = (depth(a), depth(b));
+                    if da >= T::ZERO {
+                        vertices.push(*a);
+                    }
+                    if (da > T::ZERO && db < T::ZERO) || (da < T::ZERO && db > T::ZERO) {
+                        vertices.push(cuts.cut(&depths, [&mut positions, &mut uvs, &mut normals], a, b));
+                    }
+                }
+            }
+            if vertices.len() - start >= 3 {
+                new_index.push(Some(polygons.len()));
+                polygons.push(VertexRange { start, end: vertices.len() });
+            } else {
+                vertices.truncate(start);
+                new_index.push(None);
+            }
+        }
+
+        let caps = if cap {
+            let on_plane = |pos: NonZeroU32| depths.get(pos.get() as usize - 1).map_or(true, |d| *d == T::ZERO);
+            cap_loops(&vertices, &polygons, on_plane)
+        } else {
+            Vec::new()
+        };
+        if !caps.is_empty() {
+            normals.push(plane.normal().map(|x| x * -sign));
+        }
+        obj.buffers.positions = Arc::new(positions);
+        obj.buffers.uvs = Arc::new(uvs);
+        obj.buffers.normals = Arc::new(normals);
+        obj.replace_polygons(vertices, polygons, &new_index);
+
+        if !caps.is_empty() {
+            let normal = attribute_index(&obj.buffers.normals);
+            let vertices = Arc::make_mut(&mut obj.buffers.vertices);
+            let polygons = Arc::make_mut(&mut obj.buffers.polygons);
+            let first = polygons.len();
+            for cap in caps {
+                let start = vertices.len();
+                vertices.extend(cap.into_iter().map(|pos| (pos, None, Some(normal))));
+                polygons.push(VertexRange { start, end: vertices.len() });
+            }
+            let objects = Arc::make_mut(&mut obj.objects);
+            objects
+                .entry(String::new())
+                .or_default()
+                .entry(String::from(CAP_GROUP))
+                .or_default()
+                .extend(first..polygons.len());
+            obj.names = Arc::new(NameIndex::new(objects));
+        }
+        obj.extract(|_| true)
+    }
+}
+
+/// The attributes created where edges cross the plane, keyed by the attributes at either end of the edge (lowest
+/// position first) such that the polygons on either side of an edge share them.
+#[derive(Default)]
+struct Cuts {
+    positions: HashMap<(NonZeroU32, NonZeroU32), NonZeroU32>,
+    uvs: HashMap<(NonZeroU32, NonZeroU32, NonZeroU32, NonZeroU32), NonZeroU32>,
+    normals: HashMap<(NonZeroU32, NonZeroU32, NonZeroU32, NonZeroU32), NonZeroU32>,
+}
+
+impl Cuts {
+    fn cut<T: Float>(
+        &mut self,
+        depths: &[T],
+        [positions, uvs, normals]: [&mut Vec<[T; 3]>; 3],
+        a: &VertexIndices,
+        b: &VertexIndices,
+    ) -> VertexIndices {
+        // Always interpolate in the same direction, so that both sides of an edge agree exactly
+        let (a, b) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+        let (da, db) = (depths[a.0.get() as usize - 1], depths[b.0.get() as usize - 1]);
+        let t = da / (da - db);
+        let lerp = |attrs: &mut Vec<[T; 3]>, x: NonZeroU32, y: NonZeroU32, normalize: bool| {
+            let (x, y) = (attrs[x.get() as usize - 1], attrs[y.get() as usize - 1]);
+            let mut attr = [0, 1, 2].map(|i| x[i] + (y[i] - x[i]) * t);
+            if normalize {
+                let len = math::dot(attr, attr).sqrt();
+                if len > T::ZERO {
+                    attr = attr.map(|x| x / len);
+                }
+            }
+            attrs.push(attr);
+            attribute_index(attrs)
+        };
+        (
+            *self.positions.entry((a.0, b.0)).or_insert_with(|| lerp(positions, a.0, b.0, false)),
+            a.1.zip(b.1).map(|(ta, tb)| *self.uvs
+                .entry((a.0, b.0, ta, tb))
+                .or_insert_with(|| lerp(uvs, ta, tb, false))),
+            a.2.zip(b.2).map(|(na, nb)| *self.normals
+                .entry((a.0, b.0, na, nb))
+                .or_insert_with(|| lerp(normals, na, nb, true))),
+        )
+    }
+}
+
+/// Returns the (1-based) index of the last attribute.
+fn attribute_index<T>(attrs: &[[T; 3]]) -> NonZeroU32 {
+    u32::try_from(attrs.len())
+        .ok()
+        .and_then(NonZeroU32::new)
+        .expect("Too many attributes for a clipped OBJ")
+}
+
+/// Find the loops of positions that close the boundary edges lying on the plane, wound against those edges.
+fn cap_loops(
+    vertices: &[VertexIndices],
+    polygons: &[VertexRange],
+    on_plane: impl Fn(NonZeroU32) -> bool,
+) -> Vec<Vec<NonZeroU32>> {
+    let edges = polygons
+        .iter()
+        .flat_map(|range| {
+            let poly = &vertices[range.start..range.end];
+            (0..poly.len()).map(move |i| (poly[i].0, poly[(i + 1) % poly.len()].0))
+        })
+        .filter(|(a, b)| a != b)
+        .collect::<HashSet<_>>();
+
+    // Each boundary edge on the plane is closed by the same edge of a cap, reversed
+    let mut next = BTreeMap::<NonZeroU32, Vec<NonZeroU32>>::new();
+    for &(a, b) in &edges {
+        if on_plane(a) && on_plane(b) && !edges.contains(&(b, a)) {
+            next.entry(b).or_default().push(a);
+        }
+    }
+    for ends in next.values_mut() {
+        ends.sort_unstable();
+    }
+
+    let mut loops = Vec::new();
+    while let Some((&first, _)) = next.iter().next() {
+        let mut cap = Vec::new();
+        let mut current = first;
+        loop {
+            cap.push(current);
+            let Some(ends) = next.get_mut(&current) else { break };
+            let end = ends.remove(0);
+            if ends.is_empty() {
+                next.remove(&current);
+            }
+            current = end;
+            if current == first {
+                break;
+            }
+        }
+        // Chains that don't return to where they started are left open
+        if current == first && cap.len() >= 3 {
+            loops.push(cap);
+        }
+    }
+    loops
+}
@@ -0,0 +1,379 @@
+use crate::{Buffers, Float, GenericObj, Vertex};
+use alloc::vec::Vec;
+use core::{borrow::Borrow, cmp::Ordering, fmt, hash::Hash};
+
+/// Options that control how two OBJs are compared by [`GenericObj::structurally_equal`].
+///
+/// Options are configured with a builder-style API. The default options compare exactly as [`PartialEq`] does.
+///
+/// ```
+/// use wavefront::{CompareOptions, Obj};
+///
+/// let a: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+/// let b: Obj = "o tri\nv 0 1 0\nv 0 0 0\nv 1 0 0\nf 2 3 1\n".parse().unwrap();
+/// assert!(a != b);
+/// assert!(a.structurally_equal(&b, &CompareOptions::new().ignore_names(true).ignore_order(true)));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CompareOptions {
+    ignore_names: bool,
+    ignore_order: bool,
+    epsilon: f64,
+}
+
+impl CompareOptions {
+    /// Create a new set of options that compare exactly.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ignore the names of objects and groups (default: `false`).
+    ///
+    /// The way in which polygons are divided into objects and groups must still match.
+    pub fn ignore_names(mut self, ignore_names: bool) -> Self {
+        self.ignore_names = ignore_names;
+        self
+    }
+
+    /// Ignore the order of attributes, vertices and polygons (default: `false`).
+    ///
+    /// Instead of comparing buffers, the triangles of each OBJ are compared as unordered collections, with each
+    /// triangle given by the values of its vertices' attributes, its material, and the object and group that contains
+    /// it. Triangles are compared as rotations, so each keeps its winding order, but polygons are split into triangles
+    /// as [`Polygon::triangles`](crate::Polygon::triangles) does: a quad is equal to the pair of triangles it would be
+    /// split into, but not to a rotation of itself, which is split along its other diagonal.
+    pub fn ignore_order(mut self, ignore_order: bool) -> Self {
+        self.ignore_order = ignore_order;
+        self
+    }
+
+    /// The largest difference between attribute components that are considered equal (default: `0.0`).
+    ///
+    /// When ignoring order, triangles are matched after being sorted by value, so components must be far enough apart
+    /// from unrelated components that both OBJs sort them the same way.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+}
+
+/// Compares the attributes, vertices and polygons of two OBJs, the objects and groups that contain each polygon, and
+/// the material used by each polygon.
+///
+/// Source lines (see [`ParseOptions::track_lines`](crate::ParseOptions::track_lines)) and cached triangles are not
+/// compared. See [`GenericObj::structurally_equal`] for more relaxed comparisons. [`Eq`] is not implemented, since
+/// attributes may be NaN.
+impl<T: PartialEq, N: Eq + Hash> PartialEq for GenericObj<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (&self.buffers, &other.buffers);
+        a.positions == b.positions
+            && a.uvs == b.uvs
+            && a.normals == b.normals
+            && a.vertices == b.vertices
+            && a.polygons == b.polygons
+            && same_materials(a, b)
+            && self.objects == other.objects
+    }
+}
+
+// Materials are compared by name, since the same materials may be numbered differently in each OBJ
+fn same_materials<T>(a: &Buffers<T>, b: &Buffers<T>) -> bool {
+    a.polygons.len() == b.polygons.len() && (0..a.polygons.len()).all(|i| a.material(i) == b.material(i))
+}
+
+// The values of the attributes of a vertex, in a form that can be sorted
+type VertexValues = [Option<[f64; 3]>; 3];
+
+fn values<T: Float>(vertex: Vertex<'_, T>) -> VertexValues {
+    let f64s = |x: [T; 3]| x.map(Float::to_f64);
+    [Some(f64s(vertex.position())), vertex.uv().map(f64s), vertex.normal().map(f64s)]
+}
+
+fn cmp_values(a: &VertexValues, b: &VertexValues) -> Ordering {
+    let cmp = |a: &Option<[f64; 3]>, b: &Option<[f64; 3]>| match (a, b) {
+        (Some(a), Some(b)) => (0..3).map(|i| a[i].total_cmp(&b[i])).find(|o| o.is_ne()).unwrap_or(Ordering::Equal),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    };
+    (0..3).map(|i| cmp(&a[i], &b[i])).find(|o| o.is_ne()).unwrap_or(Ordering::Equal)
+}
+
+// Returns `true` if every component of two attributes differs by no more than `epsilon`
+fn close(a: &Option<[f64; 3]>, b: &Option<[f64; 3]>, epsilon: f64) -> bool {
+    match (a, b) {
+        // Exactly equal components (including infinities) are equal regardless of the epsilon
+        (Some(a), Some(b)) => (0..3).all(|i| a[i] == b[i] || Float::abs(a[i] - b[i]) <= epsilon),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
+    /// Returns `true` if this OBJ is equal to another, according to the given [`CompareOptions`].
+    ///
+    /// With the default options this is the same as `==`. The options allow OBJs that describe the same geometry in
+    /// different ways (such as files exported by different tools) to be compared, which is useful in tests.
+    pub fn structurally_equal(&self, other: &Self, options: &CompareOptions) -> bool {
+        if options.ignore_order {
+            let sorted = |obj| sorted_triangles(obj, options.ignore_names, true);
+            let (a, b) = (sorted(self), sorted(other));
+            return a.len() == b.len() && a.iter().zip(&b).all(|(a, b)| a.names == b.names && first_difference(a, b, options.epsilon).is_none());
+        }
+
+        let (a, b) = (&self.buffers, &other.buffers);
+        let f64s = |x: &[T; 3]| Some(x.map(Float::to_f64));
+        let attributes_close = |a: &[[T; 3]], b: &[[T; 3]]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| close(&f64s(a), &f64s(b), options.epsilon))
+        };
+        if !(attributes_close(&a.positions, &b.positions)
+            && attributes_close(&a.uvs, &b.uvs)
+            && attributes_close(&a.normals, &b.normals)
+            && a.vertices == b.vertices
+            && a.polygons == b.polygons
+            && same_materials(a, b))
+        {
+            return false;
+        }
+
+        if options.ignore_names {
+            // Compare the way in which polygons are divided into objects and groups
+            partition(self) == partition(other)
+        } else {
+            self.objects == other.objects
+        }
+    }
+
+    /// Compare the triangles of this OBJ with those of another, returning the first [`Mismatch`] between them if they
+    /// aren't equal to within `epsilon`.
+    ///
+    /// This compares in the same way as [`GenericObj::structurally_equal`] when ignoring names and order, but
+    /// describes the difference it finds. Triangles are paired up after being sorted by value, so the mismatch is
+    /// between the first pair that differs in that order. See also [`assert_obj_approx_eq`](crate::assert_obj_approx_eq).
+    ///
+    /// ```
+    /// use wavefront::{Mismatch, MismatchedAttribute, Obj, ParseOptions};
+    ///
+    /// let options = ParseOptions::new().track_lines(true);
+    /// let a = Obj::from_str_with("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n", options.clone()).unwrap();
+    /// let b = Obj::from_str_with("v 0 0 0\nv 1 0 0\nv 0 1.1 0\n\nf 1 2 3\n", options).unwrap();
+    /// assert!(a.approx_diff(&b, 0.2).is_none());
+    ///
+    /// let mismatch = a.approx_diff(&b, 0.01).unwrap();
+    /// match &mismatch {
+    ///     Mismatch::Triangle { left, right, vertex, attribute, .. } => {
+    ///         assert_eq!(*attribute, MismatchedAttribute::Position);
+    ///         assert_eq!(left.positions[*vertex], [0.0, 1.0, 0.0]);
+    ///         assert_eq!((left.source_line, right.source_line), (Some(4), Some(5)));
+    ///     },
+    ///     _ => panic!(),
+    /// }
+    /// assert_eq!(
+    ///     mismatch.to_string(),
+    ///     "position of vertex 2 differs between triangle 0 (line 4) and triangle 0 (line 5): [0, 1, 0] and [0, 1.1, 0]",
+    /// );
+    /// ```
+    pub fn approx_diff(&self, other: &Self, epsilon: f64) -> Option<Mismatch<T>> {
+        let (a, b) = (sorted_triangles(self, true, false), sorted_triangles(other, true, false));
+        if a.len() != b.len() {
+            return Some(Mismatch::TriangleCount { left: a.len(), right: b.len() });
+        }
+        a.iter().zip(&b).find_map(|(a, b)| {
+            let (vertex, attribute) = first_difference(a, b, epsilon)?;
+            Some(Mismatch::Triangle { left: a.into(), right: b.into(), vertex, attribute })
+        })
+    }
+}
+
+// A triangle of an OBJ, with its vertices rotated such that it starts with its smallest
+struct SortedTriangle<'a, T> {
+    // The names of the object, group and material of the triangle
+    names: (&'a str, &'a str, Option<&'a str>),
+    values: [VertexValues; 3],
+    vertices: [Vertex<'a, T>; 3],
+    // The index of the triangle in `GenericObj::triangles_with_context`
+    index: usize,
+    source_line: Option<usize>,
+}
+
+// The triangles of an OBJ, sorted by the names of the object and group that contain each (and optionally the material
+// it uses) and then by value
+fn sorted_triangles<T: Float, N: Borrow<str> + Eq + Hash>(
+    obj: &GenericObj<T, N>,
+    ignore_names: bool,
+    materials: bool,
+) -> Vec<SortedTriangle<'_, T>> {
+    let mut triangles = obj
+        .polygons_with_context()
+        .flat_map(|(object, group, poly)| poly.triangles().map(move |tri| (object, group, poly, tri)))
+        .enumerate()
+        .map(|(index, (object, group, poly, mut vertices))| {
+            // Rotate the triangle such that it starts with its smallest vertex, keeping its winding order
+            let unrotated = vertices.map(values);
+            let first = (0..3).min_by(|a, b| cmp_values(&unrotated[*a], &unrotated[*b])).unwrap();
+            vertices.rotate_left(first);
+            let (object, group) = if ignore_names { ("", "") } else { (object, group) };
+            SortedTriangle {
+                names: (object, group, if materials { poly.material() } else { None }),
+                values: vertices.map(values),
+                vertices,
+                index,
+                source_line: poly.source_line(),
+            }
+        })
+        .collect::<Vec<_>>();
+    triangles.sort_unstable_by(|a, b| a.names
+        .cmp(&b.names)
+        .then_with(|| (0..3).map(|i| cmp_values(&a.values[i], &b.values[i])).find(|o| o.is_ne()).unwrap_or(Ordering::Equal)));
+    triangles
+}
+
+// The first vertex and attribute that differ between two triangles by more than `epsilon`
+fn first_difference<T>(a: &SortedTriangle<'_, T>, b: &SortedTriangle<'_, T>, epsilon: f64) -> Option<(usize, MismatchedAttribute)> {
+    let attributes = [MismatchedAttribute::Position, MismatchedAttribute::Uv, MismatchedAttribute::Normal];
+    (0..3).find_map(|vertex| (0..3)
+        .find(|i| !close(&a.values[vertex][*i], &b.values[vertex][*i], epsilon))
+        .map(|i| (vertex, attributes[i])))
+}
+
+/// A difference between the triangles of two OBJs, found by [`GenericObj::approx_diff`].
+///
+/// The [`Display`](fmt::Display) implementation describes the difference in a single line, suitable for a test failure.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Mismatch<T = f32> {
+    /// The OBJs have different numbers of triangles.
+    TriangleCount {
+        /// The number of triangles in the first OBJ.
+        left: usize,
+        /// The number of triangles in the second OBJ.
+        right: usize,
+    },
+    /// An attribute of a vertex differs between a pair of triangles.
+    Triangle {
+        /// The triangle of the first OBJ.
+        left: MismatchedTriangle<T>,
+        /// The triangle of the second OBJ.
+        right: MismatchedTriangle<T>,
+        /// The vertex of the triangles that differs (`0`, `1` or `2`, in the order given by [`MismatchedTriangle`]).
+        vertex: usize,
+        /// The attribute of the vertex that differs.
+        attribute: MismatchedAttribute,
+    },
+}
+
+/// A vertex attribute that differs between two triangles (see [`Mismatch::Triangle`]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MismatchedAttribute {
+    /// The vertex position.
+    Position,
+    /// The vertex texture coordinate, which may be missing from one of the vertices.
+    Uv,
+    /// The vertex normal, which may be missing from one of the vertices.
+    Normal,
+}
+
+/// A triangle involved in a [`Mismatch`].
+///
+/// Vertices are rotated (keeping their winding order) such that the triangle starts with its smallest vertex, so they
+/// may not be in the order in which the polygon lists them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MismatchedTriangle<T = f32> {
+    /// The index of the triangle in the order produced by [`GenericObj::triangles_with_context`].
+    pub index: usize,
+    /// The line on which the triangle's polygon is defined, if tracked (see
+    /// [`ParseOptions::track_lines`](crate::ParseOptions::track_lines)).
+    pub source_line: Option<usize>,
+    /// The positions of the triangle's vertices.
+    pub positions: [[T; 3]; 3],
+    /// The texture coordinates of the triangle's vertices, if any.
+    pub uvs: [Option<[T; 3]>; 3],
+    /// The normals of the triangle's vertices, if any.
+    pub normals: [Option<[T; 3]>; 3],
+}
+
+impl<'a, T: Float> From<&SortedTriangle<'a, T>> for MismatchedTriangle<T> {
+    fn from(tri: &SortedTriangle<'a, T>) -> Self {
+        Self {
+            index: tri.index,
+            source_line: tri.source_line,
+            positions: tri.vertices.map(|v| v.position()),
+            uvs: tri.vertices.map(|v| v.uv()),
+            normals: tri.vertices.map(|v| v.normal()),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for MismatchedTriangle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "triangle {}", self.index)?;
+        match self.source_line {
+            Some(line) => write!(f, " (line {})", line),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Mismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Value<'a, T>(Option<&'a [T; 3]>);
+        impl<'a, T: fmt::Display> fmt::Display for Value<'a, T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                match self.0 {
+                    Some([x, y, z]) => write!(f, "[{}, {}, {}]", x, y, z),
+                    None => write!(f, "none"),
+                }
+            }
+        }
+
+        match self {
+            Mismatch::TriangleCount { left, right } => {
+                write!(f, "the OBJs have different numbers of triangles ({} and {})", left, right)
+            },
+            Mismatch::Triangle { left, right, vertex, attribute } => {
+                let (name, a, b) = match attribute {
+                    MismatchedAttribute::Position => ("position", Some(&left.positions[*vertex]), Some(&right.positions[*vertex])),
+                    MismatchedAttribute::Uv => ("texture coordinate", left.uvs[*vertex].as_ref(), right.uvs[*vertex].as_ref()),
+                    MismatchedAttribute::Normal => ("normal", left.normals[*vertex].as_ref(), right.normals[*vertex].as_ref()),
+                };
+                write!(
+                    f,
+                    "{} of vertex {} differs between {} and {}: {} and {}",
+                    name, vertex, left, right, Value(a), Value(b),
+                )
+            },
+        }
+    }
+}
+
+/// Assert that two OBJs describe the same triangles to within an epsilon, regardless of names and order.
+///
+/// On failure, this panics with a description of the first [`Mismatch`] found by [`GenericObj::approx_diff`].
+///
+/// ```
+/// use wavefront::{assert_obj_approx_eq, Obj};
+///
+/// let a: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+/// let b: Obj = "v 0 1 0\nv 0 0 0\nv 1 0.000001 0\nf 2 3 1\n".parse().unwrap();
+/// assert_obj_approx_eq!(a, b, 1e-5);
+/// ```
+#[macro_export]
+macro_rules! assert_obj_approx_eq {
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {
+        if let Some(mismatch) = $left.approx_diff(&$right, $epsilon) {
+            panic!("assertion failed: OBJs are not approximately equal: {}", mismatch);
+        }
+    };
+}
+
+// The polygons of each group of each object, without their names and in a canonical order
+fn partition<T, N>(obj: &GenericObj<T, N>) -> Vec<Vec<&Vec<usize>>> {
+    let mut objects = obj
+        .objects
+        .values()
+        .map(|groups| {
+            let mut groups = groups.values().collect::<Vec<_>>();
+            groups.sort_unstable();
+            groups
+        })
+        .collect::<Vec<_>>();
+    objects.sort_unstable();
+    objects
+}
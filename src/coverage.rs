@@ -0,0 +1,78 @@
+use crate::{Float, GenericObj, Group, Object};
+use core::{borrow::Borrow, hash::Hash};
+
+/// How many of the vertices of a model have a particular attribute.
+///
+/// See [`GenericObj::uv_coverage`] and [`GenericObj::normal_coverage`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Coverage {
+    /// No vertices have the attribute, or there are no vertices.
+    None,
+    /// Some, but not all, vertices have the attribute.
+    Partial,
+    /// Every vertex has the attribute.
+    Full,
+}
+
+impl Coverage {
+    fn of(has_attribute: impl Iterator<Item=bool>) -> Self {
+        let (mut any, mut all) = (false, true);
+        for has in has_attribute {
+            any |= has;
+            all &= has;
+            if any && !all {
+                return Coverage::Partial;
+            }
+        }
+        if any { Coverage::Full } else { Coverage::None }
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
+    /// Returns how many of the vertices of this [`Obj`](crate::Obj)'s polygons have a texture coordinate.
+    ///
+    /// Only the vertices of polygons are considered, so texture coordinates that no polygon refers to don't count.
+    /// This is a single pass over the vertices of the OBJ.
+    pub fn uv_coverage(&self) -> Coverage {
+        Coverage::of(self.buffers.vertices.iter().map(|(_, uv, _)| uv.is_some()))
+    }
+
+    /// Returns how many of the vertices of this [`Obj`](crate::Obj)'s polygons have a normal.
+    ///
+    /// See [`GenericObj::uv_coverage`] for more information.
+    pub fn normal_coverage(&self) -> Coverage {
+        Coverage::of(self.buffers.vertices.iter().map(|(_, _, normal)| normal.is_some()))
+    }
+}
+
+impl<'a, T: Float, N: Borrow<str> + Eq + Hash> Object<'a, T, N> {
+    /// Returns how many of the vertices of this [`Object`]'s polygons have a texture coordinate.
+    ///
+    /// See [`GenericObj::uv_coverage`] for more information.
+    pub fn uv_coverage(&self) -> Coverage {
+        Coverage::of(self.polygons().flat_map(|poly| poly.vertices()).map(|v| v.uv_index().is_some()))
+    }
+
+    /// Returns how many of the vertices of this [`Object`]'s polygons have a normal.
+    ///
+    /// See [`GenericObj::uv_coverage`] for more information.
+    pub fn normal_coverage(&self) -> Coverage {
+        Coverage::of(self.polygons().flat_map(|poly| poly.vertices()).map(|v| v.normal_index().is_some()))
+    }
+}
+
+impl<'a, T: Float> Group<'a, T> {
+    /// Returns how many of the vertices of this [`Group`]'s polygons have a texture coordinate.
+    ///
+    /// See [`GenericObj::uv_coverage`] for more information.
+    pub fn uv_coverage(&self) -> Coverage {
+        Coverage::of(self.polygons().flat_map(|poly| poly.vertices()).map(|v| v.uv_index().is_some()))
+    }
+
+    /// Returns how many of the vertices of this [`Group`]'s polygons have a normal.
+    ///
+    /// See [`GenericObj::uv_coverage`] for more information.
+    pub fn normal_coverage(&self) -> Coverage {
+        Coverage::of(self.polygons().flat_map(|poly| poly.vertices()).map(|v| v.normal_index().is_some()))
+    }
+}
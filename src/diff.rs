@@ -0,0 +1,244 @@
+use crate::{Float, GenericObj};
+use alloc::{string::{String, ToString}, vec::Vec};
+use core::{borrow::Borrow, fmt, hash::Hash};
+
+/// Options that control how two OBJs are compared by [`GenericObj::diff`].
+///
+/// Options are configured with a builder-style API.
+#[derive(Clone, Debug, Default)]
+pub struct DiffOptions {
+    epsilon: f64,
+}
+
+impl DiffOptions {
+    /// Create a new set of options with the default behaviour.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The largest difference between attribute components that are not counted as a change (default: `0.0`).
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+}
+
+/// One of the buffers of an OBJ, as referred to by an [`ObjChange`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DiffBuffer {
+    /// The vertex positions.
+    Positions,
+    /// The texture coordinates.
+    Uvs,
+    /// The normals.
+    Normals,
+    /// The vertices, each a combination of attributes.
+    Vertices,
+    /// The polygons.
+    Polygons,
+}
+
+impl fmt::Display for DiffBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            DiffBuffer::Positions => "positions",
+            DiffBuffer::Uvs => "texture coordinates",
+            DiffBuffer::Normals => "normals",
+            DiffBuffer::Vertices => "vertices",
+            DiffBuffer::Polygons => "polygons",
+        })
+    }
+}
+
+/// A single difference between two OBJs, found by [`GenericObj::diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ObjChange {
+    /// An object with the given name exists only in the second OBJ.
+    ObjectAdded(String),
+    /// An object with the given name exists only in the first OBJ.
+    ObjectRemoved(String),
+    /// A group exists only in the second OBJ, within an object that exists in both.
+    GroupAdded {
+        /// The name of the object containing the group.
+        object: String,
+        /// The name of the group.
+        group: String,
+    },
+    /// A group exists only in the first OBJ, within an object that exists in both.
+    GroupRemoved {
+        /// The name of the object containing the group.
+        object: String,
+        /// The name of the group.
+        group: String,
+    },
+    /// A buffer has a different length.
+    BufferLength {
+        /// The buffer whose length changed.
+        buffer: DiffBuffer,
+        /// The length of the buffer in the first OBJ.
+        before: usize,
+        /// The length of the buffer in the second OBJ.
+        after: usize,
+    },
+    /// Some of the attributes that appear at the same index of both OBJs differ by more than the epsilon.
+    AttributesMoved {
+        /// The buffer containing the attributes: [`DiffBuffer::Positions`], [`DiffBuffer::Uvs`] or
+        /// [`DiffBuffer::Normals`].
+        buffer: DiffBuffer,
+        /// The number of attributes that differ.
+        count: usize,
+    },
+    /// Some of the polygons that appear at the same index of both OBJs use different materials.
+    MaterialsChanged {
+        /// The number of polygons whose material differs.
+        count: usize,
+    },
+    /// An object that exists in both OBJs has a different number of triangles (as produced by
+    /// [`Object::triangles`](crate::Object::triangles)).
+    TriangleCount {
+        /// The name of the object.
+        object: String,
+        /// The number of triangles in the first OBJ.
+        before: usize,
+        /// The number of triangles in the second OBJ.
+        after: usize,
+    },
+}
+
+impl fmt::Display for ObjChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjChange::ObjectAdded(object) => write!(f, "added object '{}'", object),
+            ObjChange::ObjectRemoved(object) => write!(f, "removed object '{}'", object),
+            ObjChange::GroupAdded { object, group } => write!(f, "added group '{}' to object '{}'", group, object),
+            ObjChange::GroupRemoved { object, group } => {
+                write!(f, "removed group '{}' from object '{}'", group, object)
+            },
+            ObjChange::BufferLength { buffer, before, after } => {
+                write!(f, "number of {} changed from {} to {}", buffer, before, after)
+            },
+            ObjChange::AttributesMoved { buffer, count } => write!(f, "{} {} changed", count, buffer),
+            ObjChange::MaterialsChanged { count } => write!(f, "materials of {} polygons changed", count),
+            ObjChange::TriangleCount { object, before, after } => {
+                write!(f, "triangles in object '{}' changed from {} to {}", object, before, after)
+            },
+        }
+    }
+}
+
+/// The differences between two OBJs, produced by [`GenericObj::diff`].
+///
+/// The [`Display`](fmt::Display) implementation lists the changes one per line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjDiff {
+    /// The changes, in a deterministic order: objects, then groups, then buffers, then materials, then triangle counts,
+    /// each sorted by name or buffer.
+    pub changes: Vec<ObjChange>,
+}
+
+impl ObjDiff {
+    /// Returns `true` if no changes were found.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for ObjDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.changes.iter().try_for_each(|change| writeln!(f, "{}", change))
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
+    /// Describe the differences between this OBJ and another, such as a re-exported version of the same model.
+    ///
+    /// Objects are matched by name, as are groups within objects that exist in both. Buffers are compared
+    /// positionally: attributes at the same index are compared to within the epsilon given by the [`DiffOptions`],
+    /// and buffers of different lengths are reported along with the number of differing attributes in their common
+    /// prefix. The materials of polygons at the same index are compared by name in the same way. This takes time
+    /// linear in the size of the OBJs, but means that inserting an attribute near the start of a buffer causes every
+    /// attribute after it to be counted as changed.
+    ///
+    /// ```
+    /// use wavefront::{DiffBuffer, DiffOptions, Obj, ObjChange};
+    ///
+    /// let a: Obj = "o tri\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+    /// let b: Obj = "o tri\nv 0 0 0\nv 1 0 0\nv 0 1.5 0\nf 1 2 3\n".parse().unwrap();
+    /// let diff = a.diff(&b, &DiffOptions::new());
+    /// assert_eq!(diff.changes, vec![ObjChange::AttributesMoved { buffer: DiffBuffer::Positions, count: 1 }]);
+    /// assert_eq!(diff.to_string(), "1 positions changed\n");
+    /// ```
+    pub fn diff(&self, other: &Self, options: &DiffOptions) -> ObjDiff {
+        let mut changes = Vec::new();
+        let (before, after) = (sorted_names(self.objects.keys()), sorted_names(other.objects.keys()));
+        let removed = before.iter().filter(|o| !other.objects.contains_key(**o));
+        changes.extend(removed.map(|o| ObjChange::ObjectRemoved(o.to_string())));
+        let added = after.iter().filter(|o| !self.objects.contains_key(**o));
+        changes.extend(added.map(|o| ObjChange::ObjectAdded(o.to_string())));
+        let common = before.iter().filter(|o| other.objects.contains_key(**o)).copied().collect::<Vec<_>>();
+
+        for object in &common {
+            let (a, b) = (&self.objects[*object], &other.objects[*object]);
+            for group in sorted_names(a.keys()).into_iter().filter(|g| !b.contains_key(*g)) {
+                changes.push(ObjChange::GroupRemoved { object: object.to_string(), group: group.to_string() });
+            }
+            for group in sorted_names(b.keys()).into_iter().filter(|g| !a.contains_key(*g)) {
+                changes.push(ObjChange::GroupAdded { object: object.to_string(), group: group.to_string() });
+            }
+        }
+
+        let (a, b) = (&self.buffers, &other.buffers);
+        for (buffer, before, after) in [
+            (DiffBuffer::Positions, &a.positions, &b.positions),
+            (DiffBuffer::Uvs, &a.uvs, &b.uvs),
+            (DiffBuffer::Normals, &a.normals, &b.normals),
+        ] {
+            if before.len() != after.len() {
+                changes.push(ObjChange::BufferLength { buffer, before: before.len(), after: after.len() });
+            }
+            let count = before
+                .iter()
+                .zip(after.iter())
+                .filter(|(a, b)| (0..3).any(|i| {
+                    // NaNs never compare equal, so count as moved
+                    let distance = Float::abs(a[i].to_f64() - b[i].to_f64());
+                    a[i] != b[i] && (distance > options.epsilon || distance.is_nan())
+                }))
+                .count();
+            if count > 0 {
+                changes.push(ObjChange::AttributesMoved { buffer, count });
+            }
+        }
+        for (buffer, before, after) in [
+            (DiffBuffer::Vertices, a.vertices.len(), b.vertices.len()),
+            (DiffBuffer::Polygons, a.polygons.len(), b.polygons.len()),
+        ] {
+            if before != after {
+                changes.push(ObjChange::BufferLength { buffer, before, after });
+            }
+        }
+        // Materials are compared by name for the polygons in both OBJs, since the same materials may be numbered
+        // differently in each
+        let count = (0..a.polygons.len().min(b.polygons.len())).filter(|i| a.material(*i) != b.material(*i)).count();
+        if count > 0 {
+            changes.push(ObjChange::MaterialsChanged { count });
+        }
+
+        for object in common {
+            let count = |obj: &Self| obj.object(object).map_or(0, |object| object.triangles().count());
+            let (before, after) = (count(self), count(other));
+            if before != after {
+                changes.push(ObjChange::TriangleCount { object: object.to_string(), before, after });
+            }
+        }
+
+        ObjDiff { changes }
+    }
+}
+
+fn sorted_names<'a, N: Borrow<str> + 'a>(names: impl Iterator<Item=&'a N>) -> Vec<&'a str> {
+    let mut names = names.map(Borrow::borrow).collect::<Vec<_>>();
+    names.sort_unstable();
+    names
+}
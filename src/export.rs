@@ -0,0 +1,496 @@
+use crate::{topology::polygon_edges, Float, GenericObj, Object, Group, Triangulation, Vertex, VertexIndices};
+#[cfg(feature = "bytemuck")]
+use crate::Obj;
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
+use hashbrown::HashMap;
+
+/// The attributes of a single vertex, detached from the [`Obj`] it came from.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct VertexData<T = f32> {
+    /// The position of the vertex.
+    pub position: [T; 3],
+    /// The texture coordinate of the vertex, if it has one.
+    pub uv: Option<[T; 3]>,
+    /// The normal of the vertex, if it has one.
+    pub normal: Option<[T; 3]>,
+}
+
+impl<'a, T: Float> From<Vertex<'a, T>> for VertexData<T> {
+    fn from(v: Vertex<'a, T>) -> Self {
+        Self {
+            position: v.position(),
+            uv: v.uv(),
+            normal: v.normal(),
+        }
+    }
+}
+
+/// Determines which vertices are considered to be identical when building an [`IndexedMesh`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VertexKey {
+    /// Vertices are shared only if they refer to the same position, texture coordinate and normal.
+    Attributes,
+    /// Vertices are shared if they refer to the same position, regardless of their other attributes. The texture
+    /// coordinate and normal of the first vertex to use each position are kept.
+    Position,
+}
+
+/// Determines which edges make up a wireframe, see [`GenericObj::wireframe`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WireframeEdges {
+    /// The edges of each polygon, so a quad has 4 edges.
+    Polygons,
+    /// The edges of each triangle produced by [`Polygon::triangles`](crate::Polygon::triangles), so a quad has 5
+    /// edges (including its diagonal).
+    Triangles,
+}
+
+/// A vertex attribute that may appear in an interleaved vertex buffer (see [`Obj::to_interleaved`]).
+///
+/// If a vertex lacks an attribute, a default value of zero for every component is written instead.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Attribute {
+    /// The vertex position, as 3 components.
+    Position,
+    /// The vertex normal, as 3 components.
+    Normal,
+    /// The first 2 components of the vertex texture coordinate.
+    Uv2,
+    /// The vertex texture coordinate, as 3 components.
+    Uv3,
+}
+
+impl Attribute {
+    /// Returns the number of components this attribute occupies.
+    pub fn components(&self) -> usize {
+        match self {
+            Attribute::Uv2 => 2,
+            Attribute::Position | Attribute::Normal | Attribute::Uv3 => 3,
+        }
+    }
+
+    fn write<T: Float>(&self, v: Vertex<'_, T>, buf: &mut Vec<T>) {
+        match self {
+            Attribute::Position => buf.extend_from_slice(&v.position()),
+            Attribute::Normal => buf.extend_from_slice(&v.normal().unwrap_or_default()),
+            Attribute::Uv2 => buf.extend_from_slice(&v.uv().unwrap_or_default()[..2]),
+            Attribute::Uv3 => buf.extend_from_slice(&v.uv().unwrap_or_default()),
+        }
+    }
+}
+
+/// The stride and offsets of each attribute within an interleaved vertex layout, in units of components.
+///
+/// This is useful for describing the layout of the buffer produced by [`Obj::to_interleaved`] to a graphics API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterleavedLayout {
+    /// The number of components between the start of one vertex and the next.
+    pub stride: usize,
+    /// The offset of each attribute from the start of the vertex, in the same order as the layout.
+    pub offsets: Vec<usize>,
+}
+
+impl InterleavedLayout {
+    /// Compute the stride and offsets of a layout.
+    pub fn new(layout: &[Attribute]) -> Self {
+        let mut stride = 0;
+        let offsets = layout
+            .iter()
+            .map(|attr| {
+                stride += attr.components();
+                stride - attr.components()
+            })
+            .collect();
+        Self { stride, offsets }
+    }
+
+    /// Returns the stride of the layout in bytes, assuming `f32` components.
+    pub fn stride_bytes(&self) -> usize {
+        self.stride * core::mem::size_of::<f32>()
+    }
+
+    /// Returns the offset of each attribute in bytes, assuming `f32` components.
+    pub fn offsets_bytes(&self) -> impl ExactSizeIterator<Item=usize> + '_ {
+        self.offsets.iter().map(|offset| offset * core::mem::size_of::<f32>())
+    }
+}
+
+/// The values substituted for missing attributes when building [`FlatBuffers`].
+///
+/// By default, missing normals and texture coordinates are zero.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FlatDefaults<T = f32> {
+    /// The normal used for vertices without one.
+    pub normal: [T; 3],
+    /// The texture coordinate used for vertices without one.
+    pub uv: [T; 2],
+}
+
+/// Separate, non-indexed attribute streams for a set of triangles.
+///
+/// Every stream contains one element per triangle corner, such that elements at the same index in each stream
+/// belong to the same vertex, and every three consecutive vertices form a triangle.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FlatBuffers<T = f32> {
+    /// The position of each vertex.
+    pub positions: Vec<[T; 3]>,
+    /// The normal of each vertex.
+    pub normals: Vec<[T; 3]>,
+    /// The first 2 components of the texture coordinate of each vertex.
+    pub uvs: Vec<[T; 2]>,
+}
+
+impl<T: Float> FlatBuffers<T> {
+    /// Build flat attribute streams from an iterator of triangles (such as that produced by [`Obj::triangles`]),
+    /// substituting the given defaults for missing attributes.
+    pub fn from_triangles<'a, I: IntoIterator<Item=[Vertex<'a, T>; 3]>>(triangles: I, defaults: FlatDefaults<T>) -> Self {
+        let mut buffers = Self::default();
+        for v in triangles.into_iter().flatten() {
+            buffers.positions.push(v.position());
+            buffers.normals.push(v.normal().unwrap_or(defaults.normal));
+            buffers.uvs.push(v.uv().map_or(defaults.uv, |[u, v, _]| [u, v]));
+        }
+        buffers
+    }
+}
+
+/// A triangle mesh made of deduplicated vertices and an index buffer, suitable for uploading to a GPU.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexedMesh<T = f32> {
+    /// The unique vertices of the mesh, in the order they were first encountered.
+    pub vertices: Vec<VertexData<T>>,
+    /// Indices into [`IndexedMesh::vertices`], with every three indices forming a triangle.
+    pub indices: Vec<u32>,
+}
+
+impl<T: Float> IndexedMesh<T> {
+    /// Build an indexed mesh from an iterator of triangles (such as that produced by [`Obj::triangles`]).
+    ///
+    /// Vertices are deduplicated according to their attribute indices (see [`VertexKey`]) and are assigned slots in
+    /// the order they are first encountered, so the output is deterministic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `u32::MAX + 1` unique vertices, which can't be addressed by the index buffer.
+    pub fn from_triangles<'a, I: IntoIterator<Item=[Vertex<'a, T>; 3]>>(triangles: I, key: VertexKey) -> Self {
+        let mut mesh = Self::default();
+        let mut slots = HashMap::<VertexIndices, u32>::new();
+        for triangle in triangles {
+            for v in triangle {
+                let k = match key {
+                    VertexKey::Attributes => v.indices,
+                    VertexKey::Position => (v.indices.0, None, None),
+                };
+                let vertices = &mut mesh.vertices;
+                let slot = *slots.entry(k).or_insert_with(|| {
+                    vertices.push(v.into());
+                    u32::try_from(vertices.len() - 1).expect("Too many unique vertices for a u32 index buffer")
+                });
+                mesh.indices.push(slot);
+            }
+        }
+        mesh
+    }
+
+    /// Returns a copy of the index buffer using 16-bit indices, as required by some (typically mobile) graphics APIs.
+    ///
+    /// An error is returned if the mesh has too many unique vertices to be addressed with 16 bits: indices are never
+    /// silently truncated.
+    pub fn indices_u16(&self) -> Result<Vec<u16>, IndexOverflow> {
+        if self.vertices.len() > IndexOverflow::U16_LIMIT {
+            return Err(IndexOverflow {
+                vertices: self.vertices.len(),
+                limit: IndexOverflow::U16_LIMIT,
+            });
+        }
+        Ok(self.indices.iter().map(|i| *i as u16).collect())
+    }
+
+    /// Split this mesh into several meshes, each with no more than `max_vertices` unique vertices, such that each may
+    /// be drawn with a limited index size (for example, `65536` vertices for 16-bit indices).
+    ///
+    /// Triangles are assigned to meshes greedily and in order, so triangles that are close to one another in the index
+    /// buffer (see [`IndexedMesh::optimize_vertex_cache`]) end up in the same mesh. Vertices used by triangles in
+    /// several meshes are duplicated into each of them. Every triangle appears in exactly one mesh, and a mesh without
+    /// any triangles produces no meshes at all. Indices that don't form a complete triangle are discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_vertices` is less than 3, since a triangle might not fit.
+    pub fn split_by_vertex_limit(&self, max_vertices: usize) -> Vec<Self> {
+        assert!(max_vertices >= 3, "A vertex limit must allow at least 3 vertices");
+        let mut meshes = Vec::new();
+        // The mesh that each vertex was last added to, and its index within it
+        let mut slots = alloc::vec![None; self.vertices.len()];
+        let mut mesh = Self::default();
+        for triangle in self.indices.chunks_exact(3) {
+            let new = triangle
+                .iter()
+                .enumerate()
+                .filter(|(i, v)| !triangle[..*i].contains(v))
+                .filter(|(_, v)| !matches!(slots[**v as usize], Some((m, _)) if m == meshes.len()))
+                .count();
+            if mesh.vertices.len() + new > max_vertices {
+                meshes.push(core::mem::take(&mut mesh));
+            }
+            for v in triangle {
+                let slot = match slots[*v as usize] {
+                    Some((m, slot)) if m == meshes.len() => slot,
+                    _ => {
+                        mesh.vertices.push(self.vertices[*v as usize]);
+                        let slot = (mesh.vertices.len() - 1) as u32;
+                        slots[*v as usize] = Some((meshes.len(), slot));
+                        slot
+                    },
+                };
+                mesh.indices.push(slot);
+            }
+        }
+        if !mesh.indices.is_empty() {
+            meshes.push(mesh);
+        }
+        meshes
+    }
+}
+
+/// An error produced when a mesh has more unique vertices than can be addressed by an index buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IndexOverflow {
+    /// The number of unique vertices in the mesh.
+    pub vertices: usize,
+    /// The maximum number of unique vertices that could have been addressed.
+    pub limit: usize,
+}
+
+impl IndexOverflow {
+    const U16_LIMIT: usize = u16::MAX as usize + 1;
+}
+
+impl fmt::Display for IndexOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Mesh has {} unique vertices, exceeding the limit of {}", self.vertices, self.limit)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IndexOverflow {}
+
+impl<T: Float> GenericObj<T> {
+    /// Build an [`IndexedMesh`] from the triangles in this [`Obj`].
+    ///
+    /// See [`IndexedMesh::from_triangles`] for more information.
+    pub fn to_indexed_mesh(&self) -> IndexedMesh<T> {
+        IndexedMesh::from_triangles(self.triangles(), VertexKey::Attributes)
+    }
+
+    /// Build an [`IndexedMesh`] from the triangles in this [`Obj`], triangulated according to the given options.
+    ///
+    /// See [`Polygon::triangles_with`](crate::Polygon::triangles_with) for more information.
+    pub fn to_indexed_mesh_with(&self, options: Triangulation) -> IndexedMesh<T> {
+        IndexedMesh::from_triangles(self.triangles_with(options), VertexKey::Attributes)
+    }
+
+    /// Write the attributes of every corner of every triangle in this [`Obj`], in triangle order, into a single
+    /// interleaved buffer with the given layout.
+    ///
+    /// The resulting buffer contains `triangles().count() * 3 * stride` components, where the stride is given by
+    /// [`InterleavedLayout::new`]. Missing attributes are written as zero (see [`Attribute`]).
+    pub fn to_interleaved(&self, layout: &[Attribute]) -> Vec<T> {
+        let stride = InterleavedLayout::new(layout).stride;
+        let mut buf = Vec::with_capacity(self.vertices().len() * stride);
+        for v in self.triangles().flatten() {
+            for attr in layout {
+                attr.write(v, &mut buf);
+            }
+        }
+        buf
+    }
+
+    /// Build [`FlatBuffers`] from the triangles in this [`Obj`].
+    ///
+    /// See [`FlatBuffers::from_triangles`] for more information.
+    pub fn to_flat_buffers(&self, defaults: FlatDefaults<T>) -> FlatBuffers<T> {
+        FlatBuffers::from_triangles(self.triangles(), defaults)
+    }
+
+    /// Build [`FlatBuffers`] from the triangles in this [`Obj`], triangulated according to the given options.
+    ///
+    /// See [`Polygon::triangles_with`](crate::Polygon::triangles_with) for more information.
+    pub fn to_flat_buffers_with(&self, defaults: FlatDefaults<T>, options: Triangulation) -> FlatBuffers<T> {
+        FlatBuffers::from_triangles(self.triangles_with(options), defaults)
+    }
+
+    /// Build an [`IndexedMesh`] from the triangles in this [`Obj`], returning its vertices and a 16-bit index buffer.
+    ///
+    /// See [`IndexedMesh::indices_u16`] for more information.
+    pub fn to_indexed_mesh_u16(&self) -> Result<(Vec<VertexData<T>>, Vec<u16>), IndexOverflow> {
+        let mesh = self.to_indexed_mesh();
+        let indices = mesh.indices_u16()?;
+        Ok((mesh.vertices, indices))
+    }
+
+    /// Returns the unique edges of this [`Obj`] as a list of lines between pairs of positions, for debug rendering.
+    ///
+    /// See [`GenericObj::wireframe_indices`] for more information.
+    pub fn wireframe(&self, edges: WireframeEdges) -> Vec<[[T; 3]; 2]> {
+        let positions = self.positions();
+        self
+            .wireframe_indices(edges)
+            .into_iter()
+            .map(|edge| edge.map(|index| positions[index as usize]))
+            .collect()
+    }
+
+    /// Returns the unique edges of this [`Obj`] as pairs of indices into its
+    /// [`positions`](crate::Buffers::positions), suitable for uploading to a GPU as a line list.
+    ///
+    /// Edges are identified by the positions of their end points, so an edge shared by several polygons appears
+    /// once, as do edges between vertices that share a position but differ in other attributes. Edges that collapse
+    /// to a single position are skipped. Each edge is written with its lowest index first, and the list is sorted.
+    pub fn wireframe_indices(&self, edges: WireframeEdges) -> Vec<[u32; 2]> {
+        // The parser guarantees that position indices fit in a `u32`
+        let index = |v: Vertex<'_, T>| v.position_index() as u32;
+        let mut lines = Vec::new();
+        match edges {
+            WireframeEdges::Polygons => lines.extend(self
+                .polygons()
+                .flat_map(polygon_edges)
+                .map(|edge| edge.map(|index| index as u32))),
+            WireframeEdges::Triangles => lines.extend(self
+                .triangles()
+                .flat_map(|[a, b, c]| [[index(a), index(b)], [index(b), index(c)], [index(c), index(a)]])),
+        }
+        lines.retain(|[a, b]| a != b);
+        lines.iter_mut().for_each(|edge| edge.sort_unstable());
+        lines.sort_unstable();
+        lines.dedup();
+        lines
+    }
+}
+
+impl<'a, T: Float> Object<'a, T> {
+    /// Build an [`IndexedMesh`] from the triangles in this [`Object`].
+    ///
+    /// See [`IndexedMesh::from_triangles`] for more information.
+    pub fn to_indexed_mesh(&self) -> IndexedMesh<T> {
+        IndexedMesh::from_triangles(self.triangles(), VertexKey::Attributes)
+    }
+
+    /// Build an [`IndexedMesh`] from the triangles in this [`Object`], triangulated according to the given options.
+    ///
+    /// See [`Polygon::triangles_with`](crate::Polygon::triangles_with) for more information.
+    pub fn to_indexed_mesh_with(&self, options: Triangulation) -> IndexedMesh<T> {
+        IndexedMesh::from_triangles(self.triangles_with(options), VertexKey::Attributes)
+    }
+
+    /// Build [`FlatBuffers`] from the triangles in this [`Object`].
+    ///
+    /// See [`FlatBuffers::from_triangles`] for more information.
+    pub fn to_flat_buffers(&self, defaults: FlatDefaults<T>) -> FlatBuffers<T> {
+        FlatBuffers::from_triangles(self.triangles(), defaults)
+    }
+
+    /// Build [`FlatBuffers`] from the triangles in this [`Object`], triangulated according to the given options.
+    ///
+    /// See [`Polygon::triangles_with`](crate::Polygon::triangles_with) for more information.
+    pub fn to_flat_buffers_with(&self, defaults: FlatDefaults<T>, options: Triangulation) -> FlatBuffers<T> {
+        FlatBuffers::from_triangles(self.triangles_with(options), defaults)
+    }
+}
+
+impl<'a, T: Float> Group<'a, T> {
+    /// Build an [`IndexedMesh`] from the triangles in this [`Group`].
+    ///
+    /// See [`IndexedMesh::from_triangles`] for more information.
+    pub fn to_indexed_mesh(&self) -> IndexedMesh<T> {
+        IndexedMesh::from_triangles(self.triangles(), VertexKey::Attributes)
+    }
+
+    /// Build an [`IndexedMesh`] from the triangles in this [`Group`], triangulated according to the given options.
+    ///
+    /// See [`Polygon::triangles_with`](crate::Polygon::triangles_with) for more information.
+    pub fn to_indexed_mesh_with(&self, options: Triangulation) -> IndexedMesh<T> {
+        IndexedMesh::from_triangles(self.triangles_with(options), VertexKey::Attributes)
+    }
+
+    /// Build [`FlatBuffers`] from the triangles in this [`Group`].
+    ///
+    /// See [`FlatBuffers::from_triangles`] for more information.
+    pub fn to_flat_buffers(&self, defaults: FlatDefaults<T>) -> FlatBuffers<T> {
+        FlatBuffers::from_triangles(self.triangles(), defaults)
+    }
+
+    /// Build [`FlatBuffers`] from the triangles in this [`Group`], triangulated according to the given options.
+    ///
+    /// See [`Polygon::triangles_with`](crate::Polygon::triangles_with) for more information.
+    pub fn to_flat_buffers_with(&self, defaults: FlatDefaults<T>, options: Triangulation) -> FlatBuffers<T> {
+        FlatBuffers::from_triangles(self.triangles_with(options), defaults)
+    }
+}
+
+/// A vertex laid out for direct upload to a GPU, with its texture coordinate truncated to 2 components.
+///
+/// The layout is fixed at 32 bytes: the position at offset 0, the normal at offset 12 and the texture coordinate at
+/// offset 24, with no padding. Missing normals and texture coordinates are zero.
+#[cfg(feature = "bytemuck")]
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PackedVertex {
+    /// The position of the vertex.
+    pub position: [f32; 3],
+    /// The normal of the vertex.
+    pub normal: [f32; 3],
+    /// The first 2 components of the texture coordinate of the vertex.
+    pub uv: [f32; 2],
+}
+
+#[cfg(feature = "bytemuck")]
+impl From<VertexData> for PackedVertex {
+    fn from(data: VertexData) -> Self {
+        let [u, v, _] = data.uv.unwrap_or_default();
+        Self {
+            position: data.position,
+            normal: data.normal.unwrap_or_default(),
+            uv: [u, v],
+        }
+    }
+}
+
+/// A vertex position laid out for direct upload to a GPU, occupying 12 bytes with no padding.
+#[cfg(feature = "bytemuck")]
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PackedPosition {
+    /// The position of the vertex.
+    pub position: [f32; 3],
+}
+
+#[cfg(feature = "bytemuck")]
+impl IndexedMesh {
+    /// Returns the vertices of this mesh as [`PackedVertex`]s, which may be cast to bytes with
+    /// [`bytemuck::cast_slice`].
+    pub fn packed(&self) -> Vec<PackedVertex> {
+        self.vertices.iter().map(|v| (*v).into()).collect()
+    }
+
+    /// Returns the vertex positions of this mesh as [`PackedPosition`]s, which may be cast to bytes with
+    /// [`bytemuck::cast_slice`].
+    pub fn packed_positions(&self) -> Vec<PackedPosition> {
+        self.vertices.iter().map(|v| PackedPosition { position: v.position }).collect()
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl Obj {
+    /// Returns every corner of every triangle in this [`Obj`], in triangle order, as [`PackedVertex`]s.
+    ///
+    /// See [`Obj::to_interleaved`] for a more flexible alternative.
+    pub fn to_packed_vertices(&self) -> Vec<PackedVertex> {
+        self
+            .triangles()
+            .flatten()
+            .map(|v| VertexData::from(v).into())
+            .collect()
+    }
+}
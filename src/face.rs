@@ -0,0 +1,189 @@
+use crate::{Float, GenericObj, Group, MergedGroup, Object, Polygon, Vertex};
+use core::fmt;
+
+/// A polygon with its vertices laid out according to its arity, produced by [`Polygon::as_face`].
+///
+/// Triangles and quads, by far the most common polygons, can be matched on directly, while any other polygon is left
+/// as it is.
+///
+/// ```
+/// use wavefront::{Face, Obj};
+///
+/// let obj = Obj::from_file("tests/cube.obj").unwrap();
+/// for face in obj.faces() {
+///     match face {
+///         Face::Triangle([a, b, c]) => { /* Emit a triangle */ },
+///         Face::Quad([a, b, c, d]) => { /* Emit a quad */ },
+///         Face::Ngon(poly) => { /* Triangulate the polygon */ },
+///     }
+/// }
+/// ```
+pub enum Face<'a, T = f32> {
+    /// A polygon with 3 vertices.
+    Triangle([Vertex<'a, T>; 3]),
+    /// A polygon with 4 vertices.
+    Quad([Vertex<'a, T>; 4]),
+    /// A polygon with any other number of vertices, including those with fewer than 3.
+    Ngon(Polygon<'a, T>),
+}
+
+impl<'a, T> Clone for Face<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T> Copy for Face<'a, T> {}
+
+impl<'a, T: Float> fmt::Debug for Face<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Face::Triangle(vertices) => f.debug_tuple("Triangle").field(vertices).finish(),
+            Face::Quad(vertices) => f.debug_tuple("Quad").field(vertices).finish(),
+            Face::Ngon(poly) => f.debug_tuple("Ngon").field(poly).finish(),
+        }
+    }
+}
+
+/// The number of polygons of each arity in an OBJ, or part of one, produced by [`GenericObj::face_counts`] and
+/// friends.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FaceCounts {
+    /// The number of polygons with 3 vertices.
+    pub triangles: usize,
+    /// The number of polygons with 4 vertices.
+    pub quads: usize,
+    /// The number of polygons with any other number of vertices.
+    pub ngons: usize,
+}
+
+impl FaceCounts {
+    fn new<'a, T: Float + 'a>(polygons: impl Iterator<Item=Polygon<'a, T>>) -> Self {
+        let mut counts = Self::default();
+        for poly in polygons {
+            match poly.vertices().len() {
+                3 => counts.triangles += 1,
+                4 => counts.quads += 1,
+                _ => counts.ngons += 1,
+            }
+        }
+        counts
+    }
+
+    /// Returns the total number of polygons.
+    pub fn total(&self) -> usize {
+        self.triangles + self.quads + self.ngons
+    }
+
+    /// Returns the number of polygons that aren't quads.
+    pub fn non_quads(&self) -> usize {
+        self.triangles + self.ngons
+    }
+
+    /// Returns `true` if more than half of the polygons are quads.
+    pub fn is_quad_dominant(&self) -> bool {
+        self.quads > self.non_quads()
+    }
+}
+
+impl<'a, T: Float> Polygon<'a, T> {
+    /// Returns this polygon as a [`Face`], which allows triangles and quads to be matched on directly.
+    pub fn as_face(&self) -> Face<'a, T> {
+        let v = |i| self.vertex(i).unwrap();
+        match self.vertices().len() {
+            3 => Face::Triangle([v(0), v(1), v(2)]),
+            4 => Face::Quad([v(0), v(1), v(2), v(3)]),
+            _ => Face::Ngon(*self),
+        }
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Returns an iterator over the polygons in this [`Obj`](crate::Obj) as [`Face`]s, in the same order as
+    /// [`GenericObj::polygons`].
+    pub fn faces(&self) -> impl ExactSizeIterator<Item=Face<'_, T>> + Clone + '_ {
+        self.polygons().map(|poly| poly.as_face())
+    }
+
+    /// Returns an iterator over the vertices of every polygon in this [`Obj`](crate::Obj) with exactly 4 vertices,
+    /// skipping all other polygons.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// assert_eq!(obj.quads().count(), 6);
+    /// ```
+    pub fn quads(&self) -> impl Iterator<Item=[Vertex<'_, T>; 4]> + Clone + '_ {
+        self.polygons().filter_map(quad)
+    }
+
+    /// Count the polygons of each arity in this [`Obj`](crate::Obj).
+    pub fn face_counts(&self) -> FaceCounts {
+        FaceCounts::new(self.polygons())
+    }
+
+    /// Returns `true` if more than half of the polygons in this [`Obj`](crate::Obj) are quads.
+    ///
+    /// This is a quick way to decide whether a model is worth treating as a quad mesh, such as for subdivision, or
+    /// should be triangulated instead. See [`GenericObj::face_counts`] for the underlying counts.
+    pub fn is_quad_dominant(&self) -> bool {
+        self.face_counts().is_quad_dominant()
+    }
+}
+
+impl<'a, T: Float> Object<'a, T> {
+    /// Returns an iterator over the polygons in this [`Object`] as [`Face`]s.
+    pub fn faces(&self) -> impl Iterator<Item=Face<'a, T>> + Clone + 'a {
+        self.polygons().map(|poly| poly.as_face())
+    }
+
+    /// Returns an iterator over the vertices of every polygon in this [`Object`] with exactly 4 vertices.
+    pub fn quads(&self) -> impl Iterator<Item=[Vertex<'a, T>; 4]> + Clone + 'a {
+        self.polygons().filter_map(quad)
+    }
+
+    /// Count the polygons of each arity in this [`Object`].
+    pub fn face_counts(&self) -> FaceCounts {
+        FaceCounts::new(self.polygons())
+    }
+}
+
+impl<'a, T: Float> Group<'a, T> {
+    /// Returns an iterator over the polygons in this [`Group`] as [`Face`]s.
+    pub fn faces(&self) -> impl ExactSizeIterator<Item=Face<'a, T>> + Clone + 'a {
+        self.polygons().map(|poly| poly.as_face())
+    }
+
+    /// Returns an iterator over the vertices of every polygon in this [`Group`] with exactly 4 vertices.
+    pub fn quads(&self) -> impl Iterator<Item=[Vertex<'a, T>; 4]> + Clone + 'a {
+        self.polygons().filter_map(quad)
+    }
+
+    /// Count the polygons of each arity in this [`Group`].
+    pub fn face_counts(&self) -> FaceCounts {
+        FaceCounts::new(self.polygons())
+    }
+}
+
+impl<'a, T: Float> MergedGroup<'a, T> {
+    /// Returns an iterator over the polygons in this [`MergedGroup`] as [`Face`]s.
+    pub fn faces(&self) -> impl ExactSizeIterator<Item=Face<'a, T>> + Clone + 'a {
+        self.polygons().map(|poly| poly.as_face())
+    }
+
+    /// Returns an iterator over the vertices of every polygon in this [`MergedGroup`] with exactly 4 vertices.
+    pub fn quads(&self) -> impl Iterator<Item=[Vertex<'a, T>; 4]> + Clone + 'a {
+        self.polygons().filter_map(quad)
+    }
+
+    /// Count the polygons of each arity in this [`MergedGroup`].
+    pub fn face_counts(&self) -> FaceCounts {
+        FaceCounts::new(self.polygons())
+    }
+}
+
+fn quad<T: Float>(poly: Polygon<'_, T>) -> Option<[Vertex<'_, T>; 4]> {
+    match poly.as_face() {
+        Face::Quad(vertices) => Some(vertices),
+        _ => None,
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{parse, Error, Float, GenericObj, ParseOptions};
+use std::{
+    fs::File,
+    io::{self, BufRead},
+    path::PathBuf,
+};
+
+/// How the indices in each file are interpreted by [`GenericObj::from_files`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileIndexing {
+    /// The files are parsed as though they were a single file formed by concatenating them.
+    ///
+    /// Indices in later files refer to attributes declared in earlier ones, so positive indices count from the start
+    /// of the first file and negative indices may reach back across file boundaries. This matches the behaviour of
+    /// tools that split one large OBJ into several parts.
+    Continuous,
+    /// Each file is parsed as a standalone OBJ and the results are then combined with [`GenericObj::merge`].
+    ///
+    /// Indices in each file refer only to attributes declared in that file.
+    Independent,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Read an OBJ from several files, combining them in the order given.
+    ///
+    /// See [`FileIndexing`] for how indices are handled across files. Errors that occur while reading or parsing one
+    /// of the files are produced as [`Error::InFile`], so that both the file and the line can be reported.
+    pub fn from_files<I: IntoIterator<Item = PathBuf>>(paths: I, indexing: FileIndexing) -> Result<Self, Error> {
+        Self::from_files_with(paths, indexing, ParseOptions::default())
+    }
+
+    /// Read an OBJ from several files, combining them in the order given, using the given [`ParseOptions`].
+    ///
+    /// With [`FileIndexing::Continuous`], the files are read as a single stream, so [`ParseOptions::preallocate`]
+    /// has no effect and progress is reported without a known total.
+    pub fn from_files_with<I: IntoIterator<Item = PathBuf>>(
+        paths: I,
+        indexing: FileIndexing,
+        options: ParseOptions,
+    ) -> Result<Self, Error> {
+        match indexing {
+            FileIndexing::Continuous => {
+                let mut parser = parse::Parser::new(&options);
+                let mut reporter = parse::Reporter::new(&options);
+                let mut total_lines = 0;
+                for path in paths {
+                    Self::parse_continuous(&mut parser, &mut reporter, &mut total_lines, &path)
+                        .map_err(|e| Error::InFile(path, Box::new(e)))?;
+                }
+                let obj = parser.finish()?;
+                reporter.finish();
+                Ok(obj)
+            },
+            FileIndexing::Independent => {
+                let mut obj: Option<Self> = None;
+                for path in paths {
+                    let part = Self::from_file_with(&path, options.clone())
+                        .map_err(|e| Error::InFile(path, Box::new(e)))?;
+                    match &mut obj {
+                        Some(obj) => obj.merge(&part),
+                        None => obj = Some(part),
+                    }
+                }
+                let mut obj = match obj {
+                    Some(obj) => obj,
+                    None => return parse::Parser::new(&options).finish(),
+                };
+                if options.cache_triangles {
+                    obj.cache_triangles();
+                }
+                Ok(obj)
+            },
+        }
+    }
+
+    fn parse_continuous(
+        parser: &mut parse::Parser<T>,
+        reporter: &mut parse::Reporter,
+        total_lines: &mut usize,
+        path: &PathBuf,
+    ) -> Result<(), Error> {
+        #[cfg_attr(not(feature = "flate2"), allow(unused_mut))]
+        let mut reader: Box<dyn BufRead> = Box::new(io::BufReader::new(File::open(path)?));
+        #[cfg(feature = "flate2")]
+        if reader.fill_buf()?.starts_with(&crate::gzip::MAGIC) {
+            reader = Box::new(io::BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)));
+        }
+        let mut line = String::new();
+        let mut line_num = 0;
+        while reader.read_line(&mut line)? > 0 {
+            line_num += 1;
+            *total_lines += 1;
+            reporter.statement(line_num, &line)?;
+            parser.parse_line(line_num, &line)?;
+            reporter.line(*total_lines, &line)?;
+            line.clear();
+        }
+        Ok(())
+    }
+}
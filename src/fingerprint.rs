@@ -0,0 +1,104 @@
+use crate::{Float, GenericObj};
+use alloc::vec::Vec;
+use core::{borrow::Borrow, hash::Hash, num::NonZeroU32};
+
+/// The version of the algorithm used by [`GenericObj::fingerprint`].
+///
+/// Fingerprints computed with the same version are always comparable. Should a later release of this crate need to
+/// change the algorithm, this version will be increased, so storing it alongside fingerprints allows stale ones to be
+/// recognised.
+pub const FINGERPRINT_VERSION: u32 = 1;
+
+// 64-bit FNV-1a, which is simple enough to be reproduced exactly by anything that needs to
+struct Fnv(u64);
+
+impl Fnv {
+    fn bytes(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 ^ *b as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn u64(&mut self, x: u64) {
+        self.bytes(&x.to_le_bytes());
+    }
+
+    fn str(&mut self, s: &str) {
+        self.u64(s.len() as u64);
+        self.bytes(s.as_bytes());
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
+    /// Returns a fingerprint of the contents of this OBJ, suitable for use as a cache key.
+    ///
+    /// The fingerprint covers the exact bit patterns of every attribute (as `f64`, such that an [`Obj`](crate::Obj)
+    /// and an [`ObjF64`](crate::ObjF64) with the same values have the same fingerprint, and with negative zero treated
+    /// as zero, which it's equal to), the attribute indices of every vertex, the vertices of every polygon in order,
+    /// the name of the material used by every polygon, and the names of every object and group along with the polygons
+    /// they contain. It doesn't depend on anything that doesn't affect those, such as the formatting of numbers,
+    /// whitespace, comments, source lines, or the order in which objects and groups are stored.
+    ///
+    /// Equal OBJs (see [`PartialEq`]) have equal fingerprints. Fingerprints are 64-bit FNV-1a hashes, so while
+    /// unequal OBJs are very unlikely to share a fingerprint, it isn't impossible, and they mustn't be relied upon
+    /// where an attacker could construct collisions. See [`FINGERPRINT_VERSION`] for stability across versions.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let a: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+    /// let b: Obj = "# A triangle\nv 0.0 0 0\nv 1e0 0 0\nv 0 1 0\nf 1 2 -1\n".parse().unwrap();
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv(0xcbf2_9ce4_8422_2325);
+        hash.u64(FINGERPRINT_VERSION as u64);
+
+        let buffers = &self.buffers;
+        for attributes in [&buffers.positions, &buffers.uvs, &buffers.normals] {
+            hash.u64(attributes.len() as u64);
+            for x in attributes.iter().flatten() {
+                // Adding zero turns negative zeros into positive ones
+                hash.u64((x.to_f64() + 0.0).to_bits());
+            }
+        }
+        hash.u64(buffers.vertices.len() as u64);
+        for (pos, uv, norm) in buffers.vertices.iter() {
+            for idx in [Some(*pos), *uv, *norm] {
+                hash.u64(idx.map_or(0, NonZeroU32::get) as u64);
+            }
+        }
+        hash.u64(buffers.polygons.len() as u64);
+        for range in buffers.polygons.iter() {
+            hash.u64(range.start as u64);
+            hash.u64(range.end as u64);
+        }
+        // Materials are hashed by name, since the same materials may be numbered differently
+        for i in 0..buffers.polygons.len() {
+            match buffers.material(i) {
+                Some(material) => {
+                    hash.u64(1);
+                    hash.str(material);
+                },
+                None => hash.u64(0),
+            }
+        }
+
+        // Objects and groups are hashed in order of name, so that the order of the maps they are stored in is irrelevant
+        let mut objects = self.objects.iter().collect::<Vec<_>>();
+        objects.sort_unstable_by(|(a, _), (b, _)| Borrow::<str>::borrow(*a).cmp(Borrow::<str>::borrow(*b)));
+        hash.u64(objects.len() as u64);
+        for (object, groups) in objects {
+            hash.str(object.borrow());
+            let mut groups = groups.iter().collect::<Vec<_>>();
+            groups.sort_unstable_by(|(a, _), (b, _)| Borrow::<str>::borrow(*a).cmp(Borrow::<str>::borrow(*b)));
+            hash.u64(groups.len() as u64);
+            for (group, polys) in groups {
+                hash.str(group.borrow());
+                hash.u64(polys.len() as u64);
+                polys.iter().for_each(|poly| hash.u64(*poly as u64));
+            }
+        }
+        hash.0
+    }
+}
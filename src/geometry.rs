@@ -0,0 +1,764 @@
+use crate::{math, Float, GenericObj, Index, NameIndex, Polygon, Vertex, VertexIndices, VertexRange};
+use alloc::{format, sync::Arc, vec::Vec};
+use core::{convert::TryFrom, num::NonZeroU32};
+use hashbrown::HashMap;
+
+impl<'a, T: Float> Polygon<'a, T> {
+    /// Returns `true` if no vertex of this polygon lies further than `epsilon` from its best-fit plane.
+    ///
+    /// See [`Polygon::planarity_deviation`] for more information.
+    pub fn is_planar(&self, epsilon: T) -> bool {
+        self.planarity_deviation() <= epsilon
+    }
+
+    /// Returns the greatest distance between any vertex of this polygon and its best-fit plane.
+    ///
+    /// The plane passes through the centroid of the polygon's vertices and is oriented according to the normal given
+    /// by Newell's method. Polygons with fewer than 4 vertices are always planar, as are polygons without a
+    /// well-defined plane (such as those with collinear vertices), both of which produce a deviation of zero.
+    pub fn planarity_deviation(&self) -> T {
+        if self.vertices.len() < 4 {
+            return T::ZERO;
+        }
+        let normal = match self.normal() {
+            Some(normal) => normal,
+            None => return T::ZERO,
+        };
+        let centroid = self.vertex_average().unwrap();
+        self
+            .vertices()
+            .map(|v| math::dot(math::sub(v.position(), centroid), normal).abs())
+            .fold(T::ZERO, |max, d| if d > max { d } else { max })
+    }
+
+    /// Returns the unit normal of this polygon, computed from its positions using Newell's method.
+    ///
+    /// The normal follows the winding order of the polygon: when viewed from the side it points towards, the vertices
+    /// of the polygon are arranged counter-clockwise. `None` is returned for degenerate polygons whose area is
+    /// negligible relative to their size. See [`Polygon::raw_normal`] for more information.
+    pub fn normal(&self) -> Option<[T; 3]> {
+        let normal = self.raw_normal();
+        let len_sq = math::dot(normal, normal);
+        // Compare against the squared edge lengths so that the threshold is independent of the polygon's scale
+        let n = self.vertices.len();
+        let scale = (0..n)
+            .map(|i| {
+                let edge = math::sub(self.vertex((i + 1) % n).unwrap().position(), self.vertex(i).unwrap().position());
+                math::dot(edge, edge)
+            })
+            .sum::<T>();
+        if len_sq.is_finite() && len_sq > T::EPSILON * scale * scale {
+            let len = len_sq.sqrt();
+            Some(normal.map(|x| x / len))
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the normal of each vertex of this polygon, using the polygon's [`Polygon::normal`]
+    /// for vertices that don't have one.
+    ///
+    /// Vertices are considered one at a time, so polygons in which only some vertices have normals keep those
+    /// normals. The polygon's normal is computed at most once, and only if a vertex needs it. Degenerate polygons
+    /// don't have a normal, so a zero vector is used instead.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 1 0 0\nf 1//1 2 3\n".parse().unwrap();
+    /// let normals = obj.polygon(0).unwrap().vertex_normals_or_face().collect::<Vec<_>>();
+    /// assert_eq!(normals, [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 1.0]]);
+    /// ```
+    pub fn vertex_normals_or_face(&self) -> impl ExactSizeIterator<Item=[T; 3]> + Clone + 'a {
+        let face = self.fallback_normal();
+        self.vertices().map(move |v| v.normal().unwrap_or(face))
+    }
+
+    /// Returns an iterator over the triangles of this polygon, as with [`Polygon::triangles`], with the normal of each
+    /// vertex as given by [`Polygon::vertex_normals_or_face`].
+    pub fn triangles_with_normals(&self) -> impl ExactSizeIterator<Item=[(Vertex<'a, T>, [T; 3]); 3]> + Clone + 'a {
+        let face = self.fallback_normal();
+        self.triangles().map(move |tri| tri.map(|v| (v, v.normal().unwrap_or(face))))
+    }
+
+    // The normal to use for vertices without one
+    fn fallback_normal(&self) -> [T; 3] {
+        if self.vertices.iter().all(|(_, _, norm)| norm.is_some()) {
+            [T::ZERO; 3]
+        } else {
+            self.normal().unwrap_or([T::ZERO; 3])
+        }
+    }
+
+    /// Returns the unnormalised normal of this polygon, computed from its positions using Newell's method.
+    ///
+    /// Unlike a cross product of two edges, Newell's method accounts for every vertex of the polygon and so produces a
+    /// robust result for n-gons, including concave ones. For planar polygons, the length of the vector is twice the
+    /// area of the polygon.
+    pub fn raw_normal(&self) -> [T; 3] {
+        self.newell()
+    }
+
+    /// Returns the area of this polygon, computed as half the length of its [`Polygon::raw_normal`].
+    ///
+    /// This is exact for planar polygons (including concave ones). For non-planar polygons it gives the area of the
+    /// polygon projected onto the plane perpendicular to its normal, which approximates the area of any reasonable
+    /// triangulation of it. Degenerate polygons have an area of zero.
+    pub fn area(&self) -> T {
+        area(self.raw_normal())
+    }
+
+    /// Like [`Polygon::area`], but performs all intermediate calculations with `f64` precision.
+    pub fn area_f64(&self) -> f64 {
+        let normal = self.newell::<f64>();
+        math::dot(normal, normal).sqrt() / 2.0
+    }
+
+    /// Returns the area-weighted centroid of this polygon, or `None` if it has no vertices.
+    ///
+    /// The centroid is computed by splitting the polygon into a fan of triangles and weighting each triangle's
+    /// centroid by its signed area, so it is correct for concave polygons too. Degenerate polygons (those without a
+    /// [`Polygon::normal`]) fall back to [`Polygon::vertex_average`].
+    pub fn centroid(&self) -> Option<[T; 3]> {
+        let normal = match self.normal() {
+            Some(normal) => normal,
+            None => return self.vertex_average(),
+        };
+        let three = T::ONE + T::ONE + T::ONE;
+        let a = self.vertex(0)?.position();
+        let mut sum = [T::ZERO; 3];
+        let mut total = T::ZERO;
+        for [_, b, c] in self.triangles() {
+            let (b, c) = (b.position(), c.position());
+            let weight = math::dot(math::cross(math::sub(b, a), math::sub(c, a)), normal);
+            for i in 0..3 {
+                sum[i] += weight * (a[i] + b[i] + c[i]) / three;
+            }
+            total += weight;
+        }
+        Some(sum.map(|x| x / total))
+    }
+
+    /// Returns the average of the positions of this polygon's vertices, or `None` if it has no vertices.
+    ///
+    /// This is cheaper than [`Polygon::centroid`], but is biased towards regions of the polygon with many vertices.
+    pub fn vertex_average(&self) -> Option<[T; 3]> {
+        if self.vertices.is_empty() {
+            return None;
+        }
+        let n = T::from_f64(self.vertices.len() as f64);
+        Some(self
+            .vertices()
+            .fold([T::ZERO; 3], |sum, v| {
+                let p = v.position();
+                [sum[0] + p[0], sum[1] + p[1], sum[2] + p[2]]
+            })
+            .map(|x| x / n))
+    }
+
+    fn newell<U: Float>(&self) -> [U; 3] {
+        newell(self.vertices.len(), |i| self.vertex(i).unwrap().position())
+    }
+}
+
+/// Returns the unnormalised normal of the polygon with the given positions, using Newell's method.
+fn newell<T: Float, U: Float>(n: usize, position: impl Fn(usize) -> [T; 3]) -> [U; 3] {
+    let mut normal = [U::ZERO; 3];
+    for i in 0..n {
+        let a = position(i).map(|x| U::from_f64(x.to_f64()));
+        let b = position((i + 1) % n).map(|x| U::from_f64(x.to_f64()));
+        normal[0] += (a[1] - b[1]) * (a[2] + b[2]);
+        normal[1] += (a[2] - b[2]) * (a[0] + b[0]);
+        normal[2] += (a[0] - b[0]) * (a[1] + b[1]);
+    }
+    normal
+}
+
+/// Returns the area of a polygon given its unnormalised Newell normal.
+fn area<T: Float>(normal: [T; 3]) -> T {
+    math::dot(normal, normal).sqrt() / (T::ONE + T::ONE)
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Returns the total area of the polygons in this [`Obj`](crate::Obj).
+    ///
+    /// This is the sum of [`Polygon::area`] over every polygon.
+    pub fn surface_area(&self) -> T {
+        self.polygons().map(|poly| poly.area()).sum()
+    }
+
+    /// Returns an iterator over the triangles in this [`Obj`](crate::Obj), with a normal for every vertex.
+    ///
+    /// Vertices without a normal use the normal of the polygon they belong to, which is computed once per polygon.
+    /// See [`Polygon::vertex_normals_or_face`] for more information.
+    pub fn triangles_with_normals(&self) -> impl Iterator<Item=[(Vertex<'_, T>, [T; 3]); 3]> + Clone + '_ {
+        self.polygons().flat_map(|poly| poly.triangles_with_normals())
+    }
+
+    /// Returns the volume enclosed by the polygons of this [`Obj`](crate::Obj).
+    ///
+    /// This is the sum of the signed volumes of the tetrahedra formed by the origin and each triangle (see
+    /// [`GenericObj::triangles`]), which is only meaningful for closed meshes. The volume is positive if the polygons
+    /// are wound counter-clockwise when viewed from outside, and negative if they face inwards.
+    pub fn volume(&self) -> T {
+        let sixth = T::ONE / T::from_f32(6.0);
+        self
+            .triangles()
+            .map(|tri| {
+                let [a, b, c] = tri.map(|v| v.position());
+                math::dot(a, math::cross(b, c))
+            })
+            .sum::<T>()
+            * sixth
+    }
+
+    /// Returns the centroid of the surface of this [`Obj`](crate::Obj), or `None` if it has no area.
+    ///
+    /// This is the average of the [`Polygon::centroid`] of every polygon, weighted by its [`Polygon::area`].
+    pub fn surface_centroid(&self) -> Option<[T; 3]> {
+        let mut sum = [T::ZERO; 3];
+        let mut total = T::ZERO;
+        for poly in self.polygons() {
+            let area = poly.area();
+            if let (Some(centroid), true) = (poly.centroid(), area > T::ZERO) {
+                for i in 0..3 {
+                    sum[i] += centroid[i] * area;
+                }
+                total += area;
+            }
+        }
+        if total > T::ZERO {
+            Some(sum.map(|x| x / total))
+        } else {
+            None
+        }
+    }
+}
+
+/// Statistics about the areas of the triangles of an OBJ, produced by [`GenericObj::area_stats`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AreaStats<T = f32> {
+    /// The number of triangles.
+    pub count: usize,
+    /// The area of the smallest triangle.
+    pub min: T,
+    /// The area of the largest triangle.
+    pub max: T,
+    /// The mean area of the triangles.
+    pub mean: T,
+    /// The median area of the triangles (the mean of the two middle areas, if there are an even number of triangles).
+    pub median: T,
+    /// The number of triangles whose area is less than the epsilon given to [`GenericObj::area_stats`].
+    pub below_epsilon: usize,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Returns an iterator over the area of every triangle in this [`Obj`](crate::Obj), each paired with the index
+    /// of the polygon that it belongs to.
+    ///
+    /// Triangles are produced in the same order as [`GenericObj::triangles`], and their areas are calculated in the
+    /// same way as [`Polygon::area`], so the areas of the triangles of a planar, convex polygon sum to its area.
+    pub fn triangle_areas(&self) -> impl Iterator<Item=(T, Index)> + Clone + '_ {
+        self.polygons().flat_map(|poly| {
+            let index = poly.index();
+            poly.triangles().map(move |tri| (area(newell(3, |i| tri[i].position())), index))
+        })
+    }
+
+    /// Returns statistics about the areas of the triangles in this [`Obj`](crate::Obj), or `None` if it has no
+    /// triangles.
+    ///
+    /// Triangles whose area is less than `epsilon` are counted in [`AreaStats::below_epsilon`], which is useful for
+    /// finding slivers that waste texture space or can be removed by decimation. See
+    /// [`GenericObj::triangle_areas`] for more information.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// let stats = obj.area_stats(0.001).unwrap();
+    /// assert_eq!((stats.count, stats.min, stats.max, stats.median), (12, 0.5, 0.5, 0.5));
+    /// assert_eq!(stats.below_epsilon, 0);
+    /// ```
+    pub fn area_stats(&self, epsilon: T) -> Option<AreaStats<T>> {
+        let mut areas = self.triangle_areas().map(|(area, _)| area).collect::<Vec<_>>();
+        areas.sort_unstable_by(|a, b| a.to_f64().total_cmp(&b.to_f64()));
+        let (min, max) = (*areas.first()?, *areas.last()?);
+        let count = areas.len();
+        let median = if count % 2 == 0 {
+            (areas[count / 2 - 1] + areas[count / 2]) / (T::ONE + T::ONE)
+        } else {
+            areas[count / 2]
+        };
+        Some(AreaStats {
+            count,
+            min,
+            max,
+            mean: T::from_f64(areas.iter().map(|area| area.to_f64()).sum::<f64>() / count as f64),
+            median,
+            below_epsilon: areas.iter().filter(|area| **area < epsilon).count(),
+        })
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Remove polygons that don't cover any area, such as slivers and faces that repeat a position, returning the
+    /// number of polygons removed.
+    ///
+    /// First, vertices that repeat the position of the vertex before them (including the last vertex repeating the
+    /// first) are removed, so a quad with a doubled corner becomes a valid triangle rather than being dropped. Then
+    /// polygons with fewer than 3 distinct positions are removed, as are those for which every triangle (as produced
+    /// by [`Polygon::triangles`]) has an area less than `area_epsilon` (see [`GenericObj::triangle_areas`]).
+    ///
+    /// Remaining polygons keep their order, object and group membership and source lines, and attributes are left
+    /// untouched. Groups and objects left without any polygons are removed. Cached triangles are rebuilt.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let mut obj: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nf 1 2 2 3\nf 1 2 1\n".parse().unwrap();
+    /// assert_eq!(obj.remove_degenerate_faces(1e-6), 1);
+    /// assert_eq!(obj.polygons().map(|poly| poly.vertices().len()).collect::<Vec<_>>(), vec![3, 3]);
+    /// ```
+    pub fn remove_degenerate_faces(&mut self, area_epsilon: f32) -> usize {
+        let epsilon = T::from_f32(area_epsilon);
+        let buffers = &self.buffers;
+        let mut vertices = Vec::with_capacity(buffers.vertices.len());
+        let mut polygons = Vec::with_capacity(buffers.polygons.len());
+        let mut new_index = alloc::vec![None; buffers.polygons.len()];
+        let mut positions = Vec::new();
+        for (index, range) in buffers.polygons.iter().enumerate() {
+            let start = vertices.len();
+            for v in &buffers.vertices[range.start..range.end] {
+                if vertices[start..].last().map_or(true, |last: &VertexIndices| last.0 != v.0) {
+                    vertices.push(*v);
+                }
+            }
+            while vertices.len() > start + 1 && vertices[start].0 == vertices[vertices.len() - 1].0 {
+                vertices.pop();
+            }
+
+            let polygon = &vertices[start..];
+            positions.clear();
+            positions.extend(polygon.iter().map(|v| v.0));
+            positions.sort_unstable();
+            positions.dedup();
+            let has_area = (1..polygon.len().saturating_sub(1)).any(|i| {
+                let corner = |v: &VertexIndices| buffers.positions[v.0.get() as usize - 1];
+                area::<T>(newell(3, |j| corner(&polygon[[0, i, i + 1][j]]))) >= epsilon
+            });
+            if positions.len() >= 3 && has_area {
+                new_index[index] = Some(polygons.len());
+                polygons.push(VertexRange { start, end: vertices.len() });
+            } else {
+                vertices.truncate(start);
+            }
+        }
+
+        self.replace_polygons(vertices, polygons, &new_index)
+    }
+
+    /// Replace the polygons of this OBJ, given the new index of each old polygon (or `None` if it was removed),
+    /// returning the number of polygons removed.
+    ///
+    /// Source lines, materials and object and group membership follow the polygons, groups and objects left without any polygons
+    /// are removed, and cached triangles are rebuilt.
+    pub(crate) fn replace_polygons(
+        &mut self,
+        vertices: Vec<VertexIndices>,
+        polygons: Vec<VertexRange>,
+        new_index: &[Option<usize>],
+    ) -> usize {
+        let removed = self.buffers.polygons.len() - polygons.len();
+        self.buffers.remap_polygon_data(new_index.iter().enumerate().filter(|(_, new)| new.is_some()).map(|(old, _)| old));
+        self.buffers.vertices = Arc::new(vertices);
+        self.buffers.polygons = Arc::new(polygons);
+        if removed > 0 {
+            let objects = Arc::make_mut(&mut self.objects);
+            for groups in objects.values_mut() {
+                for polys in groups.values_mut() {
+                    *polys = polys.iter().filter_map(|index| new_index[*index]).collect();
+                }
+                groups.retain(|_, polys| !polys.is_empty());
+            }
+            objects.retain(|_, groups| !groups.is_empty());
+            self.names = Arc::new(NameIndex::new(objects));
+        }
+        if self.triangles.is_some() {
+            self.cache_triangles();
+        }
+        removed
+    }
+}
+
+/// The outcome of snapping attributes to a grid, produced by [`GenericObj::quantize_positions`] and friends.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuantizeReport {
+    /// The number of components whose values were changed.
+    pub changed: usize,
+    /// The number of components that were NaN or infinite, which are left untouched.
+    pub non_finite: usize,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Round each component of every position to the nearest multiple of `step`.
+    ///
+    /// This makes models reproducible across exporters that write coordinates with different precisions, and
+    /// shortens the coordinates of written files. Positions that become identical can then be merged by welding (see
+    /// [`GenericObj::connected_components_welded`]). NaN and infinite components are left untouched and counted in
+    /// the report.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is not positive and finite.
+    pub fn quantize_positions(&mut self, step: T) -> QuantizeReport {
+        quantize(&mut self.buffers.positions, step)
+    }
+
+    /// Round each component of every texture coordinate to the nearest multiple of `step`.
+    ///
+    /// See [`GenericObj::quantize_positions`] for more information.
+    pub fn quantize_uvs(&mut self, step: T) -> QuantizeReport {
+        quantize(&mut self.buffers.uvs, step)
+    }
+
+    /// Round each component of every normal to the nearest multiple of `step`.
+    ///
+    /// Normals are not renormalised afterwards. See [`GenericObj::quantize_positions`] for more information.
+    pub fn quantize_normals(&mut self, step: T) -> QuantizeReport {
+        quantize(&mut self.buffers.normals, step)
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Merge normals that point in (nearly) the same direction, returning the number of normals removed.
+    ///
+    /// Normals are visited in order and each is replaced by the first normal kept so far that lies within
+    /// `angle_epsilon_degrees` of it, or is kept itself if there is none. Only directions are compared, so normals of
+    /// different lengths may be merged. With an epsilon of `0.0`, only exact duplicates are merged and the appearance
+    /// of the model is unchanged.
+    ///
+    /// Normals that are no longer referenced by any vertex (including those that weren't referenced to begin with)
+    /// are removed, and those that remain keep their relative order. Cached triangles remain valid.
+    pub fn merge_normals(&mut self, angle_epsilon_degrees: f32) -> usize {
+        let normals = &self.buffers.normals;
+        let mut used = alloc::vec![false; normals.len()];
+        for (_, _, norm) in self.buffers.vertices.iter() {
+            if let Some(norm) = norm {
+                used[norm.get() as usize - 1] = true;
+            }
+        }
+
+        // Unit normals within the threshold are no further apart than the chord it subtends, so bucketing them into
+        // cells that wide means only neighbouring cells need comparing.
+        let min_cos = math::cos(f64::from(angle_epsilon_degrees.clamp(0.0, 180.0)).to_radians());
+        let cell_size = (2.0 - 2.0 * min_cos).sqrt();
+        let cell = |n: [f64; 3]| n.map(|x| (x / cell_size).floor() as i64);
+
+        let mut exact = HashMap::new();
+        let mut grid = HashMap::<_, Vec<(usize, [f64; 3])>>::new();
+        let mut representative = (0..normals.len()).collect::<Vec<_>>();
+        for (i, n) in normals.iter().enumerate().filter(|(i, _)| used[*i]) {
+            let n = n.map(|x| x.to_f64());
+            let len = math::dot(n, n).sqrt();
+            let dir = if cell_size > 0.0 && len > 0.0 && len.is_finite() { Some(n.map(|x| x / len)) } else { None };
+
+            let rep = exact.get(&n.map(f64::to_bits)).copied().or_else(|| {
+                let dir = dir?;
+                let [cx, cy, cz] = cell(dir);
+                let neighbours = (-1..=1).flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| {
+                    [cx + dx, cy + dy, cz + dz]
+                })));
+                neighbours
+                    .filter_map(|key| grid.get(&key))
+                    .flatten()
+                    .filter(|(_, other)| math::dot(dir, *other) >= min_cos)
+                    .map(|(rep, _)| *rep)
+                    .min()
+            });
+            match rep {
+                Some(rep) => representative[i] = rep,
+                None => if let Some(dir) = dir {
+                    grid.entry(cell(dir)).or_default().push((i, dir));
+                },
+            }
+            exact.entry(n.map(f64::to_bits)).or_insert(representative[i]);
+        }
+
+        let mut new_index = alloc::vec![None; normals.len()];
+        let mut merged = Vec::new();
+        for (i, n) in normals.iter().enumerate() {
+            if used[i] && representative[i] == i {
+                merged.push(*n);
+                new_index[i] = NonZeroU32::new(merged.len() as u32);
+            }
+        }
+        for (_, _, norm) in Arc::make_mut(&mut self.buffers.vertices).iter_mut() {
+            if let Some(n) = norm {
+                *n = new_index[representative[n.get() as usize - 1]].unwrap();
+            }
+        }
+
+        let removed = normals.len() - merged.len();
+        self.buffers.normals = Arc::new(merged);
+        removed
+    }
+}
+
+fn quantize<T: Float>(attrs: &mut Arc<Vec<[T; 3]>>, step: T) -> QuantizeReport {
+    assert!(step > T::ZERO && step.is_finite(), "Quantization step must be positive and finite");
+    let mut report = QuantizeReport::default();
+    for x in Arc::make_mut(attrs).iter_mut().flatten() {
+        if !x.is_finite() {
+            report.non_finite += 1;
+            continue;
+        }
+        let rounded = (*x / step).round() * step;
+        if rounded != *x {
+            report.changed += 1;
+            *x = rounded;
+        }
+    }
+    report
+}
+
+/// A plane in 3D space, used by [`GenericObj::mirror`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plane<T = f32> {
+    normal: [T; 3],
+    distance: T,
+}
+
+impl<T: Float> Plane<T> {
+    /// Create the plane containing every point `p` for which `dot(normal, p) == distance`.
+    ///
+    /// The normal does not need to be normalised. For example, `Plane::new([1.0, 0.0, 0.0], 0.0)` is the plane
+    /// `x = 0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the normal has a length of zero.
+    pub fn new(normal: [T; 3], distance: T) -> Self {
+        let len = math::dot(normal, normal).sqrt();
+        assert!(len > T::ZERO, "Plane normal must have a non-zero length");
+        Self { normal: normal.map(|x| x / len), distance: distance / len }
+    }
+
+    /// Create the plane that passes through the given point, with the given normal.
+    ///
+    /// See [`Plane::new`] for more information.
+    pub fn through(point: [T; 3], normal: [T; 3]) -> Self {
+        Self::new(normal, math::dot(normal, point))
+    }
+
+    /// Returns the unit normal of this plane.
+    pub fn normal(&self) -> [T; 3] {
+        self.normal
+    }
+
+    /// Returns the signed distance of the given point from this plane, which is positive on the side that the normal
+    /// points towards.
+    pub fn signed_distance(&self, point: [T; 3]) -> T {
+        math::dot(self.normal, point) - self.distance
+    }
+
+    fn reflect(&self, v: [T; 3], dist: T) -> [T; 3] {
+        let two = T::ONE + T::ONE;
+        [0, 1, 2].map(|i| v[i] - two * dist * self.normal[i])
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Create a copy of this OBJ in which every vertex of each polygon uses that polygon's normal, for faceted
+    /// rendering.
+    ///
+    /// The normals of the OBJ are replaced by one per polygon, as given by [`Polygon::normal`], so vertices are no
+    /// longer shared between polygons with different normals once exported (for example with
+    /// [`GenericObj::to_indexed_mesh`]). Positions and texture coordinates are left alone. The vertices of degenerate
+    /// polygons, which don't have a normal, are left without one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OBJ has more than `u32::MAX` polygons.
+    pub fn flat_shaded(&self) -> Self {
+        let mut normals = Vec::with_capacity(self.buffers.polygons.len());
+        let mut vertices = self.buffers.vertices.to_vec();
+        for (poly, range) in self.polygons().zip(self.buffers.polygons.iter()) {
+            let normal = poly.normal().map(|normal| {
+                normals.push(normal);
+                u32::try_from(normals.len())
+                    .ok()
+                    .and_then(NonZeroU32::new)
+                    .expect("Too many polygons for a flat-shaded OBJ")
+            });
+            vertices[range.start..range.end].iter_mut().for_each(|v| v.2 = normal);
+        }
+
+        let mut obj = self.clone();
+        obj.buffers.normals = Arc::new(normals);
+        obj.buffers.vertices = Arc::new(vertices);
+        obj
+    }
+
+    /// Reflect this OBJ across the given plane.
+    ///
+    /// Positions and normals are reflected, and the winding order of every polygon is reversed so that the polygons
+    /// continue to face outwards. The first vertex of each polygon is kept in place, so cached triangles (see
+    /// [`GenericObj::cache_triangles`]) remain valid.
+    pub fn mirror(&mut self, plane: Plane<T>) {
+        self.map_positions(|p| plane.reflect(p, plane.signed_distance(p)))
+            .map_normals_raw(|n| plane.reflect(n, math::dot(plane.normal, n)))
+            .reverse_winding();
+    }
+
+    /// Append a copy of this OBJ reflected across the given plane, such as to complete a model that was authored as
+    /// one half of a symmetric whole.
+    ///
+    /// `suffix` is appended to the names of the objects and groups of the copy, so that they can be told apart (an
+    /// empty suffix combines them with the originals). If `weld_epsilon` is given, positions within that distance of
+    /// the plane are shared between the two halves instead of being duplicated, joining them along the seam. See
+    /// [`GenericObj::mirror`] and [`GenericObj::merge`] for more information.
+    pub fn mirrored_append(&mut self, plane: Plane<T>, weld_epsilon: Option<T>, suffix: &str) {
+        let mut copy = self.clone();
+        copy.mirror(plane);
+        if !suffix.is_empty() {
+            copy.objects = Arc::new(copy.objects
+                .iter()
+                .map(|(name, groups)| (format!("{}{}", name, suffix), groups
+                    .iter()
+                    .map(|(name, polys)| (format!("{}{}", name, suffix), polys.clone()))
+                    .collect()))
+                .collect());
+            copy.names = Arc::new(NameIndex::new(&copy.objects));
+        }
+
+        let len = self.buffers.positions.len();
+        self.merge(&copy);
+
+        if let Some(epsilon) = weld_epsilon {
+            // Copied positions on the seam are replaced by the originals, and the rest are moved down to fill the gaps
+            let mut remap = Vec::with_capacity(len);
+            let positions = Arc::make_mut(&mut self.buffers.positions);
+            let mut next = len;
+            for i in 0..len {
+                if plane.signed_distance(positions[i]).abs() <= epsilon {
+                    remap.push(i);
+                } else {
+                    positions[next] = positions[len + i];
+                    remap.push(next);
+                    next += 1;
+                }
+            }
+            positions.truncate(next);
+            for (pos, _, _) in Arc::make_mut(&mut self.buffers.vertices).iter_mut() {
+                if let Some(copied) = (pos.get() as usize - 1).checked_sub(len) {
+                    *pos = NonZeroU32::new(remap[copied] as u32 + 1).unwrap();
+                }
+            }
+        }
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Replace every position of this OBJ with the result of the given function, returning `self` for chaining.
+    ///
+    /// This is the building block for transformations such as [`GenericObj::translate`] and [`GenericObj::scale`].
+    /// Polygons refer to positions by index, so they (and any cached triangles) are unaffected. Note that normals are
+    /// left alone: use [`GenericObj::map_normals`] to keep them consistent with anything other than a translation.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// // Convert a Y-up model in centimetres into a Z-up model in metres
+    /// let mut obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// obj.map_positions(|[x, y, z]| [x / 100.0, -z / 100.0, y / 100.0])
+    ///     .map_normals(|[x, y, z]| [x, -z, y]);
+    /// assert!(obj.positions().contains(&[0.01, -0.01, 0.01]));
+    /// ```
+    pub fn map_positions(&mut self, f: impl FnMut([T; 3]) -> [T; 3]) -> &mut Self {
+        map_attributes(&mut self.buffers.positions, f);
+        self
+    }
+
+    /// Replace every texture coordinate of this OBJ with the result of the given function, returning `self` for
+    /// chaining.
+    ///
+    /// See [`GenericObj::map_positions`] for more information.
+    pub fn map_uvs(&mut self, f: impl FnMut([T; 3]) -> [T; 3]) -> &mut Self {
+        map_attributes(&mut self.buffers.uvs, f);
+        self
+    }
+
+    /// Replace every normal of this OBJ with the result of the given function, renormalised, returning `self` for
+    /// chaining.
+    ///
+    /// Results with a length of zero (or that aren't finite) are kept as they are. Use
+    /// [`GenericObj::map_normals_raw`] to keep results exactly as returned by the function.
+    pub fn map_normals(&mut self, mut f: impl FnMut([T; 3]) -> [T; 3]) -> &mut Self {
+        map_attributes(&mut self.buffers.normals, |n| {
+            let n = f(n);
+            let len = math::dot(n, n).sqrt();
+            if len > T::ZERO && len.is_finite() { n.map(|x| x / len) } else { n }
+        });
+        self
+    }
+
+    /// Replace every normal of this OBJ with the result of the given function, without renormalising, returning
+    /// `self` for chaining.
+    ///
+    /// See [`GenericObj::map_normals`] for more information.
+    pub fn map_normals_raw(&mut self, f: impl FnMut([T; 3]) -> [T; 3]) -> &mut Self {
+        map_attributes(&mut self.buffers.normals, f);
+        self
+    }
+
+    /// Move every position of this OBJ by the given offset, returning `self` for chaining.
+    pub fn translate(&mut self, offset: [T; 3]) -> &mut Self {
+        self.map_positions(|p| math::add(p, offset))
+    }
+
+    /// Scale every position of this OBJ about the origin by the given factor along each axis, returning `self` for
+    /// chaining.
+    ///
+    /// Normals are transformed with the inverse of the scale and renormalised, so they stay perpendicular to the
+    /// surface under non-uniform scaling. If an odd number of the factors are negative, the model is turned inside out
+    /// and so the winding order of every polygon is reversed, as with [`GenericObj::mirror`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the factors is zero.
+    pub fn scale(&mut self, factors: [T; 3]) -> &mut Self {
+        assert!(factors.iter().all(|f| *f != T::ZERO), "Scale factors must be non-zero");
+        self.map_positions(|p| [0, 1, 2].map(|i| p[i] * factors[i]))
+            .map_normals(|n| [0, 1, 2].map(|i| n[i] / factors[i]));
+        if factors[0] * factors[1] * factors[2] < T::ZERO {
+            self.reverse_winding();
+        }
+        self
+    }
+
+    /// Flip the vertical (`v`) component of every texture coordinate of this OBJ, replacing it with `1 - v`, and
+    /// returning `self` for chaining.
+    ///
+    /// This converts between the bottom-left texture origin used by OBJ (and OpenGL) and the top-left origin used by
+    /// most other graphics APIs.
+    pub fn flip_uv_v(&mut self) -> &mut Self {
+        self.map_uvs(|[u, v, w]| [u, T::ONE - v, w])
+    }
+
+    // Reverse the winding order of every polygon, keeping the first vertex of each in place so that cached triangles
+    // remain valid
+    fn reverse_winding(&mut self) -> &mut Self {
+        let vertices = Arc::make_mut(&mut self.buffers.vertices);
+        for range in self.buffers.polygons.iter() {
+            vertices[(range.start + 1).min(range.end)..range.end].reverse();
+        }
+        self
+    }
+}
+
+fn map_attributes<T: Float>(attrs: &mut Arc<Vec<[T; 3]>>, mut f: impl FnMut([T; 3]) -> [T; 3]) {
+    for attr in Arc::make_mut(attrs).iter_mut() {
+        *attr = f(*attr);
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{Error, Float, GenericObj, ParseOptions};
+use flate2::bufread::MultiGzDecoder;
+use std::io::{self, BufRead};
+
+/// The bytes that every gzip stream begins with.
+pub(crate) const MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+impl<T: Float> GenericObj<T> {
+    /// Read a gzip-compressed OBJ from a buffered reader (something implementing [`std::io::BufRead`]).
+    ///
+    /// The OBJ is decompressed and parsed incrementally. Files made of several concatenated gzip members are
+    /// supported. Errors encountered while decompressing are produced as [`Error::Io`].
+    pub fn from_gzip_reader<R: BufRead>(reader: R) -> Result<Self, Error> {
+        Self::from_gzip_reader_with(reader, ParseOptions::default())
+    }
+
+    /// Read a gzip-compressed OBJ from a buffered reader (something implementing [`std::io::BufRead`]), using the
+    /// given [`ParseOptions`].
+    ///
+    /// The decompressed OBJ can only be read once, so [`ParseOptions::preallocate`] has no effect.
+    pub fn from_gzip_reader_with<R: BufRead>(reader: R, options: ParseOptions) -> Result<Self, Error> {
+        Self::from_buf_reader_with(io::BufReader::new(MultiGzDecoder::new(reader)), options)
+    }
+}
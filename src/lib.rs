@@ -23,24 +23,119 @@
 extern crate alloc;
 
 use core::{
-    num::NonZeroUsize,
+    borrow::Borrow,
+    convert::TryFrom,
+    hash::Hash,
+    num::NonZeroU32,
     ops::{Deref, DerefMut},
     fmt,
 };
 use alloc::{
+    boxed::Box,
     vec::Vec,
-    string::{String, ToString},
+    string::String,
+    sync::Arc,
 };
 
 #[cfg(feature = "std")]
 use std::{
-    io::{self, Read, Write},
-    path::Path,
+    io::{self, BufRead, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
     fs::File,
     error,
 };
 use hashbrown::HashMap;
 
+#[cfg(feature = "std")]
+mod accumulate;
+#[cfg(feature = "tokio")]
+mod async_reader;
+mod bvh;
+mod builder;
+#[cfg(feature = "cache")]
+mod cache;
+mod clip;
+mod compare;
+mod coverage;
+mod diff;
+mod export;
+mod face;
+#[cfg(feature = "std")]
+mod files;
+mod fingerprint;
+mod geometry;
+#[cfg(feature = "flate2")]
+mod gzip;
+mod material;
+mod math;
+mod morph;
+mod optimize;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod parse;
+mod pull;
+mod query;
+mod rename;
+mod sample;
+mod select;
+#[cfg(feature = "std")]
+mod sequence;
+mod sink;
+mod slice;
+mod smooth;
+#[cfg(feature = "std")]
+mod source;
+mod subdivide;
+mod topology;
+mod triangulate;
+pub mod util;
+mod uv;
+mod validate;
+#[cfg(feature = "std")]
+mod writer;
+
+#[cfg(feature = "std")]
+pub use accumulate::{GenericObjAccumulator, ObjAccumulator};
+pub use builder::ObjBuilder;
+pub use bvh::{Bvh, ClosestHit};
+pub use clip::Side;
+pub use compare::{CompareOptions, Mismatch, MismatchedAttribute, MismatchedTriangle};
+pub use coverage::Coverage;
+pub use diff::{DiffBuffer, DiffOptions, ObjChange, ObjDiff};
+pub use export::{
+    Attribute, FlatBuffers, FlatDefaults, IndexedMesh, IndexOverflow, InterleavedLayout, VertexData, VertexKey,
+    WireframeEdges,
+};
+#[cfg(feature = "bytemuck")]
+pub use export::{PackedPosition, PackedVertex};
+pub use face::{Face, FaceCounts};
+#[cfg(feature = "std")]
+pub use files::FileIndexing;
+pub use fingerprint::FINGERPRINT_VERSION;
+pub use geometry::{AreaStats, Plane, QuantizeReport};
+pub use math::Float;
+pub use morph::TopologyMismatch;
+pub use parse::{LineCounts, ParseOptions, ParseReport, Progress, Warning};
+pub use pull::{FaceTerm, FaceTerms, GenericObjParser, ObjEvent, ObjParser};
+pub use query::{RayHit, SurfacePoint};
+pub use rename::NameCollision;
+pub use sample::SurfaceSample;
+pub use select::Selection;
+#[cfg(feature = "std")]
+pub use sequence::{GenericObjSequence, ObjSequence};
+pub use sink::{parse_into, MeshSink};
+pub use slice::Polyline;
+#[cfg(feature = "std")]
+pub use source::{DirSource, LoadedObj, ObjSource};
+pub use topology::{BoundaryLoop, CreaseEdge, ManifoldReport, MeshAdjacency, OrientReport};
+pub use triangulate::{ProvokingVertex, QuadSplit, Triangulation};
+pub use uv::{UvSeam, UvSeamOptions};
+pub use validate::{NonFinitePolicy, ValidationReport};
+#[cfg(feature = "std")]
+pub use writer::{NameMap, ObjWriter, WriteOptions, WriteOrdering};
+
 /// A number used to index into vertex attribute arrays.
 pub type Index = usize;
 
@@ -59,6 +154,49 @@ pub enum Error {
     ExpectedName(usize),
     /// An invalid index was encountered.
     InvalidIndex(isize),
+    /// The attribute on the given line exceeds the maximum number of positions, texture coordinates or normals that
+    /// an OBJ may contain (`u32::MAX` of each), or the limit set by [`ParseOptions::max_positions`] or one of its
+    /// siblings.
+    TooManyAttributes(usize),
+    /// The attribute on the given line has a NaN or infinite component, which is rejected by
+    /// [`ParseOptions::strict`].
+    NonFiniteAttribute(usize),
+    /// The `g` statement on the given line has a name that isn't valid (see [`util::name_is_valid`]), which is
+    /// rejected by [`ParseOptions::strict`].
+    InvalidGroupName(usize, String),
+    /// The statement on the given line was rejected, with the given message, by the callback given to
+    /// [`ParseOptions::on_unknown`].
+    Rejected(usize, String),
+    /// An object or group has the given name, which isn't valid (see [`util::name_is_valid`]) and so can't be written.
+    InvalidName(String),
+    /// Parsing was abandoned because the flag given to [`ParseOptions::cancel_flag`] was set.
+    Cancelled,
+    /// The given line could not be read, when parsing from an iterator over lines (see
+    /// [`GenericObj::try_from_lines`]).
+    ReadLine(usize, Box<Error>),
+    /// An error occurred while reading the given file, one of several being read by [`GenericObj::from_files`] or
+    /// [`GenericObj::load_from_source`].
+    #[cfg(feature = "std")]
+    InFile(PathBuf, Box<Error>),
+    /// An OBJ doesn't have the same topology as another that it was expected to match, such as the first frame of a
+    /// [`GenericObjSequence`].
+    TopologyMismatch(TopologyMismatch),
+    /// A parameter given to an operation on an OBJ, such as the factor given to [`GenericObj::smooth_laplacian`], is
+    /// out of range. The message describes the valid range.
+    InvalidParameter(&'static str),
+    /// The given [`ParseOptions`] setting isn't supported by the method used to parse, such as
+    /// [`ParseOptions::progress`] when reading asynchronously.
+    UnsupportedOption(&'static str),
+    /// Renaming objects or groups would have given two of them the given name, which isn't allowed by
+    /// [`NameCollision::Error`].
+    NameCollision(String),
+    /// The data given to [`GenericObj::from_cache`] is not a cache, or is corrupt.
+    #[cfg(feature = "cache")]
+    InvalidCache,
+    /// The cache given to [`GenericObj::from_cache`] was written by an incompatible version of this crate, using the
+    /// given version of the cache layout.
+    #[cfg(feature = "cache")]
+    UnsupportedCacheVersion(u32),
 }
 
 #[cfg(feature = "std")]
@@ -77,249 +215,566 @@ impl fmt::Display for Error {
             Error::ExpectedIdx(line) => write!(f, "Expected index on line {}", line),
             Error::ExpectedName(line) => write!(f, "Expected object or group name on line {}", line),
             Error::InvalidIndex(idx) => write!(f, "Invalid index '{}'", idx),
+            Error::TooManyAttributes(line) => write!(f, "Too many vertex attributes of one kind on line {}", line),
+            Error::NonFiniteAttribute(line) => write!(f, "Non-finite attribute on line {}", line),
+            Error::InvalidGroupName(line, name) => write!(f, "Invalid group name '{}' on line {}", name, line),
+            Error::Rejected(line, message) => write!(f, "Statement on line {} was rejected: {}", line, message),
+            Error::InvalidName(name) => write!(f, "Invalid object or group name '{}'", name),
+            Error::Cancelled => write!(f, "Parsing was cancelled"),
+            Error::ReadLine(line, e) => write!(f, "Failed to read line {}: {}", line, e),
+            #[cfg(feature = "std")]
+            Error::InFile(path, e) => write!(f, "{}: {}", path.display(), e),
+            Error::TopologyMismatch(e) => write!(f, "Topology mismatch: {}", e),
+            Error::InvalidParameter(message) => write!(f, "Invalid parameter: {}", message),
+            Error::UnsupportedOption(option) => write!(f, "Unsupported parse option: {}", option),
+            Error::NameCollision(name) => write!(f, "More than one object or group would be named '{}'", name),
+            #[cfg(feature = "cache")]
+            Error::InvalidCache => write!(f, "Invalid or corrupt cache"),
+            #[cfg(feature = "cache")]
+            Error::UnsupportedCacheVersion(version) => write!(
+                f,
+                "Unsupported cache version {} (expected version {})",
+                version,
+                cache::CACHE_VERSION,
+            ),
         }
     }
 }
 
 #[cfg(feature = "std")]
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::ReadLine(_, e) | Error::InFile(_, e) => Some(e),
+            Error::TopologyMismatch(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
-/// A struct representing the contents of a parsed OBJ file.
+/// A struct representing the contents of a parsed OBJ file, storing attributes as `f32`.
+///
+/// See [`GenericObj`] for the full API.
+pub type Obj = GenericObj<f32>;
+
+/// A struct representing the contents of a parsed OBJ file, storing attributes as `f64`.
+///
+/// This is useful for models (such as those produced by geospatial or CAD tools) with coordinates too large or too
+/// precise to be represented faithfully as `f32`. See [`GenericObj`] for the full API.
+pub type ObjF64 = GenericObj<f64>;
+
+/// A struct representing the contents of an OBJ parsed from a string, with object and group names borrowed from it.
+///
+/// See [`GenericObj::from_str_borrowed`] for more information.
+pub type ObjRef<'a, T = f32> = GenericObj<T, &'a str>;
+
+/// A struct representing the contents of a parsed OBJ file, generic over the floating point type used to store
+/// attributes and the type used to store object and group names.
+///
+/// Most users will want to use the [`Obj`] alias, which stores attributes as `f32` and names as [`String`]s.
+///
+/// Cloning is cheap: the contents of an OBJ are shared between its clones and are only copied when one of them is
+/// modified.
 #[derive(Clone)]
-pub struct Obj {
-    buffers: Buffers,
-    objects: HashMap<String, HashMap<String, Vec<VertexRange>>>,
+pub struct GenericObj<T, N = String> {
+    buffers: Buffers<T>,
+    objects: Arc<HashMap<N, HashMap<N, Vec<usize>>>>,
+    // Case-insensitive index of the names in `objects`, which must be rebuilt whenever they change
+    names: Arc<NameIndex<N>>,
+    // Indices into `buffers.vertices`, see `GenericObj::cache_triangles`. Anything that changes the vertices or
+    // polygons of the OBJ must clear this
+    triangles: Option<Arc<Vec<[Index; 3]>>>,
 }
 
-impl Obj {
+impl<T: Float> GenericObj<T> {
     /// Read an OBJ from a file.
+    ///
+    /// With the `flate2` feature enabled, gzip-compressed files are detected and decompressed automatically.
     #[cfg(feature = "std")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        Self::from_reader(io::BufReader::new(File::open(path)?))
+        Self::from_file_with(path, ParseOptions::default())
+    }
+
+    /// Read an OBJ from a file, using the given [`ParseOptions`].
+    #[cfg(feature = "std")]
+    pub fn from_file_with<P: AsRef<Path>>(path: P, options: ParseOptions) -> Result<Self, Error> {
+        #[cfg_attr(not(feature = "flate2"), allow(unused_mut))]
+        let mut reader = io::BufReader::new(File::open(path)?);
+        #[cfg(feature = "flate2")]
+        if reader.fill_buf()?.starts_with(&gzip::MAGIC) {
+            return Self::from_gzip_reader_with(reader, options);
+        }
+        Self::from_seekable_with(reader, options)
     }
 
     /// Read an OBJ from a reader (something implementing [`std::io::Read`]).
+    ///
+    /// The reader is buffered internally. If it already implements [`std::io::BufRead`], prefer
+    /// [`GenericObj::from_buf_reader`].
     #[cfg(feature = "std")]
-    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, Error> {
-        let mut buf = String::new();
-        reader.read_to_string(&mut buf)?;
-        Self::from_lines(buf.lines())
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        Self::from_reader_with(reader, ParseOptions::default())
     }
 
-    /// Read an OBJ from an iterator over its lines.
-    pub fn from_lines<I: Iterator<Item=L>, L: AsRef<str>>(lines: I) -> Result<Self, Error> {
-        let mut positions = Vec::new();
-        let mut uvs = Vec::new();
-        let mut normals = Vec::new();
-        let mut vertices = Vec::new();
-        let mut objects = HashMap::new();
+    /// Read an OBJ from a reader (something implementing [`std::io::Read`]), using the given [`ParseOptions`].
+    ///
+    /// The reader can only be read once, so [`ParseOptions::preallocate`] has no effect. See
+    /// [`GenericObj::from_seekable_with`] for readers that support seeking.
+    #[cfg(feature = "std")]
+    pub fn from_reader_with<R: Read>(reader: R, options: ParseOptions) -> Result<Self, Error> {
+        Self::from_buf_reader_with(io::BufReader::new(reader), options)
+    }
 
-        let mut object = (None, HashMap::new());
+    /// Read an OBJ from a buffered reader (something implementing [`std::io::BufRead`]).
+    ///
+    /// The OBJ is parsed one line at a time without first reading the whole input into memory, so peak memory usage
+    /// stays close to the size of the parsed OBJ.
+    #[cfg(feature = "std")]
+    pub fn from_buf_reader<R: BufRead>(reader: R) -> Result<Self, Error> {
+        Self::from_buf_reader_with(reader, ParseOptions::default())
+    }
 
-        let mut default_group = Vec::new();
-        let mut groups = HashMap::<_, Vec<VertexRange>>::new();
-        let mut selected_groups = Vec::new();
+    /// Read an OBJ from a buffered reader (something implementing [`std::io::BufRead`]), using the given
+    /// [`ParseOptions`].
+    ///
+    /// The reader can only be read once, so [`ParseOptions::preallocate`] has no effect. See
+    /// [`GenericObj::from_seekable_with`] for readers that support seeking.
+    #[cfg(feature = "std")]
+    pub fn from_buf_reader_with<R: BufRead>(reader: R, options: ParseOptions) -> Result<Self, Error> {
+        Self::parse_buf_reader(parse::Parser::new(&options), parse::Reporter::new(&options), reader)
+    }
 
-        for (i, line) in lines.enumerate() {
-            let line = line.as_ref();
-            let line_num = i + 1;
-            let mut terms = line.split_ascii_whitespace();
-            match terms.next() {
-                Some("v") => {
-                    let mut nums = terms.map(|t| t.parse()).take_while(Result::is_ok).map(Result::unwrap);
-                    positions.push([
-                        nums.next().unwrap_or(0.0),
-                        nums.next().unwrap_or(0.0),
-                        nums.next().unwrap_or(0.0),
-                    ]);
-                },
-                Some("vt") => {
-                    let mut nums = terms.map(|t| t.parse()).take_while(Result::is_ok).map(Result::unwrap);
-                    uvs.push([
-                        nums.next().unwrap_or(0.0),
-                        nums.next().unwrap_or(0.0),
-                        nums.next().unwrap_or(0.0),
-                    ]);
-                },
-                Some("vn") => {
-                    let mut nums = terms.map(|t| t.parse()).take_while(Result::is_ok).map(Result::unwrap);
-                    normals.push([
-                        nums.next().unwrap_or(0.0),
-                        nums.next().unwrap_or(0.0),
-                        nums.next().unwrap_or(0.0),
-                    ]);
-                },
-                Some("f") => {
-                    let parse_vert = |lengths: [usize; 3], v: &str| v
-                        .split('/')
-                        .enumerate()
-                        .take(3)
-                        .map(|(i, idx)| match idx.trim() {
-                            "" => Ok(None),
-                            s => s.parse::<isize>()
-                                .map_err(|_| Error::ExpectedIdx(line_num))
-                                .and_then(|idx| Ok(Some(if idx >= 0 {
-                                    NonZeroUsize::new(idx as usize).ok_or_else(|| Error::InvalidIndex(idx))?
-                                } else {
-                                    lengths[i]
-                                        .checked_sub((-idx - 1) as usize)
-                                        .map(|idx| NonZeroUsize::new(idx).unwrap())
-                                        .ok_or_else(|| Error::InvalidIndex(idx))?
-                                }))),
-                        })
-                        .collect::<Result<Vec<_>, Error>>();
-
-                    let lengths = [positions.len(), uvs.len(), normals.len()];
-                    let poly_start = vertices.len();
-
-                    for term in terms {
-                        let v = parse_vert(lengths, term)?;
-
-                        vertices.push((
-                            // Position
-                            v.get(0).copied().flatten().ok_or_else(|| Error::ExpectedIdx(line_num))?,
-                            // Uv
-                            v.get(1).copied().flatten(),
-                            // Normal
-                            v.get(2).copied().flatten(),
-                        ));
-                    }
-
-                    let poly = VertexRange {
-                        start: poly_start,
-                        end: vertices.len(),
-                    };
-
-                    if selected_groups.len() == 0 {
-                        default_group.push(poly);
-                    } else {
-                        selected_groups
-                            .iter()
-                            .for_each(|g| groups.get_mut(g).unwrap().push(poly));
-                    }
-                },
-                Some("g") => {
-                    selected_groups = terms
-                        .filter(|t| util::name_is_valid(t))
-                        .map(|g| {
-                            groups.entry(g.to_string()).or_default();
-                            g.to_string()
-                        })
-                        .collect();
-                },
-                Some("o") => {
-                    // Clean up old object
-                    object.1 = core::mem::take(&mut groups);
-                    if default_group.len() > 0 {
-                        object.1.insert(String::new(), core::mem::take(&mut default_group));
-                    }
-                    selected_groups.clear();
-                    if object.1.len() > 0 {
-                        objects.insert(object.0.unwrap_or_default(), core::mem::take(&mut object.1));
-                    }
-
-                    // Create new object
-                    let name = terms
-                        .next()
-                        .filter(|t| util::name_is_valid(t))
-                        .ok_or_else(|| Error::ExpectedName(line_num))?
-                        .to_string();
-                    object.0 = Some(name);
-                },
-                _ => {},
+    /// Read an OBJ from a buffered reader that supports seeking, using the given [`ParseOptions`].
+    ///
+    /// The OBJ is read from the current position of the reader. If [`ParseOptions::preallocate`] is enabled, the
+    /// reader is scanned once and then returned to this position before parsing.
+    #[cfg(feature = "std")]
+    pub fn from_seekable_with<R: BufRead + Seek>(mut reader: R, options: ParseOptions) -> Result<Self, Error> {
+        let parser = if options.preallocate {
+            let start = reader.stream_position()?;
+            let mut capacities = parse::Capacities::default();
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line)? > 0 {
+                capacities.count_line(&line);
+                line.clear();
             }
+            reader.seek(SeekFrom::Start(start))?;
+            parse::Parser::with_capacities(&options, capacities)
+        } else {
+            parse::Parser::new(&options)
+        };
+        let mut reporter = parse::Reporter::new(&options);
+        if reporter.wants_total_bytes() {
+            let start = reader.stream_position()?;
+            let end = reader.seek(SeekFrom::End(0))?;
+            reader.seek(SeekFrom::Start(start))?;
+            reporter = reporter.total_bytes(end.saturating_sub(start));
         }
+        Self::parse_buf_reader(parser, reporter, reader)
+    }
 
-        // Clean up old object
-        object.1 = core::mem::take(&mut groups);
-        if default_group.len() > 0 {
-            object.1.insert(String::new(), core::mem::take(&mut default_group));
-        }
-        selected_groups.clear();
-        if object.1.len() > 0 {
-            objects.insert(object.0.unwrap_or_default(), core::mem::take(&mut object.1));
+    #[cfg(feature = "std")]
+    fn parse_buf_reader<R: BufRead>(
+        mut parser: parse::Parser<T>,
+        mut reporter: parse::Reporter,
+        mut reader: R,
+    ) -> Result<Self, Error> {
+        let mut line = String::new();
+        let mut line_num = 0;
+        while reader.read_line(&mut line)? > 0 {
+            line_num += 1;
+            reporter.statement(line_num, &line)?;
+            parser.parse_line(line_num, &line)?;
+            reporter.line(line_num, &line)?;
+            line.clear();
         }
+        let obj = parser.finish()?;
+        reporter.finish();
+        Ok(obj)
+    }
 
-        // Validate indices
-        for (pos, uv, norm) in &vertices {
-            if pos.get() - 1 >= positions.len() { return Err(Error::InvalidIndex(pos.get() as isize)); }
-            if let Some(uv) = *uv {
-                if uv.get() - 1 >= uvs.len() { return Err(Error::InvalidIndex(uv.get() as isize)); }
-            }
-            if let Some(norm) = *norm {
-                if norm.get() - 1 >= normals.len() { return Err(Error::InvalidIndex(norm.get() as isize)); }
-            }
+    /// Read an OBJ from a string, using the given [`ParseOptions`].
+    ///
+    /// To parse a string with the default options, use [`str::parse`] or [`GenericObj::from_lines`].
+    pub fn from_str_with(src: &str, options: ParseOptions) -> Result<Self, Error> {
+        let reporter = parse::Reporter::new(&options).total_bytes(src.len() as u64);
+        Self::parse_lines(parse::Parser::for_str(src, &options), reporter, src.lines().map(Ok::<_, Error>))
+    }
+
+    /// Read an OBJ from a string, borrowing the names of objects and groups from it instead of allocating them.
+    ///
+    /// This avoids an allocation for every distinct object and group, which is worthwhile for models that contain
+    /// many of them. Use [`GenericObj::to_owned`] to convert the result to an owned [`Obj`].
+    pub fn from_str_borrowed(src: &str) -> Result<ObjRef<'_, T>, Error> {
+        Self::from_str_borrowed_with(src, ParseOptions::default())
+    }
+
+    /// Read an OBJ from a string, borrowing the names of objects and groups from it, using the given
+    /// [`ParseOptions`].
+    ///
+    /// See [`GenericObj::from_str_borrowed`] for more information.
+    pub fn from_str_borrowed_with(src: &str, options: ParseOptions) -> Result<ObjRef<'_, T>, Error> {
+        let mut parser = parse::Parser::for_str(src, &options);
+        let mut reporter = parse::Reporter::new(&options).total_bytes(src.len() as u64);
+        for (i, line) in src.lines().enumerate() {
+            reporter.statement(i + 1, line)?;
+            parser.parse_line(i + 1, line)?;
+            reporter.line(i + 1, line)?;
         }
+        let obj = parser.finish()?;
+        reporter.finish();
+        Ok(obj)
+    }
 
-        Ok(Self {
-            buffers: Buffers {
-                positions,
-                uvs,
-                normals,
-                vertices,
-            },
-            objects,
-        })
+    /// Read an OBJ from an iterator over its lines.
+    pub fn from_lines<I: Iterator<Item=L>, L: AsRef<str>>(lines: I) -> Result<Self, Error> {
+        Self::from_lines_with(lines, ParseOptions::default())
+    }
+
+    /// Read an OBJ from an iterator over its lines, using the given [`ParseOptions`].
+    ///
+    /// The lines can only be iterated over once, so [`ParseOptions::preallocate`] has no effect. See
+    /// [`GenericObj::from_str_with`] for an alternative.
+    pub fn from_lines_with<I: Iterator<Item=L>, L: AsRef<str>>(lines: I, options: ParseOptions) -> Result<Self, Error> {
+        Self::parse_lines(parse::Parser::new(&options), parse::Reporter::new(&options), lines.map(Ok::<_, Error>))
     }
 
+    /// Read an OBJ from an iterator over its lines, where reading each line may fail.
+    ///
+    /// This accepts iterators such as [`std::io::Lines`]. Parsing stops at the first line that fails to be read,
+    /// producing [`Error::ReadLine`].
+    pub fn try_from_lines<I: Iterator<Item=Result<L, E>>, L: AsRef<str>, E: Into<Error>>(lines: I) -> Result<Self, Error> {
+        Self::try_from_lines_with(lines, ParseOptions::default())
+    }
+
+    /// Read an OBJ from an iterator over its lines, where reading each line may fail, using the given
+    /// [`ParseOptions`].
+    ///
+    /// See [`GenericObj::try_from_lines`] and [`GenericObj::from_lines_with`] for more information.
+    pub fn try_from_lines_with<I: Iterator<Item=Result<L, E>>, L: AsRef<str>, E: Into<Error>>(
+        lines: I,
+        options: ParseOptions,
+    ) -> Result<Self, Error> {
+        Self::parse_lines(parse::Parser::new(&options), parse::Reporter::new(&options), lines)
+    }
+
+    fn parse_lines<I: Iterator<Item=Result<L, E>>, L: AsRef<str>, E: Into<Error>>(
+        mut parser: parse::Parser<T>,
+        mut reporter: parse::Reporter,
+        lines: I,
+    ) -> Result<Self, Error> {
+        for (i, line) in lines.enumerate() {
+            let line = line.map_err(|e| Error::ReadLine(i + 1, Box::new(e.into())))?;
+            reporter.statement(i + 1, line.as_ref())?;
+            parser.parse_line(i + 1, line.as_ref())?;
+            reporter.line(i + 1, line.as_ref())?;
+        }
+        let obj = parser.finish()?;
+        reporter.finish();
+        Ok(obj)
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
     /// Write this [`Obj`] to a writer (something implementing [`std::io::Write`]) in OBJ format.
+    ///
+    /// An [`Error::InvalidName`] is produced, before anything is written, if the name of an object or group isn't
+    /// valid (see [`util::name_is_valid`]), since it would be read back differently. See
+    /// [`WriteOptions::sanitize_names`] to rename them instead.
+    #[cfg(feature = "std")]
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+        self.write_with(writer, WriteOptions::default()).map(drop)
+    }
+
+    /// Write this [`Obj`] to a writer in OBJ format, using the given [`WriteOptions`], returning the objects and
+    /// groups that were renamed.
+    ///
+    /// ```
+    /// use wavefront::{Obj, WriteOptions};
+    ///
+    /// let obj = Obj::from_file("tests/ship.obj").unwrap();
+    /// let names = obj.write_with(Vec::new(), WriteOptions::new().sanitize_names(true)).unwrap();
+    /// assert!(names.is_empty());
+    /// ```
     #[cfg(feature = "std")]
-    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), Error> {
-        Ok(write!(writer, "{}", self)?)
+    pub fn write_with<W: Write>(&self, writer: W, options: WriteOptions) -> Result<NameMap, Error> {
+        let mut objects = self.objects.keys().map(Borrow::borrow).collect::<Vec<&str>>();
+        let mut groups = self.objects.values().flat_map(|groups| groups.keys().map(Borrow::borrow)).collect::<Vec<_>>();
+        objects.sort_unstable();
+        groups.sort_unstable();
+        groups.dedup();
+
+        let names = if options.sanitize_names {
+            NameMap::sanitize(&objects, &groups)
+        } else if let Some(name) = objects.iter().chain(&groups).find(|name| !util::name_is_valid(name)) {
+            return Err(Error::InvalidName(String::from(*name)));
+        } else {
+            NameMap::default()
+        };
+        if names.is_empty() {
+            writer::write_ordered(self, writer, &options)?;
+        } else {
+            let objects = self.objects
+                .iter()
+                .map(|(name, groups)| (String::from(names.object(name.borrow())), groups
+                    .iter()
+                    .map(|(name, polys)| (String::from(names.group(name.borrow())), polys.clone()))
+                    .collect()))
+                .collect();
+            let renamed: GenericObj<T> = GenericObj {
+                buffers: self.buffers.clone(),
+                names: Arc::new(NameIndex::new(&objects)),
+                objects: Arc::new(objects),
+                triangles: None,
+            };
+            writer::write_ordered(&renamed, writer, &options)?;
+        }
+        Ok(names)
     }
 
     /// Write this [`Obj`] to a file in OBJ format.
+    ///
+    /// See [`GenericObj::write`] for more information.
     #[cfg(feature = "std")]
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
-        Ok(self.write(File::create(path)?)?)
+        self.write(File::create(path)?)
+    }
+
+    /// Write this [`Obj`] to a file in OBJ format, using the given [`WriteOptions`], returning the objects and groups
+    /// that were renamed.
+    #[cfg(feature = "std")]
+    pub fn save_with<P: AsRef<Path>>(&self, path: P, options: WriteOptions) -> Result<NameMap, Error> {
+        self.write_with(File::create(path)?, options)
     }
 
     /// Returns a specific [`Object`] by name.
     ///
     /// Note that if a name is not specified in the OBJ file, the name defaults to an empty string.
-    pub fn object(&self, name: &str) -> Option<Object> {
-        self.objects.get(name).map(|groups| Object {
+    pub fn object(&self, name: &str) -> Option<Object<'_, T, N>> {
+        self.objects.get_key_value(name).map(|(name, groups)| Object {
             buffers: &self.buffers,
             groups,
+            names: &self.names.groups[name.borrow()],
         })
     }
 
+    /// Returns a specific [`Object`] by name, ignoring ASCII case.
+    ///
+    /// This is useful when names come from tools that don't preserve case consistently. Lookups use an index built
+    /// alongside the OBJ, so they don't need to scan every name. If several objects have names that differ only by
+    /// case, the one whose name sorts first (by byte value) is returned: see [`GenericObj::case_conflicts`] to detect
+    /// this.
+    pub fn object_ignore_case(&self, name: &str) -> Option<Object<'_, T, N>> {
+        self.object(self.names.objects.get(&fold_case(name))?.borrow())
+    }
+
+    /// Returns an iterator over the object and group names that differ only by ASCII case from another name, and so
+    /// are never returned by [`GenericObj::object_ignore_case`] or [`Object::group_ignore_case`].
+    ///
+    /// Object names are paired with `None`, and group names with the name of the [`Object`] that contains them.
+    pub fn case_conflicts(&self) -> impl Iterator<Item=(Option<&str>, &str)> + '_ {
+        let shadowed = |names: &HashMap<String, N>, name: &N| names[&fold_case(name.borrow())] != *name;
+        let objects = self.objects
+            .keys()
+            .filter(move |name| shadowed(&self.names.objects, name))
+            .map(|name| (None, name.borrow()));
+        let groups = self.objects.iter().flat_map(move |(object, groups)| groups
+            .keys()
+            .filter(move |name| shadowed(&self.names.groups[object.borrow()], name))
+            .map(move |name| (Some(object.borrow()), name.borrow())));
+        objects.chain(groups)
+    }
+
     /// Returns an iterator over the [`Object`]s in this [`Obj`].
-    pub fn objects(&self) -> impl ExactSizeIterator<Item=(&str, Object)> + Clone + '_ {
-        self.objects.iter().map(move |(name, groups)| (name.as_str(), Object {
+    pub fn objects(&self) -> impl ExactSizeIterator<Item=(&str, Object<'_, T, N>)> + Clone + '_ {
+        self.objects.iter().map(move |(name, groups)| (name.borrow(), Object {
             buffers: &self.buffers,
             groups,
+            names: &self.names.groups[name.borrow()],
         }))
     }
 
     /// Returns an iterator over the [`Group`]s in this [`Obj`].
-    pub fn groups(&self) -> impl Iterator<Item=(&String, Group)> + Clone + '_ {
+    pub fn groups(&self) -> impl Iterator<Item=(&N, Group<'_, T>)> + Clone + '_ {
         self
             .objects()
-            .map(|(_, object)| object.groups())
-            .flatten()
+            .flat_map(|(_, object)| object.groups())
+    }
+
+    /// Returns a [`MergedGroup`] containing the polygons of every group with the given name, across all [`Object`]s.
+    ///
+    /// Exporters often use groups independently of objects (for example, to mark polygons sharing a material), so
+    /// groups with the same name in different objects are merged into one. Polygons are produced in the order they
+    /// appear in the file. See [`GenericObj::groups_named`] to access the group in each object separately.
+    pub fn group(&self, name: &str) -> Option<MergedGroup<'_, T>> {
+        let mut polygons = self
+            .objects
+            .values()
+            .filter_map(|groups| groups.get(name))
+            .peekable();
+        polygons.peek()?;
+        let mut polygons = polygons.flatten().copied().collect::<Vec<_>>();
+        polygons.sort_unstable();
+        polygons.dedup();
+        Some(MergedGroup { buffers: &self.buffers, polygons: polygons.into() })
     }
 
-    /// Returns an iterator over the [`Polygon`]s in this [`Obj`].
-    pub fn polygons(&self) -> impl Iterator<Item=Polygon> + Clone + '_ {
+    /// Returns an iterator over every [`Group`] with the given name, paired with the name of the [`Object`] that
+    /// contains it.
+    ///
+    /// See [`GenericObj::group`] for a single group merged across all objects.
+    pub fn groups_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item=(&'a str, Group<'a, T>)> + Clone + 'a {
         self
-            .groups()
-            .map(|(_, group)| group.polygons())
-            .flatten()
+            .objects()
+            .filter_map(move |(object, o)| Some((object, o.group(name)?)))
     }
 
-    /// Returns an iterator over the triangles in this [`Obj`].
+    /// Returns an iterator over the names of the [`Object`]s in this [`Obj`].
     ///
-    /// See [`Polygon::triangles`] for more information.
-    pub fn triangles(&self) -> impl Iterator<Item=[Vertex; 3]> + Clone + '_ {
+    /// This is cheaper than [`GenericObj::objects`] when only the names are needed.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj = Obj::from_file("tests/ship.obj").unwrap();
+    /// let mut names = obj.object_names().collect::<Vec<_>>();
+    /// names.sort();
+    /// assert_eq!(names, ["SketchUp.001_ID10.002", "SketchUp.002_ID16.002", "SketchUp_ID2.002"]);
+    /// assert!(obj.contains_object("SketchUp_ID2.002"));
+    ///
+    /// // The last object only contains lines, so it has no polygons
+    /// assert!(!obj.contains_object("SketchUp.003_ID22.001"));
+    /// assert_eq!(obj.group_names().count(), 3);
+    /// ```
+    pub fn object_names(&self) -> impl ExactSizeIterator<Item=&str> + Clone + '_ {
+        self.objects.keys().map(|name| name.borrow())
+    }
+
+    /// Returns an iterator over the names of the [`Group`]s in this [`Obj`], paired with the names of the
+    /// [`Object`]s that contain them.
+    ///
+    /// A group name appears once for every object that contains a group with that name.
+    pub fn group_names(&self) -> impl Iterator<Item=(&str, &str)> + Clone + '_ {
+        self.objects.iter().flat_map(|(object, groups)| {
+            groups.keys().map(move |group| (object.borrow(), group.borrow()))
+        })
+    }
+
+    /// Returns `true` if this [`Obj`] contains an [`Object`] with the given name.
+    pub fn contains_object(&self, name: &str) -> bool {
+        self.objects.contains_key(name)
+    }
+
+    /// Returns a specific [`Polygon`] by index.
+    ///
+    /// See [`Polygon::index`] for more information.
+    pub fn polygon(&self, index: Index) -> Option<Polygon<'_, T>> {
+        if index < self.buffers.polygons.len() {
+            Some(self.buffers.lookup(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the [`Polygon`]s in this [`Obj`], in the order they appear in the file.
+    ///
+    /// A polygon that belongs to several groups is only produced once, so that every polygon is counted exactly once
+    /// by anything built on this (such as [`GenericObj::triangles`] and [`GenericObj::manifold_report`]). This
+    /// matches [`Object::polygons`]; see [`GenericObj::polygons_with_context`] to visit a polygon once per group.
+    pub fn polygons(&self) -> impl ExactSizeIterator<Item=Polygon<'_, T>> + Clone + '_ {
+        (0..self.buffers.polygons.len()).map(move |index| self.buffers.lookup(index))
+    }
+
+    /// Returns an iterator over the [`Polygon`]s in this [`Obj`], each paired with the names of the [`Object`] and
+    /// [`Group`] that contain it.
+    ///
+    /// Polygons are produced in the order they appear in the file. A polygon that belongs to several groups is
+    /// produced once for each of them, in order of group name.
+    pub fn polygons_with_context(&self) -> impl ExactSizeIterator<Item=(&str, &str, Polygon<'_, T>)> + Clone + '_ {
+        let mut memberships = self.objects
+            .iter()
+            .flat_map(|(object, groups)| groups
+                .iter()
+                .flat_map(move |(group, polys)| polys
+                    .iter()
+                    .map(move |index| (*index, object.borrow(), group.borrow()))))
+            .collect::<Vec<(_, &str, &str)>>();
+        memberships.sort_unstable();
+        memberships
+            .into_iter()
+            .map(move |(index, object, group)| (object, group, self.buffers.lookup(index)))
+    }
+
+    /// Returns an iterator over the triangles in this [`Obj`], each paired with the names of the [`Object`] and
+    /// [`Group`] that contain it.
+    ///
+    /// See [`GenericObj::polygons_with_context`] for more information.
+    pub fn triangles_with_context(&self) -> impl Iterator<Item=(&str, &str, [Vertex<'_, T>; 3])> + Clone + '_ {
         self
-            .polygons()
-            .map(|poly| poly.triangles())
-            .flatten()
+            .polygons_with_context()
+            .flat_map(|(object, group, poly)| poly.triangles().map(move |tri| (object, group, tri)))
+    }
+
+    /// Returns an iterator over the triangles in this [`Obj`].
+    ///
+    /// If the triangles have been cached (see [`GenericObj::cache_triangles`]), this walks the cache instead of
+    /// splitting up each polygon. See [`Polygon::triangles`] for more information.
+    pub fn triangles(&self) -> impl Iterator<Item=[Vertex<'_, T>; 3]> + Clone + '_ {
+        let buffers = &self.buffers;
+        let (cached, polygons) = match &self.triangles {
+            Some(triangles) => (Some(triangles.iter().map(move |triangle| triangle.map(|index| Vertex {
+                buffers,
+                indices: buffers.vertices[index],
+            }))), None),
+            None => (None, Some(self.polygons().flat_map(|poly| poly.triangles()))),
+        };
+        cached.into_iter().flatten().chain(polygons.into_iter().flatten())
+    }
+
+    /// Split every polygon into triangles ahead of time, so that [`GenericObj::triangles`] becomes a walk over a flat
+    /// list of indices.
+    ///
+    /// This is worthwhile when the triangles of a model are iterated over repeatedly. The cache uses
+    /// `3 * size_of::<usize>()` bytes per triangle. See also [`ParseOptions::cache_triangles`].
+    pub fn cache_triangles(&mut self) {
+        let mut triangles = Vec::new();
+        for range in self.buffers.polygons.iter() {
+            triangles.extend((range.start + 1..range.end.saturating_sub(1)).map(|i| [range.start, i, i + 1]));
+        }
+        self.triangles = Some(Arc::new(triangles));
+    }
+
+    /// Returns the cached triangles of this [`Obj`], if [`GenericObj::cache_triangles`] has been called.
+    ///
+    /// Each triangle is given by the indices of its vertices in the order produced by [`GenericObj::vertices`]. The
+    /// triangles appear in the same order as those produced by [`GenericObj::triangles`].
+    pub fn triangle_indices(&self) -> Option<&[[Index; 3]]> {
+        self.triangles.as_deref().map(Vec::as_slice)
+    }
+
+    /// Returns an iterator over the triangles in this [`Obj`], each given by the indices of its positions in
+    /// [`GenericObj::positions`].
+    ///
+    /// Together with [`GenericObj::positions`], this forms an indexed mesh without any copying or hashing, which is
+    /// all that's needed for collision meshes and the like. The triangles appear in the same order as those produced
+    /// by [`GenericObj::triangles`].
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// let positions = obj.positions();
+    /// for [a, b, c] in obj.triangle_position_indices() {
+    ///     let (a, b, c) = (positions[a], positions[b], positions[c]);
+    /// }
+    /// ```
+    pub fn triangle_position_indices(&self) -> impl Iterator<Item=[Index; 3]> + Clone + '_ {
+        self.triangles().map(|tri| tri.map(|v| v.position_index()))
     }
 
     /// Returns an iterator over the vertices in this [`Obj`].
-    pub fn vertices(&self) -> impl ExactSizeIterator<Item=Vertex> + Clone + '_ {
+    pub fn vertices(&self) -> impl ExactSizeIterator<Item=Vertex<'_, T>> + Clone + '_ {
         self
             .buffers
             .vertices
@@ -329,19 +784,230 @@ impl Obj {
                 indices: *indices,
             })
     }
+
+    /// Append the contents of another OBJ to this one.
+    ///
+    /// The attributes, vertices and polygons of `other` are added after those of this OBJ, with their indices offset
+    /// accordingly. Objects and groups with the same name are combined. Any cached triangles (see
+    /// [`GenericObj::cache_triangles`]) are discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined OBJ would contain more than `u32::MAX` of any kind of attribute.
+    pub fn merge(&mut self, other: &Self) where T: Clone, N: Clone {
+        fn offset(idx: NonZeroU32, by: usize) -> NonZeroU32 {
+            u32::try_from(idx.get() as usize + by)
+                .ok()
+                .and_then(NonZeroU32::new)
+                .expect("Too many attributes for a merged OBJ")
+        }
+
+        let lengths = [self.buffers.positions.len(), self.buffers.uvs.len(), self.buffers.normals.len()];
+        let vertex_offset = self.buffers.vertices.len();
+        let polygon_offset = self.buffers.polygons.len();
+
+        let buffers = &mut self.buffers;
+        Arc::make_mut(&mut buffers.positions).extend_from_slice(&other.buffers.positions);
+        Arc::make_mut(&mut buffers.uvs).extend_from_slice(&other.buffers.uvs);
+        Arc::make_mut(&mut buffers.normals).extend_from_slice(&other.buffers.normals);
+        Arc::make_mut(&mut buffers.vertices).extend(other.buffers.vertices.iter().map(|(pos, uv, norm)| (
+            offset(*pos, lengths[0]),
+            uv.map(|uv| offset(uv, lengths[1])),
+            norm.map(|norm| offset(norm, lengths[2])),
+        )));
+        Arc::make_mut(&mut buffers.polygons).extend(other.buffers.polygons.iter().map(|range| VertexRange {
+            start: range.start + vertex_offset,
+            end: range.end + vertex_offset,
+        }));
+        buffers.lines = match (buffers.lines.take(), &other.buffers.lines) {
+            (Some(mut lines), Some(other)) => {
+                Arc::make_mut(&mut lines).extend_from_slice(other);
+                Some(lines)
+            },
+            _ => None,
+        };
+        if let Some(other_materials) = &other.buffers.materials {
+            // Materials are matched by name, with those new to this OBJ added after the others
+            let names = Arc::make_mut(&mut buffers.material_names);
+            let remap = other.buffers.material_names
+                .iter()
+                .map(|name| match names.iter().position(|n| n == name) {
+                    Some(i) => NonZeroU32::new(i as u32 + 1).unwrap(),
+                    None => {
+                        names.push(name.clone());
+                        NonZeroU32::new(names.len() as u32).unwrap()
+                    },
+                })
+                .collect::<Vec<_>>();
+            let materials = Arc::make_mut(buffers.materials.get_or_insert_with(Arc::default));
+            materials.resize(polygon_offset, None);
+            materials.extend(other_materials.iter().map(|m| m.map(|m| remap[m.get() as usize - 1])));
+        }
+
+        let objects = Arc::make_mut(&mut self.objects);
+        for (name, groups) in other.objects.iter() {
+            let object = objects.entry(name.clone()).or_default();
+            for (name, polys) in groups {
+                object
+                    .entry(name.clone())
+                    .or_default()
+                    .extend(polys.iter().map(|index| index + polygon_offset));
+            }
+        }
+        self.names = Arc::new(NameIndex::new(objects));
+        self.triangles = None;
+    }
+
+    /// Create a new [`Obj`] containing only the polygons for which `keep` returns `true`.
+    ///
+    /// Polygons keep their object and group membership. Attributes are compacted such that only those referenced by
+    /// the kept polygons remain, in the order in which they are first referenced.
+    pub(crate) fn extract(&self, mut keep: impl FnMut(usize) -> bool) -> Self where N: Clone {
+        fn remap(map: &mut [Option<NonZeroU32>], len: &mut u32, idx: NonZeroU32) -> NonZeroU32 {
+            *map[idx.get() as usize - 1].get_or_insert_with(|| {
+                *len += 1;
+                NonZeroU32::new(*len).unwrap()
+            })
+        }
+
+        let mut vertices = Vec::new();
+        let mut polygons = Vec::new();
+        let mut position_map = alloc::vec![None; self.buffers.positions.len()];
+        let mut uv_map = alloc::vec![None; self.buffers.uvs.len()];
+        let mut normal_map = alloc::vec![None; self.buffers.normals.len()];
+        let mut polygon_map = alloc::vec![None; self.buffers.polygons.len()];
+        let mut lengths = [0; 3];
+
+        for (index, range) in self.buffers.polygons.iter().enumerate() {
+            if !keep(index) {
+                continue;
+            }
+            let start = vertices.len();
+            for (pos, uv, norm) in &self.buffers.vertices[range.start..range.end] {
+                vertices.push((
+                    remap(&mut position_map, &mut lengths[0], *pos),
+                    uv.map(|uv| remap(&mut uv_map, &mut lengths[1], uv)),
+                    norm.map(|norm| remap(&mut normal_map, &mut lengths[2], norm)),
+                ));
+            }
+            polygon_map[index] = Some(polygons.len());
+            polygons.push(VertexRange { start, end: vertices.len() });
+        }
+
+        fn compact<T: Float>(old: &[[T; 3]], map: &[Option<NonZeroU32>], len: u32) -> Vec<[T; 3]> {
+            let mut new = alloc::vec![[T::ZERO; 3]; len as usize];
+            for (attr, idx) in old.iter().zip(map) {
+                if let Some(idx) = idx {
+                    new[idx.get() as usize - 1] = *attr;
+                }
+            }
+            new
+        }
+
+        let mut buffers = Buffers {
+            positions: Arc::new(compact(&self.buffers.positions, &position_map, lengths[0])),
+            uvs: Arc::new(compact(&self.buffers.uvs, &uv_map, lengths[1])),
+            normals: Arc::new(compact(&self.buffers.normals, &normal_map, lengths[2])),
+            vertices: Arc::new(vertices),
+            polygons: Arc::new(polygons),
+            lines: self.buffers.lines.clone(),
+            comments: self.buffers.comments.clone(),
+            report: self.buffers.report.clone(),
+            materials: self.buffers.materials.clone(),
+            material_names: self.buffers.material_names.clone(),
+        };
+        buffers.remap_polygon_data(polygon_map.iter().enumerate().filter(|(_, new)| new.is_some()).map(|(old, _)| old));
+
+        let objects = self.objects
+            .iter()
+            .map(|(name, groups)| (name.clone(), groups
+                .iter()
+                .map(|(name, polys)| (name.clone(), polys
+                    .iter()
+                    .filter_map(|index| polygon_map[*index])
+                    .collect::<Vec<_>>()))
+                .filter(|(_, polys)| !polys.is_empty())
+                .collect::<HashMap<_, _>>()))
+            .filter(|(_, groups)| !groups.is_empty())
+            .collect();
+
+        Self { buffers, names: Arc::new(NameIndex::new(&objects)), objects: Arc::new(objects), triangles: None }
+    }
+
+    /// Convert the attributes of this OBJ to another floating point type, rounding if necessary.
+    pub fn cast<U: Float>(&self) -> GenericObj<U, N> where N: Clone {
+        fn cast<T: Float, U: Float>(attrs: &[[T; 3]]) -> Vec<[U; 3]> {
+            attrs.iter().map(|attr| attr.map(|x| U::from_f64(x.to_f64()))).collect()
+        }
+
+        GenericObj {
+            buffers: Buffers {
+                positions: Arc::new(cast(&self.buffers.positions)),
+                uvs: Arc::new(cast(&self.buffers.uvs)),
+                normals: Arc::new(cast(&self.buffers.normals)),
+                vertices: self.buffers.vertices.clone(),
+                polygons: self.buffers.polygons.clone(),
+                lines: self.buffers.lines.clone(),
+                comments: self.buffers.comments.clone(),
+                report: self.buffers.report.clone(),
+                materials: self.buffers.materials.clone(),
+                material_names: self.buffers.material_names.clone(),
+            },
+            objects: self.objects.clone(),
+            names: self.names.clone(),
+            triangles: self.triangles.clone(),
+        }
+    }
+
+    /// Convert the attributes of this OBJ to `f32`, rounding if necessary.
+    pub fn to_f32(&self) -> GenericObj<f32, N> where N: Clone {
+        self.cast()
+    }
+
+    /// Convert the attributes of this OBJ to `f64`.
+    pub fn to_f64(&self) -> GenericObj<f64, N> where N: Clone {
+        self.cast()
+    }
+}
+
+impl<'a, T: Float> ObjRef<'a, T> {
+    /// Convert this OBJ to one that owns the names of its objects and groups.
+    pub fn to_owned(&self) -> GenericObj<T> {
+        let objects = self.objects
+            .iter()
+            .map(|(name, groups)| (String::from(*name), groups
+                .iter()
+                .map(|(name, polys)| (String::from(*name), polys.clone()))
+                .collect()))
+            .collect();
+        GenericObj {
+            buffers: self.buffers.clone(),
+            names: Arc::new(NameIndex::new(&objects)),
+            objects: Arc::new(objects),
+            triangles: self.triangles.clone(),
+        }
+    }
 }
 
-impl Deref for Obj {
-    type Target = Buffers;
+impl<T: Float> core::str::FromStr for GenericObj<T> {
+    type Err = Error;
 
-    fn deref(&self) -> &Buffers { &self.buffers }
+    fn from_str(src: &str) -> Result<Self, Error> {
+        Self::from_lines(src.lines())
+    }
 }
 
-impl DerefMut for Obj {
-    fn deref_mut(&mut self) -> &mut Buffers { &mut self.buffers }
+impl<T, N> Deref for GenericObj<T, N> {
+    type Target = Buffers<T>;
+
+    fn deref(&self) -> &Buffers<T> { &self.buffers }
 }
 
-impl fmt::Debug for Obj {
+impl<T, N> DerefMut for GenericObj<T, N> {
+    fn deref_mut(&mut self) -> &mut Buffers<T> { &mut self.buffers }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash + fmt::Debug> fmt::Debug for GenericObj<T, N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Obj")
             .field("positions", &self.buffers.positions.len())
@@ -353,82 +1019,199 @@ impl fmt::Debug for Obj {
     }
 }
 
-impl fmt::Display for Obj {
+/// Formats a vertex attribute statement such as `v 1 2 3`. Shared by every writer so that they agree on the format.
+pub(crate) struct DisplayAttribute<T>(pub &'static str, pub [T; 3]);
+
+impl<T: Float> fmt::Display for DisplayAttribute<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let DisplayAttribute(keyword, [x, y, z]) = self;
+        write!(f, "{} {} {} {}", keyword, x, y, z)
+    }
+}
+
+/// Formats a face term of the form `p`, `p/t`, `p//n` or `p/t/n`.
+pub(crate) struct DisplayIndices(pub VertexIndices);
+
+impl fmt::Display for DisplayIndices {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.0)?;
+        match self.0 {
+            (_, None, None) => Ok(()),
+            (_, Some(uv), None) => write!(f, "/{}", uv),
+            (_, None, Some(norm)) => write!(f, "//{}", norm),
+            (_, Some(uv), Some(norm)) => write!(f, "/{}/{}", uv, norm),
+        }
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> fmt::Display for GenericObj<T, N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for [x, y, z] in &self.buffers.positions {
-            writeln!(f, "v {} {} {}", x, y, z)?;
+        self.fmt_obj(f, false)
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
+    // Write in OBJ format, with polygons in the order they're stored or grouped by object sorted by name
+    fn fmt_obj(&self, f: &mut fmt::Formatter, sort_names: bool) -> fmt::Result {
+        for pos in self.buffers.positions.iter() {
+            writeln!(f, "{}", DisplayAttribute("v", *pos))?;
         }
-        for [u, v, w] in &self.buffers.uvs {
-            writeln!(f, "vt {} {} {}", u, v, w)?;
+        for uv in self.buffers.uvs.iter() {
+            writeln!(f, "{}", DisplayAttribute("vt", *uv))?;
         }
-        for [x, y, z] in &self.buffers.normals {
-            writeln!(f, "vn {} {} {}", x, y, z)?;
+        for norm in self.buffers.normals.iter() {
+            writeln!(f, "{}", DisplayAttribute("vn", *norm))?;
         }
-        for (name, groups) in self.objects.iter() {
-            if name.len() > 0 {
-                writeln!(f, "o {}", name)?;
+        // Objects, groups and materials carry over between polygons, so only changes need to be written. Writing starts
+        // in the unnamed object and default group, with no material.
+        let (mut object, mut groups, mut material) = ("", Vec::new(), None);
+        for (index, poly_object, poly_groups) in self.written_polygons(sort_names) {
+            if poly_object != object {
+                object = poly_object;
+                writeln!(f, "o {}", object)?;
+                // Selecting an object selects its default group
+                groups.clear();
             }
-            for (name, polys) in groups.iter() {
-                if name.len() > 0 {
-                    writeln!(f, "g {}", name)?;
-                }
-                for range in polys {
-                    self.buffers.lookup(*range).display(f)?;
-                    writeln!(f)?;
+            if poly_groups != groups {
+                groups = poly_groups;
+                if groups.is_empty() {
+                    writeln!(f, "g")?;
+                } else {
+                    writeln!(f, "g {}", groups.join(" "))?;
                 }
             }
+            let poly = self.buffers.lookup(index);
+            if poly.material() != material {
+                material = poly.material();
+                writeln!(f, "usemtl {}", material.unwrap_or(""))?;
+            }
+            writeln!(f, "{}", poly)?;
         }
         Ok(())
     }
+
+    // The polygons in the order they're written, each with its object and its named groups, sorted by name. Each
+    // polygon is written once, in the order they're stored or grouped by object sorted by name. The unnamed object
+    // comes first either way, since it can't be selected again once another has been, and polygons that belong to no
+    // object are written as part of it.
+    fn written_polygons(&self, sort_names: bool) -> Vec<(usize, &str, Vec<&str>)> {
+        let mut memberships = self
+            .objects
+            .iter()
+            .flat_map(|(object, groups)| {
+                groups.iter().flat_map(move |(group, polys)| {
+                    polys.iter().map(move |index| (*index, object.borrow(), group.borrow()))
+                })
+            })
+            .collect::<Vec<(usize, &str, &str)>>();
+        memberships.sort_unstable();
+
+        let mut written = (0..self.buffers.polygons.len()).map(|index| (index, "", Vec::new())).collect::<Vec<_>>();
+        let mut owned = alloc::vec![false; written.len()];
+        for (index, object, group) in memberships {
+            let (_, owner, groups) = &mut written[index];
+            // A polygon can only be written as part of one object, so the first by name is chosen
+            if !owned[index] {
+                owned[index] = true;
+                *owner = object;
+            } else if *owner != object {
+                continue;
+            }
+            // The default group is selected by an empty group statement
+            if !group.is_empty() {
+                groups.push(group);
+            }
+        }
+        if sort_names {
+            written.sort_by_key(|(_, object, _)| *object);
+        } else {
+            written.sort_by_key(|(_, object, _)| !object.is_empty());
+        }
+        written
+    }
 }
 
 /// An object defined in an OBJ.
-#[derive(Copy, Clone)]
-pub struct Object<'a> {
-    buffers: &'a Buffers,
-    groups: &'a HashMap<String, Vec<VertexRange>>,
+pub struct Object<'a, T = f32, N = String> {
+    buffers: &'a Buffers<T>,
+    groups: &'a HashMap<N, Vec<usize>>,
+    names: &'a HashMap<String, N>,
 }
 
-impl<'a> Object<'a> {
+impl<'a, T: Float, N: Borrow<str> + Eq + Hash> Object<'a, T, N> {
     /// Returns a specific [`Group`] by name.
     ///
     /// Note that if a name is not specified in the OBJ file, the name defaults to an empty string.
-    pub fn group(&self, name: &str) -> Option<Group<'a>> {
+    pub fn group(&self, name: &str) -> Option<Group<'a, T>> {
         self.groups.get(name).map(|polygons| Group {
             buffers: self.buffers,
             polygons: polygons.as_slice(),
         })
     }
 
+    /// Returns a specific [`Group`] by name, ignoring ASCII case.
+    ///
+    /// If several groups have names that differ only by case, the one whose name sorts first is returned. See
+    /// [`GenericObj::object_ignore_case`] for more information.
+    pub fn group_ignore_case(&self, name: &str) -> Option<Group<'a, T>> {
+        self.group(self.names.get(&fold_case(name))?.borrow())
+    }
+
     /// Returns an iterator over the [`Group`]s in this [`Object`].
-    pub fn groups(&self) -> impl ExactSizeIterator<Item=(&'a String, Group<'a>)> + Clone + 'a {
+    pub fn groups(&self) -> impl ExactSizeIterator<Item=(&'a N, Group<'a, T>)> + Clone + 'a {
         let buffers = self.buffers;
         self.groups.iter().map(move |(name, polygons)| (name, Group {
-            buffers: buffers,
+            buffers,
             polygons: polygons.as_slice(),
         }))
     }
 
-    /// Returns an iterator over the [`Polygon`]s in this [`Object`].
-    pub fn polygons(&self) -> impl Iterator<Item=Polygon<'a>> + Clone + 'a {
-        self
-            .groups()
-            .map(|(_, group)| group.polygons())
-            .flatten()
+    /// Returns an iterator over the names of the [`Group`]s in this [`Object`].
+    pub fn group_names(&self) -> impl ExactSizeIterator<Item=&'a str> + Clone + 'a {
+        self.groups.keys().map(|name| name.borrow())
+    }
+
+    /// Returns `true` if this [`Object`] contains a [`Group`] with the given name.
+    pub fn contains_group(&self, name: &str) -> bool {
+        self.groups.contains_key(name)
+    }
+
+    /// Returns an iterator over the [`Polygon`]s in this [`Object`], in the order they appear in the file.
+    ///
+    /// As with [`GenericObj::polygons`], a polygon that belongs to several groups is only produced once.
+    pub fn polygons(&self) -> impl ExactSizeIterator<Item=Polygon<'a, T>> + Clone + 'a {
+        let mut polygons = self.groups.values().flatten().copied().collect::<Vec<_>>();
+        polygons.sort_unstable();
+        polygons.dedup();
+        let buffers = self.buffers;
+        polygons.into_iter().map(move |index| buffers.lookup(index))
     }
 
     /// Returns an iterator over the triangles in this [`Object`].
     ///
     /// See [`Polygon::triangles`] for more information.
-    pub fn triangles(&self) -> impl Iterator<Item=[Vertex<'a>; 3]> + Clone + 'a {
+    pub fn triangles(&self) -> impl Iterator<Item=[Vertex<'a, T>; 3]> + Clone + 'a {
         self
             .polygons()
-            .map(|poly| poly.triangles())
-            .flatten()
+            .flat_map(|poly| poly.triangles())
     }
+
+    /// Returns an iterator over the triangles in this [`Object`], each given by the indices of its positions in
+    /// [`GenericObj::positions`].
+    ///
+    /// See [`GenericObj::triangle_position_indices`] for more information.
+    pub fn triangle_position_indices(&self) -> impl Iterator<Item=[Index; 3]> + Clone + 'a {
+        self.triangles().map(|tri| tri.map(|v| v.position_index()))
+    }
+}
+
+impl<'a, T, N> Clone for Object<'a, T, N> {
+    fn clone(&self) -> Self { *self }
 }
 
-impl<'a> fmt::Debug for Object<'a> {
+impl<'a, T, N> Copy for Object<'a, T, N> {}
+
+impl<'a, T: Float, N: Borrow<str> + Eq + Hash + fmt::Debug> fmt::Debug for Object<'a, T, N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Object")
             .field("groups", &self.groups().collect::<Vec<_>>())
@@ -437,36 +1220,48 @@ impl<'a> fmt::Debug for Object<'a> {
 }
 
 /// A group defined in an OBJ.
-#[derive(Copy, Clone)]
-pub struct Group<'a> {
-    buffers: &'a Buffers,
-    polygons: &'a [VertexRange],
+pub struct Group<'a, T = f32> {
+    buffers: &'a Buffers<T>,
+    polygons: &'a [usize],
 }
 
-impl<'a> Group<'a> {
+impl<'a, T: Float> Group<'a, T> {
     /// Returns a specific [`Polygon`] by index.
-    pub fn polygon(&self, index: Index) -> Option<Polygon<'a>> {
-        self.polygons.get(index).map(|range| self.buffers.lookup(*range))
+    pub fn polygon(&self, index: Index) -> Option<Polygon<'a, T>> {
+        self.polygons.get(index).map(|index| self.buffers.lookup(*index))
     }
 
     /// Returns an iterator over the [`Polygon`]s in this [`Group`].
-    pub fn polygons(&self) -> impl ExactSizeIterator<Item=Polygon<'a>> + Clone + 'a {
+    pub fn polygons(&self) -> impl ExactSizeIterator<Item=Polygon<'a, T>> + Clone + 'a {
         let buffers = self.buffers;
-        self.polygons.iter().map(move |range| buffers.lookup(*range))
+        self.polygons.iter().map(move |index| buffers.lookup(*index))
     }
 
     /// Returns an iterator over the triangles in this [`Group`].
     ///
     /// See [`Polygon::triangles`] for more information.
-    pub fn triangles(&self) -> impl Iterator<Item=[Vertex<'a>; 3]> + Clone + 'a {
+    pub fn triangles(&self) -> impl Iterator<Item=[Vertex<'a, T>; 3]> + Clone + 'a {
         self
             .polygons()
-            .map(|poly| poly.triangles())
-            .flatten()
+            .flat_map(|poly| poly.triangles())
     }
+
+    /// Returns an iterator over the triangles in this [`Group`], each given by the indices of its positions in
+    /// [`GenericObj::positions`].
+    ///
+    /// See [`GenericObj::triangle_position_indices`] for more information.
+    pub fn triangle_position_indices(&self) -> impl Iterator<Item=[Index; 3]> + Clone + 'a {
+        self.triangles().map(|tri| tri.map(|v| v.position_index()))
+    }
+}
+
+impl<'a, T> Clone for Group<'a, T> {
+    fn clone(&self) -> Self { *self }
 }
 
-impl<'a> fmt::Debug for Group<'a> {
+impl<'a, T> Copy for Group<'a, T> {}
+
+impl<'a, T: Float> fmt::Debug for Group<'a, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Group")
             .field("polygons", &self.polygons().collect::<Vec<_>>())
@@ -474,16 +1269,97 @@ impl<'a> fmt::Debug for Group<'a> {
     }
 }
 
+/// A group merged from the groups with the same name in every [`Object`] of an OBJ.
+///
+/// See [`GenericObj::group`] for more information.
+pub struct MergedGroup<'a, T = f32> {
+    buffers: &'a Buffers<T>,
+    polygons: Arc<[usize]>,
+}
+
+impl<'a, T: Float> MergedGroup<'a, T> {
+    /// Returns a specific [`Polygon`] by index.
+    pub fn polygon(&self, index: Index) -> Option<Polygon<'a, T>> {
+        self.polygons.get(index).map(|index| self.buffers.lookup(*index))
+    }
+
+    /// Returns an iterator over the [`Polygon`]s in this [`MergedGroup`].
+    pub fn polygons(&self) -> impl ExactSizeIterator<Item=Polygon<'a, T>> + Clone + 'a {
+        let (buffers, polygons) = (self.buffers, self.polygons.clone());
+        (0..polygons.len()).map(move |i| buffers.lookup(polygons[i]))
+    }
+
+    /// Returns an iterator over the triangles in this [`MergedGroup`].
+    ///
+    /// See [`Polygon::triangles`] for more information.
+    pub fn triangles(&self) -> impl Iterator<Item=[Vertex<'a, T>; 3]> + Clone + 'a {
+        self
+            .polygons()
+            .flat_map(|poly| poly.triangles())
+    }
+
+    /// Returns an iterator over the triangles in this [`MergedGroup`], each given by the indices of its positions in
+    /// [`GenericObj::positions`].
+    ///
+    /// See [`GenericObj::triangle_position_indices`] for more information.
+    pub fn triangle_position_indices(&self) -> impl Iterator<Item=[Index; 3]> + Clone + 'a {
+        self.triangles().map(|tri| tri.map(|v| v.position_index()))
+    }
+
+    /// Returns this [`MergedGroup`] as a [`Group`], giving access to the rest of the [`Group`] API.
+    pub fn as_group(&self) -> Group<'_, T> {
+        Group { buffers: self.buffers, polygons: &self.polygons }
+    }
+}
+
+impl<'a, T> Clone for MergedGroup<'a, T> {
+    fn clone(&self) -> Self {
+        Self { buffers: self.buffers, polygons: self.polygons.clone() }
+    }
+}
+
+impl<'a, T: Float> fmt::Debug for MergedGroup<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MergedGroup")
+            .field("polygons", &self.polygons().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
 /// A polygon defined in an OBJ.
-#[derive(Copy, Clone)]
-pub struct Polygon<'a> {
-    buffers: &'a Buffers,
+pub struct Polygon<'a, T = f32> {
+    buffers: &'a Buffers<T>,
+    index: usize,
     vertices: &'a [VertexIndices],
 }
 
-impl<'a> Polygon<'a> {
+impl<'a, T: Float> Polygon<'a, T> {
+    /// Returns the index of this polygon within its [`Obj`].
+    ///
+    /// Polygons are indexed in the order they appear in the file, matching the order of [`Obj::polygons`].
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// Returns the (1-based) line of the file on which this polygon was defined.
+    ///
+    /// This is only available if [`ParseOptions::track_lines`] was enabled when parsing. Polygons merged from
+    /// several OBJs (see [`GenericObj::merge`]) keep the lines from their own files, provided that every OBJ tracked
+    /// them.
+    pub fn source_line(&self) -> Option<usize> {
+        self.buffers.lines.as_ref()?.get(self.index).copied()
+    }
+
+    /// Returns the name of the material used by this polygon, as selected by the last `usemtl` statement before it.
+    ///
+    /// Polygons before the first `usemtl` statement, or after one without a name, have no material. Polygons keep
+    /// their materials through operations such as [`GenericObj::merge`] and [`GenericObj::subdivided`].
+    pub fn material(&self) -> Option<&'a str> {
+        self.buffers.material(self.index)
+    }
+
     /// Returns a specific [`Vertex`] by index.
-    pub fn vertex(&self, index: usize) -> Option<Vertex<'a>> {
+    pub fn vertex(&self, index: usize) -> Option<Vertex<'a, T>> {
         self.vertices.get(index).map(|indices| Vertex {
             buffers: self.buffers,
             indices: *indices,
@@ -491,7 +1367,7 @@ impl<'a> Polygon<'a> {
     }
 
     /// Returns an iterator over the [`Vertex`]s in this [`Polygon`].
-    pub fn vertices(&self) -> impl ExactSizeIterator<Item=Vertex<'a>> + Clone + 'a {
+    pub fn vertices(&self) -> impl ExactSizeIterator<Item=Vertex<'a, T>> + Clone + 'a {
         let buffers = self.buffers;
         self.vertices.iter().map(move |indices| Vertex {
             buffers,
@@ -512,7 +1388,7 @@ impl<'a> Polygon<'a> {
     ///
     /// - The polygon is concave
     /// - The vertices of the polygon all lie in the same plane
-    pub fn triangles(&self) -> impl ExactSizeIterator<Item=[Vertex<'a>; 3]> + Clone + 'a {
+    pub fn triangles(&self) -> impl ExactSizeIterator<Item=[Vertex<'a, T>; 3]> + Clone + 'a {
         let this = *self;
         (0..this.vertices.len().saturating_sub(2))
             .map(move |i| [
@@ -521,18 +1397,26 @@ impl<'a> Polygon<'a> {
                 this.vertex(i + 2).unwrap(),
             ])
     }
+}
+
+impl<'a, T> Clone for Polygon<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T> Copy for Polygon<'a, T> {}
 
-    fn display(&self, f: &mut fmt::Formatter) -> fmt::Result {
+/// Formats this polygon as an `f` statement, as it would appear in an OBJ file.
+impl<'a, T: Float> fmt::Display for Polygon<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "f")?;
         for v in self.vertices() {
-            write!(f, " ")?;
-            v.display(f)?;
+            write!(f, " {}", v)?;
         }
         Ok(())
     }
 }
 
-impl<'a> fmt::Debug for Polygon<'a> {
+impl<'a, T: Float> fmt::Debug for Polygon<'a, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Polygon")
             .field("vertices", &self.vertices().collect::<Vec<_>>())
@@ -541,22 +1425,21 @@ impl<'a> fmt::Debug for Polygon<'a> {
 }
 
 /// A vertex defined in an OBJ.
-#[derive(Copy, Clone)]
-pub struct Vertex<'a> {
-    buffers: &'a Buffers,
+pub struct Vertex<'a, T = f32> {
+    buffers: &'a Buffers<T>,
     indices: VertexIndices,
 }
 
-impl<'a> Vertex<'a> {
+impl<'a, T: Float> Vertex<'a, T> {
     /// Returns the index of the vertex's position in the slice given by [`Buffers::positions`].
     ///
     /// Note that, unlike OBJ files themselves, this is zero-indexed.
     pub fn position_index(&self) -> Index {
-        self.indices.0.get() - 1
+        self.indices.0.get() as usize - 1
     }
 
     /// Returns the position of this vertex.
-    pub fn position(&self) -> [f32; 3] {
+    pub fn position(&self) -> [T; 3] {
         self.buffers.positions[self.position_index()]
     }
 
@@ -564,11 +1447,11 @@ impl<'a> Vertex<'a> {
     ///
     /// Note that, unlike OBJ files themselves, this is zero-indexed.
     pub fn uv_index(&self) -> Option<Index> {
-        self.indices.1.map(|idx| idx.get() - 1)
+        self.indices.1.map(|idx| idx.get() as usize - 1)
     }
 
     /// Returns the texture coordinate of this vertex, if it has one.
-    pub fn uv(&self) -> Option<[f32; 3]> {
+    pub fn uv(&self) -> Option<[T; 3]> {
         Some(self.buffers.uvs[self.uv_index()?])
     }
 
@@ -576,24 +1459,31 @@ impl<'a> Vertex<'a> {
     ///
     /// Note that, unlike OBJ files themselves, this is zero-indexed.
     pub fn normal_index(&self) -> Option<Index> {
-        self.indices.2.map(|idx| idx.get() - 1)
+        self.indices.2.map(|idx| idx.get() as usize - 1)
     }
 
     /// Returns the normal of this vertex, if it has one.
-    pub fn normal(&self) -> Option<[f32; 3]> {
+    pub fn normal(&self) -> Option<[T; 3]> {
         Some(self.buffers.normals[self.normal_index()?])
     }
+}
 
-    fn display(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, " {}/", self.indices.0)?;
-        if let Some(uv) = self.indices.1 { write!(f, "{}", uv)?; }
-        write!(f, "/")?;
-        if let Some(norm) = self.indices.2 { write!(f, "{}", norm)?; }
-        Ok(())
+impl<'a, T> Clone for Vertex<'a, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T> Copy for Vertex<'a, T> {}
+
+/// Formats this vertex as a face term (`p`, `p/t`, `p//n` or `p/t/n`), as it would appear in an OBJ file.
+///
+/// Indices are absolute and 1-based.
+impl<'a, T: Float> fmt::Display for Vertex<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        DisplayIndices(self.indices).fmt(f)
     }
 }
 
-impl<'a> fmt::Debug for Vertex<'a> {
+impl<'a, T: Float> fmt::Debug for Vertex<'a, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Vertex")
             .field("position", &self.position())
@@ -603,71 +1493,223 @@ impl<'a> fmt::Debug for Vertex<'a> {
     }
 }
 
-/// Utilities relating to the OBJ format.
-pub mod util {
-    /// Determine whether a name (of either an object or a group) is valid.
-    pub fn name_is_valid(name: &str) -> bool {
-        name.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_')
+// Indices are stored as 32-bit integers to keep face data compact, so the parser rejects OBJs with more than
+// `u32::MAX` of any kind of attribute
+type VertexIndices = (NonZeroU32, Option<NonZeroU32>, Option<NonZeroU32>);
+
+// Maps names with their case folded to the original names. Where several names fold to the same thing, the one that
+// sorts first is chosen so that lookups don't depend on hash order
+struct NameIndex<N> {
+    objects: HashMap<String, N>,
+    groups: HashMap<N, HashMap<String, N>>,
+}
+
+impl<N: Borrow<str> + Eq + Hash + Clone> NameIndex<N> {
+    fn new(objects: &HashMap<N, HashMap<N, Vec<usize>>>) -> Self {
+        fn index<'a, N: Borrow<str> + Clone + 'a>(names: impl Iterator<Item=&'a N>) -> HashMap<String, N> {
+            let mut index = HashMap::new();
+            for name in names {
+                index
+                    .entry(fold_case(name.borrow()))
+                    .and_modify(|first: &mut N| if name.borrow() < (*first).borrow() { *first = name.clone() })
+                    .or_insert_with(|| name.clone());
+            }
+            index
+        }
+
+        Self {
+            objects: index(objects.keys()),
+            groups: objects.iter().map(|(name, groups)| (name.clone(), index(groups.keys()))).collect(),
+        }
     }
 }
 
-type VertexIndices = (NonZeroUsize, Option<NonZeroUsize>, Option<NonZeroUsize>);
+fn fold_case(name: &str) -> String {
+    name.to_ascii_lowercase()
+}
 
 /// A struct of buffers that may be indexed by [`Obj`] vertices.
-#[derive(Clone, Default)]
-pub struct Buffers {
-    positions: Vec<[f32; 3]>,
-    uvs: Vec<[f32; 3]>,
-    normals: Vec<[f32; 3]>,
-    vertices: Vec<VertexIndices>,
+///
+/// Buffers are shared between clones and copied when modified, so cloning is cheap.
+#[derive(Clone)]
+pub struct Buffers<T = f32> {
+    positions: Arc<Vec<[T; 3]>>,
+    uvs: Arc<Vec<[T; 3]>>,
+    normals: Arc<Vec<[T; 3]>>,
+    vertices: Arc<Vec<VertexIndices>>,
+    polygons: Arc<Vec<VertexRange>>,
+    // The line on which each polygon is defined, see `ParseOptions::track_lines`
+    lines: Option<Arc<Vec<usize>>>,
+    // The comments and the lines they're on, see `ParseOptions::keep_comments`
+    comments: Option<Arc<Vec<(usize, String)>>>,
+    report: Arc<ParseReport>,
+    // The material used by each polygon, as a (1-based) index into `material_names`, or `None` if no polygon has one.
+    // Polygons past the end have no material
+    materials: Option<Arc<Vec<Option<NonZeroU32>>>>,
+    material_names: Arc<Vec<String>>,
 }
 
-impl Buffers {
-    fn lookup(&self, range: VertexRange) -> Polygon {
+impl<T> Default for Buffers<T> {
+    fn default() -> Self {
+        Self {
+            positions: Arc::default(),
+            uvs: Arc::default(),
+            normals: Arc::default(),
+            vertices: Arc::default(),
+            polygons: Arc::default(),
+            lines: None,
+            comments: None,
+            report: Arc::default(),
+            materials: None,
+            material_names: Arc::default(),
+        }
+    }
+}
+
+impl<T> Buffers<T> {
+    // Rearrange the source lines and materials of the polygons to match a new list of polygons, given the index of the
+    // old polygon that each new polygon came from
+    fn remap_polygon_data(&mut self, sources: impl Iterator<Item=usize> + Clone) {
+        if let Some(lines) = &self.lines {
+            self.lines = Some(Arc::new(sources.clone().map_while(|i| lines.get(i).copied()).collect()));
+        }
+        if let Some(materials) = &self.materials {
+            self.materials = Some(Arc::new(sources.map(|i| materials.get(i).copied().flatten()).collect()));
+        }
+    }
+
+    fn material(&self, polygon: usize) -> Option<&str> {
+        let material = self.materials.as_ref()?.get(polygon).copied().flatten()?;
+        Some(&self.material_names[material.get() as usize - 1])
+    }
+}
+
+impl<T: Float> Buffers<T> {
+    fn lookup(&self, index: usize) -> Polygon<'_, T> {
+        let range = self.polygons[index];
         Polygon {
             buffers: self,
+            index,
             vertices: &self.vertices[range.start..range.end],
         }
     }
 
     /// Returns a reference to the position attributes contained within this [`Obj`].
-    pub fn positions(&self) -> &[[f32; 3]] {
+    pub fn positions(&self) -> &[[T; 3]] {
         &self.positions
     }
 
     /// Returns a reference to the texture coordinate attributes contained within this [`Obj`].
-    pub fn uvs(&self) -> &[[f32; 3]] {
+    pub fn uvs(&self) -> &[[T; 3]] {
         &self.uvs
     }
 
     /// Returns a reference to the normal attributes contained within this [`Obj`].
-    pub fn normals(&self) -> &[[f32; 3]] {
+    pub fn normals(&self) -> &[[T; 3]] {
         &self.normals
     }
 
+    /// Returns a summary of how this [`Obj`] was parsed, including any problems with the input that the parser
+    /// recovered from.
+    ///
+    /// ```
+    /// use wavefront::{Obj, Warning};
+    ///
+    /// let obj: Obj = "v 0 0 0\no\nf 1 1 1\n".parse().unwrap();
+    /// assert_eq!(obj.objects().next().unwrap().0, "object_2");
+    /// assert!(matches!(&obj.parse_report().warnings[..], [Warning::InvalidObjectName { line: 2, .. }]));
+    /// ```
+    pub fn parse_report(&self) -> &ParseReport {
+        &self.report
+    }
+
+    /// Returns an iterator over the comments in this [`Obj`] and the lines they're on, if they were kept (see
+    /// [`ParseOptions::keep_comments`]). The text of each comment follows the `#`, including any leading whitespace.
+    pub fn comments(&self) -> impl Iterator<Item=(usize, &str)> + Clone + '_ {
+        self.comments.iter().flat_map(|comments| comments.iter()).map(|(line, text)| (*line, text.as_str()))
+    }
+
+    /// Returns a mutable reference to the position attributes contained within this [`Obj`].
+    ///
+    /// Changing the values of attributes is always safe: polygons refer to attributes by index, so they (and any
+    /// cached triangles) remain valid. The buffer is copied first if it's shared with a clone of this [`Obj`].
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let mut obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// obj.positions_mut().iter_mut().for_each(|p| p[1] += 1.0);
+    /// assert!(obj.vertices().all(|v| v.position()[1] >= 1.0));
+    /// ```
+    pub fn positions_mut(&mut self) -> &mut [[T; 3]] {
+        Arc::make_mut(&mut self.positions).as_mut_slice()
+    }
+
+    /// Returns a mutable reference to the texture coordinate attributes contained within this [`Obj`].
+    ///
+    /// See [`Buffers::positions_mut`] for more information.
+    pub fn uvs_mut(&mut self) -> &mut [[T; 3]] {
+        Arc::make_mut(&mut self.uvs).as_mut_slice()
+    }
+
+    /// Returns a mutable reference to the normal attributes contained within this [`Obj`].
+    ///
+    /// See [`Buffers::positions_mut`] for more information.
+    pub fn normals_mut(&mut self) -> &mut [[T; 3]] {
+        Arc::make_mut(&mut self.normals).as_mut_slice()
+    }
+
+    /// Replace the position attribute with the given index, returning [`Error::InvalidIndex`] if there is none.
+    ///
+    /// As with errors produced by the parser, the index in the error counts from 1, as it would be written in an OBJ.
+    /// The same goes for [`Buffers::set_uv`] and [`Buffers::set_normal`].
+    pub fn set_position(&mut self, index: Index, position: [T; 3]) -> Result<(), Error> {
+        set_attribute(&mut self.positions, index, position)
+    }
+
+    /// Replace the texture coordinate attribute with the given index, returning [`Error::InvalidIndex`] if there is
+    /// none.
+    pub fn set_uv(&mut self, index: Index, uv: [T; 3]) -> Result<(), Error> {
+        set_attribute(&mut self.uvs, index, uv)
+    }
+
+    /// Replace the normal attribute with the given index, returning [`Error::InvalidIndex`] if there is none.
+    pub fn set_normal(&mut self, index: Index, normal: [T; 3]) -> Result<(), Error> {
+        set_attribute(&mut self.normals, index, normal)
+    }
+
     /// Add a new position attribute to this [`Obj`], returning its index.
-    pub fn add_position(&mut self, position: [f32; 3]) -> usize {
+    pub fn add_position(&mut self, position: [T; 3]) -> usize {
         let idx = self.positions.len();
-        self.positions.push(position);
+        Arc::make_mut(&mut self.positions).push(position);
         idx
     }
 
     /// Add a new texture coordinate attribute to this [`Obj`], returning its index.
-    pub fn add_uv(&mut self, uv: [f32; 3]) -> usize {
+    pub fn add_uv(&mut self, uv: [T; 3]) -> usize {
         let idx = self.uvs.len();
-        self.uvs.push(uv);
+        Arc::make_mut(&mut self.uvs).push(uv);
         idx
     }
 
     /// Add a new normal attribute to this [`Obj`], returning its index.
-    pub fn add_normal(&mut self, normal: [f32; 3]) -> usize {
+    pub fn add_normal(&mut self, normal: [T; 3]) -> usize {
         let idx = self.normals.len();
-        self.normals.push(normal);
+        Arc::make_mut(&mut self.normals).push(normal);
         idx
     }
 }
 
-#[derive(Copy, Clone)]
+fn set_attribute<T: Clone>(attrs: &mut Arc<Vec<[T; 3]>>, index: Index, value: [T; 3]) -> Result<(), Error> {
+    if index >= attrs.len() {
+        // Report the index as it would appear in an OBJ, counting from 1
+        return Err(Error::InvalidIndex(isize::try_from(index).map_or(isize::MAX, |idx| idx.saturating_add(1))));
+    }
+    Arc::make_mut(attrs)[index] = value;
+    Ok(())
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
 struct VertexRange {
     start: usize,
     end: usize,
@@ -0,0 +1,58 @@
+use crate::{Buffers, Float, GenericObj, Group, Object};
+use alloc::vec::Vec;
+use core::{borrow::Borrow, hash::Hash, num::NonZeroU32};
+use hashbrown::HashMap;
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
+    /// Returns the materials used by the polygons of this OBJ, each with the number of polygons using it.
+    ///
+    /// Materials are given in the order in which they're first used, and polygons without a material (see
+    /// [`Polygon::material`](crate::Polygon::material)) are counted under `None`.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj: Obj = "v 0 0 0\nf 1 1 1\nusemtl red\nf 1 1 1\nf 1 1 1\n".parse().unwrap();
+    /// assert_eq!(obj.material_usage().collect::<Vec<_>>(), [(None, 1), (Some("red"), 2)]);
+    /// ```
+    pub fn material_usage(&self) -> impl Iterator<Item=(Option<&str>, usize)> + '_ {
+        material_usage(&self.buffers, 0..self.buffers.polygons.len())
+    }
+}
+
+impl<'a, T: Float, N: Borrow<str> + Eq + Hash> Object<'a, T, N> {
+    /// Returns the materials used by the polygons of this object, each with the number of polygons using it.
+    ///
+    /// Polygons that belong to several groups of the object are only counted once. See
+    /// [`GenericObj::material_usage`] for more information.
+    pub fn material_usage(&self) -> impl Iterator<Item=(Option<&'a str>, usize)> + 'a {
+        let mut polygons = self.groups.values().flatten().copied().collect::<Vec<_>>();
+        polygons.sort_unstable();
+        polygons.dedup();
+        material_usage(self.buffers, polygons.into_iter())
+    }
+}
+
+impl<'a, T: Float> Group<'a, T> {
+    /// Returns the materials used by the polygons of this group, each with the number of polygons using it.
+    ///
+    /// See [`GenericObj::material_usage`] for more information.
+    pub fn material_usage(&self) -> impl Iterator<Item=(Option<&'a str>, usize)> + 'a {
+        material_usage(self.buffers, self.polygons.iter().copied())
+    }
+}
+
+fn material_usage<T>(buffers: &Buffers<T>, polygons: impl Iterator<Item=usize>) -> alloc::vec::IntoIter<(Option<&str>, usize)> {
+    let mut slots = HashMap::<Option<NonZeroU32>, usize>::new();
+    let mut usage = Vec::new();
+    let materials = buffers.materials.as_deref().map_or(&[][..], Vec::as_slice);
+    for poly in polygons {
+        let material = materials.get(poly).copied().flatten();
+        let slot = *slots.entry(material).or_insert_with(|| {
+            usage.push((material.map(|m| buffers.material_names[m.get() as usize - 1].as_str()), 0));
+            usage.len() - 1
+        });
+        usage[slot].1 += 1;
+    }
+    usage.into_iter()
+}
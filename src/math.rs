@@ -0,0 +1,204 @@
+//! Floating point abstractions and small vector maths helpers shared between modules.
+
+use core::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+pub(crate) mod sealed {
+    pub trait Sealed: Sized {
+        /// Parse a number, accepting exactly the same syntax as [`str::parse`].
+        fn parse_str(s: &str) -> Option<Self>;
+    }
+
+    impl Sealed for f32 {
+        fn parse_str(s: &str) -> Option<Self> { parse(s) }
+    }
+
+    impl Sealed for f64 {
+        fn parse_str(s: &str) -> Option<Self> { parse(s) }
+    }
+
+    #[cfg(feature = "fast-float")]
+    fn parse<T: fast_float2::FastFloat + core::str::FromStr>(s: &str) -> Option<T> {
+        // Defer to the standard parser for anything unusual so that the accepted syntax is identical
+        fast_float2::parse(s).ok().or_else(|| s.parse().ok())
+    }
+
+    #[cfg(not(feature = "fast-float"))]
+    fn parse<T: core::str::FromStr>(s: &str) -> Option<T> {
+        s.parse().ok()
+    }
+}
+
+/// A floating point type that may be used to store the attributes of an OBJ (see [`GenericObj`](crate::GenericObj)).
+///
+/// This trait is sealed and is implemented only for `f32` and `f64`.
+pub trait Float:
+    Copy
+    + Default
+    + PartialOrd
+    + fmt::Debug
+    + fmt::Display
+    + FromStr
+    + Add<Output=Self>
+    + Sub<Output=Self>
+    + Mul<Output=Self>
+    + Div<Output=Self>
+    + Neg<Output=Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Send
+    + Sync
+    + 'static
+    + sealed::Sealed
+{
+    /// The value `0`.
+    const ZERO: Self;
+    /// The value `1`.
+    const ONE: Self;
+    /// The difference between `1` and the next largest representable number.
+    const EPSILON: Self;
+
+    /// Convert from an `f32`.
+    fn from_f32(x: f32) -> Self;
+    /// Convert from an `f64`, rounding to the nearest representable value if necessary.
+    fn from_f64(x: f64) -> Self;
+    /// Convert to an `f32`, rounding to the nearest representable value if necessary.
+    fn to_f32(self) -> f32;
+    /// Convert to an `f64`.
+    fn to_f64(self) -> f64;
+    /// Returns the square root of this number.
+    fn sqrt(self) -> Self;
+    /// Returns the absolute value of this number.
+    fn abs(self) -> Self;
+    /// Returns the largest integer less than or equal to this number.
+    fn floor(self) -> Self;
+    /// Returns the nearest integer to this number, rounding half-way cases away from zero.
+    fn round(self) -> Self;
+    /// Returns `true` if this number is neither infinite nor NaN.
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($ty:ident, $sqrt:ident, $abs:ident, $floor:ident, $round:ident) => {
+        impl Float for $ty {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const EPSILON: Self = $ty::EPSILON;
+
+            fn from_f32(x: f32) -> Self { x as $ty }
+            fn from_f64(x: f64) -> Self { x as $ty }
+            fn to_f32(self) -> f32 { self as f32 }
+            fn to_f64(self) -> f64 { self as f64 }
+            #[cfg(feature = "std")]
+            fn sqrt(self) -> Self { $ty::sqrt(self) }
+            #[cfg(not(feature = "std"))]
+            fn sqrt(self) -> Self { libm::$sqrt(self) }
+            #[cfg(feature = "std")]
+            fn abs(self) -> Self { $ty::abs(self) }
+            #[cfg(not(feature = "std"))]
+            fn abs(self) -> Self { libm::$abs(self) }
+            #[cfg(feature = "std")]
+            fn floor(self) -> Self { $ty::floor(self) }
+            #[cfg(not(feature = "std"))]
+            fn floor(self) -> Self { libm::$floor(self) }
+            #[cfg(feature = "std")]
+            fn round(self) -> Self { $ty::round(self) }
+            #[cfg(not(feature = "std"))]
+            fn round(self) -> Self { libm::$round(self) }
+            fn is_finite(self) -> bool { $ty::is_finite(self) }
+        }
+    };
+}
+
+impl_float!(f32, sqrtf, fabsf, floorf, roundf);
+impl_float!(f64, sqrt, fabs, floor, round);
+
+/// Returns the cosine of an angle given in radians.
+pub(crate) fn cos(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    { x.cos() }
+    #[cfg(not(feature = "std"))]
+    { libm::cos(x) }
+}
+
+/// Returns the angle in radians whose cosine is the given value.
+pub(crate) fn acos(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    { x.acos() }
+    #[cfg(not(feature = "std"))]
+    { libm::acos(x) }
+}
+
+pub(crate) fn add<T: Float>(a: [T; 3], b: [T; 3]) -> [T; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub(crate) fn sub<T: Float>(a: [T; 3], b: [T; 3]) -> [T; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn dot<T: Float>(a: [T; 3], b: [T; 3]) -> T {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross<T: Float>(a: [T; 3], b: [T; 3]) -> [T; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn scale<T: Float>(a: [T; 3], s: T) -> [T; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+/// A coordinate of a cell in a grid used to find nearby points, see [`grid_cell`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum GridCoord {
+    /// The number of a cell along an axis.
+    Cell(i64),
+    /// The bit pattern of a component that isn't placed in a numbered cell, so that it only shares a cell with equal
+    /// components.
+    Exact(u64),
+}
+
+/// Returns the cell of a grid with cells `size` wide that contains a point.
+///
+/// A size that isn't positive matches points exactly, as do components that are infinite, NaN, or so far from the
+/// origin compared to the size that no other value lies within `size` of them. Negative zero shares a cell with zero.
+pub(crate) fn grid_cell<T: Float>(p: [T; 3], size: T) -> [GridCoord; 3] {
+    // Beyond this, numbers no longer distinguish neighbouring cells
+    const LIMIT: f64 = (1u64 << 53) as f64;
+    p.map(|x| {
+        let cell = (x / size).floor().to_f64();
+        // NaN fails both comparisons
+        if size > T::ZERO && cell.abs() < LIMIT {
+            GridCoord::Cell(cell as i64)
+        } else {
+            // Adding zero turns negative zeros into positive ones
+            GridCoord::Exact((x.to_f64() + 0.0).to_bits())
+        }
+    })
+}
+
+/// Returns a cell of a grid along with its neighbours, which hold every point within the size of the grid's cells of
+/// the points in it.
+pub(crate) fn grid_neighbours(cell: [GridCoord; 3]) -> impl Iterator<Item = [GridCoord; 3]> {
+    let [xs, ys, zs] = cell.map(|coord| match coord {
+        GridCoord::Cell(c) => [c.checked_sub(1), Some(c), c.checked_add(1)].map(|c| c.map(GridCoord::Cell)),
+        exact => [None, Some(exact), None],
+    });
+    IntoIterator::into_iter(xs).flatten().flat_map(move |x| {
+        IntoIterator::into_iter(ys)
+            .flatten()
+            .flat_map(move |y| IntoIterator::into_iter(zs).flatten().map(move |z| [x, y, z]))
+    })
+}
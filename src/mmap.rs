@@ -0,0 +1,52 @@
+use crate::{parse::Parser, Error, Float, GenericObj, ParseOptions};
+use std::{fs::File, io, path::Path};
+
+impl<T: Float> GenericObj<T> {
+    /// Read an OBJ from a file by mapping it into memory, avoiding the need to copy its contents into a buffer.
+    ///
+    /// Each line is checked to be valid UTF-8 as it is parsed, producing an [`Error::Io`] if it is not. Lines are
+    /// split in the same way as [`str::lines`], so the result is identical to that of [`GenericObj::from_file`].
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated (by this or any other process) while it is being parsed. Memory
+    /// maps offer no protection against this: modifications may be observed part-way through parsing and, on most
+    /// platforms, truncating a mapped file causes the process to be killed by `SIGBUS` when the missing pages are
+    /// accessed.
+    ///
+    /// As a safeguard, the size and modification time of the file are compared before and after parsing, and an
+    /// [`Error::Io`] is produced if either has changed. This catches most accidental modification, but cannot
+    /// prevent the problems described above.
+    pub unsafe fn from_file_mmap<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        let before = file.metadata()?;
+        // An empty file cannot be mapped on every platform, and contains nothing to parse anyway
+        if before.len() == 0 {
+            return Parser::new(&ParseOptions::default()).finish();
+        }
+        let map = memmap2::Mmap::map(&file)?;
+
+        let mut parser = Parser::new(&ParseOptions::default());
+        let mut bytes = &map[..];
+        let mut line_num = 0;
+        while !bytes.is_empty() {
+            let (line, rest) = match bytes.iter().position(|b| *b == b'\n') {
+                Some(end) => {
+                    let line = &bytes[..end];
+                    (line.strip_suffix(b"\r").unwrap_or(line), &bytes[end + 1..])
+                },
+                None => (bytes, &[][..]),
+            };
+            line_num += 1;
+            let line = core::str::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            parser.parse_line(line_num, line)?;
+            bytes = rest;
+        }
+
+        let after = file.metadata()?;
+        if before.len() != after.len() || before.modified().ok() != after.modified().ok() {
+            return Err(io::Error::new(io::ErrorKind::Other, "file was modified while it was being parsed").into());
+        }
+        parser.finish()
+    }
+}
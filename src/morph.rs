@@ -0,0 +1,138 @@
+use crate::{math, Float, GenericObj};
+use alloc::{sync::Arc, vec::Vec};
+use core::{fmt, num::NonZeroU32};
+
+/// An error produced by [`GenericObj::morph_delta`] when two OBJs don't share the same topology, describing the first
+/// place in which they diverge.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TopologyMismatch {
+    /// The OBJs have different numbers of polygons.
+    PolygonCount {
+        /// The number of polygons in the base OBJ.
+        base: usize,
+        /// The number of polygons in the target OBJ.
+        target: usize,
+    },
+    /// A polygon has a different number of vertices in each OBJ.
+    PolygonLength {
+        /// The index of the polygon.
+        polygon: usize,
+        /// The number of vertices of the polygon in the base OBJ.
+        base: usize,
+        /// The number of vertices of the polygon in the target OBJ.
+        target: usize,
+    },
+    /// A vertex of a polygon refers to different attributes in each OBJ.
+    Vertex {
+        /// The index of the polygon.
+        polygon: usize,
+        /// The index of the vertex within the polygon.
+        vertex: usize,
+        /// The zero-based position, texture coordinate and normal indices of the vertex in the base OBJ.
+        base: (usize, Option<usize>, Option<usize>),
+        /// The zero-based position, texture coordinate and normal indices of the vertex in the target OBJ.
+        target: (usize, Option<usize>, Option<usize>),
+    },
+    /// The OBJs have identical polygons, but different numbers of positions.
+    PositionCount {
+        /// The number of positions in the base OBJ.
+        base: usize,
+        /// The number of positions in the target OBJ.
+        target: usize,
+    },
+}
+
+impl fmt::Display for TopologyMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TopologyMismatch::PolygonCount { base, target } => {
+                write!(f, "Base has {} polygons but target has {}", base, target)
+            },
+            TopologyMismatch::PolygonLength { polygon, base, target } => write!(
+                f,
+                "Polygon {} has {} vertices in base but {} in target",
+                polygon, base, target,
+            ),
+            TopologyMismatch::Vertex { polygon, vertex, base, target } => write!(
+                f,
+                "Vertex {} of polygon {} has indices {:?} in base but {:?} in target",
+                vertex, polygon, base, target,
+            ),
+            TopologyMismatch::PositionCount { base, target } => {
+                write!(f, "Base has {} positions but target has {}", base, target)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TopologyMismatch {}
+
+impl<T: Float, N> GenericObj<T, N> {
+    /// Compute the difference between the positions of two OBJs with the same topology, such as a base mesh and one of
+    /// its blend shapes exported as separate files.
+    ///
+    /// The OBJs must have the same polygons, each made up of vertices with the same position, texture coordinate and
+    /// normal indices, and the same number of positions. Indices are compared after being resolved, so a target that
+    /// uses relative indices where the base uses absolute ones still matches. If the topology differs, the first
+    /// divergence is returned. Otherwise, the returned deltas hold `target - base` for every position, and can be
+    /// blended back with [`GenericObj::apply_morph`].
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let base: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+    /// let target: Obj = "v 0 0 1\nv 1 0 1\nv 0 1 1\nf -3 -2 -1\n".parse().unwrap();
+    /// let deltas = Obj::morph_delta(&base, &target).unwrap();
+    /// assert_eq!(deltas, vec![[0.0, 0.0, 1.0]; 3]);
+    /// ```
+    pub fn morph_delta(base: &Self, target: &Self) -> Result<Vec<[T; 3]>, TopologyMismatch> {
+        check_topology(base, target)?;
+        let (a, b) = (&base.buffers, &target.buffers);
+        Ok(a.positions.iter().zip(b.positions.iter()).map(|(x, y)| math::sub(*y, *x)).collect())
+    }
+
+    /// Move every position of this OBJ by the corresponding delta (as produced by [`GenericObj::morph_delta`]),
+    /// scaled by `weight`.
+    ///
+    /// A weight of `1.0` applied to the base OBJ produces the target's positions, and `0.5` moves halfway towards them.
+    /// Several morphs may be blended by applying each of them in turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of deltas differs from the number of positions.
+    pub fn apply_morph(&mut self, deltas: &[[T; 3]], weight: T) {
+        let positions = Arc::make_mut(&mut self.buffers.positions);
+        assert_eq!(deltas.len(), positions.len(), "morph has a different number of positions to the OBJ");
+        for (p, d) in positions.iter_mut().zip(deltas) {
+            *p = math::add(*p, d.map(|x| x * weight));
+        }
+    }
+}
+
+/// Check that two OBJs have the same topology, as described by [`GenericObj::morph_delta`].
+pub(crate) fn check_topology<T, N>(base: &GenericObj<T, N>, target: &GenericObj<T, N>) -> Result<(), TopologyMismatch> {
+    let (a, b) = (&base.buffers, &target.buffers);
+    if a.polygons.len() != b.polygons.len() {
+        return Err(TopologyMismatch::PolygonCount { base: a.polygons.len(), target: b.polygons.len() });
+    }
+    for (polygon, (x, y)) in a.polygons.iter().zip(b.polygons.iter()).enumerate() {
+        let (xs, ys) = (&a.vertices[x.start..x.end], &b.vertices[y.start..y.end]);
+        if xs.len() != ys.len() {
+            return Err(TopologyMismatch::PolygonLength { polygon, base: xs.len(), target: ys.len() });
+        }
+        if let Some((vertex, (x, y))) = xs.iter().zip(ys).enumerate().find(|(_, (x, y))| x != y) {
+            let (base, target) = (resolve(*x), resolve(*y));
+            return Err(TopologyMismatch::Vertex { polygon, vertex, base, target });
+        }
+    }
+    if a.positions.len() != b.positions.len() {
+        return Err(TopologyMismatch::PositionCount { base: a.positions.len(), target: b.positions.len() });
+    }
+    Ok(())
+}
+
+fn resolve((pos, uv, norm): (NonZeroU32, Option<NonZeroU32>, Option<NonZeroU32>)) -> (usize, Option<usize>, Option<usize>) {
+    let index = |idx: NonZeroU32| idx.get() as usize - 1;
+    (index(pos), uv.map(index), norm.map(index))
+}
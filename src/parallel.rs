@@ -0,0 +1,205 @@
+use crate::{
+    parse::{parse_attribute, Capacities, LineCounts, ParseOptions, Parser, MAX_ATTRIBUTES},
+    pull::FaceTerm,
+    util::Keyword, Error, Float, GenericObj, Polygon, Vertex, VertexIndices, VertexRange,
+};
+use alloc::vec::Vec;
+use rayon::{iter::Either, prelude::*};
+
+/// Inputs smaller than this are parsed serially, since the overhead of splitting the work outweighs the benefit.
+const MIN_PARALLEL_LEN: usize = 1 << 20;
+/// The smallest chunk that an input will be split into.
+const MIN_CHUNK_LEN: usize = 1 << 18;
+
+impl<T: Float> GenericObj<T> {
+    /// Read an OBJ from a string, parsing it on multiple threads.
+    ///
+    /// The input is split into chunks at line boundaries. Vertex attributes are parsed from every chunk in parallel,
+    /// after which faces are parsed in parallel with indices resolved against the number of attributes preceding each
+    /// chunk. Objects and groups are then assembled serially. The result (and any error) is identical to that of
+    /// [`GenericObj::from_lines`].
+    ///
+    /// Small inputs are parsed serially.
+    pub fn from_str_parallel(src: &str) -> Result<Self, Error> {
+        if src.len() < MIN_PARALLEL_LEN {
+            return Self::from_lines(src.lines());
+        }
+
+        let chunks = split_lines(src, (src.len() / (rayon::current_num_threads() * 4)).max(MIN_CHUNK_LEN));
+
+        // Phase 1: parse attributes
+        let mut attributes = chunks
+            .par_iter()
+            .map(|chunk| {
+                let mut attributes = ChunkAttributes::default();
+                for line in chunk.lines() {
+                    attributes.line_counts.count_line(line);
+                    let mut terms = line.split_ascii_whitespace();
+                    match terms.next().and_then(Keyword::parse) {
+                        Some(Keyword::Position) => attributes.positions.push(parse_attribute(terms)),
+                        Some(Keyword::Uv) => attributes.uvs.push(parse_attribute(terms)),
+                        Some(Keyword::Normal) => attributes.normals.push(parse_attribute(terms)),
+                        _ => {},
+                    }
+                }
+                attributes.lines = chunk.bytes().filter(|b| *b == b'\n').count();
+                attributes
+            })
+            .collect::<Vec<_>>();
+
+        // The attribute counts and line number at the start of each chunk
+        let mut starts = Vec::with_capacity(chunks.len());
+        let mut start = ([0; 3], 1);
+        for attributes in &attributes {
+            starts.push(start);
+            start.0[0] += attributes.positions.len();
+            start.0[1] += attributes.uvs.len();
+            start.0[2] += attributes.normals.len();
+            start.1 += attributes.lines;
+        }
+
+        // Phase 2: parse faces, and find the statements that must be handled in order
+        let faces = chunks
+            .par_iter()
+            .zip(starts.par_iter())
+            .map(|(chunk, &(mut lengths, line_num))| {
+                let mut faces = ChunkFaces::default();
+                for (i, line) in chunk.lines().enumerate() {
+                    let line_num = line_num + i;
+                    let mut terms = line.split_ascii_whitespace();
+                    match terms.next().and_then(Keyword::parse) {
+                        Some(kind @ (Keyword::Position | Keyword::Uv | Keyword::Normal)) => {
+                            let len = &mut lengths[match kind { Keyword::Position => 0, Keyword::Uv => 1, _ => 2 }];
+                            // Report the first attribute past the limit, as the serial parser does
+                            if *len >= MAX_ATTRIBUTES {
+                                return Err(Error::TooManyAttributes(line_num));
+                            }
+                            *len += 1;
+                        },
+                        Some(Keyword::Face) => {
+                            for term in terms {
+                                faces.vertices.push(FaceTerm::parse(line_num, term)?.resolve(lengths)?);
+                            }
+                            faces.statements.push(Statement::Face(line_num, faces.vertices.len()));
+                        },
+                        Some(Keyword::Group | Keyword::Object) => faces.statements.push(Statement::Line(line_num, line)),
+                        _ => {},
+                    }
+                }
+                Ok(faces)
+            })
+            .collect::<Vec<Result<_, Error>>>();
+
+        // Phase 3: assemble the OBJ, reporting the first error in the order it appears in the input
+        let mut parser = Parser::with_capacities(&ParseOptions::default(), Capacities {
+            positions: start.0[0],
+            uvs: start.0[1],
+            normals: start.0[2],
+            vertices: faces.iter().flatten().map(|f| f.vertices.len()).sum(),
+            polygons: faces
+                .iter()
+                .flatten()
+                .map(|f| f.statements.iter().filter(|s| matches!(s, Statement::Face(..))).count())
+                .sum(),
+        });
+        for (attributes, faces) in attributes.iter_mut().zip(faces) {
+            let mut faces = faces?;
+            let base = parser.vertex_count();
+            parser.append(&mut attributes.positions, &mut attributes.uvs, &mut attributes.normals, &mut faces.vertices);
+            let mut start = base;
+            for statement in faces.statements {
+                match statement {
+                    Statement::Face(line_num, end) => {
+                        parser.push_polygon(line_num, VertexRange { start, end: base + end });
+                        start = base + end;
+                    },
+                    Statement::Line(line_num, line) => parser.parse_line(line_num, line)?,
+                }
+            }
+        }
+        // Only some lines were given to the parser, so the lines counted while parsing attributes are used instead
+        let mut line_counts = LineCounts::default();
+        attributes.iter().for_each(|attributes| line_counts.add(&attributes.line_counts));
+        parser.set_line_counts(line_counts);
+        parser.finish()
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Returns a parallel iterator over the [`Polygon`]s in this [`Obj`](crate::Obj), in the same order as
+    /// [`GenericObj::polygons`].
+    pub fn par_polygons(&self) -> impl IndexedParallelIterator<Item=Polygon<'_, T>> + '_ {
+        (0..self.buffers.polygons.len()).into_par_iter().map(move |index| self.buffers.lookup(index))
+    }
+
+    /// Returns a parallel iterator over the triangles in this [`Obj`](crate::Obj), in the same order as
+    /// [`GenericObj::triangles`].
+    ///
+    /// If the triangles have been cached (see [`GenericObj::cache_triangles`]), the work is split evenly across the
+    /// cached triangles. Otherwise, it is split across polygons, each of which is triangulated on the thread that
+    /// picks it up.
+    ///
+    /// ```
+    /// use rayon::{iter::Either, prelude::*};
+    /// use wavefront::Obj;
+    ///
+    /// let obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// assert_eq!(obj.par_triangles().count(), 12);
+    /// ```
+    pub fn par_triangles(&self) -> impl ParallelIterator<Item=[Vertex<'_, T>; 3]> + '_ {
+        let buffers = &self.buffers;
+        match &self.triangles {
+            Some(triangles) => Either::Left(triangles.par_iter().map(move |triangle| triangle.map(|index| Vertex {
+                buffers,
+                indices: buffers.vertices[index],
+            }))),
+            None => Either::Right(self.par_polygons().flat_map_iter(|poly| poly.triangles())),
+        }
+    }
+}
+
+struct ChunkAttributes<T> {
+    positions: Vec<[T; 3]>,
+    uvs: Vec<[T; 3]>,
+    normals: Vec<[T; 3]>,
+    lines: usize,
+    line_counts: LineCounts,
+}
+
+impl<T> Default for ChunkAttributes<T> {
+    fn default() -> Self {
+        Self { positions: Vec::new(), uvs: Vec::new(), normals: Vec::new(), lines: 0, line_counts: LineCounts::default() }
+    }
+}
+
+#[derive(Default)]
+struct ChunkFaces<'a> {
+    vertices: Vec<VertexIndices>,
+    statements: Vec<Statement<'a>>,
+}
+
+/// A statement that depends on the objects and groups that precede it.
+enum Statement<'a> {
+    /// A face, with its line number and the end of its range of vertices within the chunk.
+    Face(usize, usize),
+    /// A `g` or `o` statement, with its line number.
+    Line(usize, &'a str),
+}
+
+/// Split a string into chunks of roughly the given length, each ending just after a newline (except the last).
+fn split_lines(src: &str, len: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = src;
+    while rest.len() > len {
+        match rest.as_bytes()[len..].iter().position(|b| *b == b'\n') {
+            Some(end) => {
+                let (chunk, tail) = rest.split_at(len + end + 1);
+                chunks.push(chunk);
+                rest = tail;
+            },
+            None => break,
+        }
+    }
+    chunks.push(rest);
+    chunks
+}
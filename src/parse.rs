@@ -0,0 +1,1127 @@
+use crate::{
+    pull::{is_known_keyword, parse_event, split_keyword, ObjEvent},
+    sink::{MeshSink, Resolver},
+    util::Keyword,
+    util, Buffers, Error, Float, GenericObj, Index, NameIndex, VertexIndices, VertexRange,
+};
+use alloc::{
+    format,
+    rc::Rc,
+    vec::Vec,
+    string::{String, ToString},
+    sync::Arc,
+};
+use core::{
+    borrow::Borrow,
+    cell::RefCell,
+    convert::TryFrom,
+    fmt,
+    hash::Hash,
+    num::NonZeroU32,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use hashbrown::HashMap;
+
+/// The maximum number of each kind of vertex attribute, such that every index fits in a `u32`.
+pub(crate) const MAX_ATTRIBUTES: usize = u32::MAX as usize;
+
+/// The number of bytes parsed between each report of progress.
+const PROGRESS_INTERVAL: u64 = 1 << 20;
+
+/// The number of lines parsed between each check for cancellation.
+const CANCEL_INTERVAL: usize = 1024;
+
+/// Options that control how an OBJ is parsed.
+///
+/// Options are configured with a builder-style API and passed to constructors such as
+/// [`GenericObj::from_file_with`]. The default options are used by constructors without the `_with` suffix.
+///
+/// ```
+/// use wavefront::{Obj, ParseOptions};
+///
+/// let obj = Obj::from_file_with("tests/ship.obj", ParseOptions::new().preallocate(true)).unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ParseOptions {
+    pub(crate) preallocate: bool,
+    pub(crate) skip_uvs: bool,
+    pub(crate) skip_normals: bool,
+    pub(crate) include_objects: Option<Vec<String>>,
+    pub(crate) include_groups: Option<Vec<String>>,
+    pub(crate) compact: bool,
+    pub(crate) cache_triangles: bool,
+    pub(crate) track_lines: bool,
+    pub(crate) strict: bool,
+    pub(crate) map_position: Option<AttributeHook>,
+    pub(crate) map_uv: Option<AttributeHook>,
+    pub(crate) map_normal: Option<AttributeHook>,
+    pub(crate) progress: Option<ProgressCallback>,
+    pub(crate) on_unknown: Option<UnknownCallback>,
+    pub(crate) cancel_flag: Option<Arc<AtomicBool>>,
+    pub(crate) max_attributes: [Option<usize>; 3],
+    pub(crate) keep_comments: bool,
+}
+
+impl ParseOptions {
+    /// Create a new set of options with the default behaviour.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan the input before parsing it to count its attributes and faces, so that each buffer can be allocated
+    /// with the right capacity up front instead of being repeatedly grown (default: `false`).
+    ///
+    /// This trades a fast extra pass over the input for fewer reallocations and a lower peak memory usage, which is
+    /// worthwhile for large files. The scan is only possible when the input can be read twice: a string, a file, or
+    /// a seekable reader. For other inputs this option has no effect.
+    pub fn preallocate(mut self, preallocate: bool) -> Self {
+        self.preallocate = preallocate;
+        self
+    }
+
+    /// Ignore texture coordinates (default: `false`).
+    ///
+    /// `vt` lines are skipped without being parsed and the texture coordinate indices of face terms are discarded
+    /// after their syntax has been checked, so every [`Vertex::uv`](crate::Vertex::uv) is `None`. This saves time and
+    /// memory when only the geometry of a model is needed.
+    pub fn skip_uvs(mut self, skip_uvs: bool) -> Self {
+        self.skip_uvs = skip_uvs;
+        self
+    }
+
+    /// Ignore normals (default: `false`).
+    ///
+    /// Like [`ParseOptions::skip_uvs`], but for `vn` lines and [`Vertex::normal`](crate::Vertex::normal).
+    pub fn skip_normals(mut self, skip_normals: bool) -> Self {
+        self.skip_normals = skip_normals;
+        self
+    }
+
+    /// Only parse faces that belong to one of the given objects (default: all objects).
+    ///
+    /// Faces in other objects are skipped without being parsed, and those objects are absent from the result. Faces
+    /// that precede the first `o` statement belong to the object named `""`. Vertex attributes are always parsed, so
+    /// that relative indices resolve as they would otherwise; use [`ParseOptions::compact`] to discard those that
+    /// are left unreferenced.
+    pub fn include_objects(mut self, names: &[&str]) -> Self {
+        self.include_objects = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Only parse faces that belong to one of the given groups (default: all groups).
+    ///
+    /// A face in several groups is kept if any of them is included, but only appears in those that are. Faces
+    /// without a group belong to the group named `""`. See [`ParseOptions::include_objects`] for more information.
+    pub fn include_groups(mut self, names: &[&str]) -> Self {
+        self.include_groups = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Remove vertex attributes that are not referenced by any face once parsing is complete (default: `false`).
+    ///
+    /// Attributes that remain are kept in the order in which they are first referenced.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Cache the triangles of the OBJ once it has been parsed (default: `false`).
+    ///
+    /// See [`GenericObj::cache_triangles`] for more information.
+    pub fn cache_triangles(mut self, cache_triangles: bool) -> Self {
+        self.cache_triangles = cache_triangles;
+        self
+    }
+
+    /// Record the line on which each polygon is defined (default: `false`).
+    ///
+    /// The line is available through [`Polygon::source_line`](crate::Polygon::source_line), which is useful for
+    /// pointing users at problems in their files. This costs an extra `usize` per polygon.
+    pub fn track_lines(mut self, track_lines: bool) -> Self {
+        self.track_lines = track_lines;
+        self
+    }
+
+    /// Reject input that the default, lenient parser accepts but that is almost certainly broken (default: `false`).
+    ///
+    /// Currently, this rejects positions, texture coordinates and normals with a NaN or infinite component (such as
+    /// `v nan 0 0`) with [`Error::NonFiniteAttribute`]. Such attributes can otherwise be found after parsing with
+    /// [`GenericObj::validate`]. It also rejects `o` statements without a valid name with [`Error::ExpectedName`],
+    /// which are otherwise given a name of their own (see [`Warning::InvalidObjectName`]), and `g` statements with an
+    /// invalid name with [`Error::InvalidGroupName`], which are otherwise ignored (see
+    /// [`Warning::InvalidGroupName`]).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Transform every position with the given function as it is parsed, before it is stored (default: no function).
+    ///
+    /// This is cheaper than transforming positions after parsing, such as to convert between units, and the result
+    /// is identical. Indices are unaffected, and positions are checked by [`ParseOptions::strict`] after being
+    /// transformed. The function works with `f32`, so positions of OBJs that store another type are converted to and
+    /// from `f32` around it. Clones of these options share the same function.
+    ///
+    /// ```
+    /// use wavefront::{Obj, ParseOptions};
+    ///
+    /// let scale = 0.001;
+    /// let options = ParseOptions::new().map_position(move |p| p.map(|x| x * scale));
+    /// let obj = Obj::from_str_with("v 1000 0 0\nv 0 1000 0\nv 0 0 1000\nf 1 2 3\n", options).unwrap();
+    /// assert_eq!(obj.positions()[0], [1.0, 0.0, 0.0]);
+    /// ```
+    pub fn map_position(mut self, f: impl Fn([f32; 3]) -> [f32; 3] + Send + Sync + 'static) -> Self {
+        self.map_position = Some(AttributeHook(Arc::new(f)));
+        self
+    }
+
+    /// Transform every texture coordinate with the given function as it is parsed, before it is stored (default: no
+    /// function).
+    ///
+    /// The function isn't called when [`ParseOptions::skip_uvs`] is enabled. See [`ParseOptions::map_position`] for
+    /// more information.
+    pub fn map_uv(mut self, f: impl Fn([f32; 3]) -> [f32; 3] + Send + Sync + 'static) -> Self {
+        self.map_uv = Some(AttributeHook(Arc::new(f)));
+        self
+    }
+
+    /// Transform every normal with the given function as it is parsed, before it is stored (default: no function).
+    ///
+    /// Normals are not renormalised afterwards, and the function isn't called when [`ParseOptions::skip_normals`] is
+    /// enabled. See [`ParseOptions::map_position`] for more information.
+    pub fn map_normal(mut self, f: impl Fn([f32; 3]) -> [f32; 3] + Send + Sync + 'static) -> Self {
+        self.map_normal = Some(AttributeHook(Arc::new(f)));
+        self
+    }
+
+    /// Call the given function periodically while parsing to report how much of the input has been parsed so far
+    /// (default: no callback).
+    ///
+    /// The function is called roughly once per megabyte of input, and once more when parsing has finished
+    /// successfully, so it adds negligible overhead. The total size of the input is known when parsing from a
+    /// string, a file, or a seekable reader. Clones of these options share the same function.
+    pub fn progress(mut self, progress: impl FnMut(Progress) + 'static) -> Self {
+        self.progress = Some(ProgressCallback(Rc::new(RefCell::new(progress))));
+        self
+    }
+
+    /// Call the given function for every statement with a keyword that isn't otherwise understood, such as `s`, `l`
+    /// or a custom `x_collision` (default: no callback).
+    ///
+    /// The function is given the line number, the keyword and the rest of the line after the keyword, exactly as
+    /// written but without the line terminator. This allows custom statements to be read alongside the geometry
+    /// without parsing the input twice. Returning an error abandons parsing with that error, for which
+    /// [`Error::Rejected`] may be used. Clones of these options share the same function.
+    ///
+    /// ```
+    /// use wavefront::{Obj, ParseOptions};
+    /// use std::{cell::RefCell, rc::Rc};
+    ///
+    /// let boxes = Rc::new(RefCell::new(Vec::new()));
+    /// let options = ParseOptions::new().on_unknown({
+    ///     let boxes = boxes.clone();
+    ///     move |line, keyword, rest| {
+    ///         if keyword == "x_collision" {
+    ///             boxes.borrow_mut().push((line, rest.to_string()));
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// });
+    /// Obj::from_str_with("v 0 0 0\nx_collision box 1 2 3\n", options).unwrap();
+    /// assert_eq!(*boxes.borrow(), vec![(2, " box 1 2 3".to_string())]);
+    /// ```
+    pub fn on_unknown(mut self, f: impl FnMut(usize, &str, &str) -> Result<(), Error> + 'static) -> Self {
+        self.on_unknown = Some(UnknownCallback(Rc::new(RefCell::new(f))));
+        self
+    }
+
+    /// Abandon parsing with [`Error::Cancelled`] once the given flag has been set (default: no flag).
+    ///
+    /// The flag is checked every 1024 lines, so a parse stops promptly after the flag is set from another thread
+    /// without the check slowing it down. Parsing from an asynchronous reader can also be cancelled by dropping its
+    /// future.
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(flag);
+        self
+    }
+
+    /// Fail with [`Error::TooManyAttributes`] if the input has more than the given number of positions (default:
+    /// `u32::MAX`, the most that an OBJ may contain).
+    ///
+    /// This protects against untrusted input that would otherwise use an unreasonable amount of memory. The error
+    /// is produced as soon as the first position past the limit is reached, before it is stored.
+    ///
+    /// ```
+    /// use wavefront::{Error, Obj, ParseOptions};
+    ///
+    /// let options = ParseOptions::new().max_positions(2);
+    /// let result = Obj::from_str_with("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n", options);
+    /// assert!(matches!(result, Err(Error::TooManyAttributes(3))));
+    /// ```
+    pub fn max_positions(mut self, max: usize) -> Self {
+        self.max_attributes[0] = Some(max);
+        self
+    }
+
+    /// Fail with [`Error::TooManyAttributes`] if the input has more than the given number of texture coordinates
+    /// (default: `u32::MAX`).
+    ///
+    /// Texture coordinates are counted even when [`ParseOptions::skip_uvs`] is enabled. See
+    /// [`ParseOptions::max_positions`] for more information.
+    pub fn max_uvs(mut self, max: usize) -> Self {
+        self.max_attributes[1] = Some(max);
+        self
+    }
+
+    /// Fail with [`Error::TooManyAttributes`] if the input has more than the given number of normals (default:
+    /// `u32::MAX`).
+    ///
+    /// Normals are counted even when [`ParseOptions::skip_normals`] is enabled. See [`ParseOptions::max_positions`]
+    /// for more information.
+    pub fn max_normals(mut self, max: usize) -> Self {
+        self.max_attributes[2] = Some(max);
+        self
+    }
+
+    /// Keep the comments in the input, which are otherwise discarded (default: `false`).
+    ///
+    /// Comments are available through [`Buffers::comments`](crate::Buffers::comments) along with the lines they were
+    /// on, which is useful for reading metadata that exporters leave in them. They are not written by
+    /// [`GenericObj::write`] or stored in caches.
+    ///
+    /// ```
+    /// use wavefront::{Obj, ParseOptions};
+    ///
+    /// let obj = Obj::from_str_with("# Exported by hand\nv 0 0 0\n", ParseOptions::new().keep_comments(true)).unwrap();
+    /// assert_eq!(obj.comments().collect::<Vec<_>>(), [(1, " Exported by hand")]);
+    /// ```
+    pub fn keep_comments(mut self, keep_comments: bool) -> Self {
+        self.keep_comments = keep_comments;
+        self
+    }
+
+    // The most of each kind of attribute that may be parsed
+    pub(crate) fn attribute_limits(&self) -> [usize; 3] {
+        self.max_attributes.map(|max| max.map_or(MAX_ATTRIBUTES, |max| max.min(MAX_ATTRIBUTES)))
+    }
+}
+
+/// The progress of an OBJ that is being parsed, as reported to the function given to [`ParseOptions::progress`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Progress {
+    /// The number of bytes of the input that have been parsed.
+    pub bytes: u64,
+    /// The total number of bytes in the input, if known.
+    pub total_bytes: Option<u64>,
+    /// The number of lines that have been parsed.
+    pub lines: usize,
+}
+
+impl Progress {
+    /// Returns the fraction of the input that has been parsed, between `0.0` and `1.0`, if the total size of the input
+    /// is known.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total_bytes {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.bytes as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+}
+
+/// A summary of how an OBJ was parsed, available through [`Buffers::parse_report`](crate::Buffers::parse_report).
+///
+/// OBJs that weren't parsed, or that were read from a cache, have an empty report.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseReport {
+    /// Problems with the input that the parser recovered from, in the order of the lines they're on.
+    pub warnings: Vec<Warning>,
+    /// The number of each kind of line in the input.
+    pub line_counts: LineCounts,
+}
+
+/// The number of each kind of line in a parsed OBJ, see [`ParseReport::line_counts`].
+///
+/// Lines are counted as they're parsed, so this is a cheap way to check what a file contains. A large number of
+/// `unknown` lines suggests that the file uses an extension of the format that isn't being handled.
+///
+/// ```
+/// use wavefront::Obj;
+///
+/// let obj: Obj = "# A triangle\nv 0 0 0\nv 1 0 0\nv 0 1 0\n\ns off\nf 1 2 3\n".parse().unwrap();
+/// let counts = obj.parse_report().line_counts;
+/// assert_eq!((counts.total, counts.geometry, counts.unsupported), (7, 4, 1));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct LineCounts {
+    /// Every line, of any kind.
+    pub total: usize,
+    /// Lines beginning with `#`, after any whitespace.
+    pub comments: usize,
+    /// Lines containing only whitespace.
+    pub blank: usize,
+    /// Vertex attribute and face statements: `v`, `vt`, `vn` and `f`, including those that were skipped (such as by
+    /// [`ParseOptions::skip_uvs`]).
+    pub geometry: usize,
+    /// Statements that arrange faces into objects, groups and materials: `o`, `g`, `usemtl` and `mtllib`.
+    pub structure: usize,
+    /// Statements with a keyword of the OBJ format that the parser ignores, such as `s`, `l` or `curv` (see
+    /// [`util::Keyword::is_supported`]).
+    pub unsupported: usize,
+    /// Statements with a keyword that isn't part of the OBJ format, such as a custom `x_collision`.
+    pub unknown: usize,
+}
+
+impl LineCounts {
+    /// Count a line, given its keyword (see [`split_keyword`]) and the keyword that it parses as, or `None` if the
+    /// line is blank.
+    #[inline]
+    pub(crate) fn count(&mut self, statement: Option<(&str, Option<Keyword>)>) {
+        self.total += 1;
+        let count = match statement {
+            None => &mut self.blank,
+            Some(("#", _)) => &mut self.comments,
+            Some((_, Some(Keyword::Position | Keyword::Uv | Keyword::Normal | Keyword::Face))) => &mut self.geometry,
+            Some((_, Some(keyword))) if keyword.is_supported() => &mut self.structure,
+            Some((_, Some(_))) => &mut self.unsupported,
+            Some((_, None)) => &mut self.unknown,
+        };
+        *count += 1;
+    }
+
+    /// Count a whole line.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn count_line(&mut self, line: &str) {
+        self.count(split_keyword(line).map(|(keyword, _)| (keyword, Keyword::parse(keyword))));
+    }
+
+    /// Add the counts of another part of the input.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn add(&mut self, other: &Self) {
+        self.total += other.total;
+        self.comments += other.comments;
+        self.blank += other.blank;
+        self.geometry += other.geometry;
+        self.structure += other.structure;
+        self.unsupported += other.unsupported;
+        self.unknown += other.unknown;
+    }
+}
+
+/// A problem with the input that the parser recovered from, see [`ParseReport::warnings`].
+///
+/// Each of these is an error instead if [`ParseOptions::strict`] is enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// The `o` statement on the given line didn't have a valid name (see [`util::name_is_valid`]), only the given
+    /// text (which may be empty), so the object was renamed.
+    ///
+    /// Objects are renamed to `object_` followed by the line number, with a further suffix if another object already
+    /// has that name. When names are borrowed from the input (see [`GenericObj::from_str_borrowed`]), new names
+    /// can't be made, so the object becomes part of the unnamed object and `renamed` is empty.
+    InvalidObjectName {
+        /// The line of the `o` statement.
+        line: usize,
+        /// The text following the `o`.
+        name: String,
+        /// The name that the object was given.
+        renamed: String,
+    },
+    /// The `g` statement on the given line has a name that isn't valid (see [`util::name_is_valid`]), which was
+    /// ignored.
+    ///
+    /// The faces that follow belong to the other groups named by the statement, or to the default group if there
+    /// are none. Every invalid name produces a warning of its own.
+    InvalidGroupName {
+        /// The line of the `g` statement.
+        line: usize,
+        /// The name that was ignored.
+        name: String,
+    },
+}
+
+impl Warning {
+    /// Returns the line that the warning is about.
+    pub fn line(&self) -> usize {
+        match self {
+            Warning::InvalidObjectName { line, .. } | Warning::InvalidGroupName { line, .. } => *line,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::InvalidObjectName { line, name, renamed } => {
+                write!(f, "Invalid object name '{}' on line {} was replaced with '{}'", name, line, renamed)
+            },
+            Warning::InvalidGroupName { line, name } => write!(f, "Invalid group name '{}' on line {} was ignored", name, line),
+        }
+    }
+}
+
+/// A function given to [`ParseOptions::map_position`] or one of its siblings.
+#[derive(Clone)]
+pub(crate) struct AttributeHook(Arc<dyn Fn([f32; 3]) -> [f32; 3] + Send + Sync>);
+
+impl AttributeHook {
+    fn apply<T: Float>(hook: &Option<Self>, attr: &mut [T; 3]) {
+        if let Some(AttributeHook(f)) = hook {
+            *attr = f(attr.map(T::to_f32)).map(T::from_f32);
+        }
+    }
+}
+
+impl fmt::Debug for AttributeHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("AttributeHook")
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ProgressCallback(Rc<RefCell<dyn FnMut(Progress)>>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+type UnknownFn = dyn FnMut(usize, &str, &str) -> Result<(), Error>;
+
+#[derive(Clone)]
+pub(crate) struct UnknownCallback(Rc<RefCell<UnknownFn>>);
+
+impl fmt::Debug for UnknownCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("UnknownCallback")
+    }
+}
+
+/// Fail with [`Error::Cancelled`] if the flag given to [`ParseOptions::cancel_flag`] has been set, checking it only
+/// once every [`CANCEL_INTERVAL`] lines.
+#[inline]
+pub(crate) fn check_cancelled(flag: Option<&AtomicBool>, line_num: usize) -> Result<(), Error> {
+    match flag {
+        Some(flag) if line_num % CANCEL_INTERVAL == 0 && flag.load(Ordering::Relaxed) => Err(Error::Cancelled),
+        _ => Ok(()),
+    }
+}
+
+/// Tracks how much of the input has been parsed, reporting it to the callback given to [`ParseOptions::progress`]
+/// and checking the flag given to [`ParseOptions::cancel_flag`] at intervals. Statements that the parser ignores are
+/// passed to the callback given to [`ParseOptions::on_unknown`].
+///
+/// This is kept apart from [`Parser`] so that the parser remains `Send` even though the callbacks need not be.
+pub(crate) struct Reporter {
+    callback: Option<ProgressCallback>,
+    on_unknown: Option<UnknownCallback>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    progress: Progress,
+    next_report: u64,
+}
+
+impl Reporter {
+    pub fn new(options: &ParseOptions) -> Self {
+        Self {
+            callback: options.progress.clone(),
+            on_unknown: options.on_unknown.clone(),
+            cancel_flag: options.cancel_flag.clone(),
+            progress: Progress { bytes: 0, total_bytes: None, lines: 0 },
+            next_report: PROGRESS_INTERVAL,
+        }
+    }
+
+    /// Returns `true` if progress is being reported, such that the size of the input is worth finding.
+    #[cfg(feature = "std")]
+    pub fn wants_total_bytes(&self) -> bool {
+        self.callback.is_some()
+    }
+
+    /// Set the total size of the input.
+    pub fn total_bytes(mut self, total: u64) -> Self {
+        self.progress.total_bytes = Some(total);
+        self
+    }
+
+    /// Record that a line, given its (1-based) line number, has been parsed, failing if parsing has been cancelled.
+    #[inline]
+    pub fn line(&mut self, line_num: usize, line: &str) -> Result<(), Error> {
+        check_cancelled(self.cancel_flag.as_deref(), line_num)?;
+        if self.callback.is_some() {
+            // Line terminators may have been stripped, in which case assume that the line ended with a newline
+            self.progress.bytes += (line.len() + !line.ends_with('\n') as usize) as u64;
+            self.progress.lines = line_num;
+            if self.progress.bytes >= self.next_report {
+                self.report();
+            }
+        }
+        Ok(())
+    }
+
+    /// Pass a line, given its (1-based) line number within its file, to the callback given to
+    /// [`ParseOptions::on_unknown`] if it is a statement that the parser ignores.
+    #[inline]
+    pub fn statement(&mut self, line_num: usize, line: &str) -> Result<(), Error> {
+        let callback = match &self.on_unknown {
+            Some(callback) => callback,
+            None => return Ok(()),
+        };
+        let line = line.strip_suffix('\n').map_or(line, |line| line.strip_suffix('\r').unwrap_or(line));
+        let line = line.trim_start_matches(|c: char| c.is_ascii_whitespace());
+        let end = line.find(|c: char| c.is_ascii_whitespace()).unwrap_or(line.len());
+        let (keyword, rest) = line.split_at(end);
+        if keyword.is_empty() || keyword.starts_with('#') || is_known_keyword(keyword) {
+            return Ok(());
+        }
+        (callback.0.borrow_mut())(line_num, keyword, rest)
+    }
+
+    /// Record that the whole input has been parsed successfully.
+    pub fn finish(&mut self) {
+        if self.callback.is_some() {
+            // The byte count may only be approximate, so correct it now that it is known
+            self.progress.bytes = self.progress.total_bytes.unwrap_or(self.progress.bytes);
+            self.report();
+        }
+    }
+
+    fn report(&mut self) {
+        if let Some(callback) = &self.callback {
+            (callback.0.borrow_mut())(self.progress);
+        }
+        self.next_report = self.progress.bytes + PROGRESS_INTERVAL;
+    }
+}
+
+/// The number of each kind of element in an OBJ, as counted by a quick scan of its lines.
+#[derive(Default)]
+pub(crate) struct Capacities {
+    pub positions: usize,
+    pub uvs: usize,
+    pub normals: usize,
+    pub vertices: usize,
+    pub polygons: usize,
+}
+
+impl Capacities {
+    pub fn count_line(&mut self, line: &[u8]) {
+        let line = match line.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(start) => &line[start..],
+            None => return,
+        };
+        let keyword = line.iter().position(u8::is_ascii_whitespace).unwrap_or(line.len());
+        match &line[..keyword] {
+            b"v" => self.positions += 1,
+            b"vt" => self.uvs += 1,
+            b"vn" => self.normals += 1,
+            b"f" => {
+                // Terms are almost always separated by single spaces, so counting separators is a cheap estimate of the
+                // number of terms (the buffers will simply grow as normal if this underestimates)
+                let terms = &line[keyword..];
+                let terms = &terms[..terms.iter().rposition(|b| !b.is_ascii_whitespace()).map_or(0, |i| i + 1)];
+                self.vertices += terms.iter().filter(|b| matches!(b, b' ' | b'\t')).count();
+                self.polygons += 1;
+            },
+            _ => {},
+        }
+    }
+}
+
+/// A type that object and group names can be stored as.
+pub(crate) trait Name<'a>: Borrow<str> + Eq + Hash + Default {
+    fn new(name: &'a str) -> Self;
+
+    /// Make a name that doesn't appear in the input, if names of this type can be made.
+    fn synthesize(name: String) -> Option<Self>;
+}
+
+impl<'a> Name<'a> for String {
+    fn new(name: &'a str) -> Self {
+        name.to_string()
+    }
+
+    fn synthesize(name: String) -> Option<Self> {
+        Some(name)
+    }
+}
+
+impl<'a, 'b: 'a> Name<'b> for &'a str {
+    fn new(name: &'b str) -> Self {
+        name
+    }
+
+    fn synthesize(_: String) -> Option<Self> {
+        None
+    }
+}
+
+/// The state of an OBJ that is being parsed one line at a time, storing object and group names as `N`.
+pub(crate) struct Parser<T, N = String> {
+    sink: ObjSink<T, N>,
+    resolver: Resolver,
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash + Default> Parser<T, N> {
+    pub fn new(options: &ParseOptions) -> Self {
+        Self::with_capacities(options, Capacities::default())
+    }
+
+    /// Create a parser for the given string, scanning it first if [`ParseOptions::preallocate`] is enabled.
+    pub fn for_str(src: &str, options: &ParseOptions) -> Self {
+        if options.preallocate {
+            let mut capacities = Capacities::default();
+            src.lines().for_each(|line| capacities.count_line(line.as_bytes()));
+            Self::with_capacities(options, capacities)
+        } else {
+            Self::new(options)
+        }
+    }
+
+    pub fn with_capacities(options: &ParseOptions, capacities: Capacities) -> Self {
+        Self {
+            sink: ObjSink::with_capacities(options, capacities),
+            // Indices are checked once parsing is complete, so faces may refer to attributes defined after them
+            resolver: Resolver::new(true).with_limits(options.attribute_limits()),
+        }
+    }
+
+    /// Parse a single line of an OBJ, given its (1-based) line number.
+    pub fn parse_line<'a>(&mut self, line_num: usize, line: &'a str) -> Result<(), Error> where N: Name<'a> {
+        let sink = &mut self.sink;
+        let (keyword, rest) = match split_keyword(line) {
+            Some(statement) => statement,
+            None => {
+                sink.line_counts.count(None);
+                return Ok(());
+            },
+        };
+        let parsed = Keyword::parse(keyword);
+        sink.line_counts.count(Some((keyword, parsed)));
+        // Statements whose contents would be discarded anyway aren't parsed at all
+        match parsed {
+            Some(Keyword::Uv) if sink.skip_uvs => self.resolver.skip(1, line_num)?,
+            Some(Keyword::Normal) if sink.skip_normals => self.resolver.skip(2, line_num)?,
+            Some(Keyword::Face) if sink.object_excluded || sink.groups_excluded => {},
+            None if keyword == "#" => if let Some(comments) = &mut sink.comments {
+                comments.push((line_num, String::from(rest.trim_end_matches(['\n', '\r']))));
+            },
+            _ => {
+                sink.line_num = line_num;
+                let mut event = match parse_event(line_num, keyword, rest) {
+                    // Only `o` statements produce this, and the faces that follow one can be kept under another name
+                    Err(Error::ExpectedName(_)) if !sink.strict => {
+                        sink.begin_renamed_object(line_num, rest);
+                        return Ok(());
+                    },
+                    event => event?,
+                };
+                match &mut event {
+                    ObjEvent::Position(attr) => AttributeHook::apply(&sink.map_position, attr),
+                    ObjEvent::Uv(attr) => AttributeHook::apply(&sink.map_uv, attr),
+                    ObjEvent::Normal(attr) => AttributeHook::apply(&sink.map_normal, attr),
+                    _ => {},
+                }
+                if sink.strict {
+                    if let ObjEvent::Position(attr) | ObjEvent::Uv(attr) | ObjEvent::Normal(attr) = &event {
+                        if !attr.iter().all(|x: &T| x.is_finite()) {
+                            return Err(Error::NonFiniteAttribute(line_num));
+                        }
+                    }
+                }
+                // Invalid group names are skipped individually, so the valid names around them are still used
+                if let ObjEvent::Group(names) = &event {
+                    for name in names.iter().filter(|name| !util::name_is_valid(name)) {
+                        if sink.strict {
+                            return Err(Error::InvalidGroupName(line_num, String::from(*name)));
+                        }
+                        sink.warnings.push(Warning::InvalidGroupName { line: line_num, name: String::from(*name) });
+                    }
+                }
+                self.resolver.apply(sink, line_num, event)?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Add a polygon made of vertices that have already been parsed to the currently selected groups.
+    #[cfg(feature = "rayon")]
+    pub fn push_polygon(&mut self, line_num: usize, range: VertexRange) {
+        self.sink.push_polygon(line_num, range);
+    }
+
+    /// Append attributes and vertices that have been parsed elsewhere.
+    #[cfg(feature = "rayon")]
+    pub fn append(
+        &mut self,
+        positions: &mut Vec<[T; 3]>,
+        uvs: &mut Vec<[T; 3]>,
+        normals: &mut Vec<[T; 3]>,
+        vertices: &mut Vec<VertexIndices>,
+    ) {
+        let sink = &mut self.sink;
+        self.resolver.lengths[0] += positions.len();
+        self.resolver.lengths[1] += uvs.len();
+        self.resolver.lengths[2] += normals.len();
+        sink.positions.append(positions);
+        sink.uvs.append(uvs);
+        sink.normals.append(normals);
+        sink.vertices.append(vertices);
+    }
+
+    /// Replace the counts of the lines parsed so far, when lines have been counted elsewhere.
+    #[cfg(feature = "rayon")]
+    pub fn set_line_counts(&mut self, line_counts: LineCounts) {
+        self.sink.line_counts = line_counts;
+    }
+
+    /// Returns the number of vertices parsed so far.
+    #[cfg(feature = "rayon")]
+    pub fn vertex_count(&self) -> usize {
+        self.sink.vertices.len()
+    }
+
+    /// Finish parsing, validating the indices of every vertex.
+    pub fn finish<'a>(self) -> Result<GenericObj<T, N>, Error> where N: Clone + Name<'a> {
+        self.sink.finish()
+    }
+}
+
+/// A [`MeshSink`] that builds an OBJ.
+struct ObjSink<T, N> {
+    positions: Vec<[T; 3]>,
+    uvs: Vec<[T; 3]>,
+    normals: Vec<[T; 3]>,
+    vertices: Vec<VertexIndices>,
+    polygons: Vec<VertexRange>,
+    // The line on which each polygon is defined, if `ParseOptions::track_lines` is enabled
+    lines: Option<Vec<usize>>,
+    // The comments and their lines, if `ParseOptions::keep_comments` is enabled
+    comments: Option<Vec<(usize, String)>>,
+    // The material of each polygon, as a (1-based) index into `material_names`
+    materials: Vec<Option<NonZeroU32>>,
+    material_names: Vec<String>,
+    material_ids: HashMap<String, NonZeroU32>,
+    material: Option<NonZeroU32>,
+    objects: HashMap<N, HashMap<N, Vec<usize>>>,
+    // Objects without a valid name, with the line and text of their `o` statements, which are named once every other
+    // name is known. Their groups are empty if they're excluded
+    renamed_objects: Vec<(usize, String, HashMap<N, Vec<usize>>)>,
+    warnings: Vec<Warning>,
+    line_counts: LineCounts,
+    // The line of the statement currently being applied
+    line_num: usize,
+
+    object: Option<N>,
+    // The line and text of the current object's `o` statement, if it doesn't have a valid name
+    renamed_object: Option<(usize, String)>,
+    default_group: Vec<usize>,
+    // Group names in the current object are interned so that faces can be assigned to groups without hashing
+    group_ids: HashMap<N, usize>,
+    groups: Vec<(N, Vec<usize>)>,
+    selected_groups: Vec<usize>,
+
+    include_objects: Option<Vec<String>>,
+    include_groups: Option<Vec<String>>,
+    // Whether faces are currently being skipped because their object or all of their groups are excluded
+    object_excluded: bool,
+    groups_excluded: bool,
+    compact: bool,
+    cache_triangles: bool,
+
+    skip_uvs: bool,
+    skip_normals: bool,
+    strict: bool,
+    map_position: Option<AttributeHook>,
+    map_uv: Option<AttributeHook>,
+    map_normal: Option<AttributeHook>,
+}
+
+impl<'a, T: Float, N: Name<'a>> MeshSink<'a, T> for ObjSink<T, N> {
+    type Error = Error;
+
+    fn position(&mut self, position: [T; 3]) {
+        self.positions.push(position);
+    }
+
+    fn uv(&mut self, uv: [T; 3]) {
+        // Skipped texture coordinates are still counted by the resolver, so indices remain correct
+        if !self.skip_uvs {
+            self.uvs.push(uv);
+        }
+    }
+
+    fn normal(&mut self, normal: [T; 3]) {
+        if !self.skip_normals {
+            self.normals.push(normal);
+        }
+    }
+
+    fn begin_object(&mut self, name: &'a str) {
+        // Clean up old object
+        self.finish_object();
+
+        // Create new object
+        self.object_excluded = self.include_objects.as_ref().is_some_and(|names| !names.iter().any(|n| n == name));
+        self.object = Some(N::new(name));
+    }
+
+    fn begin_group(&mut self, names: &[&'a str]) {
+        let (group_ids, groups) = (&mut self.group_ids, &mut self.groups);
+        let include = self.include_groups.as_deref();
+        let mut any_excluded = false;
+        self.selected_groups.clear();
+        self.selected_groups.extend(names
+            .iter()
+            .copied()
+            .filter(|t| util::name_is_valid(t))
+            .filter(|g| {
+                let included = include.map_or(true, |include| include.iter().any(|n| n == g));
+                any_excluded |= !included;
+                included
+            })
+            .map(|g| match group_ids.get(g) {
+                Some(id) => *id,
+                None => {
+                    groups.push((N::new(g), Vec::new()));
+                    group_ids.insert(N::new(g), groups.len() - 1);
+                    groups.len() - 1
+                },
+            }));
+        // A group named more than once still only gets each polygon once
+        self.selected_groups.sort_unstable();
+        self.selected_groups.dedup();
+        // Faces without any selected groups belong to the default group, unless every group was excluded
+        self.groups_excluded = self.selected_groups.is_empty()
+            && (any_excluded || excludes_default(&self.include_groups));
+    }
+
+    fn use_material(&mut self, name: &'a str) {
+        let (names, ids) = (&mut self.material_names, &mut self.material_ids);
+        self.material = if name.is_empty() {
+            None
+        } else {
+            Some(match ids.get(name) {
+                Some(id) => *id,
+                None => {
+                    names.push(String::from(name));
+                    let id = u32::try_from(names.len())
+                        .ok()
+                        .and_then(NonZeroU32::new)
+                        .expect("Too many materials for an OBJ");
+                    ids.insert(String::from(name), id);
+                    id
+                },
+            })
+        };
+    }
+
+    fn face(&mut self, vertices: &[(Index, Option<Index>, Option<Index>)]) -> Result<(), Error> {
+        if self.object_excluded || self.groups_excluded {
+            return Ok(());
+        }
+        // Resolved indices always fit in a `u32`, since no more than `MAX_ATTRIBUTES` of each kind can be defined
+        let index = |idx: Index| NonZeroU32::new(idx as u32 + 1).ok_or(Error::InvalidIndex(idx as isize + 1));
+        let start = self.vertices.len();
+        for (pos, uv, norm) in vertices {
+            self.vertices.push((
+                index(*pos)?,
+                uv.filter(|_| !self.skip_uvs).map(index).transpose()?,
+                norm.filter(|_| !self.skip_normals).map(index).transpose()?,
+            ));
+        }
+        self.push_polygon(self.line_num, VertexRange { start, end: self.vertices.len() });
+        Ok(())
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash + Default> ObjSink<T, N> {
+    fn with_capacities(options: &ParseOptions, capacities: Capacities) -> Self {
+        Self {
+            positions: Vec::with_capacity(capacities.positions),
+            uvs: Vec::with_capacity(if options.skip_uvs { 0 } else { capacities.uvs }),
+            normals: Vec::with_capacity(if options.skip_normals { 0 } else { capacities.normals }),
+            vertices: Vec::with_capacity(capacities.vertices),
+            polygons: Vec::with_capacity(capacities.polygons),
+            lines: if options.track_lines { Some(Vec::with_capacity(capacities.polygons)) } else { None },
+            comments: if options.keep_comments { Some(Vec::new()) } else { None },
+            materials: Vec::new(),
+            material_names: Vec::new(),
+            material_ids: HashMap::new(),
+            material: None,
+            objects: HashMap::new(),
+            renamed_objects: Vec::new(),
+            warnings: Vec::new(),
+            line_counts: LineCounts::default(),
+            line_num: 0,
+
+            object: None,
+            renamed_object: None,
+            default_group: Vec::new(),
+            group_ids: HashMap::new(),
+            groups: Vec::new(),
+            selected_groups: Vec::new(),
+
+            include_objects: options.include_objects.clone(),
+            include_groups: options.include_groups.clone(),
+            object_excluded: excludes_default(&options.include_objects),
+            groups_excluded: excludes_default(&options.include_groups),
+            compact: options.compact,
+            cache_triangles: options.cache_triangles,
+
+            skip_uvs: options.skip_uvs,
+            skip_normals: options.skip_normals,
+            strict: options.strict,
+            map_position: options.map_position.clone(),
+            map_uv: options.map_uv.clone(),
+            map_normal: options.map_normal.clone(),
+        }
+    }
+
+    /// Add a polygon made of vertices that have already been parsed to the currently selected groups.
+    fn push_polygon(&mut self, line_num: usize, range: VertexRange) {
+        let poly = self.polygons.len();
+        self.polygons.push(range);
+        if let Some(lines) = &mut self.lines {
+            lines.push(line_num);
+        }
+        // Materials are only recorded once one has been used, leaving earlier polygons without one
+        if self.material.is_some() || !self.materials.is_empty() {
+            self.materials.resize(poly, None);
+            self.materials.push(self.material);
+        }
+
+        if self.selected_groups.is_empty() {
+            self.default_group.push(poly);
+        } else {
+            let groups = &mut self.groups;
+            self.selected_groups
+                .iter()
+                .for_each(|g| groups[*g].1.push(poly));
+        }
+    }
+
+    fn finish_object(&mut self) {
+        self.group_ids.clear();
+        let mut groups = self.groups.drain(..).collect::<HashMap<_, _>>();
+        if !self.default_group.is_empty() {
+            groups.insert(N::default(), core::mem::take(&mut self.default_group));
+        }
+        self.selected_groups.clear();
+        self.groups_excluded = excludes_default(&self.include_groups);
+        if self.object_excluded {
+            groups.clear();
+        }
+        if let Some((line, name)) = self.renamed_object.take() {
+            self.renamed_objects.push((line, name, groups));
+        } else if !groups.is_empty() {
+            // An object may be named more than once, in which case its groups are merged
+            merge_groups(self.objects.entry(self.object.take().unwrap_or_default()).or_default(), groups);
+        }
+    }
+
+    /// Start an object whose `o` statement, on the given line, doesn't have a valid name.
+    fn begin_renamed_object(&mut self, line_num: usize, name: &str) {
+        self.finish_object();
+        // The object is treated as unnamed until it is renamed
+        self.object_excluded = excludes_default(&self.include_objects);
+        self.object = None;
+        self.renamed_object = Some((line_num, String::from(name)));
+    }
+
+    /// Name the objects that didn't have a valid name, avoiding the names of every other object.
+    fn rename_objects<'a>(&mut self) where N: Name<'a> {
+        for (line, name, groups) in core::mem::take(&mut self.renamed_objects) {
+            let mut renamed = format!("object_{}", line);
+            let mut suffix = 1;
+            while self.objects.contains_key(renamed.as_str()) {
+                suffix += 1;
+                renamed = format!("object_{}_{}", line, suffix);
+            }
+            let (object, renamed) = match N::synthesize(renamed.clone()) {
+                Some(object) => (object, renamed),
+                None => (N::default(), String::new()),
+            };
+            if !groups.is_empty() {
+                merge_groups(self.objects.entry(object).or_default(), groups);
+            }
+            self.warnings.push(Warning::InvalidObjectName { line, name, renamed });
+        }
+        self.warnings.sort_by_key(Warning::line);
+    }
+
+    /// Finish parsing, validating the indices of every vertex.
+    fn finish<'a>(mut self) -> Result<GenericObj<T, N>, Error> where N: Clone + Name<'a> {
+        // Clean up old object
+        self.finish_object();
+        self.rename_objects();
+
+        // Validate indices
+        for (pos, uv, norm) in &self.vertices {
+            let invalid = |idx: NonZeroU32| Error::InvalidIndex(idx.get() as isize);
+            if pos.get() as usize > self.positions.len() { return Err(invalid(*pos)); }
+            if let Some(uv) = *uv {
+                if uv.get() as usize > self.uvs.len() { return Err(invalid(uv)); }
+            }
+            if let Some(norm) = *norm {
+                if norm.get() as usize > self.normals.len() { return Err(invalid(norm)); }
+            }
+        }
+
+        let obj = GenericObj {
+            buffers: Buffers {
+                positions: Arc::new(self.positions),
+                uvs: Arc::new(self.uvs),
+                normals: Arc::new(self.normals),
+                vertices: Arc::new(self.vertices),
+                polygons: Arc::new(self.polygons),
+                lines: self.lines.map(Arc::new),
+                comments: self.comments.map(Arc::new),
+                report: Arc::new(ParseReport { warnings: self.warnings, line_counts: self.line_counts }),
+                materials: if self.material_names.is_empty() { None } else { Some(Arc::new(self.materials)) },
+                material_names: Arc::new(self.material_names),
+            },
+            names: Arc::new(NameIndex::new(&self.objects)),
+            objects: Arc::new(self.objects),
+            triangles: None,
+        };
+        let mut obj = if self.compact { obj.extract(|_| true) } else { obj };
+        if self.cache_triangles {
+            obj.cache_triangles();
+        }
+        Ok(obj)
+    }
+}
+
+/// Returns `true` if a list of included object or group names excludes the default (unnamed) one.
+fn excludes_default(include: &Option<Vec<String>>) -> bool {
+    include.as_ref().is_some_and(|names| !names.iter().any(|name| name.is_empty()))
+}
+
+/// Parse up to 3 numeric components of a vertex attribute, stopping at the first term that isn't a number and
+/// defaulting missing components to zero.
+// Add the polygons of some groups to those of an object, keeping the polygons of each group sorted and unique
+fn merge_groups<N: Eq + Hash>(object: &mut HashMap<N, Vec<usize>>, groups: HashMap<N, Vec<usize>>) {
+    for (group, polys) in groups {
+        let existing = object.entry(group).or_default();
+        let sorted = existing.last().map_or(true, |last| polys.first().map_or(true, |first| last < first));
+        existing.extend(polys);
+        if !sorted {
+            existing.sort_unstable();
+            existing.dedup();
+        }
+    }
+}
+
+pub(crate) fn parse_attribute<'a, T: Float>(terms: impl Iterator<Item=&'a str>) -> [T; 3] {
+    let mut nums = terms.map_while(T::parse_str).fuse();
+    [
+        nums.next().unwrap_or(T::ZERO),
+        nums.next().unwrap_or(T::ZERO),
+        nums.next().unwrap_or(T::ZERO),
+    ]
+}
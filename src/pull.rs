@@ -0,0 +1,189 @@
+use crate::{parse::parse_attribute, util::Keyword, Error, Float, VertexIndices};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, iter::Enumerate, marker::PhantomData, num::NonZeroU32, str::{Lines, SplitAsciiWhitespace}};
+
+/// A pull parser that produces the statements of an OBJ one at a time, without building an [`Obj`](crate::Obj).
+///
+/// Most users will want to use the [`ObjParser`] alias, which parses attributes as `f32`.
+///
+/// This is useful for tools that need to see the exact structure of a file (such as linters, format converters and
+/// pretty-printers) or that process models too large to hold in memory. It is the same parser that is used to build
+/// an [`Obj`](crate::Obj), so the two always agree on the meaning of a file.
+///
+/// ```
+/// use wavefront::{ObjEvent, ObjParser};
+///
+/// let src = "# A triangle\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+/// let positions = ObjParser::new(src)
+///     .filter(|event| matches!(event, Ok((_, ObjEvent::Position(_)))))
+///     .count();
+/// assert_eq!(positions, 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GenericObjParser<'a, T> {
+    lines: Enumerate<Lines<'a>>,
+    phantom: PhantomData<T>,
+}
+
+/// A pull parser that parses attributes as `f32`. See [`GenericObjParser`] for more information.
+pub type ObjParser<'a> = GenericObjParser<'a, f32>;
+
+impl<'a, T: Float> GenericObjParser<'a, T> {
+    /// Create a parser for the given OBJ source.
+    pub fn new(src: &'a str) -> Self {
+        Self { lines: src.lines().enumerate(), phantom: PhantomData }
+    }
+}
+
+impl<'a, T: Float> Iterator for GenericObjParser<'a, T> {
+    /// An event, paired with the (1-based) line number of the statement that produced it.
+    type Item = Result<(usize, ObjEvent<'a, T>), Error>;
+
+    /// Returns the event produced by the next statement, skipping blank lines.
+    ///
+    /// A line that fails to parse produces an error, after which parsing may continue with the next line.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.find_map(|(i, line)| {
+            let (keyword, rest) = split_keyword(line)?;
+            Some(parse_event(i + 1, keyword, rest).map(|event| (i + 1, event)))
+        })
+    }
+}
+
+/// A single statement of an OBJ file, produced by [`GenericObjParser`].
+///
+/// Names and other text are borrowed from the source.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum ObjEvent<'a, T = f32> {
+    /// A vertex position (`v`). Missing components are zero.
+    Position([T; 3]),
+    /// A texture coordinate (`vt`). Missing components are zero.
+    Uv([T; 3]),
+    /// A vertex normal (`vn`). Missing components are zero.
+    Normal([T; 3]),
+    /// A face (`f`), made of the given terms.
+    Face(FaceTerms<'a>),
+    /// The start of a new object (`o`).
+    Object(&'a str),
+    /// A change of the groups that subsequent faces belong to (`g`), as written. Names that aren't valid are ignored
+    /// when building an [`Obj`](crate::Obj).
+    Group(Vec<&'a str>),
+    /// A change of the material used by subsequent faces (`usemtl`), as written.
+    UseMtl(&'a str),
+    /// A reference to one or more material libraries (`mtllib`).
+    MtlLib(Vec<&'a str>),
+    /// A comment: the text following a `#` at the start of a line.
+    Comment(&'a str),
+    /// Any other statement, such as `s` or `l`, which is ignored when building an [`Obj`](crate::Obj).
+    Unknown {
+        /// The first term of the statement.
+        keyword: &'a str,
+        /// The rest of the statement, without leading or trailing whitespace.
+        rest: &'a str,
+    },
+}
+
+/// The terms of a face, produced by [`ObjEvent::Face`].
+///
+/// Terms are parsed as they are iterated over, so a malformed term produces an error at that point.
+#[derive(Clone, Debug)]
+pub struct FaceTerms<'a> {
+    line_num: usize,
+    terms: SplitAsciiWhitespace<'a>,
+}
+
+impl<'a> Iterator for FaceTerms<'a> {
+    type Item = Result<FaceTerm, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.terms.next().map(|term| FaceTerm::parse(self.line_num, term))
+    }
+}
+
+/// A single term of a face, of the form `p`, `p/t`, `p//n` or `p/t/n`.
+///
+/// Indices are given as written: they are 1-based, and negative indices count backwards from the most recently
+/// defined attribute of their kind (so `-1` is the last).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FaceTerm {
+    /// The index of the vertex position.
+    pub position: isize,
+    /// The index of the texture coordinate, if the term has one.
+    pub uv: Option<isize>,
+    /// The index of the normal, if the term has one.
+    pub normal: Option<isize>,
+}
+
+impl FaceTerm {
+    /// Parse a face term. Any fields after the third are ignored.
+    pub(crate) fn parse(line_num: usize, term: &str) -> Result<Self, Error> {
+        let mut indices = [None; 3];
+        for (i, idx) in term.splitn(4, '/').take(3).enumerate() {
+            indices[i] = match idx.trim() {
+                "" => None,
+                s => Some(s.parse::<isize>().map_err(|_| Error::ExpectedIdx(line_num))?),
+            };
+        }
+        Ok(Self { position: indices[0].ok_or(Error::ExpectedIdx(line_num))?, uv: indices[1], normal: indices[2] })
+    }
+
+    /// Resolve relative indices against the number of positions, texture coordinates and normals defined so far.
+    pub(crate) fn resolve(&self, lengths: [usize; 3]) -> Result<VertexIndices, Error> {
+        let resolve = |idx: isize, len: usize| {
+            let resolved = if idx >= 0 { Some(idx as usize) } else { len.checked_sub(idx.unsigned_abs() - 1) };
+            resolved
+                .and_then(|idx| u32::try_from(idx).ok())
+                .and_then(NonZeroU32::new)
+                .ok_or(Error::InvalidIndex(idx))
+        };
+        Ok((
+            resolve(self.position, lengths[0])?,
+            self.uv.map(|uv| resolve(uv, lengths[1])).transpose()?,
+            self.normal.map(|normal| resolve(normal, lengths[2])).transpose()?,
+        ))
+    }
+}
+
+/// Split a line into its keyword and the rest of the statement, or `None` if the line is blank. Comments produce a
+/// keyword of `#` followed by the text of the comment.
+pub(crate) fn split_keyword(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start_matches(|c: char| c.is_ascii_whitespace());
+    if let Some(comment) = line.strip_prefix('#') {
+        return Some(("#", comment));
+    }
+    let end = line.find(|c: char| c.is_ascii_whitespace()).unwrap_or(line.len());
+    let (keyword, rest) = line.split_at(end);
+    if keyword.is_empty() {
+        None
+    } else {
+        Some((keyword, rest.trim_matches(|c: char| c.is_ascii_whitespace())))
+    }
+}
+
+/// Returns `true` if statements with the given keyword produce an event other than [`ObjEvent::Unknown`].
+pub(crate) fn is_known_keyword(keyword: &str) -> bool {
+    keyword == "#" || Keyword::parse(keyword).is_some_and(|keyword| keyword.is_supported())
+}
+
+/// Parse a statement that has been split with [`split_keyword`].
+pub(crate) fn parse_event<'a, T: Float>(line_num: usize, keyword: &'a str, rest: &'a str) -> Result<ObjEvent<'a, T>, Error> {
+    let mut terms = rest.split_ascii_whitespace();
+    if keyword == "#" {
+        return Ok(ObjEvent::Comment(rest));
+    }
+    Ok(match Keyword::parse(keyword) {
+        Some(Keyword::Position) => ObjEvent::Position(parse_attribute(terms)),
+        Some(Keyword::Uv) => ObjEvent::Uv(parse_attribute(terms)),
+        Some(Keyword::Normal) => ObjEvent::Normal(parse_attribute(terms)),
+        Some(Keyword::Face) => ObjEvent::Face(FaceTerms { line_num, terms }),
+        Some(Keyword::Object) => ObjEvent::Object(terms
+            .next()
+            .filter(|t| crate::util::name_is_valid(t))
+            .ok_or(Error::ExpectedName(line_num))?),
+        Some(Keyword::Group) => ObjEvent::Group(terms.collect()),
+        Some(Keyword::UseMaterial) => ObjEvent::UseMtl(rest),
+        Some(Keyword::MaterialLibrary) => ObjEvent::MtlLib(terms.collect()),
+        _ => ObjEvent::Unknown { keyword, rest },
+    })
+}
@@ -0,0 +1,214 @@
+use crate::{bvh::closest_on_triangle, math, Float, GenericObj, Vertex, Index};
+use alloc::vec::Vec;
+
+/// An intersection between a ray and the surface of an [`Obj`].
+#[derive(Copy, Clone, Debug)]
+pub struct RayHit<'a, T: Float = f32> {
+    /// The distance along the ray at which the intersection occurred, in multiples of the length of the ray's
+    /// direction (if the direction is normalised, this is the euclidean distance from the ray's origin).
+    pub distance: T,
+    /// The barycentric coordinates of the intersection within [`RayHit::triangle`], such that the point of
+    /// intersection is the weighted sum of the positions of its vertices.
+    pub barycentric: [T; 3],
+    /// The triangle that was hit, as produced by [`Polygon::triangles`](crate::Polygon::triangles).
+    pub triangle: [Vertex<'a, T>; 3],
+    /// The index of the polygon that was hit (see [`Polygon::index`](crate::Polygon::index)).
+    pub polygon: Index,
+    /// The name of the object containing the polygon that was hit.
+    pub object: &'a str,
+    /// The name of the group containing the polygon that was hit.
+    ///
+    /// If the polygon belongs to several groups, the group with the lowest name is given.
+    pub group: &'a str,
+}
+
+/// The closest point on the surface of an [`Obj`] to a query point, produced by [`GenericObj::nearest_surface_point`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SurfacePoint<T = f32> {
+    /// The closest point on the surface.
+    pub position: [T; 3],
+    /// The euclidean distance between the query point and [`SurfacePoint::position`].
+    pub distance: T,
+    /// The barycentric coordinates of [`SurfacePoint::position`] within the triangle containing it.
+    pub barycentric: [T; 3],
+    /// The index of the triangle containing the point, in the order produced by [`GenericObj::triangles`].
+    pub triangle: Index,
+    /// The index of the polygon containing the point (see [`Polygon::index`](crate::Polygon::index)).
+    pub polygon: Index,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Find the closest intersection between a ray and the triangles of this [`Obj`], if any.
+    ///
+    /// When `cull_backfaces` is `true`, triangles facing away from the ray (according to their winding order) are
+    /// ignored. If several triangles are hit at exactly the same distance, the one belonging to the polygon with the
+    /// lowest index is returned.
+    ///
+    /// Hits are inclusive of triangle edges, so a ray passing exactly through an edge shared by two triangles will not
+    /// slip between them.
+    pub fn raycast(&self, origin: [T; 3], dir: [T; 3], cull_backfaces: bool) -> Option<RayHit<'_, T>> {
+        let (hit, polygon) = self
+            .polygon_hits(origin, dir, cull_backfaces)
+            .fold(None, |closest: Option<(TriangleHit<T>, Index)>, (hit, polygon)| match closest {
+                Some(closest) if closest.0.distance <= hit.distance => Some(closest),
+                _ => Some((hit, polygon)),
+            })?;
+        let owners = self.polygon_owners();
+        Some(hit.into_ray_hit(polygon, owners[polygon]))
+    }
+
+    /// Find every intersection between a ray and the polygons of this [`Obj`], sorted by distance.
+    ///
+    /// Each polygon is reported at most once, even if the ray passes through an edge between two of its triangles.
+    /// See [`Obj::raycast`] for more information.
+    pub fn raycast_all(&self, origin: [T; 3], dir: [T; 3], cull_backfaces: bool) -> Vec<RayHit<'_, T>> {
+        let owners = self.polygon_owners();
+        let mut hits = self
+            .polygon_hits(origin, dir, cull_backfaces)
+            .map(|(hit, polygon)| hit.into_ray_hit(polygon, owners[polygon]))
+            .collect::<Vec<_>>();
+        hits.sort_by(|a, b| a.distance
+            .to_f64()
+            .total_cmp(&b.distance.to_f64())
+            .then(a.polygon.cmp(&b.polygon)));
+        hits
+    }
+
+    /// Find the position closest to `p`, returning its index in [`GenericObj::positions`] and its distance from `p`.
+    ///
+    /// Every position is considered, including those not used by any polygon. If several positions are equally close,
+    /// the one with the lowest index is chosen. This searches every position, so see [`Bvh::nearest_position`] for
+    /// repeated queries on large models.
+    ///
+    /// [`Bvh::nearest_position`]: crate::Bvh::nearest_position
+    pub fn nearest_position(&self, p: [T; 3]) -> Option<(Index, T)> {
+        self
+            .positions()
+            .iter()
+            .enumerate()
+            .map(|(i, &q)| (i, math::dot(math::sub(q, p), math::sub(q, p))))
+            .fold(None, |closest: Option<(Index, T)>, (i, dist_sq)| match closest {
+                Some(closest) if closest.1 <= dist_sq => Some(closest),
+                _ => Some((i, dist_sq)),
+            })
+            .map(|(i, dist_sq)| (i, dist_sq.sqrt()))
+    }
+
+    /// Find the closest point to `p` on the triangles of this [`Obj`], or `None` if it has no triangles.
+    ///
+    /// If several triangles are equally close, the one that comes first in [`GenericObj::triangles`] is chosen. This
+    /// searches every triangle, so see [`Bvh::nearest_surface_point`] for repeated queries on large models, which
+    /// gives identical results.
+    ///
+    /// [`Bvh::nearest_surface_point`]: crate::Bvh::nearest_surface_point
+    pub fn nearest_surface_point(&self, p: [T; 3]) -> Option<SurfacePoint<T>> {
+        self
+            .polygons()
+            .flat_map(|poly| poly.triangles().map(move |tri| (tri, poly.index())))
+            .enumerate()
+            .map(|(i, (tri, polygon))| {
+                let (position, barycentric) = closest_on_triangle(p, tri.map(|v| v.position()));
+                let d = math::sub(position, p);
+                (math::dot(d, d), SurfacePoint { position, distance: T::ZERO, barycentric, triangle: i, polygon })
+            })
+            .fold(None, |closest: Option<(T, SurfacePoint<T>)>, (dist_sq, point)| match closest {
+                Some(closest) if closest.0 <= dist_sq => Some(closest),
+                _ => Some((dist_sq, point)),
+            })
+            .map(|(dist_sq, point)| SurfacePoint { distance: dist_sq.sqrt(), ..point })
+    }
+
+    /// Returns the closest hit on each polygon hit by the ray.
+    fn polygon_hits(
+        &self,
+        origin: [T; 3],
+        dir: [T; 3],
+        cull_backfaces: bool,
+    ) -> impl Iterator<Item=(TriangleHit<'_, T>, Index)> + '_ {
+        self.polygons().filter_map(move |poly| {
+            poly
+                .triangles()
+                .filter_map(|tri| intersect_triangle(origin, dir, tri, cull_backfaces))
+                .fold(None, |closest: Option<TriangleHit<T>>, hit| match closest {
+                    Some(closest) if closest.distance <= hit.distance => Some(closest),
+                    _ => Some(hit),
+                })
+                .map(|hit| (hit, poly.index()))
+        })
+    }
+
+    /// Returns the object and group names of each polygon, choosing the group with the lowest name for polygons that
+    /// belong to several groups.
+    pub(crate) fn polygon_owners(&self) -> Vec<(&str, &str)> {
+        let mut owners = alloc::vec![None; self.polygons().len()];
+        for (object, groups) in self.objects.iter() {
+            for (group, polys) in groups.iter() {
+                for index in polys {
+                    let owner: &mut Option<(&str, &str)> = &mut owners[*index];
+                    if owner.map_or(true, |(_, g)| group.as_str() < g) {
+                        *owner = Some((object.as_str(), group.as_str()));
+                    }
+                }
+            }
+        }
+        owners.into_iter().map(Option::unwrap_or_default).collect()
+    }
+}
+
+pub(crate) struct TriangleHit<'a, T> {
+    pub(crate) distance: T,
+    barycentric: [T; 3],
+    triangle: [Vertex<'a, T>; 3],
+}
+
+impl<'a, T: Float> TriangleHit<'a, T> {
+    pub(crate) fn into_ray_hit(self, polygon: Index, (object, group): (&'a str, &'a str)) -> RayHit<'a, T> {
+        RayHit {
+            distance: self.distance,
+            barycentric: self.barycentric,
+            triangle: self.triangle,
+            polygon,
+            object,
+            group,
+        }
+    }
+}
+
+/// Möller–Trumbore ray/triangle intersection.
+pub(crate) fn intersect_triangle<T: Float>(
+    origin: [T; 3],
+    dir: [T; 3],
+    triangle: [Vertex<'_, T>; 3],
+    cull_backfaces: bool,
+) -> Option<TriangleHit<'_, T>> {
+    let [a, b, c] = triangle.map(|v| v.position());
+    let e1 = math::sub(b, a);
+    let e2 = math::sub(c, a);
+    let p = math::cross(dir, e2);
+    let det = math::dot(e1, p);
+    // Parallel rays never hit, and back-facing triangles have a negative determinant. Non-finite positions (which
+    // lenient parsing allows) produce a non-finite determinant and can't be hit either.
+    if det == T::ZERO || !det.is_finite() || (cull_backfaces && det < T::ZERO) {
+        return None;
+    }
+    let inv_det = T::ONE / det;
+    let s = math::sub(origin, a);
+    let u = math::dot(s, p) * inv_det;
+    if u < T::ZERO || u > T::ONE {
+        return None;
+    }
+    let q = math::cross(s, e1);
+    let v = math::dot(dir, q) * inv_det;
+    if v < T::ZERO || u + v > T::ONE {
+        return None;
+    }
+    let distance = math::dot(e2, q) * inv_det;
+    if distance < T::ZERO || !distance.is_finite() {
+        return None;
+    }
+    Some(TriangleHit {
+        distance,
+        barycentric: [T::ONE - u - v, u, v],
+        triangle,
+    })
+}
@@ -0,0 +1,131 @@
+use crate::{Error, Float, GenericObj, NameIndex};
+use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use hashbrown::HashMap;
+
+/// What to do when renaming objects or groups would give two of them the same name, used by
+/// [`GenericObj::rename_objects`] and [`GenericObj::rename_groups`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NameCollision {
+    /// Combine them into one, containing the polygons of both.
+    Merge,
+    /// Produce an [`Error::NameCollision`], leaving the OBJ unchanged.
+    Error,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Rename the objects of this OBJ, returning the names that changed as `(old, new)` pairs sorted by old name.
+    ///
+    /// The function is called once for each object, in sorted order, and returns its new name or `None` to keep the
+    /// name it has. Lookups (including case-insensitive ones), iteration and [`GenericObj::write`] all see the new
+    /// names. If two objects end up with the same name (whether both were renamed, or one was renamed to the name of
+    /// another), `collision` decides whether they are merged, combining groups with the same name, or an error is
+    /// produced.
+    ///
+    /// New names aren't checked for validity, so they may need to be sanitized before writing (see
+    /// [`WriteOptions::sanitize_names`](crate::WriteOptions::sanitize_names)).
+    ///
+    /// ```
+    /// use wavefront::{NameCollision, Obj};
+    ///
+    /// let mut obj: Obj = "v 0 0 0\no scene01_wheel\nf 1 1 1\no scene01_door\nf 1 1 1\n".parse().unwrap();
+    /// let renamed = obj
+    ///     .rename_objects(|name| name.strip_prefix("scene01_").map(String::from), NameCollision::Error)
+    ///     .unwrap();
+    /// assert_eq!(renamed[0], ("scene01_door".to_string(), "door".to_string()));
+    /// assert!(obj.object("wheel").is_some());
+    /// ```
+    pub fn rename_objects(
+        &mut self,
+        mut rename: impl FnMut(&str) -> Option<String>,
+        collision: NameCollision,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let (renamed, names) = new_names(self.objects.keys(), &mut rename);
+        let mut objects = HashMap::<String, HashMap<String, Vec<usize>>>::new();
+        for (name, groups) in sorted(&self.objects) {
+            let new_name = &names[name.as_str()];
+            match objects.get_mut(new_name) {
+                Some(_) if collision == NameCollision::Error => return Err(Error::NameCollision(new_name.clone())),
+                Some(existing) => merge(existing, groups),
+                None => {
+                    objects.insert(new_name.clone(), groups.clone());
+                },
+            }
+        }
+        self.set_objects(objects);
+        Ok(renamed)
+    }
+
+    /// Rename the groups of this OBJ, returning the names that changed as `(old, new)` pairs sorted by old name.
+    ///
+    /// The function is called once for each distinct group name, in sorted order, such that groups with the same name
+    /// in different objects are given the same new name. If two groups in the same object end up with the same name,
+    /// `collision` decides whether they are merged, containing the polygons of both, or an error is produced. See
+    /// [`GenericObj::rename_objects`] for more information.
+    pub fn rename_groups(
+        &mut self,
+        mut rename: impl FnMut(&str) -> Option<String>,
+        collision: NameCollision,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let (renamed, names) = new_names(self.objects.values().flat_map(|groups| groups.keys()), &mut rename);
+        let mut objects = HashMap::with_capacity(self.objects.len());
+        for (object, groups) in self.objects.iter() {
+            let mut groups_out = HashMap::<String, Vec<usize>>::with_capacity(groups.len());
+            for (name, polys) in sorted(groups) {
+                let new_name = &names[name.as_str()];
+                match groups_out.get_mut(new_name) {
+                    Some(_) if collision == NameCollision::Error => return Err(Error::NameCollision(new_name.clone())),
+                    Some(existing) => merge_polygons(existing, polys),
+                    None => {
+                        groups_out.insert(new_name.clone(), polys.clone());
+                    },
+                }
+            }
+            objects.insert(object.clone(), groups_out);
+        }
+        self.set_objects(objects);
+        Ok(renamed)
+    }
+
+    fn set_objects(&mut self, objects: HashMap<String, HashMap<String, Vec<usize>>>) {
+        self.names = Arc::new(NameIndex::new(&objects));
+        self.objects = Arc::new(objects);
+    }
+}
+
+/// Find the new name of every distinct name, returning those that changed and a map from every name to its new name.
+fn new_names<'a>(
+    names: impl Iterator<Item=&'a String>,
+    rename: &mut impl FnMut(&str) -> Option<String>,
+) -> (Vec<(String, String)>, BTreeMap<&'a str, String>) {
+    let mut map = names.map(|name| (name.as_str(), String::new())).collect::<BTreeMap<_, _>>();
+    let mut renamed = Vec::new();
+    for (name, new_name) in map.iter_mut() {
+        *new_name = match rename(name) {
+            Some(new_name) if new_name != *name => {
+                renamed.push((String::from(*name), new_name.clone()));
+                new_name
+            },
+            _ => String::from(*name),
+        };
+    }
+    (renamed, map)
+}
+
+/// Returns the entries of a map sorted by name, so that merging doesn't depend on hash order.
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|(name, _)| *name);
+    entries
+}
+
+fn merge(groups: &mut HashMap<String, Vec<usize>>, other: &HashMap<String, Vec<usize>>) {
+    for (name, polys) in other {
+        merge_polygons(groups.entry(name.clone()).or_default(), polys);
+    }
+}
+
+fn merge_polygons(polys: &mut Vec<usize>, other: &[usize]) {
+    polys.extend_from_slice(other);
+    polys.sort_unstable();
+    polys.dedup();
+}
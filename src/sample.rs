@@ -0,0 +1,131 @@
+use crate::{math, Float, GenericObj, Index, Vertex};
+use alloc::vec::Vec;
+
+/// A point on the surface of an [`Obj`](crate::Obj), produced by [`GenericObj::sample_surface`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SurfaceSample<T = f32> {
+    /// The position of the point.
+    pub position: [T; 3],
+    /// The normal at the point, interpolated from the normals of the triangle's vertices and normalised, or `None`
+    /// if any of them has no normal (or they cancel out).
+    pub normal: Option<[T; 3]>,
+    /// The texture coordinate at the point, interpolated from those of the triangle's vertices, or `None` if any of
+    /// them has no texture coordinate.
+    pub uv: Option<[T; 3]>,
+    /// The index of the polygon that the point lies on (see [`Polygon::index`](crate::Polygon::index)).
+    pub polygon: Index,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Sample `n` points distributed uniformly over the surface of this OBJ.
+    ///
+    /// Each point is placed by picking a triangle (as produced by [`Polygon::triangles`](crate::Polygon::triangles))
+    /// with probability proportional to its area, then picking a point uniformly within it. Degenerate triangles are
+    /// never picked, and an OBJ without any area produces no samples.
+    ///
+    /// The samples are determined entirely by the OBJ and `seed`, using a small built-in random number generator
+    /// (PCG-XSH-RR), so the same inputs always produce the same points on every platform.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// let samples = obj.sample_surface(100, 42);
+    /// assert_eq!(samples.len(), 100);
+    /// assert!(samples.iter().all(|s| s.position.iter().all(|x| (0.0..=1.0).contains(x))));
+    /// ```
+    pub fn sample_surface(&self, n: usize, seed: u64) -> Vec<SurfaceSample<T>> {
+        // The cumulative area of every triangle, such that triangles may be picked with a binary search
+        let mut triangles = Vec::new();
+        let mut total = 0.0;
+        for poly in self.polygons() {
+            for triangle in poly.triangles() {
+                let [a, b, c] = triangle.map(|v| v.position().map(Float::to_f64));
+                let normal = math::cross(math::sub(b, a), math::sub(c, a));
+                let area = Float::sqrt(math::dot(normal, normal)) / 2.0;
+                if area > 0.0 && area.is_finite() {
+                    total += area;
+                    triangles.push((total, triangle, poly.index()));
+                }
+            }
+        }
+        if triangles.is_empty() {
+            return Vec::new();
+        }
+
+        let mut rng = Pcg32::new(seed);
+        (0..n)
+            .map(|_| {
+                let target = rng.next_f64() * total;
+                let (_, triangle, polygon) = triangles[triangles
+                    .partition_point(|(cumulative, _, _)| *cumulative <= target)
+                    .min(triangles.len() - 1)];
+                // Mapping the unit square onto the triangle like this preserves uniformity
+                let r = Float::sqrt(rng.next_f64());
+                let s = rng.next_f64();
+                let weights = [1.0 - r, r * (1.0 - s), r * s].map(T::from_f64);
+                SurfaceSample {
+                    position: interpolate(triangle, weights, |v| Some(v.position())).unwrap(),
+                    normal: interpolate(triangle, weights, Vertex::normal).and_then(normalize),
+                    uv: interpolate(triangle, weights, Vertex::uv),
+                    polygon,
+                }
+            })
+            .collect()
+    }
+}
+
+fn interpolate<'a, T: Float>(
+    triangle: [Vertex<'a, T>; 3],
+    weights: [T; 3],
+    attribute: impl Fn(&Vertex<'a, T>) -> Option<[T; 3]>,
+) -> Option<[T; 3]> {
+    let mut sum = [T::ZERO; 3];
+    for (v, w) in triangle.iter().zip(weights) {
+        let x = attribute(v)?;
+        for i in 0..3 {
+            sum[i] += x[i] * w;
+        }
+    }
+    Some(sum)
+}
+
+fn normalize<T: Float>(v: [T; 3]) -> Option<[T; 3]> {
+    let len = math::dot(v, v).sqrt();
+    if len > T::ZERO && len.is_finite() {
+        Some(v.map(|x| x / len))
+    } else {
+        None
+    }
+}
+
+/// The PCG-XSH-RR generator described by O'Neill, with 64 bits of state and 32-bit outputs.
+struct Pcg32 {
+    state: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+    const INCREMENT: u64 = 1442695040888963407;
+
+    fn new(seed: u64) -> Self {
+        let mut rng = Self { state: 0 };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(Self::MULTIPLIER).wrapping_add(Self::INCREMENT);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// Returns a number in the range `[0, 1)` with 53 bits of precision.
+    fn next_f64(&mut self) -> f64 {
+        let bits = ((self.next_u32() as u64) << 32 | self.next_u32() as u64) >> 11;
+        bits as f64 / (1u64 << 53) as f64
+    }
+}
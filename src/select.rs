@@ -0,0 +1,149 @@
+use crate::{Float, GenericObj, Polygon, Vertex};
+use alloc::{string::String, vec::Vec};
+use core::{borrow::Borrow, fmt, hash::Hash};
+
+/// A set of polygons selected from an [`Obj`](crate::Obj) by name.
+///
+/// See [`GenericObj::select`] for more information.
+pub struct Selection<'a, T = f32, N = String> {
+    obj: &'a GenericObj<T, N>,
+    // Sorted and deduplicated
+    polygons: Vec<usize>,
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
+    /// Select the polygons of every object or group with a name matching the given pattern.
+    ///
+    /// Patterns take the form `object/group`, where `*` matches any sequence of characters and `?` matches any single
+    /// character. For example, `*/col_*` selects every group beginning with `col_` in any object. A pattern without a
+    /// `/` selects whole objects. Polygons that match several times are only selected once.
+    pub fn select(&self, pattern: &str) -> Selection<'_, T, N> {
+        let (object_pattern, group_pattern) = match pattern.find('/') {
+            Some(idx) => (&pattern[..idx], Some(&pattern[idx + 1..])),
+            None => (pattern, None),
+        };
+        let mut polygons = self
+            .objects
+            .iter()
+            .filter(|(name, _)| glob_matches(object_pattern, (*name).borrow()))
+            .flat_map(|(_, groups)| groups
+                .iter()
+                .filter(move |(name, _)| group_pattern.map_or(true, |pattern| glob_matches(pattern, (*name).borrow())))
+                .flat_map(|(_, polygons)| polygons.iter().copied()))
+            .collect::<Vec<_>>();
+        polygons.sort_unstable();
+        polygons.dedup();
+        Selection { obj: self, polygons }
+    }
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash + Clone> GenericObj<T, N> {
+    /// Split this OBJ into a standalone OBJ for each of its objects, paired with the name of the object.
+    ///
+    /// Each OBJ has compacted attribute buffers and contains only the polygons of its object, which keep their group
+    /// membership. Objects are ordered by the first polygon they contain.
+    pub fn split_objects(&self) -> Vec<(N, Self)> {
+        let objects = self
+            .objects
+            .iter()
+            .map(|(name, groups)| (name, groups.values().flatten().copied().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        self.split(objects)
+            .into_iter()
+            .map(|(name, obj)| (name.clone(), obj))
+            .collect()
+    }
+
+    /// Split this OBJ into a standalone OBJ for each group of each of its objects, paired with the names of the
+    /// object and the group.
+    ///
+    /// Polygons that belong to several groups appear in each of their OBJs, and keep their membership of the other
+    /// groups. Groups that begin with the same polygon are ordered by name. See [`GenericObj::split_objects`] for more information.
+    pub fn split_groups(&self) -> Vec<(N, N, Self)> {
+        let mut groups = self
+            .objects
+            .iter()
+            .flat_map(|(object, groups)| groups.iter().map(move |(group, polys)| ((object, group), polys.clone())))
+            .collect::<Vec<_>>();
+        // Groups that share their first polygon are ordered by name
+        groups.sort_by_key(|((object, group), _)| -> (&str, &str) { ((*object).borrow(), (*group).borrow()) });
+        self.split(groups)
+            .into_iter()
+            .map(|((object, group), obj)| (object.clone(), group.clone(), obj))
+            .collect()
+    }
+
+    fn split<K>(&self, mut parts: Vec<(K, Vec<usize>)>) -> Vec<(K, Self)> {
+        for (_, polygons) in parts.iter_mut() {
+            polygons.sort_unstable();
+            polygons.dedup();
+        }
+        // Stable, so that the order given is kept for ties
+        parts.sort_by_key(|(_, polygons)| polygons.first().copied().unwrap_or(usize::MAX));
+        parts
+            .into_iter()
+            .map(|(key, polygons)| (key, self.extract(|index| polygons.binary_search(&index).is_ok())))
+            .collect()
+    }
+}
+
+impl<'a, T: Float, N: Borrow<str> + Eq + Hash> Selection<'a, T, N> {
+    /// Returns an iterator over the selected [`Polygon`]s, in the order they appear in the file.
+    pub fn polygons(&self) -> impl ExactSizeIterator<Item=Polygon<'a, T>> + Clone + '_ {
+        let obj = self.obj;
+        self.polygons.iter().map(move |index| obj.buffers.lookup(*index))
+    }
+
+    /// Returns an iterator over the triangles of the selected polygons.
+    ///
+    /// See [`Polygon::triangles`] for more information.
+    pub fn triangles(&self) -> impl Iterator<Item=[Vertex<'a, T>; 3]> + Clone + '_ {
+        self
+            .polygons()
+            .flat_map(|poly| poly.triangles())
+    }
+
+    /// Create a new OBJ containing only the selected polygons.
+    ///
+    /// Polygons keep their object and group membership, and only the attributes that they reference are kept.
+    pub fn to_obj(&self) -> GenericObj<T, N> where N: Clone {
+        self.obj.extract(|index| self.polygons.binary_search(&index).is_ok())
+    }
+}
+
+impl<'a, T: Float, N: Borrow<str> + Eq + Hash> fmt::Debug for Selection<'a, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Selection")
+            .field("polygons", &self.polygons().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+// A minimal glob matcher supporting `*` and `?`, which backtracks to the most recent `*` on a mismatch
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            },
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            },
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                },
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
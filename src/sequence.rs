@@ -0,0 +1,176 @@
+use crate::{math, morph, Error, Float, GenericObj, ParseOptions};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A sequence of OBJs with the same topology, such as the frames of a fluid or cloth simulation exported as numbered
+/// files.
+///
+/// Most users will want to use the [`ObjSequence`] alias, which stores attributes as `f32`.
+///
+/// Every frame is checked to have the same topology as the first (see [`GenericObj::morph_delta`]), so frames differ
+/// only in their attributes and [`GenericObjSequence::position_deltas`] can be used to update a mesh that was built
+/// from the first frame.
+///
+/// ```no_run
+/// use wavefront::ObjSequence;
+///
+/// let seq = ObjSequence::from_pattern("cache", "frame_####.obj").unwrap();
+/// for i in 1..seq.len() {
+///     let deltas = seq.position_deltas(i).unwrap();
+///     // Upload the deltas...
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GenericObjSequence<T: Float = f32> {
+    frames: Vec<GenericObj<T>>,
+    paths: Vec<PathBuf>,
+}
+
+/// A sequence of OBJs that stores attributes as `f32`. See [`GenericObjSequence`] for more information.
+pub type ObjSequence = GenericObjSequence<f32>;
+
+impl<T: Float> GenericObjSequence<T> {
+    /// Read the files in a directory whose names match a pattern, in order of frame number.
+    ///
+    /// The pattern is a file name containing a single run of `#` characters, which matches the frame number: any
+    /// number of ASCII digits, with or without leading zeros. For example, `frame_####.obj` matches `frame_0001.obj`
+    /// and `frame_12.obj`, which are read in that order. Other files in the directory are ignored.
+    ///
+    /// Errors that occur while reading or parsing a frame are produced as [`Error::InFile`], as are frames whose
+    /// topology differs from that of the first frame (as an [`Error::TopologyMismatch`] describing the first
+    /// divergence). An [`Error::Io`] is produced if the pattern is invalid or no files match it.
+    pub fn from_pattern<P: AsRef<Path>>(dir: P, pattern: &str) -> Result<Self, Error> {
+        Self::from_paths(matching_paths(dir.as_ref(), pattern)?)
+    }
+
+    /// Read the files in a directory whose names match a pattern, in order of frame number, using the given
+    /// [`ParseOptions`].
+    ///
+    /// See [`GenericObjSequence::from_pattern`] for more information.
+    pub fn from_pattern_with<P: AsRef<Path>>(dir: P, pattern: &str, options: ParseOptions) -> Result<Self, Error> {
+        Self::from_paths_with(matching_paths(dir.as_ref(), pattern)?, options)
+    }
+
+    /// Read the given files as consecutive frames.
+    ///
+    /// With the `rayon` feature enabled, the files are parsed in parallel. See [`GenericObjSequence::from_pattern`]
+    /// for more information.
+    pub fn from_paths<I: IntoIterator<Item = PathBuf>>(paths: I) -> Result<Self, Error> {
+        let paths = paths.into_iter().collect::<Vec<_>>();
+        #[cfg(feature = "rayon")]
+        let frames = {
+            use rayon::prelude::*;
+            paths.par_iter().map(GenericObj::from_file).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let frames = paths.iter().map(GenericObj::from_file).collect();
+        Self::from_frames(frames, paths)
+    }
+
+    /// Read the given files as consecutive frames, using the given [`ParseOptions`].
+    ///
+    /// The files are always parsed one at a time, since the callback given to [`ParseOptions::progress`] need not be
+    /// thread-safe.
+    pub fn from_paths_with<I: IntoIterator<Item = PathBuf>>(paths: I, options: ParseOptions) -> Result<Self, Error> {
+        let paths = paths.into_iter().collect::<Vec<_>>();
+        let frames = paths.iter().map(|path| GenericObj::from_file_with(path, options.clone())).collect();
+        Self::from_frames(frames, paths)
+    }
+
+    // Check that the frames were parsed successfully, in order, and that they share the topology of the first frame
+    fn from_frames(frames: Vec<Result<GenericObj<T>, Error>>, paths: Vec<PathBuf>) -> Result<Self, Error> {
+        let frames = frames
+            .into_iter()
+            .zip(&paths)
+            .map(|(frame, path)| frame.map_err(|e| Error::InFile(path.clone(), Box::new(e))))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(base) = frames.first() {
+            for (frame, path) in frames.iter().zip(&paths).skip(1) {
+                morph::check_topology(base, frame)
+                    .map_err(|e| Error::InFile(path.clone(), Box::new(Error::TopologyMismatch(e))))?;
+            }
+        }
+
+        Ok(Self { frames, paths })
+    }
+
+    /// Returns the number of frames in this sequence.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if this sequence has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns a specific frame by index.
+    pub fn frame(&self, index: usize) -> Option<&GenericObj<T>> {
+        self.frames.get(index)
+    }
+
+    /// Returns the path of the file that a specific frame was read from.
+    pub fn path(&self, index: usize) -> Option<&Path> {
+        self.paths.get(index).map(PathBuf::as_path)
+    }
+
+    /// Returns an iterator over the frames of this sequence.
+    pub fn frames(&self) -> impl ExactSizeIterator<Item = &GenericObj<T>> + Clone + '_ {
+        self.frames.iter()
+    }
+
+    /// Returns the difference between the positions of a specific frame and those of the first frame.
+    ///
+    /// Since every frame has the same topology, the deltas can be applied to the positions of the first frame (such
+    /// as with [`GenericObj::apply_morph`]) to produce those of the given frame.
+    pub fn position_deltas(&self, index: usize) -> Option<Vec<[T; 3]>> {
+        let (base, frame) = (self.frames.first()?, self.frames.get(index)?);
+        Some(base.positions().iter().zip(frame.positions()).map(|(a, b)| math::sub(*b, *a)).collect())
+    }
+}
+
+// Find the files in a directory that match a pattern, in order of frame number
+fn matching_paths(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>, Error> {
+    let (prefix, suffix) = split_pattern(pattern)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "pattern must contain a single run of '#'"))?;
+
+    let mut frames = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = match entry.file_name().into_string() {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        if let Some(frame) = frame_number(&name, prefix, suffix) {
+            frames.push((frame, name, entry.path()));
+        }
+    }
+    if frames.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "no files match the pattern").into());
+    }
+    // Frames with the same number but different padding are ordered by name, so that the order is deterministic
+    frames.sort_unstable();
+    Ok(frames.into_iter().map(|(_, _, path)| path).collect())
+}
+
+// Split a pattern into the parts before and after its run of `#`
+fn split_pattern(pattern: &str) -> Option<(&str, &str)> {
+    let start = pattern.find('#')?;
+    let end = pattern.rfind('#')? + 1;
+    if pattern[start..end].bytes().all(|b| b == b'#') {
+        Some((&pattern[..start], &pattern[end..]))
+    } else {
+        None
+    }
+}
+
+fn frame_number(name: &str, prefix: &str, suffix: &str) -> Option<u64> {
+    let digits = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
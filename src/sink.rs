@@ -0,0 +1,172 @@
+use crate::{
+    parse::MAX_ATTRIBUTES,
+    pull::{parse_event, split_keyword},
+    Error, Float, Index, ObjEvent,
+};
+use alloc::vec::Vec;
+
+/// A receiver for the contents of an OBJ, allowing it to be parsed directly into a mesh representation of your own
+/// with [`parse_into`].
+///
+/// Attributes are given in the order in which they appear, such that the first position has index `0`, the second
+/// has index `1`, and so on. By the time a face is given, every index within it has been resolved to this zero-based
+/// form (including relative indices, which count backwards from the most recent attribute) and checked to refer to an
+/// attribute that has already been given.
+///
+/// Names are borrowed from the input for `'a`, so a sink may keep them without copying. Sinks that don't need to can
+/// be implemented for any lifetime.
+///
+/// ```
+/// use wavefront::{parse_into, Error, Index, MeshSink};
+///
+/// #[derive(Default)]
+/// struct Mesh {
+///     positions: Vec<[f32; 3]>,
+///     triangles: Vec<[u32; 3]>,
+/// }
+///
+/// impl<'a> MeshSink<'a> for Mesh {
+///     type Error = Error;
+///
+///     fn position(&mut self, position: [f32; 3]) {
+///         self.positions.push(position);
+///     }
+///
+///     fn face(&mut self, vertices: &[(Index, Option<Index>, Option<Index>)]) -> Result<(), Error> {
+///         for i in 1..vertices.len().saturating_sub(1) {
+///             let [a, b, c] = [0, i, i + 1].map(|v| vertices[v].0 as u32);
+///             self.triangles.push([a, b, c]);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let mut mesh = Mesh::default();
+/// parse_into("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n", &mut mesh).unwrap();
+/// assert_eq!(mesh.triangles, vec![[0, 1, 2], [0, 2, 3]]);
+/// ```
+pub trait MeshSink<'a, T = f32> {
+    /// The error produced by the sink. Errors produced while parsing are converted into it.
+    type Error: From<Error>;
+
+    /// Receive a vertex position. Missing components are zero.
+    fn position(&mut self, position: [T; 3]);
+
+    /// Receive a texture coordinate. Missing components are zero.
+    fn uv(&mut self, _uv: [T; 3]) {}
+
+    /// Receive a vertex normal. Missing components are zero.
+    fn normal(&mut self, _normal: [T; 3]) {}
+
+    /// Begin a new object, to which subsequent faces belong.
+    fn begin_object(&mut self, _name: &'a str) {}
+
+    /// Select the groups to which subsequent faces belong, as written. No names selects the default group.
+    fn begin_group(&mut self, _names: &[&'a str]) {}
+
+    /// Select the material used by subsequent faces (`usemtl`), as written. An empty name selects no material.
+    fn use_material(&mut self, _name: &'a str) {}
+
+    /// Receive a face, made of the (zero-based) position, texture coordinate and normal indices of its vertices.
+    ///
+    /// An error returned from here stops parsing, and is returned from [`parse_into`].
+    fn face(&mut self, vertices: &[(Index, Option<Index>, Option<Index>)]) -> Result<(), Self::Error>;
+}
+
+/// Parse an OBJ directly into the given [`MeshSink`], without building an [`Obj`](crate::Obj).
+///
+/// Parsing stops at the first error, whether it is produced by the parser or by the sink. Statements that the sink
+/// has no callback for (such as `mtllib`) are ignored.
+pub fn parse_into<'a, T: Float, S: MeshSink<'a, T>>(src: &'a str, sink: &mut S) -> Result<(), S::Error> {
+    let mut resolver = Resolver::new(false);
+    for (i, line) in src.lines().enumerate() {
+        if let Some((keyword, rest)) = split_keyword(line) {
+            resolver.apply(sink, i + 1, parse_event(i + 1, keyword, rest)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Passes the statements of an OBJ on to a [`MeshSink`], resolving the indices of faces.
+pub(crate) struct Resolver {
+    // The number of each kind of attribute given to the sink so far, and the most that may be given
+    pub lengths: [usize; 3],
+    limits: [usize; 3],
+    terms: Vec<(Index, Option<Index>, Option<Index>)>,
+    // Whether faces may refer to attributes that have yet to be given, leaving the sink to check them later
+    allow_forward: bool,
+}
+
+impl Resolver {
+    pub fn new(allow_forward: bool) -> Self {
+        Self { lengths: [0; 3], limits: [MAX_ATTRIBUTES; 3], terms: Vec::new(), allow_forward }
+    }
+
+    /// Limit the number of each kind of attribute to fewer than can be indexed.
+    pub fn with_limits(mut self, limits: [usize; 3]) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Count an attribute of the given kind (`0` for positions, `1` for texture coordinates and `2` for normals)
+    /// without giving it to the sink.
+    pub fn skip(&mut self, kind: usize, line_num: usize) -> Result<(), Error> {
+        self.count(kind, line_num)
+    }
+
+    pub fn apply<'a, T, S: MeshSink<'a, T>>(
+        &mut self,
+        sink: &mut S,
+        line_num: usize,
+        event: ObjEvent<'a, T>,
+    ) -> Result<(), S::Error> {
+        match event {
+            ObjEvent::Position(pos) => {
+                self.count(0, line_num)?;
+                sink.position(pos);
+            },
+            ObjEvent::Uv(uv) => {
+                self.count(1, line_num)?;
+                sink.uv(uv);
+            },
+            ObjEvent::Normal(norm) => {
+                self.count(2, line_num)?;
+                sink.normal(norm);
+            },
+            ObjEvent::Face(terms) => {
+                self.terms.clear();
+                for term in terms {
+                    let (pos, uv, norm) = term?.resolve(self.lengths)?;
+                    let (lengths, allow_forward) = (self.lengths, self.allow_forward);
+                    let index = |kind: usize, idx: core::num::NonZeroU32| {
+                        if !allow_forward && idx.get() as usize > lengths[kind] {
+                            Err(Error::InvalidIndex(idx.get() as isize))
+                        } else {
+                            Ok(idx.get() as usize - 1)
+                        }
+                    };
+                    self.terms.push((
+                        index(0, pos)?,
+                        uv.map(|uv| index(1, uv)).transpose()?,
+                        norm.map(|norm| index(2, norm)).transpose()?,
+                    ));
+                }
+                sink.face(&self.terms)?;
+            },
+            ObjEvent::Object(name) => sink.begin_object(name),
+            ObjEvent::Group(names) => sink.begin_group(&names),
+            ObjEvent::UseMtl(name) => sink.use_material(name),
+            _ => {},
+        }
+        Ok(())
+    }
+
+    // Count an attribute that is about to be given to the sink, failing if it's past the limit
+    fn count(&mut self, kind: usize, line_num: usize) -> Result<(), Error> {
+        if self.lengths[kind] >= self.limits[kind] {
+            return Err(Error::TooManyAttributes(line_num));
+        }
+        self.lengths[kind] += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    math::{self, GridCoord},
+    Float, GenericObj, Plane,
+};
+use alloc::vec::Vec;
+use hashbrown::{HashMap, HashSet};
+
+/// A chain of points along a cross-section of an [`Obj`](crate::Obj), produced by [`GenericObj::slice`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polyline<T = f32> {
+    /// The points along the polyline, in order.
+    ///
+    /// The first point of a closed polyline is not repeated at the end.
+    pub points: Vec<[T; 3]>,
+    /// Whether the polyline is a closed loop, with its last point joined back to its first.
+    pub closed: bool,
+}
+
+impl<T: Float> Polyline<T> {
+    /// Returns the total length of the segments of this polyline, including the closing segment of a closed loop.
+    pub fn length(&self) -> T {
+        let n = self.points.len();
+        let segments = if self.closed { n } else { n.saturating_sub(1) };
+        (0..segments)
+            .map(|i| {
+                let d = math::sub(self.points[(i + 1) % n], self.points[i]);
+                math::dot(d, d).sqrt()
+            })
+            .fold(T::ZERO, |a, b| a + b)
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Find the cross-section of this OBJ along a plane, as a list of polylines.
+    ///
+    /// Each triangle (see [`GenericObj::triangles`]) crossing the plane contributes a segment, and segments are
+    /// chained together where their ends lie within `epsilon` of each other. Ends are computed identically for
+    /// triangles sharing an edge, so a welded mesh chains correctly with an `epsilon` of zero. A closed mesh produces
+    /// closed loops, wound counter-clockwise when viewed from the side that the normal of the plane points towards
+    /// (provided that its polygons face outwards). Triangles lying in the plane don't contribute anything. An `epsilon`
+    /// of zero or less (or NaN) only chains ends that are exactly equal.
+    ///
+    /// Polylines are split wherever more than two segments meet, such as at a non-manifold edge, so every polyline is
+    /// a simple chain. Polylines that start or end at such a junction are open.
+    ///
+    /// ```
+    /// use wavefront::{Obj, Plane};
+    ///
+    /// let obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// let sections = obj.slice(Plane::new([0.0, 0.0, 1.0], 0.5), 0.0);
+    /// assert_eq!(sections.len(), 1);
+    /// assert!(sections[0].closed);
+    /// assert_eq!(sections[0].length(), 4.0);
+    /// ```
+    pub fn slice(&self, plane: Plane<T>, epsilon: T) -> Vec<Polyline<T>> {
+        let depths = self.positions().iter().map(|p| plane.signed_distance(*p)).collect::<Vec<_>>();
+        let mut nodes = Nodes { points: Vec::new(), cells: HashMap::new(), epsilon };
+
+        // Undirected edges between nodes, each with the direction that it was first seen in
+        let mut seen = HashSet::new();
+        let mut edges = Vec::new();
+        for tri in self.triangles() {
+            let [a, b, c] = tri.map(|v| (v.position_index(), v.position()));
+            let mut ends = Vec::with_capacity(3);
+            for ((i, p), (j, q)) in [(a, b), (b, c), (c, a)] {
+                let (di, dj) = (depths[i], depths[j]);
+                let end = if di == T::ZERO {
+                    p
+                } else if (di > T::ZERO && dj < T::ZERO) || (di < T::ZERO && dj > T::ZERO) {
+                    // Always interpolate in the same direction, so that both sides of an edge agree exactly
+                    let ((dp, p), (dq, q)) = if i <= j { ((di, p), (dj, q)) } else { ((dj, q), (di, p)) };
+                    let t = dp / (dp - dq);
+                    [0, 1, 2].map(|k| p[k] + (q[k] - p[k]) * t)
+                } else {
+                    continue;
+                };
+                ends.push(nodes.find_or_insert(end));
+            }
+            ends.sort_unstable();
+            ends.dedup();
+            // Triangles touching the plane at a single point, or lying in it, have no segment
+            let [from, to] = match ends[..] {
+                [from, to] => [from, to],
+                _ => continue,
+            };
+
+            // Wind the segment around the normal of the plane, following the surface
+            let [pa, pb, pc] = [a.1, b.1, c.1];
+            let normal = math::cross(math::sub(pb, pa), math::sub(pc, pa));
+            let dir = math::cross(plane.normal(), normal);
+            let [from, to] = if math::dot(math::sub(nodes.points[to], nodes.points[from]), dir) < T::ZERO {
+                [to, from]
+            } else {
+                [from, to]
+            };
+            if seen.insert((from.min(to), from.max(to))) {
+                edges.push([from, to]);
+            }
+        }
+
+        let mut incident = alloc::vec![Vec::new(); nodes.points.len()];
+        for (e, [from, to]) in edges.iter().enumerate() {
+            incident[*from].push(e);
+            incident[*to].push(e);
+        }
+
+        let mut used = alloc::vec![false; edges.len()];
+        let mut polylines = Vec::new();
+        let walk = |start: usize, mut edge: usize, used: &mut [bool]| {
+            let mut points = alloc::vec![nodes.points[start]];
+            let mut node = start;
+            loop {
+                used[edge] = true;
+                let [from, to] = edges[edge];
+                node = if from == node { to } else { from };
+                if node == start {
+                    return Polyline { points, closed: true };
+                }
+                points.push(nodes.points[node]);
+                match incident[node][..] {
+                    [a, b] => edge = if a == edge { b } else { a },
+                    _ => return Polyline { points, closed: false },
+                }
+            }
+        };
+        // Chains between junctions and loose ends, followed by closed loops
+        for (node, node_edges) in incident.iter().enumerate() {
+            if node_edges.len() != 2 {
+                for &edge in node_edges {
+                    if !used[edge] {
+                        polylines.push(walk(node, edge, &mut used));
+                    }
+                }
+            }
+        }
+        for edge in 0..edges.len() {
+            if !used[edge] {
+                polylines.push(walk(edges[edge][0], edge, &mut used));
+            }
+        }
+        polylines
+    }
+}
+
+/// Points at which segments end, merged where they lie within an epsilon of each other.
+struct Nodes<T> {
+    points: Vec<[T; 3]>,
+    // The nodes in each cell of a grid with cells as large as the epsilon
+    cells: HashMap<[GridCoord; 3], Vec<usize>>,
+    epsilon: T,
+}
+
+impl<T: Float> Nodes<T> {
+    fn find_or_insert(&mut self, p: [T; 3]) -> usize {
+        let cell = math::grid_cell(p, self.epsilon);
+        let max_dist_sq = if self.epsilon > T::ZERO { self.epsilon * self.epsilon } else { T::ZERO };
+        let mut found = None;
+        for neighbour in math::grid_neighbours(cell) {
+            for &node in self.cells.get(&neighbour).into_iter().flatten() {
+                let d = math::sub(self.points[node], p);
+                if math::dot(d, d) <= max_dist_sq && found.map_or(true, |found| node < found) {
+                    found = Some(node);
+                }
+            }
+        }
+        found.unwrap_or_else(|| {
+            self.points.push(p);
+            self.cells.entry(cell).or_default().push(self.points.len() - 1);
+            self.points.len() - 1
+        })
+    }
+}
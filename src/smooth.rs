@@ -0,0 +1,109 @@
+use crate::{math, Error, Float, GenericObj};
+use alloc::{sync::Arc, vec::Vec};
+use core::{convert::TryFrom, num::NonZeroU32};
+
+impl<T: Float> GenericObj<T> {
+    /// Smooth the surface of this OBJ by repeatedly moving each position towards the average of its neighbours.
+    ///
+    /// Each iteration moves every position a fraction `lambda` of the way towards the centroid of its one-ring (see
+    /// [`MeshAdjacency::vertex_neighbors`](crate::MeshAdjacency::vertex_neighbors)), with every neighbour weighted
+    /// equally. All positions are moved at once, based on where their neighbours were at the start of the iteration,
+    /// so the result doesn't depend on the order of the positions. If `preserve_boundary` is `true`, positions on a
+    /// boundary edge (see [`GenericObj::boundary_edges`]) stay where they are, which stops open meshes from shrinking
+    /// away from their outline.
+    ///
+    /// Only positions are changed: texture coordinates, normals and the polygons themselves are left untouched, as are
+    /// positions that aren't used by any polygon. Normals may be brought up to date afterwards with
+    /// [`GenericObj::recompute_normals`].
+    ///
+    /// Returns [`Error::InvalidParameter`] if `lambda` isn't within `(0, 1]`, in which case this OBJ is unchanged.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let mut obj = Obj::from_file("tests/cube.obj").unwrap();
+    /// obj.smooth_laplacian(4, 0.5, false).unwrap();
+    /// assert!(obj.positions().iter().all(|p| p.iter().all(|x| *x > 0.0 && *x < 1.0)));
+    /// assert!(obj.smooth_laplacian(1, 1.5, false).is_err());
+    /// ```
+    pub fn smooth_laplacian(&mut self, iterations: u32, lambda: T, preserve_boundary: bool) -> Result<(), Error> {
+        if !(lambda > T::ZERO && lambda <= T::ONE) {
+            return Err(Error::InvalidParameter("smoothing factor must be greater than 0 and at most 1"));
+        }
+        if iterations == 0 {
+            return Ok(());
+        }
+
+        let adjacency = self.adjacency();
+        let mut fixed = alloc::vec![false; self.buffers.positions.len()];
+        if preserve_boundary {
+            for [a, b] in self.boundary_edges() {
+                fixed[a] = true;
+                fixed[b] = true;
+            }
+        }
+        // The positions that are free to move
+        let movable = (0..fixed.len())
+            .filter(|i| !fixed[*i] && !adjacency.vertex_neighbors(*i).is_empty())
+            .collect::<Vec<_>>();
+        if movable.is_empty() {
+            return Ok(());
+        }
+
+        let positions = Arc::make_mut(&mut self.buffers.positions);
+        let mut moved = Vec::with_capacity(movable.len());
+        for _ in 0..iterations {
+            moved.clear();
+            moved.extend(movable.iter().map(|i| {
+                let neighbors = adjacency.vertex_neighbors(*i);
+                let sum = neighbors.iter().fold([T::ZERO; 3], |sum, j| math::add(sum, positions[*j]));
+                let centroid = math::scale(sum, T::ONE / T::from_f64(neighbors.len() as f64));
+                let p = positions[*i];
+                math::add(p, math::scale(math::sub(centroid, p), lambda))
+            }));
+            for (i, p) in movable.iter().zip(&moved) {
+                positions[*i] = *p;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the normals of this OBJ with smooth normals computed from its positions.
+    ///
+    /// Each position used by a polygon gets a single unit normal, the average of the normals (see
+    /// [`Polygon::raw_normal`](crate::Polygon::raw_normal)) of the polygons around it weighted by their area, and every
+    /// vertex of every polygon is given the normal of its position. Normals are stored in the order of their positions,
+    /// and those that can't be computed (such as around degenerate polygons) are zero. The previous normals are
+    /// discarded. Cached triangles remain valid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result would contain more than `u32::MAX` normals.
+    pub fn recompute_normals(&mut self) {
+        let mut sums = alloc::vec![[T::ZERO; 3]; self.buffers.positions.len()];
+        let mut used = alloc::vec![false; sums.len()];
+        for poly in self.polygons() {
+            let normal = poly.raw_normal();
+            for (pos, _, _) in poly.vertices {
+                let i = pos.get() as usize - 1;
+                sums[i] = math::add(sums[i], normal);
+                used[i] = true;
+            }
+        }
+
+        let mut normals = Vec::new();
+        let mut new_index = alloc::vec![None; sums.len()];
+        for (i, sum) in sums.into_iter().enumerate().filter(|(i, _)| used[*i]) {
+            let len = math::dot(sum, sum).sqrt();
+            normals.push(if len > T::ZERO && len.is_finite() { math::scale(sum, T::ONE / len) } else { [T::ZERO; 3] });
+            new_index[i] = Some(u32::try_from(normals.len())
+                .ok()
+                .and_then(NonZeroU32::new)
+                .expect("Too many normals for an OBJ"));
+        }
+        for (pos, _, norm) in Arc::make_mut(&mut self.buffers.vertices).iter_mut() {
+            *norm = new_index[pos.get() as usize - 1];
+        }
+        self.buffers.normals = Arc::new(normals);
+    }
+}
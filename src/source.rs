@@ -0,0 +1,138 @@
+use crate::{Error, Float, GenericObj, ParseOptions};
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// A source of named files, such as a directory, an archive or a remote server.
+///
+/// This allows an OBJ and the material libraries it references to be loaded from places other than the filesystem.
+/// See [`GenericObj::load_from_source`] for more information.
+pub trait ObjSource {
+    /// Open the file with the given name, as written in the OBJ or MTL file that refers to it.
+    fn open(&mut self, name: &str) -> io::Result<Box<dyn Read>>;
+}
+
+/// An [`ObjSource`] that opens files relative to a directory on the filesystem.
+#[derive(Clone, Debug)]
+pub struct DirSource {
+    root: PathBuf,
+}
+
+impl DirSource {
+    /// Create a new [`DirSource`] that opens files relative to the given directory.
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl ObjSource for DirSource {
+    fn open(&mut self, name: &str) -> io::Result<Box<dyn Read>> {
+        Ok(Box::new(File::open(self.root.join(name))?))
+    }
+}
+
+/// An OBJ loaded together with the material libraries that it references.
+///
+/// See [`GenericObj::load_from_source`] for more information.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct LoadedObj<T: Float = f32> {
+    /// The parsed OBJ.
+    pub obj: GenericObj<T>,
+    /// The names of the material libraries referenced by `mtllib` statements, in the order they first appear.
+    pub material_libraries: Vec<String>,
+    /// The texture maps referenced by the material libraries, in the order they first appear and without
+    /// duplicates.
+    ///
+    /// Names are given exactly as written in the material libraries, so they can be opened with the same
+    /// [`ObjSource`].
+    pub textures: Vec<String>,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Read an OBJ from a file, along with the material libraries that it references.
+    ///
+    /// Material libraries are opened relative to the directory containing the file. See
+    /// [`GenericObj::load_from_source`] for more information.
+    pub fn from_file_with_materials<P: AsRef<Path>>(path: P) -> Result<LoadedObj<T>, Error> {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "file name is not valid UTF-8"))?;
+        let root = path.parent().unwrap_or_else(|| Path::new(""));
+        Self::load_from_source(name, &mut DirSource::new(root))
+    }
+
+    /// Read an OBJ and the material libraries that it references from an [`ObjSource`].
+    ///
+    /// The file called `root_name` is opened and parsed, then every library named by one of its `mtllib` statements
+    /// is opened in turn and scanned for texture maps (`map_Kd`, `bump`, etc.), which are listed in the result so
+    /// that they can be fetched through the same source. Materials themselves are not otherwise interpreted.
+    ///
+    /// Errors are produced as [`Error::InFile`] with the name of the file that could not be opened or parsed,
+    /// including material libraries that are missing from the source.
+    pub fn load_from_source<S: ObjSource + ?Sized>(root_name: &str, source: &mut S) -> Result<LoadedObj<T>, Error> {
+        Self::load_from_source_with(root_name, source, ParseOptions::default())
+    }
+
+    /// Read an OBJ and the material libraries that it references from an [`ObjSource`], using the given
+    /// [`ParseOptions`].
+    ///
+    /// See [`GenericObj::load_from_source`] for more information.
+    pub fn load_from_source_with<S: ObjSource + ?Sized>(
+        root_name: &str,
+        source: &mut S,
+        options: ParseOptions,
+    ) -> Result<LoadedObj<T>, Error> {
+        let src = read_source(source, root_name).map_err(in_file(root_name))?;
+        let obj = Self::from_str_with(&src, options).map_err(in_file(root_name))?;
+
+        let mut material_libraries = Vec::new();
+        for line in src.lines() {
+            let mut terms = line.split_ascii_whitespace();
+            if terms.next() == Some("mtllib") {
+                push_unique(&mut material_libraries, terms);
+            }
+        }
+
+        let mut textures = Vec::new();
+        for name in &material_libraries {
+            let mtl = read_source(source, name).map_err(in_file(name))?;
+            for line in mtl.lines() {
+                let mut terms = line.split_ascii_whitespace();
+                match terms.next() {
+                    // Options may come before the file name, so it's always the last term
+                    Some(kind) if is_texture_map(kind) => push_unique(&mut textures, terms.last()),
+                    _ => {},
+                }
+            }
+        }
+
+        Ok(LoadedObj { obj, material_libraries, textures })
+    }
+}
+
+fn in_file(name: &str) -> impl FnOnce(Error) -> Error + '_ {
+    move |e| Error::InFile(PathBuf::from(name), Box::new(e))
+}
+
+fn read_source<S: ObjSource + ?Sized>(source: &mut S, name: &str) -> Result<String, Error> {
+    let mut src = String::new();
+    source.open(name)?.read_to_string(&mut src)?;
+    Ok(src)
+}
+
+fn is_texture_map(kind: &str) -> bool {
+    kind.starts_with("map_") || matches!(kind, "bump" | "disp" | "decal" | "refl" | "norm")
+}
+
+fn push_unique<'a>(names: &mut Vec<String>, new: impl IntoIterator<Item = &'a str>) {
+    for name in new {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.into());
+        }
+    }
+}
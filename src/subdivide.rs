@@ -0,0 +1,107 @@
+use crate::{math, Buffers, Float, GenericObj, NameIndex, VertexIndices, VertexRange};
+use alloc::{sync::Arc, vec::Vec};
+use core::{convert::TryFrom, num::NonZeroU32};
+use hashbrown::HashMap;
+
+impl<T: Float> GenericObj<T> {
+    /// Create a new OBJ by repeatedly splitting each triangle into four at the midpoints of its edges.
+    ///
+    /// Polygons are first split into triangles (see [`Polygon::triangles`](crate::Polygon::triangles)), then each
+    /// level of subdivision quadruples the number of triangles. Midpoints are shared between the triangles on either
+    /// side of an edge, so a welded mesh stays welded and no attribute is duplicated. Texture coordinates and normals
+    /// are interpolated linearly (normals are then renormalised) where both ends of an edge have them. Triangles keep
+    /// the object and group membership, and the material, of the polygon they came from.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result would contain more than `u32::MAX` of any kind of attribute.
+    pub fn subdivided(&self, levels: u32) -> Self {
+        let mut positions = self.buffers.positions.to_vec();
+        let mut uvs = self.buffers.uvs.to_vec();
+        let mut normals = self.buffers.normals.to_vec();
+
+        // The triangles of each polygon, in order
+        let mut triangles = Vec::new();
+        let mut ranges = Vec::with_capacity(self.buffers.polygons.len());
+        for range in self.buffers.polygons.iter() {
+            let start = triangles.len();
+            let vertices = &self.buffers.vertices[range.start..range.end];
+            triangles.extend((1..vertices.len().saturating_sub(1)).map(|i| [vertices[0], vertices[i], vertices[i + 1]]));
+            ranges.push(start..triangles.len());
+        }
+
+        for _ in 0..levels {
+            let mut midpoints = [HashMap::new(), HashMap::new(), HashMap::new()];
+            let mut split = Vec::with_capacity(triangles.len() * 4);
+            for range in ranges.iter_mut() {
+                let start = split.len();
+                for [a, b, c] in &triangles[range.clone()] {
+                    let mut midpoint = |a: &VertexIndices, b: &VertexIndices| -> VertexIndices {
+                        let [p, t, n] = &mut midpoints;
+                        (
+                            midpoint(p, &mut positions, a.0, b.0, false),
+                            a.1.zip(b.1).map(|(ta, tb)| midpoint(t, &mut uvs, ta, tb, false)),
+                            a.2.zip(b.2).map(|(na, nb)| midpoint(n, &mut normals, na, nb, true)),
+                        )
+                    };
+                    let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                    split.extend([[*a, ab, ca], [ab, *b, bc], [ca, bc, *c], [ab, bc, ca]]);
+                }
+                *range = start..split.len();
+            }
+            triangles = split;
+        }
+
+        let mut buffers = Buffers {
+            positions: Arc::new(positions),
+            uvs: Arc::new(uvs),
+            normals: Arc::new(normals),
+            vertices: Arc::new(triangles.iter().flatten().copied().collect()),
+            polygons: Arc::new((0..triangles.len()).map(|i| VertexRange { start: i * 3, end: i * 3 + 3 }).collect()),
+            lines: self.buffers.lines.clone(),
+            comments: self.buffers.comments.clone(),
+            report: self.buffers.report.clone(),
+            materials: self.buffers.materials.clone(),
+            material_names: self.buffers.material_names.clone(),
+        };
+        buffers.remap_polygon_data(ranges.iter().enumerate().flat_map(|(old, range)| range.clone().map(move |_| old)));
+        let objects = self.objects
+            .iter()
+            .map(|(name, groups)| (name.clone(), groups
+                .iter()
+                .map(|(name, polys)| (name.clone(), polys
+                    .iter()
+                    .flat_map(|index| ranges[*index].clone())
+                    .collect()))
+                .collect()))
+            .collect();
+
+        Self { buffers, names: Arc::new(NameIndex::new(&objects)), objects: Arc::new(objects), triangles: None }
+    }
+}
+
+// Returns the index of the attribute half-way between two others, adding it if this edge hasn't been seen before
+fn midpoint<T: Float>(
+    midpoints: &mut HashMap<(NonZeroU32, NonZeroU32), NonZeroU32>,
+    attrs: &mut Vec<[T; 3]>,
+    a: NonZeroU32,
+    b: NonZeroU32,
+    normalize: bool,
+) -> NonZeroU32 {
+    *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+        let (a, b) = (attrs[a.get() as usize - 1], attrs[b.get() as usize - 1]);
+        let half = T::ONE / (T::ONE + T::ONE);
+        let mut mid = [0, 1, 2].map(|i| (a[i] + b[i]) * half);
+        if normalize {
+            let len = math::dot(mid, mid).sqrt();
+            if len > T::ZERO {
+                mid = mid.map(|x| x / len);
+            }
+        }
+        attrs.push(mid);
+        u32::try_from(attrs.len())
+            .ok()
+            .and_then(NonZeroU32::new)
+            .expect("Too many attributes for a subdivided OBJ")
+    })
+}
@@ -0,0 +1,598 @@
+use crate::{math, Float, GenericObj, Polygon, Index};
+use alloc::{sync::Arc, vec::Vec};
+use hashbrown::HashMap;
+
+/// A report describing whether an [`Obj`] forms a closed, consistently oriented 2-manifold surface.
+///
+/// Edges are identified by the position indices of their end points, so vertices that share a position but differ
+/// in texture coordinate or normal (such as those along a UV seam) are considered to be the same vertex.
+///
+/// All lists are sorted in ascending order and each edge is written with its lowest position index first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ManifoldReport {
+    /// Edges used by only a single polygon.
+    pub boundary_edges: Vec<[Index; 2]>,
+    /// Edges shared by more than two polygons.
+    pub non_manifold_edges: Vec<[Index; 2]>,
+    /// Edges shared by two polygons that both traverse them in the same direction, implying inconsistent winding.
+    pub orientation_conflicts: Vec<[Index; 2]>,
+    /// Positions whose surrounding polygons do not form a single connected fan (two cones touching at their tips,
+    /// for example).
+    pub non_manifold_vertices: Vec<Index>,
+}
+
+impl ManifoldReport {
+    /// Returns `true` if every edge is shared by at least two polygons (i.e: the surface has no holes).
+    pub fn is_closed(&self) -> bool {
+        self.boundary_edges.is_empty()
+    }
+
+    /// Returns `true` if no edge is shared by more than two polygons, every vertex is surrounded by a single fan of
+    /// polygons and neighbouring polygons agree on their winding order.
+    pub fn is_manifold(&self) -> bool {
+        self.non_manifold_edges.is_empty()
+            && self.orientation_conflicts.is_empty()
+            && self.non_manifold_vertices.is_empty()
+    }
+}
+
+/// A report describing the changes made by [`Obj::orient_consistently`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrientReport {
+    /// The polygons whose winding order was reversed, in ascending order.
+    pub flipped: Vec<Index>,
+    /// The connected components that could not be oriented consistently (such as a Möbius strip) and were left
+    /// unchanged, each given as a list of its polygons in ascending order. Components are ordered by the first polygon
+    /// they contain.
+    pub non_orientable: Vec<Vec<Index>>,
+}
+
+/// A chain of boundary edges, produced by [`Obj::boundary_loops`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BoundaryLoop {
+    /// The position indices visited by the chain, in the direction that the polygons bordering it traverse its
+    /// edges.
+    ///
+    /// For a closed loop, each position appears once and the last position is connected back to the first. For an
+    /// open chain, the first and last positions are its two ends.
+    pub positions: Vec<Index>,
+    /// Whether the chain forms a closed loop.
+    pub closed: bool,
+}
+
+/// An edge at which polygons meet at a sharp angle, produced by [`Obj::crease_edges`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CreaseEdge {
+    /// The position indices of the ends of the edge, lowest first.
+    pub edge: [Index; 2],
+    /// The indices of the polygons that meet at the edge, lowest first, or the only polygon using a boundary edge.
+    pub faces: (Index, Option<Index>),
+    /// The angle between the normals of the two polygons, in degrees, or `None` for a boundary edge.
+    pub angle_degrees: Option<f32>,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Analyse the surface of this [`Obj`], reporting any boundary edges, non-manifold edges and vertices, and
+    /// inconsistently wound neighbouring polygons.
+    ///
+    /// See [`ManifoldReport`] for more information.
+    pub fn manifold_report(&self) -> ManifoldReport {
+        let edges = self.edges();
+        let mut report = ManifoldReport::default();
+
+        let mut vertex_edges = HashMap::<Index, Vec<&[EdgeUse]>>::new();
+        for (edge, uses) in edges.iter() {
+            match uses.len() {
+                1 => report.boundary_edges.push(*edge),
+                2 => if uses[0].forward == uses[1].forward {
+                    report.orientation_conflicts.push(*edge);
+                },
+                _ => report.non_manifold_edges.push(*edge),
+            }
+            vertex_edges.entry(edge[0]).or_default().push(uses);
+            vertex_edges.entry(edge[1]).or_default().push(uses);
+        }
+
+        // A vertex is manifold if the polygons around it are all connected to one another through the edges that
+        // meet at it.
+        for (vertex, uses) in vertex_edges.iter() {
+            let mut faces = uses
+                .iter()
+                .flat_map(|uses| uses.iter().map(|u| u.polygon))
+                .collect::<Vec<_>>();
+            faces.sort_unstable();
+            faces.dedup();
+
+            let mut sets = UnionFind::new(faces.len());
+            for uses in uses {
+                let first = faces.binary_search(&uses[0].polygon).unwrap();
+                for u in &uses[1..] {
+                    sets.union(first, faces.binary_search(&u.polygon).unwrap());
+                }
+            }
+            if sets.count() > 1 {
+                report.non_manifold_vertices.push(*vertex);
+            }
+        }
+
+        report.boundary_edges.sort_unstable();
+        report.non_manifold_edges.sort_unstable();
+        report.orientation_conflicts.sort_unstable();
+        report.non_manifold_vertices.sort_unstable();
+        report
+    }
+
+    /// Returns the edges of this [`Obj`] that are used by only a single polygon, such as those around a hole.
+    ///
+    /// Edges are those of the polygons as written, so the diagonals introduced by triangulation are never boundary
+    /// edges. Each edge is given as a pair of position indices in the direction that its polygon traverses it, and the
+    /// edges are sorted in ascending order. See [`ManifoldReport`] for how vertices are identified.
+    pub fn boundary_edges(&self) -> Vec<[Index; 2]> {
+        let mut edges = self
+            .edges()
+            .into_iter()
+            .filter(|(_, uses)| uses.len() == 1)
+            .map(|([a, b], uses)| if uses[0].forward { [a, b] } else { [b, a] })
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Chain the edges produced by [`Obj::boundary_edges`] into loops, such as the outline of each hole in a mesh.
+    ///
+    /// A cube with one (quad) face missing has a single closed loop of 4 positions, whether or not the cube has been
+    /// triangulated. Where several boundary edges start or end at the same position (such as two holes meeting at a
+    /// corner), it isn't clear how the loops should continue, so they are conservatively split into open chains that
+    /// end at that position. Closed loops start from their lowest position index, and chains are ordered by their
+    /// first edge.
+    pub fn boundary_loops(&self) -> Vec<BoundaryLoop> {
+        let edges = self.boundary_edges();
+        let (mut outgoing, mut incoming) = (HashMap::<Index, usize>::new(), HashMap::<Index, usize>::new());
+        for [a, b] in &edges {
+            *outgoing.entry(*a).or_default() += 1;
+            *incoming.entry(*b).or_default() += 1;
+        }
+        // Positions through which a chain continues unambiguously
+        let regular = |p: &Index| outgoing.get(p) == Some(&1) && incoming.get(p) == Some(&1);
+
+        let mut used = alloc::vec![false; edges.len()];
+        let mut loops = Vec::new();
+        let walk = |start: usize, used: &mut [bool]| {
+            let mut positions = alloc::vec![edges[start][0]];
+            let mut edge = start;
+            loop {
+                used[edge] = true;
+                let end = edges[edge][1];
+                if end == positions[0] && regular(&end) {
+                    return BoundaryLoop { positions, closed: true };
+                }
+                positions.push(end);
+                if !regular(&end) {
+                    return BoundaryLoop { positions, closed: false };
+                }
+                // Edges are sorted, so the only edge leaving a regular position can be found with a binary search
+                edge = edges.partition_point(|e| e[0] < end);
+            }
+        };
+        // Chains that start at a junction or a dead end are walked first, leaving only closed loops
+        for i in 0..edges.len() {
+            if !used[i] && !regular(&edges[i][0]) {
+                loops.push(walk(i, &mut used));
+            }
+        }
+        for i in 0..edges.len() {
+            if !used[i] {
+                loops.push(walk(i, &mut used));
+            }
+        }
+        loops.sort_unstable_by_key(|l| (l.positions[0], l.positions[1 % l.positions.len()]));
+        loops
+    }
+
+    /// Returns the edges at which neighbouring polygons meet at an angle greater than `angle_degrees`, such as for
+    /// drawing outlines or deciding where smoothing should stop.
+    ///
+    /// The angle is that between the normals of the two polygons, as given by [`Polygon::normal`] (using Newell's
+    /// method), so it doesn't matter whether the OBJ has normals of its own. Degenerate polygons have no normal and so
+    /// never form creases. Edges shared by more than two polygons are reported once for every pair that forms a
+    /// crease. When `include_boundary` is `true`, edges used by only one polygon are reported too. Edges are
+    /// identified as in [`ManifoldReport`], and the result is sorted by edge and then by faces.
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let cube = Obj::from_file("tests/cube.obj").unwrap();
+    /// assert_eq!(cube.crease_edges(30.0, false).len(), 12);
+    /// assert_eq!(cube.crease_edges(90.0, false).len(), 0);
+    /// ```
+    pub fn crease_edges(&self, angle_degrees: f32, include_boundary: bool) -> Vec<CreaseEdge> {
+        let normals = self
+            .polygons()
+            .map(|poly| Some(poly.normal()?.map(Float::to_f64)))
+            .collect::<Vec<_>>();
+        let threshold = angle_degrees.clamp(0.0, 180.0);
+
+        let mut creases = Vec::new();
+        for (edge, uses) in self.edges() {
+            if uses.len() == 1 {
+                if include_boundary {
+                    creases.push(CreaseEdge { edge, faces: (uses[0].polygon, None), angle_degrees: None });
+                }
+                continue;
+            }
+            for (i, a) in uses.iter().enumerate() {
+                for b in &uses[i + 1..] {
+                    let (a, b) = (a.polygon.min(b.polygon), a.polygon.max(b.polygon));
+                    if let (Some(n), Some(m)) = (normals[a], normals[b]) {
+                        // Comparing the angle that will be reported, rather than cosines, means that a right angle
+                        // isn't a crease at a threshold of exactly 90 degrees
+                        let angle = math::acos(math::dot(n, m).clamp(-1.0, 1.0)).to_degrees() as f32;
+                        if angle > threshold {
+                            creases.push(CreaseEdge { edge, faces: (a, Some(b)), angle_degrees: Some(angle) });
+                        }
+                    }
+                }
+            }
+        }
+        creases.sort_unstable_by_key(|crease| (crease.edge, crease.faces));
+        creases
+    }
+
+    /// Split this [`Obj`] into its connected components: sets of polygons that are joined to one another through
+    /// shared positions.
+    ///
+    /// Each component is returned as a standalone [`Obj`] with compacted attribute buffers, in which polygons keep
+    /// their original object and group membership. Components are ordered by the first polygon they contain.
+    pub fn connected_components(&self) -> Vec<Self> {
+        self.components(None)
+    }
+
+    /// Like [`Obj::connected_components`], but additionally treats any two positions no further than `epsilon`
+    /// apart as being the same position when deciding connectivity.
+    ///
+    /// This is useful for models in which touching pieces of geometry do not share position indices. An `epsilon` of
+    /// zero or less (or NaN) only joins positions that are exactly equal.
+    pub fn connected_components_welded(&self, epsilon: T) -> Vec<Self> {
+        self.components(Some(epsilon))
+    }
+
+    fn components(&self, epsilon: Option<T>) -> Vec<Self> {
+        let mut sets = UnionFind::new(self.positions().len());
+        for poly in self.polygons() {
+            for (pos, _, _) in poly.vertices.iter().skip(1) {
+                sets.union(poly.vertices[0].0.get() as usize - 1, pos.get() as usize - 1);
+            }
+        }
+
+        if let Some(epsilon) = epsilon {
+            // Bucket positions into a grid of cells `epsilon` wide so that only neighbouring cells need comparing
+            let mut grid = HashMap::<_, Vec<Index>>::new();
+            for (i, p) in self.positions().iter().enumerate() {
+                grid.entry(math::grid_cell(*p, epsilon)).or_default().push(i);
+            }
+            let max_dist_sq = if epsilon > T::ZERO { epsilon * epsilon } else { T::ZERO };
+            for (i, p) in self.positions().iter().enumerate() {
+                for cell in math::grid_neighbours(math::grid_cell(*p, epsilon)) {
+                    for &j in grid.get(&cell).into_iter().flatten() {
+                        let q = self.positions()[j];
+                        let dist_sq = (0..3).map(|k| (p[k] - q[k]) * (p[k] - q[k])).sum::<T>();
+                        if j > i && dist_sq <= max_dist_sq {
+                            sets.union(i, j);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Number components in the order that their first polygon appears. Polygons without vertices belong to no
+        // component.
+        let mut roots = HashMap::new();
+        let component_of = self
+            .polygons()
+            .map(|poly| poly.vertices.first().map(|(pos, _, _)| {
+                let count = roots.len();
+                *roots.entry(sets.find(pos.get() as usize - 1)).or_insert(count)
+            }))
+            .collect::<Vec<_>>();
+
+        (0..roots.len())
+            .map(|c| self.extract(|index| component_of[index] == Some(c)))
+            .collect()
+    }
+
+    /// Compute the connectivity between the polygons and positions of this [`Obj`].
+    ///
+    /// See [`MeshAdjacency`] for more information.
+    pub fn adjacency(&self) -> MeshAdjacency {
+        let mut face_faces = Vec::new();
+        let mut vertex_vertices = Vec::new();
+        for ([a, b], uses) in self.edges() {
+            vertex_vertices.push((a, b));
+            vertex_vertices.push((b, a));
+            for u in &uses {
+                face_faces.extend(uses
+                    .iter()
+                    .filter(|v| v.polygon != u.polygon)
+                    .map(|v| (u.polygon, v.polygon)));
+            }
+        }
+
+        let vertex_faces = self
+            .polygons()
+            .flat_map(|poly| poly.vertices.iter().map(move |(pos, _, _)| (pos.get() as usize - 1, poly.index())))
+            .collect();
+
+        let positions = self.positions().len();
+        MeshAdjacency {
+            face_faces: Csr::from_pairs(self.polygons().len(), face_faces),
+            vertex_faces: Csr::from_pairs(positions, vertex_faces),
+            vertex_vertices: Csr::from_pairs(positions, vertex_vertices),
+        }
+    }
+
+    /// Returns the valence of every position: the number of polygons that make use of it.
+    ///
+    /// Polygons are counted as written, so a quad counts once rather than once for each of its triangles, and a
+    /// polygon that uses a position several times counts once. Positions that aren't used by any polygon have a
+    /// valence of zero. The result is indexed by position index, and agrees with the lengths of the slices produced by
+    /// [`MeshAdjacency::faces_around_vertex`].
+    pub fn vertex_valences(&self) -> Vec<u32> {
+        let mut valences = alloc::vec![0u32; self.positions().len()];
+        let mut positions = Vec::new();
+        for poly in self.polygons() {
+            positions.clear();
+            positions.extend(poly.vertices.iter().map(|(pos, _, _)| pos.get() as usize - 1));
+            positions.sort_unstable();
+            positions.dedup();
+            for pos in &positions {
+                valences[*pos] = valences[*pos].saturating_add(1);
+            }
+        }
+        valences
+    }
+
+    /// Returns the number of positions with each valence, indexed by valence.
+    ///
+    /// The histogram is as long as is needed to hold the highest valence, so it's empty only if the [`Obj`] has no
+    /// positions. See [`Obj::vertex_valences`] for more information.
+    pub fn valence_histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        for valence in self.vertex_valences() {
+            let valence = valence as usize;
+            if histogram.len() <= valence {
+                histogram.resize(valence + 1, 0);
+            }
+            histogram[valence] += 1;
+        }
+        histogram
+    }
+
+    /// Returns the positions whose valence is greater than `threshold`, in ascending order.
+    ///
+    /// In a quad mesh, most positions have a valence of 4, and those with a higher valence (poles) are often worth
+    /// reviewing. See [`Obj::vertex_valences`] for more information.
+    pub fn high_valence_positions(&self, threshold: u32) -> Vec<Index> {
+        self
+            .vertex_valences()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, valence)| *valence > threshold)
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// Reverse the winding order of polygons such that neighbouring polygons agree on their orientation.
+    ///
+    /// Orientation is flood-filled across the edges shared by exactly two polygons, so each connected component of
+    /// the surface is made consistent on its own. Closed components are then oriented such that their enclosed volume
+    /// is positive (i.e: their polygons face outwards), while open components keep whichever orientation requires the
+    /// fewest polygons to be flipped. Components that cannot be oriented consistently are left unchanged and listed
+    /// in the report.
+    ///
+    /// Like [`GenericObj::mirror`], the first vertex of each flipped polygon is kept in place, so cached triangles
+    /// remain valid. Normals are not modified.
+    pub fn orient_consistently(&mut self) -> OrientReport {
+        let edges = self.edges();
+        let len = self.polygons().len();
+
+        // For each polygon, its neighbours across manifold edges and whether they must be flipped relative to it
+        let mut neighbours = alloc::vec![Vec::new(); len];
+        let mut closed = alloc::vec![true; len];
+        for uses in edges.values() {
+            match uses.as_slice() {
+                [a, b] if a.polygon != b.polygon => {
+                    let conflict = a.forward == b.forward;
+                    neighbours[a.polygon].push((b.polygon, conflict));
+                    neighbours[b.polygon].push((a.polygon, conflict));
+                },
+                uses => uses.iter().for_each(|u| closed[u.polygon] = false),
+            }
+        }
+
+        let mut report = OrientReport::default();
+        let mut flip = alloc::vec![None; len];
+        for seed in 0..len {
+            if flip[seed].is_some() {
+                continue;
+            }
+            flip[seed] = Some(false);
+            let (mut component, mut stack) = (Vec::new(), alloc::vec![seed]);
+            let mut orientable = true;
+            while let Some(poly) = stack.pop() {
+                component.push(poly);
+                let flipped = flip[poly] == Some(true);
+                for &(other, conflict) in &neighbours[poly] {
+                    match flip[other] {
+                        None => {
+                            flip[other] = Some(flipped ^ conflict);
+                            stack.push(other);
+                        },
+                        Some(f) => orientable &= f == flipped ^ conflict,
+                    }
+                }
+            }
+            component.sort_unstable();
+
+            if !orientable {
+                report.non_orientable.push(component);
+                continue;
+            }
+
+            let flips = component.iter().filter(|poly| flip[**poly] == Some(true)).count();
+            let invert = if component.iter().all(|poly| closed[*poly]) {
+                let volume = component
+                    .iter()
+                    .map(|poly| {
+                        let volume = self.buffers.lookup(*poly).triangles().fold(T::ZERO, |volume, [a, b, c]| {
+                            volume + math::dot(a.position(), math::cross(b.position(), c.position()))
+                        });
+                        if flip[*poly] == Some(true) { -volume } else { volume }
+                    })
+                    .fold(T::ZERO, |a, b| a + b);
+                volume < T::ZERO || (volume == T::ZERO && flips * 2 > component.len())
+            } else {
+                flips * 2 > component.len()
+            };
+            report.flipped.extend(component.into_iter().filter(|poly| (flip[*poly] == Some(true)) != invert));
+        }
+        report.flipped.sort_unstable();
+
+        let vertices = Arc::make_mut(&mut self.buffers.vertices);
+        for poly in &report.flipped {
+            let range = &self.buffers.polygons[*poly];
+            vertices[(range.start + 1).min(range.end)..range.end].reverse();
+        }
+        report
+    }
+
+    /// Build a map from each (undirected) edge to the polygons that make use of it.
+    pub(crate) fn edges(&self) -> HashMap<[Index; 2], Vec<EdgeUse>> {
+        let mut edges = HashMap::<_, Vec<EdgeUse>>::new();
+        for (polygon, poly) in self.polygons().enumerate() {
+            for [a, b] in polygon_edges(poly) {
+                edges
+                    .entry([a.min(b), a.max(b)])
+                    .or_default()
+                    .push(EdgeUse { polygon, forward: a < b });
+            }
+        }
+        edges
+    }
+}
+
+/// Connectivity information about the polygons and positions of an [`Obj`], created with [`Obj::adjacency`].
+///
+/// Polygons are referred to by their [`Polygon::index`](crate::Polygon::index) and vertices by their position index.
+/// Queries never fail: indices without any connectivity (including those that are out of range) simply produce empty
+/// slices. Every slice is sorted in ascending order.
+#[derive(Clone, Debug)]
+pub struct MeshAdjacency {
+    face_faces: Csr,
+    vertex_faces: Csr,
+    vertex_vertices: Csr,
+}
+
+impl MeshAdjacency {
+    /// Returns the polygons that share at least one edge with the given polygon.
+    ///
+    /// Non-manifold edges are supported: every other polygon using a shared edge is included.
+    pub fn faces_adjacent_to_face(&self, polygon: Index) -> &[Index] {
+        self.face_faces.get(polygon)
+    }
+
+    /// Returns the polygons that make use of the given position.
+    pub fn faces_around_vertex(&self, position: Index) -> &[Index] {
+        self.vertex_faces.get(position)
+    }
+
+    /// Returns the positions connected to the given position by a polygon edge (i.e: its one-ring).
+    pub fn vertex_neighbors(&self, position: Index) -> &[Index] {
+        self.vertex_vertices.get(position)
+    }
+}
+
+/// A compressed list of sorted, deduplicated lists.
+#[derive(Clone, Debug)]
+struct Csr {
+    offsets: Vec<usize>,
+    items: Vec<Index>,
+}
+
+impl Csr {
+    fn from_pairs(len: usize, mut pairs: Vec<(Index, Index)>) -> Self {
+        pairs.sort_unstable();
+        pairs.dedup();
+        let mut offsets = Vec::with_capacity(len + 1);
+        let mut items = Vec::with_capacity(pairs.len());
+        let mut pairs = pairs.into_iter().peekable();
+        for key in 0..len {
+            offsets.push(items.len());
+            while let Some((_, item)) = pairs.next_if(|(k, _)| *k == key) {
+                items.push(item);
+            }
+        }
+        offsets.push(items.len());
+        Self { offsets, items }
+    }
+
+    fn get(&self, key: Index) -> &[Index] {
+        match (self.offsets.get(key), self.offsets.get(key + 1)) {
+            (Some(start), Some(end)) => &self.items[*start..*end],
+            _ => &[],
+        }
+    }
+}
+
+/// A use of an edge by a polygon.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct EdgeUse {
+    /// The index of the polygon using the edge.
+    pub polygon: usize,
+    /// Whether the polygon traverses the edge from its lowest position index to its highest.
+    pub forward: bool,
+}
+
+/// Returns an iterator over the directed edges of a polygon, as pairs of position indices, skipping edges that
+/// collapse to a single position.
+pub(crate) fn polygon_edges<T>(poly: Polygon<'_, T>) -> impl Iterator<Item=[Index; 2]> + '_ {
+    let n = poly.vertices.len();
+    (0..n)
+        .map(move |i| [
+            poly.vertices[i].0.get() as usize - 1,
+            poly.vertices[(i + 1) % n].0.get() as usize - 1,
+        ])
+        .filter(|[a, b]| a != b)
+}
+
+/// A disjoint-set forest, used to find connected sets of elements.
+pub(crate) struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self { parents: (0..len).collect() }
+    }
+
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+        x
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        // Always keep the lowest element as the root so that results are deterministic
+        if a < b {
+            self.parents[b] = a;
+        } else {
+            self.parents[a] = b;
+        }
+    }
+
+    pub fn count(&mut self) -> usize {
+        (0..self.parents.len()).filter(|&x| self.find(x) == x).count()
+    }
+}
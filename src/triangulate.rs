@@ -0,0 +1,154 @@
+use crate::{math, Float, GenericObj, Group, Object, Polygon, Vertex};
+
+/// Options controlling how polygons are split into triangles (see [`Polygon::triangles_with`]).
+///
+/// The default options produce exactly the same triangles as [`Polygon::triangles`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Triangulation {
+    /// How quads are split into a pair of triangles.
+    pub quad_split: QuadSplit,
+    /// How polygons are decomposed into triangles, and which vertex of each triangle comes first.
+    pub provoking_vertex: ProvokingVertex,
+}
+
+/// Determines which diagonal is used to split a quad into a pair of triangles.
+///
+/// Polygons with any other number of vertices are unaffected. When both diagonals are equally good, the quad is split
+/// along the diagonal from its first vertex to its third.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum QuadSplit {
+    /// Always split along the diagonal from the first vertex to the third, as with [`Polygon::triangles`].
+    #[default]
+    Fixed,
+    /// Split along the shorter of the two diagonals.
+    ShortestDiagonal,
+    /// Split along the diagonal that minimises the dihedral angle between the two resulting triangles, producing the
+    /// flattest pair of triangles for non-planar quads.
+    MinDihedral,
+}
+
+/// Determines how a polygon is decomposed into triangles, and the order of the vertices of each triangle.
+///
+/// Renderers that don't interpolate an attribute across a triangle (such as with a `flat` qualifier) take it from
+/// the triangle's provoking vertex, which is its first or last vertex depending on the API. Every mode keeps the
+/// winding order of the polygon, and the triangles of a polygon together cover the same area in every mode.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProvokingVertex {
+    /// Fan out from a single vertex, which comes first in every triangle, as with [`Polygon::triangles`].
+    #[default]
+    FanFirst,
+    /// Fan out from a single vertex, which comes last in every triangle.
+    FanLast,
+    /// Zig-zag across the polygon like a triangle strip, in which every triangle adds a vertex that the others don't,
+    /// and put that vertex first.
+    ///
+    /// Every vertex of a polygon except two is the provoking vertex of exactly one of its triangles, so a
+    /// per-triangle attribute can be stored in it.
+    StripFirst,
+    /// Like [`ProvokingVertex::StripFirst`], but with the distinct vertex of each triangle last.
+    StripLast,
+}
+
+impl<'a, T: Float> Polygon<'a, T> {
+    /// Returns an iterator over the triangles of this polygon, triangulated according to the given options.
+    ///
+    /// Triangles keep the winding order of the polygon. See [`Polygon::triangles`] for more information.
+    pub fn triangles_with(&self, options: Triangulation) -> impl ExactSizeIterator<Item=[Vertex<'a, T>; 3]> + Clone + 'a {
+        let this = *self;
+        let n = this.vertices.len();
+        // Splitting a quad along its other diagonal is equivalent to fanning from its second vertex
+        let start = if n == 4 && this.prefer_second_diagonal(options.quad_split) { 1 } else { 0 };
+        let mode = options.provoking_vertex;
+        (0..n.saturating_sub(2))
+            .map(move |i| {
+                let corners = match mode {
+                    ProvokingVertex::FanFirst => [start, start + i + 1, start + i + 2],
+                    ProvokingVertex::FanLast => [start + i + 1, start + i + 2, start],
+                    // A strip from the vertex after the fan's starts by splitting quads along the same diagonal
+                    ProvokingVertex::StripFirst => {
+                        let [a, b, c] = strip_triangle(start + 1, n, i);
+                        [c, a, b]
+                    },
+                    ProvokingVertex::StripLast => strip_triangle(start + 1, n, i),
+                };
+                corners.map(|corner| this.vertex(corner % n).unwrap())
+            })
+    }
+
+    /// Returns `true` if this quad should be split along the diagonal from its second vertex to its fourth.
+    fn prefer_second_diagonal(&self, split: QuadSplit) -> bool {
+        let [a, b, c, d] = [0, 1, 2, 3].map(|i| self.vertex(i).unwrap().position());
+        match split {
+            QuadSplit::Fixed => false,
+            QuadSplit::ShortestDiagonal => {
+                let (ac, bd) = (math::sub(c, a), math::sub(d, b));
+                math::dot(bd, bd) < math::dot(ac, ac)
+            },
+            QuadSplit::MinDihedral => flatness([b, c, d, a]) > flatness([a, b, c, d]),
+        }
+    }
+}
+
+/// Returns the `i`th triangle of a strip that zig-zags across a polygon with `n` vertices, starting at vertex `start`
+/// and visiting `start + 1`, `start - 1`, `start + 2`, `start - 2` and so on. The triangle keeps the polygon's winding
+/// order, and ends with the vertex that it added to the strip.
+fn strip_triangle(start: usize, n: usize, i: usize) -> [usize; 3] {
+    let strip = |j: usize| if j % 2 == 0 { start + n - j / 2 } else { start + (j + 1) / 2 };
+    // Every other triangle of a strip is wound backwards
+    if i % 2 == 0 {
+        [strip(i), strip(i + 1), strip(i + 2)]
+    } else {
+        [strip(i + 1), strip(i), strip(i + 2)]
+    }
+}
+
+/// Returns the cosine of the angle between the normals of the triangles `(p, q, r)` and `(p, r, s)`, formed by
+/// splitting the quad `p, q, r, s` along the diagonal `p`-`r`. Larger values indicate a flatter split.
+///
+/// Splits that produce a degenerate triangle are considered worse than any other.
+fn flatness<T: Float>([p, q, r, s]: [[T; 3]; 4]) -> T {
+    let n1 = math::cross(math::sub(q, p), math::sub(r, p));
+    let n2 = math::cross(math::sub(r, p), math::sub(s, p));
+    let len = (math::dot(n1, n1) * math::dot(n2, n2)).sqrt();
+    if len > T::ZERO {
+        math::dot(n1, n2) / len
+    } else {
+        -(T::ONE + T::ONE)
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Returns an iterator over the triangles in this [`Obj`](crate::Obj), triangulated according to the given
+    /// options.
+    ///
+    /// The result may be passed to [`IndexedMesh::from_triangles`](crate::IndexedMesh::from_triangles) or
+    /// [`FlatBuffers::from_triangles`](crate::FlatBuffers::from_triangles) for export. See
+    /// [`Polygon::triangles_with`] for more information.
+    pub fn triangles_with(&self, options: Triangulation) -> impl Iterator<Item=[Vertex<'_, T>; 3]> + Clone + '_ {
+        self
+            .polygons()
+            .flat_map(move |poly| poly.triangles_with(options))
+    }
+}
+
+impl<'a, T: Float> Object<'a, T> {
+    /// Returns an iterator over the triangles in this [`Object`], triangulated according to the given options.
+    ///
+    /// See [`Polygon::triangles_with`] for more information.
+    pub fn triangles_with(&self, options: Triangulation) -> impl Iterator<Item=[Vertex<'a, T>; 3]> + Clone + 'a {
+        self
+            .polygons()
+            .flat_map(move |poly| poly.triangles_with(options))
+    }
+}
+
+impl<'a, T: Float> Group<'a, T> {
+    /// Returns an iterator over the triangles in this [`Group`], triangulated according to the given options.
+    ///
+    /// See [`Polygon::triangles_with`] for more information.
+    pub fn triangles_with(&self, options: Triangulation) -> impl Iterator<Item=[Vertex<'a, T>; 3]> + Clone + 'a {
+        self
+            .polygons()
+            .flat_map(move |poly| poly.triangles_with(options))
+    }
+}
@@ -0,0 +1,371 @@
+//! Utilities relating to the OBJ format.
+
+use crate::pull::split_keyword;
+use alloc::{borrow::Cow, format, string::String};
+use core::fmt;
+use hashbrown::{HashMap, HashSet};
+
+/// Determine whether a name (of either an object or a group) is valid.
+pub fn name_is_valid(name: &str) -> bool {
+    name.chars().all(valid_char)
+}
+
+/// Turn any string into a valid name for an object or group (see [`name_is_valid`]).
+///
+/// Every character that may not appear in a name (anything other than an ASCII letter or digit, `.` or `_`) is
+/// replaced with a single `_`, so `"Left wheel/tyre"` becomes `"Left_wheel_tyre"` and `"Tür"` becomes `"T_r"`.
+/// Valid names are returned unchanged without allocating. Since different names may become the same, use a
+/// [`NameSanitizer`] when the results need to remain distinct.
+pub fn sanitize_name(name: &str) -> Cow<'_, str> {
+    if name_is_valid(name) {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(name.chars().map(|c| if valid_char(c) { c } else { '_' }).collect())
+    }
+}
+
+fn valid_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '.' || c == '_'
+}
+
+/// Turns strings into valid names like [`sanitize_name`], while ensuring that different strings never produce the
+/// same name.
+///
+/// The sanitizer remembers every name that it has produced. A string that is given again produces the same name
+/// as before, while one whose sanitized name has already been produced for a different string has a suffix of
+/// `_2`, `_3` and so on appended. Strings are handled in the order given, so give those that are already valid
+/// first to keep them unchanged.
+///
+/// ```
+/// use wavefront::util::NameSanitizer;
+///
+/// let mut sanitizer = NameSanitizer::new();
+/// assert_eq!(sanitizer.sanitize("door_left"), "door_left");
+/// assert_eq!(sanitizer.sanitize("door left"), "door_left_2");
+/// assert_eq!(sanitizer.sanitize("door/left"), "door_left_3");
+/// assert_eq!(sanitizer.sanitize("door left"), "door_left_2");
+/// assert_eq!(sanitizer.get("door/left"), Some("door_left_3"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NameSanitizer {
+    names: HashMap<String, String>,
+    emitted: HashSet<String>,
+}
+
+impl NameSanitizer {
+    /// Create a sanitizer that hasn't produced any names yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a valid name for the given string that is distinct from those produced for any other string.
+    pub fn sanitize(&mut self, name: &str) -> &str {
+        if !self.names.contains_key(name) {
+            let base = sanitize_name(name);
+            let mut sanitized = String::from(&*base);
+            let mut suffix = 2;
+            while self.emitted.contains(&sanitized) {
+                sanitized = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            self.emitted.insert(sanitized.clone());
+            self.names.insert(String::from(name), sanitized);
+        }
+        &self.names[name]
+    }
+
+    /// Returns the name previously produced for the given string, if any.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.names.get(name).map(String::as_str)
+    }
+
+    /// Returns an iterator over the strings that were changed by sanitizing them, along with their new names, in
+    /// an unspecified order.
+    pub fn renamed(&self) -> impl Iterator<Item=(&str, &str)> + Clone + '_ {
+        self.names.iter().filter(|(old, new)| old != new).map(|(old, new)| (old.as_str(), new.as_str()))
+    }
+}
+
+/// A keyword that begins a statement, covering every directive in the OBJ specification.
+///
+/// Only some of these are understood when building an [`Obj`](crate::Obj): see [`Keyword::is_supported`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Keyword {
+    /// A geometric vertex (`v`).
+    Position,
+    /// A texture vertex (`vt`).
+    Uv,
+    /// A vertex normal (`vn`).
+    Normal,
+    /// A parameter space vertex of a free-form curve or surface (`vp`).
+    ParameterVertex,
+    /// The type of subsequent free-form curves and surfaces (`cstype`).
+    CurveSurfaceType,
+    /// The degree of subsequent free-form curves and surfaces (`deg`).
+    Degree,
+    /// The basis matrix of subsequent free-form curves and surfaces (`bmat`).
+    BasisMatrix,
+    /// The step size of subsequent free-form curves and surfaces (`step`).
+    Step,
+    /// A point element (`p`).
+    Point,
+    /// A line element (`l`).
+    Line,
+    /// A face element (`f`).
+    Face,
+    /// A free-form curve element (`curv`).
+    Curve,
+    /// A free-form curve on a surface, in parameter space (`curv2`).
+    Curve2,
+    /// A free-form surface element (`surf`).
+    Surface,
+    /// Global parameter values of a free-form curve or surface body (`parm`).
+    Parameter,
+    /// An outer trimming loop of a free-form surface body (`trim`).
+    Trim,
+    /// An inner trimming loop of a free-form surface body (`hole`).
+    Hole,
+    /// A special curve of a free-form surface body (`scrv`).
+    SpecialCurve,
+    /// Special points of a free-form curve or surface body (`sp`).
+    SpecialPoint,
+    /// The end of a free-form curve or surface body (`end`).
+    End,
+    /// The connectivity between two free-form surfaces (`con`).
+    Connect,
+    /// The groups that subsequent elements belong to (`g`).
+    Group,
+    /// The smoothing group of subsequent elements (`s`).
+    SmoothingGroup,
+    /// The merging group of subsequent free-form surfaces (`mg`).
+    MergingGroup,
+    /// The object that subsequent elements belong to (`o`).
+    Object,
+    /// Whether bevel interpolation is used (`bevel`).
+    Bevel,
+    /// Whether color interpolation is used (`c_interp`).
+    ColorInterpolation,
+    /// Whether dissolve interpolation is used (`d_interp`).
+    DissolveInterpolation,
+    /// The level of detail at which subsequent elements are displayed (`lod`).
+    LevelOfDetail,
+    /// The texture map used by subsequent elements (`usemap`).
+    UseMap,
+    /// A reference to one or more texture map libraries (`maplib`).
+    MapLibrary,
+    /// The material used by subsequent elements (`usemtl`).
+    UseMaterial,
+    /// A reference to one or more material libraries (`mtllib`).
+    MaterialLibrary,
+    /// The object that casts shadows for this one (`shadow_obj`).
+    ShadowObject,
+    /// The object that is used for ray tracing reflections (`trace_obj`).
+    TraceObject,
+    /// The technique used to approximate curves (`ctech`).
+    CurveTechnique,
+    /// The technique used to approximate surfaces (`stech`).
+    SurfaceTechnique,
+    /// Read another file in place of this statement (`call`).
+    Call,
+    /// Run a shell command (`csh`).
+    Shell,
+}
+
+impl Keyword {
+    /// Every keyword, in the order in which they are declared.
+    pub const ALL: [Keyword; 39] = [
+        Keyword::Position,
+        Keyword::Uv,
+        Keyword::Normal,
+        Keyword::ParameterVertex,
+        Keyword::CurveSurfaceType,
+        Keyword::Degree,
+        Keyword::BasisMatrix,
+        Keyword::Step,
+        Keyword::Point,
+        Keyword::Line,
+        Keyword::Face,
+        Keyword::Curve,
+        Keyword::Curve2,
+        Keyword::Surface,
+        Keyword::Parameter,
+        Keyword::Trim,
+        Keyword::Hole,
+        Keyword::SpecialCurve,
+        Keyword::SpecialPoint,
+        Keyword::End,
+        Keyword::Connect,
+        Keyword::Group,
+        Keyword::SmoothingGroup,
+        Keyword::MergingGroup,
+        Keyword::Object,
+        Keyword::Bevel,
+        Keyword::ColorInterpolation,
+        Keyword::DissolveInterpolation,
+        Keyword::LevelOfDetail,
+        Keyword::UseMap,
+        Keyword::MapLibrary,
+        Keyword::UseMaterial,
+        Keyword::MaterialLibrary,
+        Keyword::ShadowObject,
+        Keyword::TraceObject,
+        Keyword::CurveTechnique,
+        Keyword::SurfaceTechnique,
+        Keyword::Call,
+        Keyword::Shell,
+    ];
+
+    /// Returns the keyword written as the given string, if any. Keywords are case-sensitive.
+    ///
+    /// ```
+    /// use wavefront::util::Keyword;
+    ///
+    /// assert_eq!(Keyword::parse("usemtl"), Some(Keyword::UseMaterial));
+    /// assert_eq!(Keyword::parse("USEMTL"), None);
+    /// ```
+    pub fn parse(keyword: &str) -> Option<Self> {
+        Some(match keyword {
+            "v" => Keyword::Position,
+            "vt" => Keyword::Uv,
+            "vn" => Keyword::Normal,
+            "vp" => Keyword::ParameterVertex,
+            "cstype" => Keyword::CurveSurfaceType,
+            "deg" => Keyword::Degree,
+            "bmat" => Keyword::BasisMatrix,
+            "step" => Keyword::Step,
+            "p" => Keyword::Point,
+            "l" => Keyword::Line,
+            "f" => Keyword::Face,
+            "curv" => Keyword::Curve,
+            "curv2" => Keyword::Curve2,
+            "surf" => Keyword::Surface,
+            "parm" => Keyword::Parameter,
+            "trim" => Keyword::Trim,
+            "hole" => Keyword::Hole,
+            "scrv" => Keyword::SpecialCurve,
+            "sp" => Keyword::SpecialPoint,
+            "end" => Keyword::End,
+            "con" => Keyword::Connect,
+            "g" => Keyword::Group,
+            "s" => Keyword::SmoothingGroup,
+            "mg" => Keyword::MergingGroup,
+            "o" => Keyword::Object,
+            "bevel" => Keyword::Bevel,
+            "c_interp" => Keyword::ColorInterpolation,
+            "d_interp" => Keyword::DissolveInterpolation,
+            "lod" => Keyword::LevelOfDetail,
+            "usemap" => Keyword::UseMap,
+            "maplib" => Keyword::MapLibrary,
+            "usemtl" => Keyword::UseMaterial,
+            "mtllib" => Keyword::MaterialLibrary,
+            "shadow_obj" => Keyword::ShadowObject,
+            "trace_obj" => Keyword::TraceObject,
+            "ctech" => Keyword::CurveTechnique,
+            "stech" => Keyword::SurfaceTechnique,
+            "call" => Keyword::Call,
+            "csh" => Keyword::Shell,
+            _ => return None,
+        })
+    }
+
+    /// Returns this keyword as it is written.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Keyword::Position => "v",
+            Keyword::Uv => "vt",
+            Keyword::Normal => "vn",
+            Keyword::ParameterVertex => "vp",
+            Keyword::CurveSurfaceType => "cstype",
+            Keyword::Degree => "deg",
+            Keyword::BasisMatrix => "bmat",
+            Keyword::Step => "step",
+            Keyword::Point => "p",
+            Keyword::Line => "l",
+            Keyword::Face => "f",
+            Keyword::Curve => "curv",
+            Keyword::Curve2 => "curv2",
+            Keyword::Surface => "surf",
+            Keyword::Parameter => "parm",
+            Keyword::Trim => "trim",
+            Keyword::Hole => "hole",
+            Keyword::SpecialCurve => "scrv",
+            Keyword::SpecialPoint => "sp",
+            Keyword::End => "end",
+            Keyword::Connect => "con",
+            Keyword::Group => "g",
+            Keyword::SmoothingGroup => "s",
+            Keyword::MergingGroup => "mg",
+            Keyword::Object => "o",
+            Keyword::Bevel => "bevel",
+            Keyword::ColorInterpolation => "c_interp",
+            Keyword::DissolveInterpolation => "d_interp",
+            Keyword::LevelOfDetail => "lod",
+            Keyword::UseMap => "usemap",
+            Keyword::MapLibrary => "maplib",
+            Keyword::UseMaterial => "usemtl",
+            Keyword::MaterialLibrary => "mtllib",
+            Keyword::ShadowObject => "shadow_obj",
+            Keyword::TraceObject => "trace_obj",
+            Keyword::CurveTechnique => "ctech",
+            Keyword::SurfaceTechnique => "stech",
+            Keyword::Call => "call",
+            Keyword::Shell => "csh",
+        }
+    }
+
+    /// Returns `true` if statements with this keyword are used when building an [`Obj`](crate::Obj). Other statements
+    /// are ignored, and are reported as [`ObjEvent::Unknown`](crate::ObjEvent::Unknown) by the pull parser.
+    pub fn is_supported(&self) -> bool {
+        matches!(
+            self,
+            Keyword::Position
+                | Keyword::Uv
+                | Keyword::Normal
+                | Keyword::Face
+                | Keyword::Group
+                | Keyword::Object
+                | Keyword::UseMaterial
+                | Keyword::MaterialLibrary
+        )
+    }
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The kind of a line of an OBJ, as determined by [`classify_line`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineKind<'a> {
+    /// A line beginning with `#`, after any whitespace.
+    Comment,
+    /// A line containing only whitespace.
+    Blank,
+    /// A statement beginning with the given keyword.
+    Statement(Keyword),
+    /// A statement beginning with the given word, which isn't a keyword.
+    Unknown(&'a str),
+}
+
+/// Determine what kind of line a line of an OBJ is, without parsing the rest of it.
+///
+/// This is the same classification that the parser itself uses, so it is suitable for tools that process OBJs one
+/// line at a time, such as to count statements or to split a file.
+///
+/// ```
+/// use wavefront::util::{classify_line, Keyword, LineKind};
+///
+/// assert_eq!(classify_line("  v 1 2 3"), LineKind::Statement(Keyword::Position));
+/// assert_eq!(classify_line("# exported by hand"), LineKind::Comment);
+/// assert_eq!(classify_line(" \t"), LineKind::Blank);
+/// assert_eq!(classify_line("x_collision box"), LineKind::Unknown("x_collision"));
+/// ```
+pub fn classify_line(line: &str) -> LineKind<'_> {
+    match split_keyword(line) {
+        None => LineKind::Blank,
+        Some(("#", _)) => LineKind::Comment,
+        Some((keyword, _)) => Keyword::parse(keyword).map_or(LineKind::Unknown(keyword), LineKind::Statement),
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{topology::UnionFind, Float, GenericObj, Group, Index, Object, Vertex};
+use alloc::{sync::Arc, vec::Vec};
+use core::{borrow::Borrow, hash::Hash};
+use hashbrown::HashMap;
+
+/// Returns the smallest and largest `u` and `v` components of the given texture coordinates.
+fn uv_bounds<T: Float>(uvs: impl Iterator<Item=[T; 3]>) -> Option<([T; 2], [T; 2])> {
+    uvs.fold(None, |bounds, [u, v, _]| Some(match bounds {
+        None => ([u, v], [u, v]),
+        Some(([min_u, min_v], [max_u, max_v])) => (
+            [if u < min_u { u } else { min_u }, if v < min_v { v } else { min_v }],
+            [if u > max_u { u } else { max_u }, if v > max_v { v } else { max_v }],
+        ),
+    }))
+}
+
+/// Options controlling which edges are reported by [`GenericObj::uv_seams`].
+///
+/// Options are configured with a builder-style API.
+#[derive(Clone, Debug, Default)]
+pub struct UvSeamOptions {
+    epsilon: Option<f64>,
+    ignore_missing_uvs: bool,
+}
+
+impl UvSeamOptions {
+    /// Create a new set of options with the default behaviour.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare the values of texture coordinates rather than their indices, treating components that differ by no
+    /// more than `epsilon` as equal (default: indices are compared).
+    ///
+    /// This means that polygons referring to separate but identical texture coordinates, as written by some
+    /// exporters, aren't considered to be separated by a seam.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = Some(epsilon);
+        self
+    }
+
+    /// Don't report edges between a polygon with texture coordinates and one without (default: `false`).
+    ///
+    /// Edges between two polygons without texture coordinates are never seams.
+    pub fn ignore_missing_uvs(mut self, ignore_missing_uvs: bool) -> Self {
+        self.ignore_missing_uvs = ignore_missing_uvs;
+        self
+    }
+}
+
+/// An edge along which the texture mapping is discontinuous, produced by [`GenericObj::uv_seams`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UvSeam<T = f32> {
+    /// The position indices of the ends of the edge, lowest first.
+    pub edge: [Index; 2],
+    /// The indices of the two polygons that meet at the edge, lowest first.
+    pub faces: [Index; 2],
+    /// The texture coordinates that each of the polygons (in the same order as [`UvSeam::faces`]) gives to each end
+    /// of the edge (in the same order as [`UvSeam::edge`]).
+    pub uvs: [[Option<[T; 3]>; 2]; 2],
+}
+
+impl<T: Float, N: Borrow<str> + Eq + Hash> GenericObj<T, N> {
+    /// Returns the smallest and largest `u` and `v` components of the texture coordinates used by the polygons of
+    /// this [`Obj`](crate::Obj), or `None` if none are used.
+    ///
+    /// Texture coordinates that no polygon refers to are ignored.
+    pub fn uv_bounds(&self) -> Option<([T; 2], [T; 2])> {
+        uv_bounds(self.polygons().flat_map(|poly| poly.vertices()).filter_map(|v| v.uv()))
+    }
+
+    /// Returns the indices of the polygons that have a texture coordinate with a `u` or `v` component outside of the
+    /// given (inclusive) range.
+    ///
+    /// For example, lightmaps require every texture coordinate to be within `[0.0, 0.0]..=[1.0, 1.0]`. NaN components
+    /// are considered to be out of range.
+    pub fn polygons_with_uvs_outside(&self, min: [T; 2], max: [T; 2]) -> Vec<Index> {
+        let inside = |v: Vertex<'_, T>| v
+            .uv()
+            .map_or(true, |[u, v, _]| u >= min[0] && u <= max[0] && v >= min[1] && v <= max[1]);
+        self
+            .polygons()
+            .filter(|poly| !poly.vertices().all(inside))
+            .map(|poly| poly.index())
+            .collect()
+    }
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Returns the edges along which the texture mapping is discontinuous, such as where a texture wraps around a
+    /// cylinder.
+    ///
+    /// An edge is a seam if two polygons that share it give different texture coordinates to either of its ends, or
+    /// if only one of them has texture coordinates there (see [`UvSeamOptions`]). Edges are identified as in
+    /// [`ManifoldReport`](crate::ManifoldReport), and those shared by more than two polygons are reported once for
+    /// every pair of polygons that is separated by a seam. The result is sorted by edge and then by faces, and the
+    /// number of seams is simply its length.
+    ///
+    /// ```
+    /// use wavefront::{Obj, UvSeamOptions};
+    ///
+    /// // A quad split into two triangles, one of which uses a duplicate of a texture coordinate
+    /// let obj: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nvt 1 1\nvt 0 1
+    ///     f 1/1 2/2 3/3\nf 2/2 4/4 3/5\n".parse().unwrap();
+    /// let seams = obj.uv_seams(&UvSeamOptions::new());
+    /// assert_eq!(seams.len(), 1);
+    /// assert_eq!((seams[0].edge, seams[0].faces), ([1, 2], [0, 1]));
+    /// assert!(obj.uv_seams(&UvSeamOptions::new().epsilon(0.0)).is_empty());
+    /// ```
+    pub fn uv_seams(&self, options: &UvSeamOptions) -> Vec<UvSeam<T>> {
+        // The texture coordinate indices that each polygon gives to the ends of each edge, lowest position first
+        let mut edges = HashMap::<[Index; 2], Vec<(Index, [Option<Index>; 2])>>::new();
+        for poly in self.polygons() {
+            let n = poly.vertices.len();
+            for i in 0..n {
+                let (a, b) = (poly.vertices[i], poly.vertices[(i + 1) % n]);
+                let [(a, a_uv), (b, b_uv)] = [a, b].map(|(pos, uv, _)| {
+                    (pos.get() as usize - 1, uv.map(|uv| uv.get() as usize - 1))
+                });
+                if a != b {
+                    let (edge, uvs) = if a < b { ([a, b], [a_uv, b_uv]) } else { ([b, a], [b_uv, a_uv]) };
+                    edges.entry(edge).or_default().push((poly.index(), uvs));
+                }
+            }
+        }
+
+        let uvs = &self.buffers.uvs;
+        let differ = |x: Option<Index>, y: Option<Index>| match (x, y) {
+            (None, None) => false,
+            (Some(x), Some(y)) => match options.epsilon {
+                None => x != y,
+                Some(epsilon) => (0..3).any(|i| {
+                    let distance = Float::abs(uvs[x][i].to_f64() - uvs[y][i].to_f64());
+                    distance > epsilon || distance.is_nan()
+                }),
+            },
+            _ => !options.ignore_missing_uvs,
+        };
+        let mut seams = Vec::new();
+        for (edge, uses) in edges {
+            for (i, (a, a_uvs)) in uses.iter().enumerate() {
+                for (b, b_uvs) in uses[i + 1..].iter().filter(|(b, _)| b != a) {
+                    if differ(a_uvs[0], b_uvs[0]) || differ(a_uvs[1], b_uvs[1]) {
+                        let get = |uvs: [Option<Index>; 2]| uvs.map(|uv| Some(self.buffers.uvs[uv?]));
+                        seams.push(UvSeam { edge, faces: [*a, *b], uvs: [get(*a_uvs), get(*b_uvs)] });
+                    }
+                }
+            }
+        }
+        seams.sort_unstable_by_key(|seam| (seam.edge, seam.faces));
+        seams
+    }
+
+    /// Move each UV island by a whole number of texture repeats such that its lowest `u` and `v` components fall
+    /// within `0.0..1.0`.
+    ///
+    /// An island is a set of polygons connected by shared texture coordinates, so islands are moved as a whole and
+    /// aren't torn apart along edges that cross a texture boundary. As a result, an island that straddles a texture
+    /// boundary (or is larger than the texture) still extends past `1.0` afterwards. Islands are only as connected
+    /// as their texture coordinate indices: polygons that refer to separate but identical texture coordinates are
+    /// moved independently. Texture coordinates that no polygon refers to are left alone.
+    pub fn wrap_uvs(&mut self) {
+        let len = self.buffers.uvs.len();
+        let mut islands = UnionFind::new(len);
+        for poly in self.polygons() {
+            let mut uvs = poly.vertices().filter_map(|v| v.uv_index());
+            if let Some(first) = uvs.next() {
+                uvs.for_each(|uv| islands.union(first, uv));
+            }
+        }
+
+        let mut used = alloc::vec![false; len];
+        self.buffers.vertices.iter().filter_map(|(_, uv, _)| *uv).for_each(|uv| used[uv.get() as usize - 1] = true);
+
+        // The lowest components of each island, stored at its root
+        let mut mins: Vec<Option<[T; 2]>> = alloc::vec![None; len];
+        for (i, [u, v, _]) in self.buffers.uvs.iter().enumerate().filter(|(i, _)| used[*i]) {
+            let min = &mut mins[islands.find(i)];
+            *min = Some(match *min {
+                None => [*u, *v],
+                Some([min_u, min_v]) => [if *u < min_u { *u } else { min_u }, if *v < min_v { *v } else { min_v }],
+            });
+        }
+
+        for (i, uv) in Arc::make_mut(&mut self.buffers.uvs).iter_mut().enumerate().filter(|(i, _)| used[*i]) {
+            if let Some([min_u, min_v]) = mins[islands.find(i)] {
+                if min_u.is_finite() && min_v.is_finite() {
+                    uv[0] -= min_u.floor();
+                    uv[1] -= min_v.floor();
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T: Float, N: Borrow<str> + Eq + Hash> Object<'a, T, N> {
+    /// Returns the smallest and largest `u` and `v` components of the texture coordinates used by the polygons of
+    /// this [`Object`], or `None` if none are used.
+    pub fn uv_bounds(&self) -> Option<([T; 2], [T; 2])> {
+        uv_bounds(self.polygons().flat_map(|poly| poly.vertices()).filter_map(|v| v.uv()))
+    }
+}
+
+impl<'a, T: Float> Group<'a, T> {
+    /// Returns the smallest and largest `u` and `v` components of the texture coordinates used by the polygons of
+    /// this [`Group`], or `None` if none are used.
+    pub fn uv_bounds(&self) -> Option<([T; 2], [T; 2])> {
+        uv_bounds(self.polygons().flat_map(|poly| poly.vertices()).filter_map(|v| v.uv()))
+    }
+}
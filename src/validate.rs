@@ -0,0 +1,167 @@
+use crate::{Float, GenericObj, Index, UvSeamOptions, VertexRange};
+use alloc::{sync::Arc, vec::Vec};
+use core::fmt;
+
+/// A report describing problems with the contents of an [`Obj`](crate::Obj), produced by [`GenericObj::validate`],
+/// along with some statistics that don't make it invalid.
+///
+/// All lists are sorted in ascending order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidationReport {
+    /// Positions with a NaN or infinite component.
+    pub non_finite_positions: Vec<Index>,
+    /// Texture coordinates with a NaN or infinite component.
+    pub non_finite_uvs: Vec<Index>,
+    /// Normals with a NaN or infinite component.
+    pub non_finite_normals: Vec<Index>,
+    /// The number of UV seams, as found by [`GenericObj::uv_seams`] with the default [`UvSeamOptions`]. Seams are
+    /// expected in most textured models, so they don't make the OBJ invalid.
+    pub uv_seams: usize,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no problems were found. Statistics such as the number of UV seams aren't considered.
+    pub fn is_valid(&self) -> bool {
+        self.non_finite_positions.is_empty() && self.non_finite_uvs.is_empty() && self.non_finite_normals.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} non-finite positions, {} non-finite texture coordinates and {} non-finite normals",
+            self.non_finite_positions.len(),
+            self.non_finite_uvs.len(),
+            self.non_finite_normals.len(),
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationReport {}
+
+/// What [`GenericObj::repair_non_finite`] should do with attributes that have a NaN or infinite component.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NonFinitePolicy {
+    /// Replace every component of the attribute with zero.
+    ReplaceWithZero,
+    /// Remove every polygon that refers to the attribute, then replace its components with zero.
+    RemoveFaces,
+    /// Leave the OBJ untouched and produce a [`ValidationReport`] listing the attributes.
+    Error,
+}
+
+impl<T: Float> GenericObj<T> {
+    /// Check the contents of this OBJ for problems, such as the NaN or infinite coordinates written by some broken
+    /// exporters, which would otherwise poison every computation that uses them.
+    ///
+    /// Problems can be repaired with [`GenericObj::repair_non_finite`], or rejected while parsing with
+    /// [`ParseOptions::strict`](crate::ParseOptions::strict).
+    ///
+    /// ```
+    /// use wavefront::Obj;
+    ///
+    /// let obj: Obj = "v 0 0 0\nv nan 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+    /// assert_eq!(obj.validate().non_finite_positions, vec![1]);
+    /// ```
+    pub fn validate(&self) -> ValidationReport {
+        ValidationReport {
+            non_finite_positions: non_finite(&self.buffers.positions),
+            non_finite_uvs: non_finite(&self.buffers.uvs),
+            non_finite_normals: non_finite(&self.buffers.normals),
+            uv_seams: self.uv_seams(&UvSeamOptions::new()).len(),
+        }
+    }
+
+    /// Repair attributes that have a NaN or infinite component according to the given policy, returning the number
+    /// of attributes replaced (for [`NonFinitePolicy::ReplaceWithZero`]) or polygons removed (for
+    /// [`NonFinitePolicy::RemoveFaces`]).
+    ///
+    /// Attributes are never removed, so the indices of the others remain the same. When polygons are removed, those
+    /// that remain keep their order, object and group membership and source lines, and groups and objects left without
+    /// any polygons are removed. With [`NonFinitePolicy::Error`], the OBJ is left untouched and the result of
+    /// [`GenericObj::validate`] is produced if it found any non-finite attributes.
+    ///
+    /// ```
+    /// use wavefront::{NonFinitePolicy, Obj};
+    ///
+    /// let mut obj: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv inf 0 0\nf 1 2 3\nf 2 4 3\n".parse().unwrap();
+    /// assert!(obj.repair_non_finite(NonFinitePolicy::Error).is_err());
+    /// assert_eq!(obj.repair_non_finite(NonFinitePolicy::RemoveFaces), Ok(1));
+    /// assert!(obj.validate().is_valid());
+    /// assert_eq!(obj.polygons().count(), 1);
+    /// ```
+    pub fn repair_non_finite(&mut self, policy: NonFinitePolicy) -> Result<usize, ValidationReport> {
+        let report = self.validate();
+        if report.is_valid() {
+            return Ok(0);
+        }
+        let removed = match policy {
+            NonFinitePolicy::Error => return Err(report),
+            NonFinitePolicy::ReplaceWithZero => None,
+            NonFinitePolicy::RemoveFaces => Some(self.remove_referencing(&report)),
+        };
+
+        let buffers = &mut self.buffers;
+        for (attributes, indices) in [
+            (&mut buffers.positions, &report.non_finite_positions),
+            (&mut buffers.uvs, &report.non_finite_uvs),
+            (&mut buffers.normals, &report.non_finite_normals),
+        ] {
+            if !indices.is_empty() {
+                let attributes = Arc::make_mut(attributes);
+                for index in indices {
+                    attributes[*index] = [T::ZERO; 3];
+                }
+            }
+        }
+        Ok(removed.unwrap_or(
+            report.non_finite_positions.len() + report.non_finite_uvs.len() + report.non_finite_normals.len(),
+        ))
+    }
+
+    // Remove the polygons that refer to any of the attributes in the report, returning the number removed
+    fn remove_referencing(&mut self, report: &ValidationReport) -> usize {
+        fn flags(len: usize, indices: &[Index]) -> Vec<bool> {
+            let mut flags = alloc::vec![false; len];
+            indices.iter().for_each(|index| flags[*index] = true);
+            flags
+        }
+
+        let buffers = &self.buffers;
+        let bad_positions = flags(buffers.positions.len(), &report.non_finite_positions);
+        let bad_uvs = flags(buffers.uvs.len(), &report.non_finite_uvs);
+        let bad_normals = flags(buffers.normals.len(), &report.non_finite_normals);
+        let is_bad = |flags: &[bool], index: core::num::NonZeroU32| flags[index.get() as usize - 1];
+
+        let mut vertices = Vec::with_capacity(buffers.vertices.len());
+        let mut polygons = Vec::with_capacity(buffers.polygons.len());
+        let mut new_index = alloc::vec![None; buffers.polygons.len()];
+        for (index, range) in buffers.polygons.iter().enumerate() {
+            let polygon = &buffers.vertices[range.start..range.end];
+            let bad = polygon.iter().any(|(pos, uv, norm)| {
+                is_bad(&bad_positions, *pos)
+                    || uv.is_some_and(|uv| is_bad(&bad_uvs, uv))
+                    || norm.is_some_and(|norm| is_bad(&bad_normals, norm))
+            });
+            if !bad {
+                new_index[index] = Some(polygons.len());
+                let start = vertices.len();
+                vertices.extend_from_slice(polygon);
+                polygons.push(VertexRange { start, end: vertices.len() });
+            }
+        }
+        self.replace_polygons(vertices, polygons, &new_index)
+    }
+}
+
+fn non_finite<T: Float>(attributes: &[[T; 3]]) -> Vec<Index> {
+    attributes
+        .iter()
+        .enumerate()
+        .filter(|(_, attribute)| !attribute.iter().all(|x| x.is_finite()))
+        .map(|(index, _)| index)
+        .collect()
+}
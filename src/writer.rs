@@ -0,0 +1,460 @@
+use crate::{
+    parse::MAX_ATTRIBUTES, util, Buffers, DisplayAttribute, DisplayIndices, Error, Float, GenericObj,
+    Index, ObjEvent, VertexIndices,
+};
+use alloc::sync::Arc;
+use core::{borrow::Borrow, convert::TryFrom, fmt, hash::Hash, marker::PhantomData, num::NonZeroU32};
+use std::{collections::HashMap, io::Write};
+
+/// Options that control how an OBJ is written by [`GenericObj::write_with`](crate::GenericObj::write_with).
+///
+/// Options are configured with a builder-style API, like [`ParseOptions`](crate::ParseOptions).
+///
+/// By default, attributes are written exactly as they're stored and faces refer to them by the same indices, so an
+/// attribute shared by several vertices is written once and referred to by each of them.
+#[derive(Clone, Debug, Default)]
+pub struct WriteOptions {
+    pub(crate) sanitize_names: bool,
+    pub(crate) ordering: WriteOrdering,
+    pub(crate) expand_corners: bool,
+}
+
+/// Determines the order in which the objects, groups and attributes of an OBJ are written, see
+/// [`WriteOptions::ordering`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WriteOrdering {
+    /// Write attributes and polygons in the order they're stored, except that polygons of the unnamed object come
+    /// first. Each polygon is written once, after `o` and `g` statements wherever its object or groups change.
+    #[default]
+    Preserve,
+    /// Write attributes in the order they're stored, but polygons grouped by object, with objects sorted by name.
+    /// The polygons of each object are written in the order they're stored.
+    SortNames,
+    /// Write polygons as for [`WriteOrdering::SortNames`], and renumber attributes in the order in which the written
+    /// polygons first refer to them, leaving out any that aren't referred to.
+    ///
+    /// This is a normal form: OBJs with the same objects and groups, containing the same polygons in the same order
+    /// with the same attribute values and materials, are written identically however their attributes are stored.
+    Canonical,
+}
+
+impl WriteOptions {
+    /// Create a new set of options with the default behaviour.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rename objects and groups whose names aren't valid (see [`util::name_is_valid`]) instead of refusing to write
+    /// them (default: `false`).
+    ///
+    /// Names are sanitized with a [`util::NameSanitizer`], such that distinct names remain distinct and valid names
+    /// are kept. Invalid names are renamed in sorted order, so the result doesn't depend on the order in which they
+    /// are stored. The new names are returned as a [`NameMap`].
+    pub fn sanitize_names(mut self, sanitize_names: bool) -> Self {
+        self.sanitize_names = sanitize_names;
+        self
+    }
+
+    /// Determines the order in which objects, groups and attributes are written (default:
+    /// [`WriteOrdering::Preserve`]).
+    ///
+    /// Sorting makes the output of the same OBJ reproducible, and [`WriteOrdering::Canonical`] makes it comparable
+    /// between OBJs exported by different tools, such that a textual diff only shows real differences.
+    ///
+    /// ```
+    /// use wavefront::{Obj, WriteOptions, WriteOrdering};
+    ///
+    /// let a: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+    /// let b: Obj = "v 0 1 0\nv 0 0 0\nv 1 0 0\nf 2 3 1\n".parse().unwrap();
+    /// let write = |obj: &Obj| {
+    ///     let mut bytes = Vec::new();
+    ///     obj.write_with(&mut bytes, WriteOptions::new().ordering(WriteOrdering::Canonical)).unwrap();
+    ///     bytes
+    /// };
+    /// assert_eq!(write(&a), write(&b));
+    /// ```
+    pub fn ordering(mut self, ordering: WriteOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Write a separate position, texture coordinate and normal for every corner of every polygon, instead of
+    /// sharing attributes between the vertices that refer to them (default: `false`).
+    ///
+    /// This makes the output much larger, but some tools expect every face to have attributes of its own.
+    ///
+    /// ```
+    /// use wavefront::{Obj, WriteOptions};
+    ///
+    /// let obj: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nf 1 2 3\nf 3 2 4\n".parse().unwrap();
+    /// let mut bytes = Vec::new();
+    /// obj.write_with(&mut bytes, WriteOptions::new().expand_corners(true)).unwrap();
+    /// let expanded: Obj = String::from_utf8(bytes).unwrap().parse().unwrap();
+    /// assert_eq!(expanded.positions().len(), 6);
+    /// ```
+    pub fn expand_corners(mut self, expand_corners: bool) -> Self {
+        self.expand_corners = expand_corners;
+        self
+    }
+}
+
+/// Write an OBJ with the given options, once its objects and groups have been renamed.
+pub(crate) fn write_ordered<T: Float, N: Borrow<str> + Eq + Hash, W: Write>(
+    obj: &GenericObj<T, N>,
+    mut writer: W,
+    options: &WriteOptions,
+) -> Result<(), Error> {
+    struct SortedNames<'a, T, N>(&'a GenericObj<T, N>);
+
+    impl<'a, T: Float, N: Borrow<str> + Eq + Hash> fmt::Display for SortedNames<'a, T, N> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.fmt_obj(f, true)
+        }
+    }
+
+    let expanded;
+    let obj = if options.expand_corners {
+        expanded = expand_corners(obj)?;
+        &expanded
+    } else {
+        obj
+    };
+    match options.ordering {
+        WriteOrdering::Preserve => write!(writer, "{}", obj)?,
+        WriteOrdering::SortNames => write!(writer, "{}", SortedNames(obj))?,
+        WriteOrdering::Canonical => write!(writer, "{}", SortedNames(&canonical(obj)))?,
+    }
+    Ok(())
+}
+
+// Renumber the attributes of an OBJ in the order in which they're first referred to when written with sorted names
+fn canonical<T: Float, N: Borrow<str> + Eq + Hash>(obj: &GenericObj<T, N>) -> GenericObj<T, N> {
+    let buffers = &obj.buffers;
+    let old = [&buffers.positions, &buffers.uvs, &buffers.normals];
+    let mut new_index = old.map(|attributes| vec![None; attributes.len()]);
+    let mut attributes = [Vec::new(), Vec::new(), Vec::new()];
+    let mut renumber = |kind: usize, index: NonZeroU32| {
+        let new_index = &mut new_index[kind][index.get() as usize - 1];
+        let attributes = &mut attributes[kind];
+        *new_index.get_or_insert_with(|| {
+            attributes.push(old[kind][index.get() as usize - 1]);
+            // There can't be more new attributes than old ones
+            NonZeroU32::new(attributes.len() as u32).unwrap()
+        })
+    };
+
+    let written = obj
+        .written_polygons(true)
+        .into_iter()
+        .map(|(poly, _, _)| buffers.polygons[poly])
+        .flat_map(|range| range.start..range.end);
+    let mut renumber_vertex = |(pos, uv, norm): VertexIndices| {
+        (renumber(0, pos), uv.map(|uv| renumber(1, uv)), norm.map(|norm| renumber(2, norm)))
+    };
+    for i in written {
+        renumber_vertex(buffers.vertices[i]);
+    }
+    // Vertices that aren't part of any polygon (if there are any) still need valid indices, so they come last
+    let vertices = buffers.vertices.iter().map(|vertex| renumber_vertex(*vertex)).collect();
+
+    let [positions, uvs, normals] = attributes;
+    GenericObj {
+        buffers: Buffers {
+            positions: Arc::new(positions),
+            uvs: Arc::new(uvs),
+            normals: Arc::new(normals),
+            vertices: Arc::new(vertices),
+            ..buffers.clone()
+        },
+        objects: obj.objects.clone(),
+        names: obj.names.clone(),
+        triangles: None,
+    }
+}
+
+// Give every vertex attributes of its own, in the order in which the vertices are stored
+fn expand_corners<T: Float, N: Borrow<str> + Eq + Hash>(obj: &GenericObj<T, N>) -> Result<GenericObj<T, N>, Error> {
+    let buffers = &obj.buffers;
+    // Every vertex has a position, and positions are written first, so the first one that doesn't fit is on this line
+    if buffers.vertices.len() > MAX_ATTRIBUTES {
+        return Err(Error::TooManyAttributes(MAX_ATTRIBUTES + 1));
+    }
+    let (mut positions, mut uvs, mut normals) = (Vec::new(), Vec::new(), Vec::new());
+    let expand = |attributes: &mut Vec<[T; 3]>, old: &[[T; 3]], index: NonZeroU32| {
+        attributes.push(old[index.get() as usize - 1]);
+        // There's at most one new attribute for every vertex, which was checked above
+        NonZeroU32::new(attributes.len() as u32).unwrap()
+    };
+    let vertices = buffers
+        .vertices
+        .iter()
+        .map(|(pos, uv, norm)| (
+            expand(&mut positions, &buffers.positions, *pos),
+            uv.map(|uv| expand(&mut uvs, &buffers.uvs, uv)),
+            norm.map(|norm| expand(&mut normals, &buffers.normals, norm)),
+        ))
+        .collect();
+
+    Ok(GenericObj {
+        buffers: Buffers {
+            positions: Arc::new(positions),
+            uvs: Arc::new(uvs),
+            normals: Arc::new(normals),
+            vertices: Arc::new(vertices),
+            ..buffers.clone()
+        },
+        objects: obj.objects.clone(),
+        names: obj.names.clone(),
+        triangles: None,
+    })
+}
+
+/// The objects and groups that were renamed while writing an OBJ, mapping their old names to their new names.
+///
+/// See [`WriteOptions::sanitize_names`]. Names that were kept are absent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NameMap {
+    /// The objects that were renamed.
+    pub objects: HashMap<String, String>,
+    /// The groups that were renamed. Groups with the same name in different objects are given the same new name.
+    pub groups: HashMap<String, String>,
+}
+
+impl NameMap {
+    /// Returns the name under which an object was written.
+    pub fn object<'a>(&'a self, name: &'a str) -> &'a str {
+        self.objects.get(name).map_or(name, String::as_str)
+    }
+
+    /// Returns the name under which a group was written.
+    pub fn group<'a>(&'a self, name: &'a str) -> &'a str {
+        self.groups.get(name).map_or(name, String::as_str)
+    }
+
+    /// Returns `true` if no objects or groups were renamed.
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty() && self.groups.is_empty()
+    }
+
+    /// Sanitize the given names (see [`WriteOptions::sanitize_names`]), which must be sorted and contain no duplicates.
+    pub(crate) fn sanitize(objects: &[&str], groups: &[&str]) -> Self {
+        let sanitize = |names: &[&str]| {
+            let mut sanitizer = util::NameSanitizer::new();
+            let (valid, invalid): (Vec<&str>, Vec<&str>) = names.iter().partition(|name| util::name_is_valid(name));
+            for name in valid.into_iter().chain(invalid) {
+                sanitizer.sanitize(name);
+            }
+            sanitizer.renamed().map(|(old, new)| (old.to_string(), new.to_string())).collect()
+        };
+        Self { objects: sanitize(objects), groups: sanitize(groups) }
+    }
+}
+
+/// A writer that produces an OBJ one statement at a time, without building an [`Obj`](crate::Obj) in memory.
+///
+/// Each statement is written as soon as it is given, in the same format as [`GenericObj::write`](crate::GenericObj::write),
+/// so models of any size can be generated. Indices are checked against the attributes written so far, such that the
+/// output is always a valid OBJ. Wrap the writer in a [`std::io::BufWriter`] if it isn't already buffered.
+///
+/// Together with [`GenericObjParser`](crate::GenericObjParser), this allows files to be transformed one statement at
+/// a time (see [`ObjWriter::event`]).
+///
+/// ```
+/// use wavefront::{Obj, ObjWriter};
+///
+/// let mut writer = ObjWriter::new(Vec::new());
+/// let a = writer.position([0.0, 0.0, 0.0]).unwrap();
+/// let b = writer.position([1.0, 0.0, 0.0]).unwrap();
+/// let c = writer.position([0.0, 1.0, 0.0]).unwrap();
+/// writer.face(&[(a, None, None), (b, None, None), (c, None, None)]).unwrap();
+///
+/// let obj: Obj = String::from_utf8(writer.finish().unwrap()).unwrap().parse().unwrap();
+/// assert_eq!(obj.polygons().len(), 1);
+/// ```
+pub struct ObjWriter<W: Write, T = f32> {
+    writer: W,
+    // The number of each kind of attribute written so far
+    lengths: [usize; 3],
+    lines: usize,
+    terms: Vec<VertexIndices>,
+    phantom: PhantomData<T>,
+}
+
+impl<W: Write, T: Float> ObjWriter<W, T> {
+    /// Create a new writer that writes to the given [`Write`]r.
+    pub fn new(writer: W) -> Self {
+        Self { writer, lengths: [0; 3], lines: 0, terms: Vec::new(), phantom: PhantomData }
+    }
+
+    /// Write a vertex position, returning its (zero-based) index.
+    pub fn position(&mut self, position: [T; 3]) -> Result<Index, Error> {
+        self.attribute(0, "v", position)
+    }
+
+    /// Write a texture coordinate, returning its (zero-based) index.
+    pub fn uv(&mut self, uv: [T; 3]) -> Result<Index, Error> {
+        self.attribute(1, "vt", uv)
+    }
+
+    /// Write a vertex normal, returning its (zero-based) index.
+    pub fn normal(&mut self, normal: [T; 3]) -> Result<Index, Error> {
+        self.attribute(2, "vn", normal)
+    }
+
+    /// Start a new object, to which subsequent faces belong.
+    ///
+    /// An [`Error::ExpectedName`] is produced if the name is empty or contains characters that the parser doesn't
+    /// accept.
+    pub fn object(&mut self, name: &str) -> Result<(), Error> {
+        if name.is_empty() || !util::name_is_valid(name) {
+            return Err(Error::ExpectedName(self.lines + 1));
+        }
+        self.line(format_args!("o {}", name))
+    }
+
+    /// Select the groups to which subsequent faces belong. No names selects the default group.
+    ///
+    /// An [`Error::ExpectedName`] is produced if a name is empty or contains characters that the parser doesn't
+    /// accept.
+    pub fn group(&mut self, names: &[&str]) -> Result<(), Error> {
+        self.names("g", names.iter().copied(), util::name_is_valid)
+    }
+
+    /// Select the material used by subsequent faces.
+    ///
+    /// An [`Error::ExpectedName`] is produced if the name is empty or contains whitespace.
+    pub fn usemtl(&mut self, name: &str) -> Result<(), Error> {
+        self.names("usemtl", core::iter::once(name), |_| true)
+    }
+
+    /// Write a reference to one or more material libraries.
+    ///
+    /// An [`Error::ExpectedName`] is produced if a name is empty or contains whitespace.
+    pub fn mtllib(&mut self, names: &[&str]) -> Result<(), Error> {
+        self.names("mtllib", names.iter().copied(), |_| true)
+    }
+
+    /// Write a face made of the given (zero-based) position, texture coordinate and normal indices.
+    ///
+    /// An [`Error::InvalidIndex`] (giving the index as it would have been written, counting from 1) is produced if an
+    /// index refers to an attribute that hasn't been written yet, and an [`Error::InvalidParameter`] if there are fewer
+    /// than 3 vertices. Nothing is written in either case.
+    pub fn face(&mut self, vertices: &[(Index, Option<Index>, Option<Index>)]) -> Result<(), Error> {
+        let index = |idx: Index, len: usize| {
+            Some(idx)
+                .filter(|idx| *idx < len)
+                .and_then(|idx| u32::try_from(idx + 1).ok())
+                .and_then(NonZeroU32::new)
+                .ok_or(Error::InvalidIndex(idx as isize + 1))
+        };
+        self.terms.clear();
+        for (pos, uv, norm) in vertices {
+            let term = (
+                index(*pos, self.lengths[0])?,
+                uv.map(|uv| index(uv, self.lengths[1])).transpose()?,
+                norm.map(|norm| index(norm, self.lengths[2])).transpose()?,
+            );
+            self.terms.push(term);
+        }
+        if self.terms.len() < 3 {
+            return Err(Error::InvalidParameter("a face must have at least 3 vertices"));
+        }
+        self.write_face()
+    }
+
+    /// Write a statement produced by a pull parser (see [`GenericObjParser`](crate::GenericObjParser)).
+    ///
+    /// Relative indices in faces are resolved against the attributes written so far, and are written as absolute
+    /// indices. Material names, comments and unrecognised statements are written as they are.
+    pub fn event(&mut self, event: ObjEvent<'_, T>) -> Result<(), Error> {
+        match event {
+            ObjEvent::Position(pos) => self.position(pos).map(drop),
+            ObjEvent::Uv(uv) => self.uv(uv).map(drop),
+            ObjEvent::Normal(norm) => self.normal(norm).map(drop),
+            ObjEvent::Face(terms) => {
+                self.terms.clear();
+                for term in terms {
+                    let (pos, uv, norm) = term?.resolve(self.lengths)?;
+                    let check = |idx: NonZeroU32, len: usize| if idx.get() as usize > len {
+                        Err(Error::InvalidIndex(idx.get() as isize))
+                    } else {
+                        Ok(idx)
+                    };
+                    let term = (
+                        check(pos, self.lengths[0])?,
+                        uv.map(|uv| check(uv, self.lengths[1])).transpose()?,
+                        norm.map(|norm| check(norm, self.lengths[2])).transpose()?,
+                    );
+                    self.terms.push(term);
+                }
+                self.write_face()
+            },
+            ObjEvent::Object(name) => self.object(name),
+            ObjEvent::Group(names) => self.group(&names),
+            ObjEvent::UseMtl("") => self.line(format_args!("usemtl")),
+            ObjEvent::UseMtl(name) => self.line(format_args!("usemtl {}", name)),
+            ObjEvent::MtlLib(names) => self.mtllib(&names),
+            ObjEvent::Comment(text) => self.line(format_args!("#{}", text)),
+            ObjEvent::Unknown { keyword, rest: "" } => self.line(format_args!("{}", keyword)),
+            ObjEvent::Unknown { keyword, rest } => self.line(format_args!("{} {}", keyword, rest)),
+        }
+    }
+
+    /// Flush the writer and return it.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn attribute(&mut self, kind: usize, keyword: &'static str, attribute: [T; 3]) -> Result<Index, Error> {
+        if self.lengths[kind] >= MAX_ATTRIBUTES {
+            return Err(Error::TooManyAttributes(self.lines + 1));
+        }
+        self.line(format_args!("{}", DisplayAttribute(keyword, attribute)))?;
+        self.lengths[kind] += 1;
+        Ok(self.lengths[kind] - 1)
+    }
+
+    fn names<'a>(
+        &mut self,
+        keyword: &str,
+        names: impl Iterator<Item=&'a str> + Clone,
+        is_valid: impl Fn(&str) -> bool,
+    ) -> Result<(), Error> {
+        let valid = |name: &str| !name.is_empty() && !name.contains(char::is_whitespace) && is_valid(name);
+        if !names.clone().all(valid) {
+            return Err(Error::ExpectedName(self.lines + 1));
+        }
+        write!(self.writer, "{}", keyword)?;
+        for name in names {
+            write!(self.writer, " {}", name)?;
+        }
+        self.line(format_args!(""))
+    }
+
+    fn write_face(&mut self) -> Result<(), Error> {
+        write!(self.writer, "f")?;
+        for term in &self.terms {
+            write!(self.writer, " {}", DisplayIndices(*term))?;
+        }
+        self.line(format_args!(""))
+    }
+
+    // Write the end of a line
+    fn line(&mut self, args: core::fmt::Arguments) -> Result<(), Error> {
+        writeln!(self.writer, "{}", args)?;
+        self.lines += 1;
+        Ok(())
+    }
+}
+
+impl<W: Write, T> core::fmt::Debug for ObjWriter<W, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("ObjWriter")
+            .field("positions", &self.lengths[0])
+            .field("uvs", &self.lengths[1])
+            .field("normals", &self.lengths[2])
+            .field("lines", &self.lines)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,67 @@
+use wavefront::{Error, Obj, ObjAccumulator};
+
+const SHIP: &[u8] = include_bytes!("ship.obj");
+
+fn feed_in_chunks(src: &[u8], mut chunk_len: impl FnMut() -> usize) -> Obj {
+    let mut acc = ObjAccumulator::new();
+    let mut rest = src;
+    while !rest.is_empty() {
+        let (chunk, tail) = rest.split_at(chunk_len().min(rest.len()));
+        acc.feed(chunk).unwrap();
+        rest = tail;
+    }
+    acc.finish().unwrap()
+}
+
+// A description of an OBJ that doesn't depend on the iteration order of its objects and groups
+fn summary(obj: &Obj) -> Vec<String> {
+    obj
+        .positions()
+        .iter()
+        .map(|pos| format!("{:?}", pos))
+        .chain(obj.polygons_with_context().map(|(object, group, poly)| format!("{}/{}: {}", object, group, poly)))
+        .collect()
+}
+
+#[test]
+fn matches_reader() {
+    let expected = summary(&Obj::from_reader(SHIP).unwrap());
+    let crlf = String::from_utf8(SHIP.to_vec()).unwrap().replace('\n', "\r\n");
+
+    for src in [SHIP, crlf.as_bytes()] {
+        assert_eq!(summary(&feed_in_chunks(src, || 1)), expected);
+        assert_eq!(summary(&feed_in_chunks(src, || src.len())), expected);
+
+        // A simple linear congruential generator, so that the chunk sizes are the same on every run
+        let mut state = 12345u32;
+        let obj = feed_in_chunks(src, || {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as usize % 100 + 1
+        });
+        assert_eq!(summary(&obj), expected);
+    }
+}
+
+#[test]
+fn errors() {
+    // The final line needn't end with a line break
+    let mut acc = ObjAccumulator::new();
+    acc.feed(b"v 0 0 0\r").unwrap();
+    acc.feed(b"\nv 1 0 0\nf 1 ").unwrap();
+    acc.feed(b"x").unwrap();
+    assert!(matches!(acc.finish(), Err(Error::ExpectedIdx(3))));
+
+    let mut acc = ObjAccumulator::new();
+    // Indices are only checked once every position has been seen
+    acc.feed(b"v 0 0 0\n\nf 1").unwrap();
+    acc.feed(b" 2\n").unwrap();
+    assert!(matches!(acc.finish(), Err(Error::InvalidIndex(2))));
+
+    // Objects without a name are only an error in strict mode
+    let mut acc = ObjAccumulator::with_options(wavefront::ParseOptions::new().strict(true));
+    acc.feed(b"v 0 0 0\n").unwrap();
+    assert!(matches!(acc.feed(b"o\n"), Err(Error::ExpectedName(2))));
+
+    let mut acc = ObjAccumulator::new();
+    assert!(matches!(acc.feed(b"o \xff\n"), Err(Error::Io(_))));
+}
@@ -1,4 +1,4 @@
-use wavefront::Obj;
+use wavefront::{Error, Obj, ObjF64};
 
 #[test]
 fn basic() {
@@ -6,3 +6,769 @@ fn basic() {
 
     println!("{}", obj);
 }
+
+#[test]
+fn f64_precision() {
+    let src = "v 6378137.123456 -1234567.654321 0.000000001\nv 6378138 -1234567 0\nv 6378137 -1234566 0\nf 1 2 3\n";
+
+    let obj = ObjF64::from_lines(src.lines()).unwrap();
+    assert_eq!(obj.positions()[0], [6378137.123456, -1234567.654321, 0.000000001]);
+    let [a, _, _] = obj.triangles().next().unwrap();
+    assert_eq!(a.position()[0], 6378137.123456);
+
+    // The same coordinates lose precision when stored as `f32`
+    let narrow = Obj::from_lines(src.lines()).unwrap();
+    assert_ne!(narrow.positions()[0][0] as f64, 6378137.123456);
+    assert_eq!(obj.to_f32().positions(), narrow.positions());
+    assert_eq!(narrow.to_f64().positions()[1], [6378138.0, -1234567.0, 0.0]);
+
+    // Queries and exports work at either precision
+    let hit = obj.raycast([6378137.5, -1234566.9, 1.0], [0.0, 0.0, -1.0], false).unwrap();
+    assert_eq!(hit.polygon, 0);
+    assert_eq!(obj.to_indexed_mesh().vertices[0].position[1], -1234567.654321);
+}
+
+fn summary(obj: &Obj) -> impl PartialEq + std::fmt::Debug {
+    let mut objects = obj
+        .objects()
+        .map(|(name, object)| {
+            let mut groups = object
+                .groups()
+                .map(|(name, group)| (name.clone(), group.polygons().map(|p| p.index()).collect::<Vec<_>>()))
+                .collect::<Vec<_>>();
+            groups.sort();
+            (name.to_string(), groups)
+        })
+        .collect::<Vec<_>>();
+    objects.sort();
+    let polygons = obj
+        .polygons()
+        .map(|p| p.vertices().map(|v| (v.position(), v.uv(), v.normal())).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    (obj.positions().to_vec(), obj.uvs().to_vec(), obj.normals().to_vec(), polygons, objects)
+}
+
+#[test]
+fn buf_reader() {
+    let models: [&[u8]; 5] = [
+        include_bytes!("ship.obj"),
+        include_bytes!("torus.obj"),
+        include_bytes!("cube.obj"),
+        include_bytes!("cubes.obj"),
+        include_bytes!("plane.obj"),
+    ];
+    for model in models {
+        let lines = Obj::from_lines(std::str::from_utf8(model).unwrap().lines()).unwrap();
+        let streamed = Obj::from_buf_reader(model).unwrap();
+        assert_eq!(summary(&lines), summary(&streamed));
+    }
+
+    // Errors report the same line numbers, including with Windows line endings
+    let src = "v 0 0 0\r\nv 1 0 0\r\nf 1 x\r\n";
+    assert!(matches!(Obj::from_lines(src.lines()), Err(wavefront::Error::ExpectedIdx(3))));
+    assert!(matches!(Obj::from_buf_reader(src.as_bytes()), Err(wavefront::Error::ExpectedIdx(3))));
+    let streamed = Obj::from_buf_reader("v 0 0 0\r\nv 1 0 0\r\nv 0 1 0\r\nf 1 2 3\r\n".as_bytes()).unwrap();
+    assert_eq!(streamed.positions()[2], [0.0, 1.0, 0.0]);
+
+    // I/O errors part way through the input are reported
+    struct Failing;
+    impl std::io::Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disconnected"))
+        }
+    }
+    let reader = std::io::BufReader::new(std::io::Read::chain(b"v 0 0 0\nv 1 0 0\n" as &[u8], Failing));
+    assert!(matches!(Obj::from_buf_reader(reader), Err(wavefront::Error::Io(_))));
+}
+
+#[test]
+fn face_terms() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\nf 1/1/1 2//1 -1/-1/-1/7\nf 1 2 3\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let indices = |p: usize| obj
+        .polygon(p)
+        .unwrap()
+        .vertices()
+        .map(|v| (v.position(), v.uv().is_some(), v.normal().is_some()))
+        .collect::<Vec<_>>();
+    assert_eq!(indices(0), [([0.0; 3], true, true), ([1.0, 0.0, 0.0], false, true), ([0.0, 1.0, 0.0], true, true)]);
+    assert_eq!(indices(1), [([0.0; 3], false, false), ([1.0, 0.0, 0.0], false, false), ([0.0, 1.0, 0.0], false, false)]);
+
+    let error = |src: &str| Obj::from_lines(src.lines()).err().unwrap().to_string();
+    assert_eq!(error("v 0 0 0\nf 1/x"), "Expected index on line 2");
+    assert_eq!(error("v 0 0 0\nf /1"), "Expected index on line 2");
+    assert_eq!(error("v 0 0 0\nf 0"), "Invalid index '0'");
+    assert_eq!(error("f -1"), "Invalid index '-1'");
+    assert_eq!(error("v 0 0 0\nf -2"), "Invalid index '-2'");
+    assert_eq!(error("v 0 0 0\nf 4294967296"), "Invalid index '4294967296'");
+    assert_eq!(error(&format!("v 0 0 0\nf {}", isize::MIN)), format!("Invalid index '{}'", isize::MIN));
+}
+
+#[test]
+fn groups() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\ng a b\nf 1 2 3\ng b\nf 1 2 3\ng c\no other\ng a\nf 1 2 3\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let polygons = |object: &str, group: &str| obj
+        .object(object)
+        .and_then(|o| o.group(group))
+        .map(|g| g.polygons().map(|p| p.index()).collect::<Vec<_>>());
+    assert_eq!(polygons("", ""), Some(vec![0]));
+    assert_eq!(polygons("", "a"), Some(vec![1]));
+    assert_eq!(polygons("", "b"), Some(vec![1, 2]));
+    assert_eq!(polygons("", "c"), Some(vec![]));
+    assert_eq!(polygons("other", "a"), Some(vec![3]));
+    assert_eq!(polygons("other", "b"), None);
+
+    let mut names = obj.group_names().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, [("", ""), ("", "a"), ("", "b"), ("", "c"), ("other", "a")]);
+    let object = obj.object("").unwrap();
+    assert_eq!(object.group_names().len(), 4);
+    assert!(object.contains_group("c") && !object.contains_group("other"));
+    assert!(obj.contains_object("other") && !obj.contains_object("a"));
+}
+
+#[test]
+fn repeated_object_names() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\no a\nf 1 2 3\ng x\nf 1 2 3\no b\nf 1 2 3\no a\nf 1 2 3\ng x\nf 1 2 3\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let polygons = |object: &str, group: &str| obj
+        .object(object)
+        .and_then(|o| o.group(group))
+        .map(|g| g.polygons().map(|p| p.index()).collect::<Vec<_>>());
+
+    // The second `o a` continues the first object rather than replacing it
+    assert_eq!(polygons("a", ""), Some(vec![0, 3]));
+    assert_eq!(polygons("a", "x"), Some(vec![1, 4]));
+    assert_eq!(polygons("b", ""), Some(vec![2]));
+    assert_eq!(obj.objects().map(|(_, o)| o.polygons().len()).sum::<usize>(), obj.polygons().len());
+}
+
+#[test]
+fn groups_across_objects() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\no first\ng shared\nf 1 2 3\ng other\nf 1 2 3\no second\ng shared\nf 1 2 3\nf 1 2 3\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+
+    let shared = obj.group("shared").unwrap();
+    assert_eq!(shared.polygons().len(), 3);
+    assert_eq!(shared.polygons().map(|p| p.index()).collect::<Vec<_>>(), [0, 2, 3]);
+    assert_eq!(shared.polygon(1).unwrap().index(), 2);
+    assert_eq!(shared.triangles().count(), 3);
+    assert_eq!(shared.as_group().polygons().len(), 3);
+
+    // Groups borrowed from a single object stay `Copy`
+    let first = obj.object("first").unwrap().group("shared").unwrap();
+    let copy = first;
+    assert_eq!(first.polygons().len(), copy.polygons().len());
+    assert_eq!(obj.group("other").unwrap().polygons().len(), 1);
+    assert!(obj.group("missing").is_none());
+
+    let mut named = obj
+        .groups_named("shared")
+        .map(|(object, group)| (object, group.polygons().len()))
+        .collect::<Vec<_>>();
+    named.sort();
+    assert_eq!(named, [("first", 1), ("second", 2)]);
+}
+
+#[test]
+fn polygons_with_context() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nf 1 2 3\no b\ng y x\nf 1 2 3 4\ng x\nf 1 2 3\no a\nf 1 2 3\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+
+    // Polygons in several groups appear once per group
+    let context = obj
+        .polygons_with_context()
+        .map(|(object, group, poly)| (object, group, poly.index()))
+        .collect::<Vec<_>>();
+    assert_eq!(context, [("", "", 0), ("b", "x", 1), ("b", "y", 1), ("b", "x", 2), ("a", "", 3)]);
+
+    let triangles = obj
+        .triangles_with_context()
+        .map(|(object, group, _)| (object, group))
+        .collect::<Vec<_>>();
+    assert_eq!(triangles, [("", ""), ("b", "x"), ("b", "x"), ("b", "y"), ("b", "y"), ("b", "x"), ("a", "")]);
+}
+
+#[test]
+fn track_lines() {
+    use wavefront::ParseOptions;
+
+    let src = "# Comment\nv 0 0 0\nv 1 0 0\nv 0 1 0\n\nf 1 2 3\ng a\nf 3 2 1\n\nf 1 3 2\n";
+    let lines = |obj: &Obj| obj.polygons().map(|p| p.source_line()).collect::<Vec<_>>();
+
+    let obj = Obj::from_str_with(src, ParseOptions::new().track_lines(true)).unwrap();
+    assert_eq!(lines(&obj), [Some(6), Some(8), Some(10)]);
+    assert_eq!(lines(&src.parse().unwrap()), [None, None, None]);
+
+    // Lines survive compaction and filtering
+    let options = ParseOptions::new().track_lines(true).include_groups(&["a"]).compact(true);
+    assert_eq!(lines(&Obj::from_str_with(src, options).unwrap()), [Some(8), Some(10)]);
+    assert_eq!(lines(&obj.select("*/a").to_obj()), [Some(8), Some(10)]);
+
+    let mut merged = obj.clone();
+    merged.merge(&obj);
+    assert_eq!(lines(&merged), [Some(6), Some(8), Some(10), Some(6), Some(8), Some(10)]);
+    merged.merge(&src.parse().unwrap());
+    assert_eq!(merged.polygon(0).unwrap().source_line(), None);
+}
+
+#[test]
+fn display() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\nf 1 2 3\nf 1/1 2/1 3/1\nf 1//1 2//1 3//1\nf 1/1/1 -2/1/1 3/1/-1\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+
+    let vertices = obj
+        .polygons()
+        .map(|p| p.vertex(1).unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(vertices, ["2", "2/1", "2//1", "2/1/1"]);
+
+    let polygons = obj.polygons().map(|p| p.to_string()).collect::<Vec<_>>();
+    assert_eq!(polygons, ["f 1 2 3", "f 1/1 2/1 3/1", "f 1//1 2//1 3//1", "f 1/1/1 2/1/1 3/1/1"]);
+
+    // Displayed polygons parse back to the same vertices
+    let attributes = src.lines().filter(|l| !l.starts_with('f')).collect::<Vec<_>>();
+    let reparsed = Obj::from_lines(attributes.into_iter().chain(polygons.iter().map(|p| p.as_str()))).unwrap();
+    let vertices = |obj: &Obj| obj
+        .polygons()
+        .map(|p| p.vertices().map(|v| (v.position(), v.uv(), v.normal())).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(vertices(&reparsed), vertices(&obj));
+    assert_eq!(vertices(&obj.to_string().parse().unwrap()), vertices(&obj));
+}
+
+#[test]
+fn try_from_lines() {
+    use std::io::{self, BufRead, BufReader};
+    use wavefront::Error;
+
+    let file = std::fs::File::open("tests/ship.obj").unwrap();
+    let obj = Obj::try_from_lines(BufReader::new(file).lines()).unwrap();
+    assert_eq!(summary(&obj), summary(&Obj::from_file("tests/ship.obj").unwrap()));
+
+    // Invalid UTF-8 on the third line
+    let lines = BufReader::new(&b"v 0 0 0\nv 1 0 0\nv \xff 1 0\nf 1 2 3\n"[..]).lines();
+    assert!(matches!(Obj::try_from_lines(lines), Err(Error::ReadLine(3, e)) if matches!(*e, Error::Io(_))));
+
+    let lines = vec![Ok("v 0 0 0"), Ok("f 1 x"), Err(io::Error::other("unreachable"))];
+    assert!(matches!(Obj::try_from_lines(lines.into_iter()), Err(Error::ExpectedIdx(2))));
+}
+
+#[test]
+fn ignore_case() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\no Wheel_FL\ng Tyre\nf 1 2 3\ng tyre\nf 1 2 3\nf 1 2 3\no wheel_fr\nf 1 2 3\no WHEEL_FR\nf 1 2 3\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+
+    let wheel = obj.object_ignore_case("wheel_fl").unwrap();
+    assert_eq!(wheel.polygons().count(), 3);
+    assert!(obj.object("wheel_fl").is_none());
+    assert!(obj.object_ignore_case("wheel").is_none());
+
+    // Ambiguous names resolve to the one that sorts first
+    assert_eq!(wheel.group_ignore_case("TYRE").unwrap().polygons().len(), 1);
+    assert_eq!(obj.object_ignore_case("Wheel_Fr").unwrap().polygons().next().unwrap().index(), 4);
+
+    let mut conflicts = obj.case_conflicts().collect::<Vec<_>>();
+    conflicts.sort();
+    assert_eq!(conflicts, [(None, "wheel_fr"), (Some("Wheel_FL"), "tyre")]);
+
+    // The index follows the OBJ through conversions
+    let mut merged: Obj = "v 0 0 0\no Other\nf 1 1 1\n".parse().unwrap();
+    merged.merge(&obj);
+    assert!(merged.object_ignore_case("WHEEL_FL").is_some() && merged.object_ignore_case("other").is_some());
+    assert!(Obj::from_str_borrowed(src).unwrap().to_owned().object_ignore_case("wheel_fr").is_some());
+}
+
+#[test]
+fn polygons_in_several_groups() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\ng a b\nf 1 2 3\ng b\nf 1 2 3\ng c a\nf 1 2 3\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+
+    // Each polygon is produced once, in file order, however it is reached
+    let indices = |polygons: &mut dyn Iterator<Item=wavefront::Polygon>| polygons.map(|p| p.index()).collect::<Vec<_>>();
+    assert_eq!(indices(&mut obj.polygons()), [0, 1, 2]);
+    assert_eq!(indices(&mut obj.object("").unwrap().polygons()), [0, 1, 2]);
+    assert_eq!(obj.object("").unwrap().triangles().count(), 3);
+    assert_eq!(obj.polygons_with_context().count(), 5);
+}
+
+#[test]
+fn preallocate() {
+    use std::io::{Cursor, Seek, SeekFrom};
+    use wavefront::ParseOptions;
+
+    let src = std::str::from_utf8(include_bytes!("ship.obj")).unwrap();
+    let expected = summary(&src.parse::<Obj>().unwrap());
+    let options = ParseOptions::new().preallocate(true);
+
+    assert_eq!(summary(&Obj::from_str_with(src, options.clone()).unwrap()), expected);
+    assert_eq!(summary(&Obj::from_file_with("tests/ship.obj", options.clone()).unwrap()), expected);
+    assert_eq!(summary(&Obj::from_lines_with(src.lines(), options.clone()).unwrap()), expected);
+
+    // Seekable readers are parsed from (and returned to) their current position
+    let mut reader = Cursor::new(format!("garbage\n{}", src).into_bytes());
+    reader.seek(SeekFrom::Start(8)).unwrap();
+    assert_eq!(summary(&Obj::from_seekable_with(&mut reader, options).unwrap()), expected);
+}
+
+#[test]
+fn skip_attributes() {
+    use wavefront::ParseOptions;
+
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\nf 1/1/1 2//1 -1/-1/-1\n";
+    let attributes = |options: ParseOptions| {
+        let obj = Obj::from_str_with(src, options).unwrap();
+        assert_eq!(obj.positions().len(), 3);
+        obj.vertices().map(|v| (v.position_index(), v.uv_index(), v.normal_index())).collect::<Vec<_>>()
+    };
+    assert_eq!(attributes(ParseOptions::new()), [(0, Some(0), Some(0)), (1, None, Some(0)), (2, Some(0), Some(0))]);
+    assert_eq!(attributes(ParseOptions::new().skip_uvs(true)), [(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))]);
+    assert_eq!(attributes(ParseOptions::new().skip_normals(true)), [(0, Some(0), None), (1, None, None), (2, Some(0), None)]);
+    let options = ParseOptions::new().skip_uvs(true).skip_normals(true).preallocate(true);
+    assert_eq!(attributes(options.clone()), [(0, None, None), (1, None, None), (2, None, None)]);
+
+    // Face terms are still checked
+    let error = |src: &str| Obj::from_str_with(src, options.clone()).err().unwrap().to_string();
+    assert_eq!(error("v 0 0 0\nf 1/x"), "Expected index on line 2");
+    assert_eq!(error("v 0 0 0\nvt 0 0\nf 1/-2"), "Invalid index '-2'");
+}
+
+#[test]
+fn include() {
+    use wavefront::ParseOptions;
+
+    let full = Obj::from_file("tests/cubes.obj").unwrap();
+    let positions = |obj: &Obj, object: &str| obj
+        .object(object)
+        .unwrap()
+        .polygons()
+        .map(|p| p.vertices().map(|v| v.position()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for compact in [false, true] {
+        let options = ParseOptions::new().include_objects(&["cube1"]).compact(compact);
+        let obj = Obj::from_file_with("tests/cubes.obj", options).unwrap();
+        assert_eq!(obj.objects().map(|(name, _)| name).collect::<Vec<_>>(), ["cube1"]);
+        assert_eq!(obj.positions().len(), if compact { 8 } else { full.positions().len() });
+        assert_eq!(positions(&obj, "cube1"), positions(&full, "cube1"));
+    }
+
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\ng a b\nf 1 2 3\ng b\nf 1 2 3\no other\ng a\nf -3 -2 -1\ng\nf 1 2 3\n";
+    let groups = |options: ParseOptions| {
+        let obj = Obj::from_str_with(src, options).unwrap();
+        let mut objects = obj
+            .objects()
+            .map(|(name, object)| {
+                let mut groups = object
+                    .groups()
+                    .map(|(name, group)| (name.clone(), group.polygons().count()))
+                    .collect::<Vec<_>>();
+                groups.sort();
+                (name.to_string(), groups)
+            })
+            .collect::<Vec<_>>();
+        objects.sort();
+        objects
+    };
+    let s = String::from;
+    assert_eq!(groups(ParseOptions::new().include_groups(&["a"])), [
+        (s(""), vec![(s("a"), 1)]),
+        (s("other"), vec![(s("a"), 1)]),
+    ]);
+    assert_eq!(groups(ParseOptions::new().include_groups(&[""])), [
+        (s(""), vec![(s(""), 1)]),
+        (s("other"), vec![(s(""), 1)]),
+    ]);
+    assert_eq!(groups(ParseOptions::new().include_objects(&["other"]).include_groups(&["a", "b"])), [
+        (s("other"), vec![(s("a"), 1)]),
+    ]);
+    assert_eq!(groups(ParseOptions::new().include_objects(&["missing"])), []);
+}
+
+#[test]
+fn borrowed() {
+    let src = std::str::from_utf8(include_bytes!("ship.obj")).unwrap();
+    let obj = Obj::from_str_borrowed(src).unwrap();
+    assert_eq!(summary(&obj.to_owned()), summary(&src.parse::<Obj>().unwrap()));
+
+    // Names point into the source
+    let range = src.as_bytes().as_ptr_range();
+    assert!(obj.objects().count() > 1);
+    for (name, object) in obj.objects() {
+        assert!(name.is_empty() || range.contains(&name.as_ptr()));
+        assert!(object.groups().all(|(name, _)| name.is_empty() || range.contains(&name.as_ptr())));
+    }
+}
+
+#[test]
+fn float_syntax() {
+    let mut corpus = [
+        "0", "-0", "+0", "1", "+1", "-1", "1.", ".5", "+.5", "-.5", "1e5", "1E5", "1e+5", "1e-5", "1.5e3", "-1.5E-3",
+        "00012", "1e400", "-1e400", "1e-400", "3.4028236e38", "1.7976931348623157e308", "2.2250738585072014e-308",
+        "inf", "-inf", "+inf", "Inf", "INF", "infinity", "-Infinity", "NaN", "nan", "-nan", "nan(1)", "infin", "0x10",
+        "1_000", "+", "-", ".", "e5", "1e", "1e+", "1.2.3", "1,5", "1f", "--1", "+-1", "٣", "1e5x",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect::<Vec<_>>();
+    let mut seed = 0x2545f4914f6cdd1du64;
+    for _ in 0..2000 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        let x = f64::from_bits(seed);
+        if x.is_finite() {
+            corpus.extend([format!("{}", x), format!("{:e}", x), format!("{:.4}", x as f32), format!("{}", x as f32)]);
+        }
+    }
+
+    for term in &corpus {
+        let src = format!("v {} 7 7", term);
+        // Parsing stops at the first term that isn't a number, defaulting the rest to zero
+        let expected = |x: Option<f64>| x.map_or([0.0; 3], |x| [x, 7.0, 7.0]);
+
+        let obj = Obj::from_lines(src.lines()).unwrap();
+        let expected32 = expected(term.parse::<f32>().ok().map(f64::from));
+        assert_eq!(obj.positions()[0].map(|x| f64::from(x).to_bits()), expected32.map(f64::to_bits), "{:?}", term);
+
+        let obj = ObjF64::from_lines(src.lines()).unwrap();
+        let expected64 = expected(term.parse::<f64>().ok());
+        assert_eq!(obj.positions()[0].map(f64::to_bits), expected64.map(f64::to_bits), "{:?}", term);
+    }
+}
+
+#[test]
+fn clone_on_write() {
+    let mut obj = Obj::from_file("tests/ship.obj").unwrap();
+    let snapshot = obj.clone();
+    assert_eq!(snapshot.positions().as_ptr(), obj.positions().as_ptr());
+
+    let index = obj.add_position([1.0, 2.0, 3.0]);
+    assert_eq!(obj.positions()[index], [1.0, 2.0, 3.0]);
+    assert_eq!(snapshot.positions().len(), index);
+    assert_ne!(snapshot.positions().as_ptr(), obj.positions().as_ptr());
+    assert_eq!(snapshot.uvs().as_ptr(), obj.uvs().as_ptr());
+    assert_eq!(summary(&snapshot), summary(&Obj::from_file("tests/ship.obj").unwrap()));
+}
+
+#[test]
+fn mutate_attributes() {
+    let mut obj = Obj::from_file("tests/cube.obj").unwrap();
+    obj.cache_triangles();
+    let snapshot = obj.clone();
+
+    obj.positions_mut()[0] = [-1.0, -2.0, -3.0];
+    assert!(obj.vertices().filter(|v| v.position_index() == 0).all(|v| v.position() == [-1.0, -2.0, -3.0]));
+    assert!(obj.triangles().flatten().any(|v| v.position() == [-1.0, -2.0, -3.0]));
+    assert_eq!(snapshot.positions()[0], [0.0, 0.0, 0.0]);
+
+    obj.uvs_mut().iter_mut().for_each(|uv| *uv = [0.0; 3]);
+    assert!(obj.vertices().all(|v| v.uv().map_or(true, |uv| uv == [0.0; 3])));
+    obj.normals_mut()[0][0] = 2.0;
+    assert_eq!(obj.normals()[0][0], 2.0);
+
+    obj.set_position(1, [4.0, 5.0, 6.0]).unwrap();
+    assert!(obj.vertices().filter(|v| v.position_index() == 1).all(|v| v.position() == [4.0, 5.0, 6.0]));
+    obj.set_normal(0, [0.0, 1.0, 0.0]).unwrap();
+    let (positions, uvs) = (obj.positions().len(), obj.uvs().len());
+    assert!(matches!(obj.set_position(positions, [0.0; 3]), Err(Error::InvalidIndex(i)) if i as usize == positions + 1));
+    assert!(obj.set_uv(uvs, [0.0; 3]).is_err());
+    assert!(obj.set_normal(usize::MAX, [0.0; 3]).is_err());
+    assert_eq!(summary(&snapshot), summary(&Obj::from_file("tests/cube.obj").unwrap()));
+}
+
+#[test]
+fn progress() {
+    use std::{cell::RefCell, fmt::Write, rc::Rc};
+    use wavefront::{ParseOptions, Progress};
+
+    let mut src = String::new();
+    for i in 0..200_000 {
+        writeln!(src, "v {} {} {}\r", i, i * 2, i * 3).unwrap();
+        writeln!(src, "f -1 -1 -1").unwrap();
+    }
+    let reports = Rc::new(RefCell::new(Vec::<Progress>::new()));
+    let options = {
+        let reports = reports.clone();
+        ParseOptions::new().progress(move |progress| reports.borrow_mut().push(progress))
+    };
+    let check = |lines: usize, total: Option<u64>| {
+        let reports = reports.take();
+        // Roughly one report per megabyte, plus a final one
+        assert!(reports.len() >= src.len() >> 20 && reports.len() <= (src.len() >> 20) + 2, "{}", reports.len());
+        assert!(reports.windows(2).all(|w| w[0].bytes <= w[1].bytes && w[0].lines <= w[1].lines));
+        assert!(reports.iter().all(|p| p.total_bytes == total));
+        let last = reports.last().unwrap();
+        assert_eq!((last.bytes, last.lines), (src.len() as u64, lines));
+        assert_eq!(last.fraction(), total.map(|_| 1.0));
+    };
+
+    Obj::from_str_with(&src, options.clone()).unwrap();
+    check(400_000, Some(src.len() as u64));
+    Obj::from_seekable_with(std::io::Cursor::new(&src), options.clone()).unwrap();
+    check(400_000, Some(src.len() as u64));
+    Obj::from_buf_reader_with(src.as_bytes(), options.clone()).unwrap();
+    check(400_000, None);
+
+    // Nothing is reported after an error
+    assert!(Obj::from_str_with("f 1", options).is_err());
+    assert!(reports.borrow().is_empty());
+}
+
+#[test]
+fn cancel() {
+    use std::{cell::Cell, sync::{atomic::{AtomicBool, Ordering}, Arc}};
+    use wavefront::{Error, ParseOptions};
+
+    let flag = Arc::new(AtomicBool::new(false));
+    let options = ParseOptions::new().cancel_flag(flag.clone());
+    let count = Cell::new(0);
+    let lines = std::iter::from_fn(|| {
+        count.set(count.get() + 1);
+        if count.get() == 5000 {
+            let flag = flag.clone();
+            std::thread::spawn(move || flag.store(true, Ordering::Relaxed)).join().unwrap();
+        }
+        Some("v 0 0 0")
+    });
+    assert!(matches!(Obj::from_lines_with(lines.take(1_000_000), options.clone()), Err(Error::Cancelled)));
+    assert!(count.get() - 5000 <= 1024, "{} lines parsed after cancellation", count.get() - 5000);
+
+    // A flag that has already been set cancels any long parse
+    assert!(matches!(Obj::from_str_with(&"v 0 0 0\n".repeat(2000), options.clone()), Err(Error::Cancelled)));
+    assert!(Obj::from_str_with("v 0 0 0\n", options).is_ok());
+}
+
+#[test]
+fn map_attributes() {
+    use wavefront::{Error, ParseOptions};
+
+    // Scaling while parsing matches scaling afterwards exactly, and indices are unchanged
+    let scale = 0.001;
+    let options = ParseOptions::new().map_position(move |p| p.map(|x| x * scale));
+    let mapped = Obj::from_file_with("tests/ship.obj", options).unwrap();
+    let obj = Obj::from_file("tests/ship.obj").unwrap();
+    let scaled = obj.positions().iter().map(|p| p.map(|x| x * scale)).collect::<Vec<_>>();
+    assert_eq!(mapped.positions(), &scaled[..]);
+    assert_eq!(mapped.uvs(), obj.uvs());
+    assert_eq!(mapped.normals(), obj.normals());
+    let indices = |obj: &Obj| obj.vertices().map(|v| (v.position_index(), v.uv_index(), v.normal_index())).collect::<Vec<_>>();
+    assert_eq!(indices(&mapped), indices(&obj));
+
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.5 1\nvn 0 0 1\nf 1/1/1 2/1/1 3/1/1\n";
+    let options = ParseOptions::new()
+        .map_uv(|[u, v, w]| [u, 1.0 - v, w])
+        .map_normal(|n| n.map(|x| -x));
+    let obj = Obj::from_str_with(src, options.clone()).unwrap();
+    assert_eq!(obj.uvs(), [[0.5, 0.0, 0.0]]);
+    assert_eq!(obj.normals(), [[-0.0, -0.0, -1.0]]);
+    let skipped = Obj::from_str_with(src, options.skip_uvs(true).skip_normals(true)).unwrap();
+    assert!(skipped.uvs().is_empty() && skipped.normals().is_empty());
+
+    // Strict mode checks the transformed attributes
+    let options = ParseOptions::new().strict(true).map_position(|p| p.map(|x| x / 0.0));
+    assert!(matches!(Obj::from_str_with(src, options.clone()), Err(Error::NonFiniteAttribute(1))));
+    assert!(Obj::from_str_with(src, options.strict(false)).is_ok());
+}
+
+#[test]
+fn on_unknown() {
+    use std::{cell::RefCell, rc::Rc};
+    use wavefront::{Error, ParseOptions};
+
+    let src = "# collision volumes\nv 0 0 0\nx_collision box 1 2 3\nv 1 0 0\n  x_collision\tsphere 0.5  \r\nv 0 1 0\ns off\nf 1 2 3\nusemtl red\nx_tag\n";
+    let statements = Rc::new(RefCell::new(Vec::new()));
+    let options = ParseOptions::new().on_unknown({
+        let statements = statements.clone();
+        move |line, keyword, rest| {
+            statements.borrow_mut().push((line, keyword.to_string(), rest.to_string()));
+            Ok(())
+        }
+    });
+    let expected = [
+        (3, "x_collision", " box 1 2 3"),
+        (5, "x_collision", "\tsphere 0.5  "),
+        (7, "s", " off"),
+        (10, "x_tag", ""),
+    ]
+    .map(|(line, keyword, rest)| (line, keyword.to_string(), rest.to_string()));
+
+    // Every way of parsing with options sees the same statements, with line terminators removed
+    let obj = Obj::from_str_with(src, options.clone()).unwrap();
+    assert_eq!(obj.polygons().count(), 1);
+    assert_eq!(statements.take(), expected);
+    Obj::from_reader_with(src.as_bytes(), options.clone()).unwrap();
+    assert_eq!(statements.take(), expected);
+    Obj::from_lines_with(src.lines(), options.clone()).unwrap();
+    assert_eq!(statements.take(), expected);
+
+    // The callback can abandon parsing
+    let options = ParseOptions::new().on_unknown(|line, keyword, _| match keyword {
+        "x_tag" => Err(Error::Rejected(line, "tags are not supported".to_string())),
+        _ => Ok(()),
+    });
+    assert_eq!(
+        Obj::from_str_with(src, options).unwrap_err().to_string(),
+        "Statement on line 10 was rejected: tags are not supported",
+    );
+}
+
+#[test]
+fn max_attributes() {
+    use wavefront::ParseOptions;
+
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvn 0 0 1\nf 1/1/1 2/2/1 3/2/1\n";
+    let parse = |options: ParseOptions| Obj::from_str_with(src, options).map(|obj| obj.polygons().len());
+    assert!(matches!(parse(ParseOptions::new().max_positions(3).max_uvs(2).max_normals(1)), Ok(1)));
+    assert!(matches!(parse(ParseOptions::new().max_positions(2)), Err(Error::TooManyAttributes(3))));
+    assert!(matches!(parse(ParseOptions::new().max_uvs(1)), Err(Error::TooManyAttributes(5))));
+    assert!(matches!(parse(ParseOptions::new().max_normals(0)), Err(Error::TooManyAttributes(6))));
+    // Skipped attributes still count
+    assert!(matches!(parse(ParseOptions::new().skip_uvs(true).max_uvs(1)), Err(Error::TooManyAttributes(5))));
+    assert!(matches!(Obj::from_reader_with(src.as_bytes(), ParseOptions::new().max_positions(1)), Err(Error::TooManyAttributes(2))));
+}
+
+#[test]
+fn keep_comments() {
+    use wavefront::ParseOptions;
+
+    let src = "# Exported by hand\r\nv 0 0 0\n  #indented\nv 1 0 0 # not a comment\nv 0 1 0\n#\nf 1 2 3\n";
+    let expected = [(1, " Exported by hand"), (3, "indented"), (6, "")];
+    let options = ParseOptions::new().keep_comments(true);
+    let obj = Obj::from_str_with(src, options.clone()).unwrap();
+    assert_eq!(obj.comments().collect::<Vec<_>>(), expected);
+    let obj = Obj::from_reader_with(src.as_bytes(), options.clone()).unwrap();
+    assert_eq!(obj.comments().collect::<Vec<_>>(), expected);
+    // Comments are kept by operations that rebuild the OBJ
+    let obj = Obj::from_str_with(src, options.compact(true)).unwrap();
+    assert_eq!(obj.comments().collect::<Vec<_>>(), expected);
+
+    assert_eq!(src.parse::<Obj>().unwrap().comments().count(), 0);
+}
+
+#[test]
+fn compose_options() {
+    use std::{cell::RefCell, rc::Rc};
+    use wavefront::ParseOptions;
+
+    // The default options parse exactly as the constructors without them
+    let obj = Obj::from_file("tests/ship.obj").unwrap();
+    assert_eq!(summary(&Obj::from_file_with("tests/ship.obj", ParseOptions::new()).unwrap()), summary(&obj));
+
+    let src = "# tagged\nv 0 0 0\nv 1 0 0\nv 0 1 0\nx_tag a\nf 1 2 3\nv 0 0 1\n";
+    let tags = Rc::new(RefCell::new(Vec::new()));
+    let options = ParseOptions::new()
+        .strict(true)
+        .max_positions(3)
+        .keep_comments(true)
+        .track_lines(true)
+        .on_unknown({
+            let tags = tags.clone();
+            move |line, _, rest| {
+                tags.borrow_mut().push((line, rest.to_string()));
+                Ok(())
+            }
+        });
+    // The limit is reached after the callback has seen the tag, and attributes are checked before they're counted
+    assert!(matches!(Obj::from_str_with(src, options.clone()), Err(Error::TooManyAttributes(7))));
+    assert_eq!(tags.take(), [(5, " a".to_string())]);
+    let non_finite = src.replace("v 0 0 1", "v 0 0 inf");
+    assert!(matches!(Obj::from_str_with(&non_finite, options.clone()), Err(Error::NonFiniteAttribute(7))));
+    tags.take();
+
+    let obj = Obj::from_str_with(src, options.max_positions(4)).unwrap();
+    assert_eq!(obj.comments().collect::<Vec<_>>(), [(1, " tagged")]);
+    assert_eq!(obj.polygons().next().unwrap().source_line(), Some(6));
+    assert_eq!(tags.take(), [(5, " a".to_string())]);
+}
+
+#[test]
+fn invalid_object_names() {
+    use wavefront::{ParseOptions, Warning};
+
+    // A bare `o` (here with a trailing tab), and one with only invalid characters, are renamed after their lines,
+    // avoiding the names of other objects, even those that come later
+    let src = "v 0 0 0\nf 1 1 1\no\t\nf 1 1 1\no a\nf 1 1 1\no wheel-left\nf 1 1 1\no object_7\nf 1 1 1\no\n";
+    let obj: Obj = src.parse().unwrap();
+    let mut objects = obj.objects().map(|(name, object)| (name, object.polygons().count())).collect::<Vec<_>>();
+    objects.sort();
+    assert_eq!(objects, [("", 1), ("a", 1), ("object_3", 1), ("object_7", 1), ("object_7_2", 1)]);
+    let warning = |line, name: &str, renamed: &str| Warning::InvalidObjectName {
+        line,
+        name: name.to_string(),
+        renamed: renamed.to_string(),
+    };
+    assert_eq!(obj.parse_report().warnings, [
+        warning(3, "", "object_3"),
+        warning(7, "wheel-left", "object_7_2"),
+        warning(11, "", "object_11"),
+    ]);
+    assert_eq!(
+        obj.parse_report().warnings[1].to_string(),
+        "Invalid object name 'wheel-left' on line 7 was replaced with 'object_7_2'",
+    );
+    let reparsed: Obj = obj.to_string().parse().unwrap();
+    assert!(reparsed.parse_report().warnings.is_empty());
+    assert_eq!(reparsed.objects().count(), 5);
+
+    // Borrowed names can't be made up, so those objects become part of the unnamed object
+    let borrowed = Obj::from_str_borrowed(src).unwrap();
+    let mut objects = borrowed.objects().map(|(name, object)| (name, object.polygons().count())).collect::<Vec<_>>();
+    objects.sort();
+    assert_eq!(objects, [("", 3), ("a", 1), ("object_7", 1)]);
+    assert_eq!(borrowed.parse_report().warnings[1], warning(7, "wheel-left", ""));
+
+    // Strict mode rejects them
+    let strict = ParseOptions::new().strict(true);
+    assert!(matches!(Obj::from_str_with(src, strict.clone()), Err(Error::ExpectedName(3))));
+    assert!(matches!(Obj::from_str_with("v 0 0 0\no -\nf 1 1 1\n", strict), Err(Error::ExpectedName(2))));
+
+    // They're treated as unnamed when filtering objects
+    let options = ParseOptions::new().include_objects(&["a"]);
+    let obj = Obj::from_str_with(src, options).unwrap();
+    assert_eq!(obj.objects().map(|(name, _)| name).collect::<Vec<_>>(), ["a"]);
+    assert_eq!(obj.parse_report().warnings.len(), 3);
+}
+
+#[test]
+fn invalid_group_names() {
+    use wavefront::{ParseOptions, Warning};
+
+    // An invalid name doesn't stop the valid names after it from being used
+    let src = "v 0 0 0\ng valid some-invalid other_valid\nf 1 1 1\ng some-group\nf 1 1 1\ng a-b c+d\nf 1 1 1\n";
+    let obj: Obj = src.parse().unwrap();
+    let object = obj.object("").unwrap();
+    let mut groups = object.groups().map(|(name, group)| (name.as_str(), group.polygons().count())).collect::<Vec<_>>();
+    groups.sort();
+    assert_eq!(groups, [("", 2), ("other_valid", 1), ("valid", 1)]);
+    let warning = |line, name: &str| Warning::InvalidGroupName { line, name: name.to_string() };
+    assert_eq!(obj.parse_report().warnings, [
+        warning(2, "some-invalid"),
+        warning(4, "some-group"),
+        warning(6, "a-b"),
+        warning(6, "c+d"),
+    ]);
+    assert_eq!(obj.parse_report().warnings[0].to_string(), "Invalid group name 'some-invalid' on line 2 was ignored");
+
+    // Strict mode rejects the first of them
+    let result = Obj::from_str_with(src, ParseOptions::new().strict(true));
+    assert!(matches!(&result, Err(Error::InvalidGroupName(2, name)) if name == "some-invalid"));
+    assert_eq!(result.unwrap_err().to_string(), "Invalid group name 'some-invalid' on line 2");
+}
+
+#[test]
+fn line_counts() {
+    use wavefront::{LineCounts, ParseOptions};
+
+    let counts = |counts: LineCounts| {
+        (counts.total, counts.comments, counts.blank, counts.geometry, counts.structure, counts.unsupported, counts.unknown)
+    };
+    let expected = (26, 3, 3, 9, 5, 4, 2);
+    let obj = Obj::from_file("tests/everything.obj").unwrap();
+    assert_eq!(counts(obj.parse_report().line_counts), expected);
+    let src = std::fs::read_to_string("tests/everything.obj").unwrap();
+    assert_eq!(counts(Obj::from_str_borrowed(&src).unwrap().parse_report().line_counts), expected);
+    // Skipped statements are still counted
+    let options = ParseOptions::new().skip_uvs(true).include_groups(&["back"]);
+    assert_eq!(counts(Obj::from_str_with(&src, options).unwrap().parse_report().line_counts), expected);
+}
@@ -0,0 +1,101 @@
+use wavefront::{Error, Obj, ObjBuilder, VertexData};
+
+/// The corners of the 12 triangles of a unit cube, with a normal per face.
+fn cube_corners() -> Vec<[VertexData; 3]> {
+    let cube = Obj::from_file("tests/cube.obj").unwrap();
+    cube.triangles()
+        .map(|tri| tri.map(|v| VertexData { position: v.position(), uv: None, normal: v.normal() }))
+        .collect()
+}
+
+#[test]
+fn cube() {
+    let mut builder = ObjBuilder::new();
+    assert!(builder.is_empty());
+    builder.object("cube").unwrap();
+    for corners in cube_corners() {
+        builder.triangle(corners);
+    }
+    assert_eq!(builder.len(), 12);
+
+    let obj = builder.build();
+    assert_eq!(obj.positions().len(), 8);
+    assert_eq!(obj.normals().len(), 6);
+    assert!(obj.uvs().is_empty());
+    assert_eq!(obj.object("cube").unwrap().group("").unwrap().polygons().len(), 12);
+    let report = obj.manifold_report();
+    assert!(report.is_closed() && report.is_manifold());
+    assert!((obj.volume() - 1.0).abs() < 1e-6);
+
+    // Corners are kept exactly, with attributes in the order they're first used
+    let built = obj.triangles().map(|tri| tri.map(VertexData::from)).collect::<Vec<_>>();
+    assert_eq!(built, cube_corners());
+    assert_eq!(obj.positions()[0], cube_corners()[0][0].position);
+}
+
+#[test]
+fn objects_and_groups() {
+    let corners = cube_corners();
+    let mut builder = ObjBuilder::new();
+    builder.triangle(corners[0]);
+    builder.object("a").unwrap().group(&["x", "y"]).unwrap();
+    builder.triangle(corners[1]).polygon(&[corners[2][0], corners[2][1], corners[2][2], corners[3][2]]);
+    builder.object("b").unwrap();
+    builder.triangle(corners[4]);
+    // Returning to an object adds to it
+    builder.object("a").unwrap().group(&["y"]).unwrap();
+    builder.triangle(corners[5]);
+
+    assert!(matches!(builder.object("bad name"), Err(Error::InvalidName(_))));
+    assert!(matches!(builder.group(&["ok", ""]), Err(Error::InvalidName(_))));
+    builder.triangle(corners[6]);
+
+    let obj = builder.build();
+    assert_eq!(obj.polygons().len(), 6);
+    assert_eq!(obj.object("").unwrap().polygons().map(|poly| poly.index()).collect::<Vec<_>>(), [0]);
+    let a = obj.object("a").unwrap();
+    assert_eq!(a.group("x").unwrap().polygons().map(|poly| poly.index()).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(a.group("y").unwrap().polygons().map(|poly| poly.index()).collect::<Vec<_>>(), [1, 2, 4, 5]);
+    assert_eq!(obj.polygon(2).unwrap().vertices().len(), 4);
+    assert_eq!(obj.object("b").unwrap().polygons().count(), 1);
+
+    // The result survives being written and read back
+    let reparsed: Obj = obj.to_string().parse().unwrap();
+    assert_eq!(reparsed.positions(), obj.positions());
+    assert_eq!(reparsed.object("").unwrap().polygons().count(), 1);
+    assert_eq!(reparsed.object("b").unwrap().polygons().count(), 1);
+}
+
+#[test]
+fn dedup_epsilon() {
+    let corner = |x: f32, y: f32| VertexData { position: [x, y, 0.0], uv: None, normal: Some([0.0, 0.0, 1.0]) };
+    let noisy = [
+        [corner(0.0, 0.0), corner(1.0, 0.0), corner(1.0, 1.0)],
+        [corner(0.0, 0.000_01), corner(1.0, 1.000_01), corner(0.0, 1.0)],
+    ];
+
+    let mut exact = ObjBuilder::new();
+    noisy.iter().for_each(|tri| { exact.triangle(*tri); });
+    let exact = exact.build();
+    assert_eq!(exact.positions().len(), 6);
+    assert_eq!(exact.normals().len(), 1);
+
+    let mut snapped = ObjBuilder::new().dedup_epsilon(Some(1.0 / 1024.0));
+    noisy.iter().for_each(|tri| { snapped.triangle(*tri); });
+    let snapped = snapped.build();
+    assert_eq!(snapped.positions(), [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]);
+
+    // Values that can't be placed in a cell are only merged with identical ones, rather than with unrelated values in
+    // the first or last cells
+    let mut extremes = ObjBuilder::new().dedup_epsilon(Some(1.0 / 1024.0));
+    let values = [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, f32::MAX, f32::MIN, 1e30, 0.0];
+    for x in values.iter().chain(values.iter()) {
+        extremes.triangle([corner(*x, 0.0); 3]);
+    }
+    assert_eq!(extremes.build().positions().len(), values.len());
+
+    // Negative and positive zero have different bit patterns
+    let mut zeros = ObjBuilder::new();
+    zeros.triangle([corner(0.0, 0.0), corner(-0.0, 0.0), corner(1.0, 1.0)]);
+    assert_eq!(zeros.build().positions().len(), 3);
+}
@@ -0,0 +1,238 @@
+use wavefront::Obj;
+
+/// A small xorshift generator producing numbers in `[-1, 1)`.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    fn point(&mut self, scale: f32) -> [f32; 3] {
+        [self.next() * scale, self.next() * scale, self.next() * scale]
+    }
+}
+
+fn models() -> Vec<Obj> {
+    [&include_bytes!("ship.obj")[..], include_bytes!("torus.obj"), include_bytes!("cubes.obj"), include_bytes!("mixed.obj")]
+        .iter()
+        .map(|src| Obj::from_reader(*src).unwrap())
+        .collect()
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn dist_sq(a: [f32; 3], b: [f32; 3]) -> f32 {
+    dot(sub(a, b), sub(a, b))
+}
+
+/// The closest point to `p` on the segment from `a` to `b`.
+fn closest_on_segment(p: [f32; 3], a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    let e = sub(b, a);
+    let t = if dot(e, e) > 0.0 { (dot(sub(p, a), e) / dot(e, e)).clamp(0.0, 1.0) } else { 0.0 };
+    [a[0] + e[0] * t, a[1] + e[1] * t, a[2] + e[2] * t]
+}
+
+#[test]
+fn raycast() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for obj in models() {
+        let bvh = obj.build_bvh();
+        assert_eq!(bvh.len(), obj.triangles().count());
+
+        let mut hits = 0;
+        for i in 0..500 {
+            // Aim most rays at a vertex of the model, so that they hit something
+            let origin = rng.point(10.0);
+            let target = obj.positions()[i * 7 % obj.positions().len()];
+            let dir = if i % 4 == 0 { rng.point(1.0) } else { sub(target, origin) };
+            let cull = i % 2 == 0;
+            let expected = obj.raycast(origin, dir, cull);
+            let hit = bvh.raycast(origin, dir, cull);
+            assert_eq!(hit.is_some(), expected.is_some());
+            if let (Some(hit), Some(expected)) = (hit, expected) {
+                hits += 1;
+                assert_eq!(hit.distance, expected.distance);
+                assert_eq!(hit.barycentric, expected.barycentric);
+                assert_eq!(hit.polygon, expected.polygon);
+                assert_eq!((hit.object, hit.group), (expected.object, expected.group));
+                assert_eq!(hit.triangle.map(|v| v.position()), expected.triangle.map(|v| v.position()));
+            }
+        }
+        assert!(hits > 250, "{}", hits);
+    }
+
+    // Axis-aligned rays, which skim along the faces of the cubes
+    let obj = Obj::from_reader(include_bytes!("cubes.obj") as &[u8]).unwrap();
+    let bvh = obj.build_bvh();
+    for origin in [[-1.0, 0.25, 0.5], [-1.0, 0.5, 0.5], [-1.0, 0.0, 0.0], [0.5, 0.5, -1.0], [0.0, 0.5, 0.5]] {
+        for dir in [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]] {
+            let (hit, expected) = (bvh.raycast(origin, dir, false), obj.raycast(origin, dir, false));
+            assert_eq!(hit.map(|h| (h.distance, h.polygon)), expected.map(|h| (h.distance, h.polygon)));
+        }
+    }
+}
+
+#[test]
+fn intersect_aabb() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for obj in models() {
+        let bvh = obj.build_bvh();
+        let triangles = obj.triangles().map(|tri| tri.map(|v| v.position())).collect::<Vec<_>>();
+        for _ in 0..200 {
+            let (a, b) = (rng.point(6.0), rng.point(6.0));
+            let min = [0, 1, 2].map(|i| a[i].min(b[i]));
+            let max = [0, 1, 2].map(|i| a[i].max(b[i]));
+            let inside = |p: [f32; 3]| (0..3).all(|i| min[i] <= p[i] && p[i] <= max[i]);
+
+            let found = bvh.intersect_aabb(min, max).collect::<Vec<_>>();
+            assert!(found.windows(2).all(|w| w[0] < w[1]));
+            for (i, tri) in triangles.iter().enumerate() {
+                let overlaps_bounds = (0..3).all(|axis| {
+                    tri.iter().any(|p| p[axis] >= min[axis]) && tri.iter().any(|p| p[axis] <= max[axis])
+                });
+                if tri.iter().any(|&p| inside(p)) {
+                    assert!(found.contains(&i));
+                } else if !overlaps_bounds {
+                    assert!(!found.contains(&i));
+                }
+            }
+        }
+
+        // A box around everything finds every triangle
+        assert_eq!(bvh.intersect_aabb([-1e3; 3], [1e3; 3]).count(), bvh.len());
+    }
+
+    // A large triangle passing through a small box, with none of its vertices inside
+    let obj = Obj::from_lines("v -10 -10 0\nv 10 -10 0\nv 0 10 0\nf 1 2 3\n".lines()).unwrap();
+    let bvh = obj.build_bvh();
+    assert_eq!(bvh.intersect_aabb([-1.0; 3], [1.0; 3]).collect::<Vec<_>>(), [0]);
+    assert_eq!(bvh.intersect_aabb([-1.0, -1.0, 0.5], [1.0, 1.0, 1.0]).count(), 0);
+    assert_eq!(bvh.intersect_aabb([8.0, 8.0, -1.0], [9.0, 9.0, 1.0]).count(), 0);
+}
+
+#[test]
+fn closest_point() {
+    let mut rng = Rng(0x853c49e6748fea9b);
+    for obj in models() {
+        let bvh = obj.build_bvh();
+        let triangles = obj.triangles().map(|tri| tri.map(|v| v.position())).collect::<Vec<_>>();
+        for _ in 0..200 {
+            let p = rng.point(8.0);
+            let hit = bvh.closest_point(p).unwrap();
+
+            // The point lies on the reported triangle, as given by its barycentric coordinates
+            let tri = hit.triangle.map(|v| v.position());
+            assert_eq!(tri, triangles[hit.triangle_index]);
+            let point = [0, 1, 2].map(|i| (0..3).map(|v| tri[v][i] * hit.barycentric[v]).sum::<f32>());
+            assert!(dist_sq(point, hit.point) < 1e-6);
+            assert!((hit.distance - dist_sq(p, hit.point).sqrt()).abs() < 1e-4);
+
+            // No point on the boundary of any triangle is closer
+            for tri in &triangles {
+                for (a, b) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                    assert!(hit.distance <= dist_sq(p, closest_on_segment(p, a, b)).sqrt() + 1e-4);
+                }
+            }
+        }
+    }
+
+    // The closest point lies in the interior of a face of the cube
+    let obj = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    let hit = obj.build_bvh().closest_point([0.25, 0.5, -2.0]).unwrap();
+    assert_eq!(hit.point, [0.25, 0.5, 0.0]);
+    assert_eq!(hit.distance, 2.0);
+}
+
+#[test]
+fn degenerate() {
+    // Many copies of the same triangle, including zero-area ones, can't be split apart
+    let mut src = String::from("v 0 0 0\nv 1 0 0\nv 0 1 0\nv 2 0 0\n");
+    for _ in 0..100 {
+        src.push_str("f 1 2 3\nf 1 2 4\nf 1 1 1\n");
+    }
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let bvh = obj.build_bvh();
+    assert_eq!(bvh.len(), 300);
+
+    // Ties are broken in favour of the first triangle
+    let hit = bvh.raycast([0.25, 0.25, 1.0], [0.0, 0.0, -1.0], false).unwrap();
+    assert_eq!(hit.polygon, 0);
+    let closest = bvh.closest_point([0.25, 0.25, 1.0]).unwrap();
+    assert_eq!((closest.triangle_index, closest.distance), (0, 1.0));
+    // Only the collinear triangles reach this far
+    let closest = bvh.closest_point([1.5, -1.0, 0.0]).unwrap();
+    assert_eq!((closest.triangle_index, closest.point), (1, [1.5, 0.0, 0.0]));
+    assert_eq!(bvh.intersect_aabb([0.0; 3], [0.0; 3]).count(), 300);
+
+    let empty = Obj::from_lines("v 0 0 0\n".lines()).unwrap();
+    let bvh = empty.build_bvh();
+    assert!(bvh.is_empty());
+    assert!(bvh.raycast([0.0; 3], [1.0, 0.0, 0.0], false).is_none());
+    assert!(bvh.closest_point([0.0; 3]).is_none());
+    assert_eq!(bvh.intersect_aabb([-1.0; 3], [1.0; 3]).count(), 0);
+}
+
+#[test]
+fn nearest() {
+    let mut rng = Rng(0xda942042e4dd58b5);
+    for obj in models() {
+        let bvh = obj.build_bvh();
+        let triangles = obj.triangles().map(|tri| tri.map(|v| v.position())).collect::<Vec<_>>();
+        for _ in 0..200 {
+            let p = rng.point(8.0);
+
+            // Brute force, with ties going to the lowest index
+            let (index, nearest_sq) = obj
+                .positions()
+                .iter()
+                .enumerate()
+                .map(|(i, &q)| (i, dist_sq(p, q)))
+                .reduce(|a, b| if b.1 < a.1 { b } else { a })
+                .unwrap();
+            assert_eq!(obj.nearest_position(p), Some((index, nearest_sq.sqrt())));
+            if obj.triangles().flatten().any(|v| v.position_index() == index) {
+                assert_eq!(bvh.nearest_position(p), Some((index, nearest_sq.sqrt())));
+            }
+
+            let point = obj.nearest_surface_point(p).unwrap();
+            assert_eq!(bvh.nearest_surface_point(p), Some(point));
+            let closest = bvh.closest_point(p).unwrap();
+            let closest = (closest.point, closest.triangle_index, closest.polygon);
+            assert_eq!(closest, (point.position, point.triangle, point.polygon));
+            let tri = triangles[point.triangle];
+            let position = [0, 1, 2].map(|i| (0..3).map(|v| tri[v][i] * point.barycentric[v]).sum::<f32>());
+            assert!(dist_sq(position, point.position) < 1e-6);
+            assert!(point.distance <= nearest_sq.sqrt());
+            for tri in &triangles {
+                for (a, b) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                    assert!(point.distance <= dist_sq(p, closest_on_segment(p, a, b)).sqrt() + 1e-4);
+                }
+            }
+        }
+    }
+
+    // Duplicate positions are tied, and unreferenced positions are only found by the brute-force search
+    let obj = Obj::from_lines("v 5 5 5\nv 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 0\nf 2 3 4\nf 5 3 4\n".lines()).unwrap();
+    assert_eq!(obj.nearest_position([0.0, 0.0, -1.0]), Some((1, 1.0)));
+    assert_eq!(obj.build_bvh().nearest_position([0.0, 0.0, -1.0]), Some((1, 1.0)));
+    assert_eq!(obj.nearest_position([5.0, 5.0, 4.0]), Some((0, 1.0)));
+    assert_eq!(obj.build_bvh().nearest_position([5.0, 5.0, 4.0]).unwrap().0, 2);
+    let point = obj.nearest_surface_point([0.25, 0.25, 1.0]).unwrap();
+    assert_eq!((point.triangle, point.polygon, point.distance), (0, 0, 1.0));
+    assert_eq!(point.position, [0.25, 0.25, 0.0]);
+
+    let empty = Obj::from_lines("v 0 0 0\n".lines()).unwrap();
+    assert_eq!(empty.nearest_position([1.0, 0.0, 0.0]), Some((0, 1.0)));
+    assert_eq!(empty.nearest_surface_point([1.0, 0.0, 0.0]), None);
+    assert_eq!(empty.build_bvh().nearest_position([1.0, 0.0, 0.0]), None);
+}
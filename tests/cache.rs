@@ -0,0 +1,115 @@
+#![cfg(feature = "cache")]
+
+use std::convert::TryInto;
+use wavefront::{Error, Obj, ObjF64, ParseOptions};
+
+fn summary(obj: &Obj) -> Vec<String> {
+    let mut summary = vec![format!("{:?} {:?} {:?}", obj.positions(), obj.uvs(), obj.normals())];
+    summary.extend(obj.polygons_with_context().map(|(o, g, poly)| {
+        let vertices = poly.vertices().map(|v| (v.position_index(), v.uv_index(), v.normal_index())).collect::<Vec<_>>();
+        format!("{} {} {:?} {:?} {:?}", o, g, vertices, poly.source_line(), poly.material())
+    }));
+    summary
+}
+
+fn cache(obj: &Obj) -> Vec<u8> {
+    let mut cache = Vec::new();
+    obj.to_cache(&mut cache).unwrap();
+    cache
+}
+
+#[test]
+fn round_trip() {
+    let src = "o a\nv 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.5 0.25\nvn 0 0 1\ng x y\nf 1/1/1 2/1/1 3/1/1\ng\nf 3 2 1\no Big\n\
+        g z\nusemtl red\nf 1//1 2//1 3//1\no part.2\nusemtl\nf 1 2 3\n";
+    for options in [ParseOptions::new(), ParseOptions::new().track_lines(true).cache_triangles(true)] {
+        let obj = Obj::from_str_with(src, options).unwrap();
+        let loaded = Obj::from_cache(&cache(&obj)[..]).unwrap();
+        assert_eq!(summary(&loaded), summary(&obj));
+        assert_eq!(loaded.triangle_indices(), obj.triangle_indices());
+        assert!(loaded.object("part.2").is_some());
+        assert!(loaded.object_ignore_case("BIG").is_some());
+        assert!(loaded.object("a").unwrap().group("y").is_some());
+    }
+
+    let ship = Obj::from_file("tests/ship.obj").unwrap();
+    assert_eq!(summary(&Obj::from_cache(&cache(&ship)[..]).unwrap()), summary(&ship));
+
+    // Attributes are converted when loaded with a different precision
+    let cubes = Obj::from_file("tests/cubes.obj").unwrap();
+    let loaded = ObjF64::from_cache(&cache(&cubes)[..]).unwrap();
+    assert_eq!(loaded.positions().iter().map(|p| p.map(|x| x as f32)).collect::<Vec<_>>(), cubes.positions());
+    assert_eq!(loaded.objects().count(), 3);
+}
+
+#[test]
+fn errors() {
+    let cache = cache(&Obj::from_file("tests/cubes.obj").unwrap());
+    assert!(Obj::from_cache(&cache[..]).is_ok());
+
+    assert!(matches!(Obj::from_cache(&b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n"[..]), Err(Error::InvalidCache)));
+    assert!(matches!(Obj::from_cache(&[][..]), Err(Error::InvalidCache)));
+
+    let mut newer = cache.clone();
+    newer[8..12].copy_from_slice(&3u32.to_le_bytes());
+    let e = Obj::from_cache(&newer[..]).unwrap_err();
+    assert!(matches!(e, Error::UnsupportedCacheVersion(3)));
+    assert_eq!(e.to_string(), "Unsupported cache version 3 (expected version 2)");
+
+    // A cache cut short anywhere is rejected
+    for len in (0..cache.len()).step_by(7) {
+        assert!(matches!(Obj::from_cache(&cache[..len]), Err(Error::InvalidCache)), "{}", len);
+    }
+
+    // As is one that refers to a position that doesn't exist (the first vertex follows the header, the three
+    // attribute sections and the vertex count)
+    let positions = u64::from_le_bytes(cache[18..26].try_into().unwrap()) as usize;
+    let first_vertex = 18 + 8 + positions * 12 + 8 + 8 + 8;
+    let mut corrupt = cache.clone();
+    corrupt[first_vertex..first_vertex + 4].copy_from_slice(&(positions as u32 + 1).to_le_bytes());
+    assert!(matches!(Obj::from_cache(&corrupt[..]), Err(Error::InvalidCache)));
+}
+
+#[test]
+fn sanitize_names() {
+    use wavefront::WriteOptions;
+
+    // Names can only be invalid if they didn't come from parsing, so swap them for invalid ones of the same length
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\no Wheel.Front\ng Tyre_Left\nf 1 2 3\ng TyreXLeft\nf 1 2 3\nf 1 2 3\no Door__One\ng Panel\nf 1 2 3\ng Tyre_Left\nf 1 2 3\n";
+    let mut bytes = cache(&src.parse().unwrap());
+    for (old, new) in [("Wheel.Front", "Wheel/Front"), ("Tyre_Left", "Tyre Left"), ("TyreXLeft", "Tyre/Left"), ("Door__One", "Döor One"), ("Panel", "Pänl")] {
+        let old = old.as_bytes();
+        for i in 0..bytes.len() - old.len() {
+            if &bytes[i..i + old.len()] == old {
+                bytes[i..i + old.len()].copy_from_slice(new.as_bytes());
+            }
+        }
+    }
+    let obj = Obj::from_cache(&bytes[..]).unwrap();
+    assert!(obj.object("Wheel/Front").unwrap().group("Tyre Left").is_some());
+
+    // By default, the first invalid name in sorted order is refused
+    match obj.write(Vec::new()) {
+        Err(Error::InvalidName(name)) => assert_eq!(name, "Döor One"),
+        other => panic!("expected an invalid name, found {:?}", other),
+    }
+
+    let mut out = Vec::new();
+    let names = obj.write_with(&mut out, WriteOptions::new().sanitize_names(true)).unwrap();
+    fn sorted(map: &std::collections::HashMap<String, String>) -> Vec<(&str, &str)> {
+        let mut pairs = map.iter().map(|(a, b)| (a.as_str(), b.as_str())).collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+    assert_eq!(sorted(&names.objects), [("Döor One", "D_or_One"), ("Wheel/Front", "Wheel_Front")]);
+    assert_eq!(sorted(&names.groups), [("Pänl", "P_nl"), ("Tyre Left", "Tyre_Left"), ("Tyre/Left", "Tyre_Left_2")]);
+
+    // Every object and group can be found in the written OBJ through its new name
+    let written: Obj = String::from_utf8(out).unwrap().parse().unwrap();
+    assert_eq!(written.object_names().count(), obj.object_names().count());
+    for (object, group) in obj.group_names() {
+        let old = obj.object(object).unwrap().group(group).unwrap();
+        let new = written.object(names.object(object)).unwrap().group(names.group(group)).unwrap();
+        assert_eq!(new.polygons().len(), old.polygons().len());
+    }
+}
@@ -0,0 +1,114 @@
+use wavefront::{Obj, Plane, Side};
+
+fn load(src: &[u8]) -> Obj {
+    Obj::from_reader(src).unwrap()
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn cube() {
+    let cube = load(include_bytes!("cube.obj"));
+    assert!(close(cube.volume(), 1.0));
+
+    for (keep, bottom) in [(Side::Back, 0.0), (Side::Front, 1.0)] {
+        let half = cube.clipped(Plane::new([0.0, 0.0, 1.0], 0.5), keep, true);
+        assert!(close(half.volume(), 0.5), "{}", half.volume());
+        assert!(half.manifold_report().is_closed());
+        assert_eq!(half.polygons().len(), 6);
+        // The cut is welded: the four corners of the cut are shared by the sides and the cap
+        assert_eq!(half.positions().len(), 8);
+        assert!(half.positions().iter().all(|p| p[2] == 0.5 || p[2] == bottom));
+
+        let cap = half.object("").unwrap().group("cap").unwrap();
+        assert_eq!(cap.polygons().len(), 1);
+        let cap = cap.polygons().next().unwrap();
+        let normal = cap.normal().unwrap();
+        assert_eq!(normal, if keep == Side::Back { [0.0, 0.0, 1.0] } else { [0.0, 0.0, -1.0] });
+        assert!(cap.vertices().all(|v| v.normal() == Some(normal) && v.uv().is_none()));
+
+        // Without a cap, the cut is left open
+        let open = cube.clipped(Plane::new([0.0, 0.0, 1.0], 0.5), keep, false);
+        assert_eq!(open.polygons().len(), 5);
+        assert_eq!(open.boundary_loops().len(), 1);
+        assert!(open.object("").is_none());
+    }
+
+    // An oblique cut through the centre also halves the cube, and cuts a hexagon
+    let half = cube.clipped(Plane::through([0.5; 3], [1.0, 1.0, 1.0]), Side::Front, true);
+    assert!(close(half.volume(), 0.5), "{}", half.volume());
+    assert!(half.manifold_report().is_closed());
+    let cap = half.object("").unwrap().group("cap").unwrap().polygons().next().unwrap();
+    assert_eq!(cap.vertices().len(), 6);
+
+    // Cuts through a layer of vertices and along faces
+    let cut = cube.clipped(Plane::through([0.0; 3], [1.0, -1.0, 0.0]), Side::Front, true);
+    assert!(close(cut.volume(), 0.5), "{}", cut.volume());
+    assert!(cut.manifold_report().is_closed());
+    let bottom = cube.clipped(Plane::new([0.0, 0.0, 1.0], 0.0), Side::Front, true);
+    assert_eq!(bottom.polygons().len(), 6);
+    assert!(close(bottom.volume(), 1.0));
+}
+
+#[test]
+fn interpolation() {
+    let cube = load(include_bytes!("cube.obj"));
+    let half = cube.clipped(Plane::new([1.0, 0.0, 0.0], 0.25), Side::Back, false);
+
+    // Each face keeps its object and group, with texture coordinates and normals interpolated along the cut
+    assert!(half.object("cube").is_some());
+    let front = half.polygons().find(|poly| poly.vertices().all(|v| v.position()[2] == 0.0)).unwrap();
+    let corners = front.vertices().map(|v| (v.position(), v.uv().unwrap())).collect::<Vec<_>>();
+    assert_eq!(corners, [
+        ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0]),
+        ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+        ([0.25, 1.0, 0.0], [1.0, 0.25, 0.0]),
+        ([0.25, 0.0, 0.0], [0.0, 0.25, 0.0]),
+    ]);
+    assert!(front.vertices().all(|v| v.normal() == Some([0.0, 0.0, -1.0])));
+
+    // Normals are interpolated and renormalised
+    let obj = Obj::from_lines("v 0 0 0\nv 2 0 0\nv 0 1 0\nvn 1 0 0\nvn 0 1 0\nf 1//1 2//2 3//1\n".lines()).unwrap();
+    let half = obj.clipped(Plane::new([1.0, 0.0, 0.0], 1.0), Side::Back, false);
+    let cut = half.vertices().find(|v| v.position() == [1.0, 0.0, 0.0]).unwrap();
+    let n = cut.normal().unwrap();
+    assert!(close(n[0], 0.5f32.sqrt()) && close(n[1], 0.5f32.sqrt()));
+}
+
+#[test]
+fn torus() {
+    let torus = load(include_bytes!("torus.obj"));
+    let volume = torus.volume();
+    assert!(volume > 0.0);
+
+    // Slicing through the ring leaves an annulus-shaped cut, with loops around the inside and outside of the ring
+    for (plane, loops) in [(Plane::new([0.0, 1.0, 0.0], 0.0), 2), (Plane::new([0.0, 1.0, 0.0], 0.1), 2)] {
+        let [front, back] = [Side::Front, Side::Back].map(|side| torus.clipped(plane, side, true));
+        assert!(close(front.volume() + back.volume(), volume), "{} {}", front.volume(), back.volume());
+        for half in [&front, &back] {
+            assert!(half.manifold_report().is_closed());
+            assert_eq!(half.object("").unwrap().group("cap").unwrap().polygons().len(), loops);
+        }
+    }
+    let front = torus.clipped(Plane::new([0.0, 1.0, 0.0], 0.0), Side::Front, true);
+    assert!(close(front.volume(), volume / 2.0), "{} {}", front.volume(), volume);
+
+    // Slicing across the ring leaves half of it, with a cap at either end
+    let half = torus.clipped(Plane::new([1.0, 0.0, 0.0], 0.0), Side::Front, true);
+    assert!(half.manifold_report().is_closed());
+    assert_eq!(half.object("").unwrap().group("cap").unwrap().polygons().len(), 2);
+}
+
+#[test]
+fn trivial() {
+    let cube = load(include_bytes!("cube.obj"));
+    let all = cube.clipped(Plane::new([0.0, 0.0, 1.0], -1.0), Side::Front, true);
+    assert_eq!(all.polygons().len(), 6);
+    let positions = |obj: &Obj| obj.triangles().map(|tri| tri.map(|v| v.position())).collect::<Vec<_>>();
+    assert_eq!(positions(&all), positions(&cube));
+    let none = cube.clipped(Plane::new([0.0, 0.0, 1.0], -1.0), Side::Back, true);
+    assert_eq!(none.polygons().len(), 0);
+    assert!(none.positions().is_empty());
+}
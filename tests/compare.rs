@@ -0,0 +1,138 @@
+use wavefront::{assert_obj_approx_eq, CompareOptions, Mismatch, MismatchedAttribute, Obj, ParseOptions};
+
+const A: &str = "
+o left
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vn 0 0 1
+f 1//1 2//1 3//1 4//1
+o right
+v 2 0 0
+f 2//1 5//1 3//1
+";
+
+// The same geometry, with attributes, faces and the vertices of each triangle in a different order, the quad split
+// into the triangles it is made of, and different names
+const B: &str = "
+v 2 0 0
+v 1 1 0
+v 0 1 0
+v 1 0 0
+v 0 0 0
+vn 0 0 1
+o b
+f 2//1 4//1 1//1
+o a
+g quad
+f 3//1 5//1 2//1
+f 4//1 2//1 5//1
+";
+
+#[test]
+fn exact() {
+    let a: Obj = A.parse().unwrap();
+    assert!(a == A.parse().unwrap());
+    assert!(a.structurally_equal(&A.parse().unwrap(), &CompareOptions::new()));
+
+    let ship = Obj::from_file("tests/ship.obj").unwrap();
+    assert!(ship == ship.clone());
+    assert!(ship != a);
+
+    // A single moved position makes the OBJs unequal, unless it is within the epsilon
+    let moved: Obj = A.replace("v 1 1 0", "v 1 1.0001 0").parse().unwrap();
+    assert!(a != moved);
+    assert!(!a.structurally_equal(&moved, &CompareOptions::new()));
+    assert!(a.structurally_equal(&moved, &CompareOptions::new().epsilon(0.001)));
+    assert!(!a.structurally_equal(&moved, &CompareOptions::new().epsilon(0.00001)));
+}
+
+#[test]
+fn materials() {
+    let a: Obj = A.parse().unwrap();
+    let red: Obj = A.replace("o right", "o right\nusemtl red").parse().unwrap();
+    assert!(a != red);
+    assert!(!a.structurally_equal(&red, &CompareOptions::new()));
+    assert!(!a.structurally_equal(&red, &CompareOptions::new().ignore_names(true).ignore_order(true)));
+
+    let b: Obj = B.parse().unwrap();
+    // Only the triangle in object `b` (the one in object `right` of `A`) uses the material
+    let with_material = |material| B.replace("o b", &format!("o b\nusemtl {}", material)).replace("o a", "usemtl\no a");
+    let blue: Obj = with_material("blue").parse().unwrap();
+    let options = CompareOptions::new().ignore_names(true).ignore_order(true);
+    assert!(a.structurally_equal(&b, &options));
+    assert!(!a.structurally_equal(&blue, &options));
+    assert!(!red.structurally_equal(&blue, &options));
+    assert!(red.structurally_equal(&with_material("red").parse().unwrap(), &options));
+
+    // Materials don't affect approximate comparisons, which only look at attributes
+    assert!(a.approx_diff(&blue, 0.0).is_none());
+}
+
+#[test]
+fn ignore_names() {
+    let a: Obj = A.parse().unwrap();
+    let renamed: Obj = A.replace("o left", "o up").replace("o right", "o down").parse().unwrap();
+    assert!(a != renamed);
+    assert!(a.structurally_equal(&renamed, &CompareOptions::new().ignore_names(true)));
+
+    // Polygons must still be divided into objects in the same way
+    let merged: Obj = A.replace("o right", "").parse().unwrap();
+    assert!(!a.structurally_equal(&merged, &CompareOptions::new().ignore_names(true)));
+}
+
+#[test]
+fn ignore_order() {
+    let (a, b): (Obj, Obj) = (A.parse().unwrap(), B.parse().unwrap());
+    let relaxed = CompareOptions::new().ignore_names(true).ignore_order(true);
+    assert!(a != b);
+    assert!(!a.structurally_equal(&b, &CompareOptions::new()));
+    assert!(!a.structurally_equal(&b, &CompareOptions::new().ignore_names(true)));
+    assert!(a.structurally_equal(&b, &relaxed));
+    assert!(b.structurally_equal(&a, &relaxed));
+    // The objects have different names, and in B the quad is also in a group
+    assert!(!a.structurally_equal(&b, &CompareOptions::new().ignore_order(true)));
+
+    // Reversing a triangle's winding order changes its geometry
+    let flipped: Obj = B.replace("f 2//1 4//1 1//1", "f 4//1 2//1 1//1").parse().unwrap();
+    assert!(!a.structurally_equal(&flipped, &relaxed));
+    // As does dropping its normals
+    let flat: Obj = B.replace("f 2//1 4//1 1//1", "f 2 4 1").parse().unwrap();
+    assert!(!a.structurally_equal(&flat, &relaxed));
+}
+
+#[test]
+fn approx_diff() {
+    let (a, b): (Obj, Obj) = (A.parse().unwrap(), B.parse().unwrap());
+    assert_eq!(a.approx_diff(&b, 0.0), None);
+    assert_obj_approx_eq!(a, b, 1e-5);
+
+    let missing: Obj = B.replace("f 2//1 4//1 1//1\n", "").parse().unwrap();
+    assert_eq!(a.approx_diff(&missing, 1.0), Some(Mismatch::TriangleCount { left: 3, right: 2 }));
+
+    let options = ParseOptions::new().track_lines(true);
+    let tilted = Obj::from_str_with(&B.replace("vn 0 0 1", "vn 0 0.5 1"), options.clone()).unwrap();
+    let a = Obj::from_str_with(A, options).unwrap();
+    assert!(a.approx_diff(&tilted, 0.6).is_none());
+    match a.approx_diff(&tilted, 0.1) {
+        Some(Mismatch::Triangle { left, right, vertex, attribute }) => {
+            assert_eq!(attribute, MismatchedAttribute::Normal);
+            assert_eq!((vertex, left.normals[vertex], right.normals[vertex]), (0, Some([0.0, 0.0, 1.0]), Some([0.0, 0.5, 1.0])));
+            // The quad's first triangle is the smallest
+            assert_eq!((left.index, left.source_line), (0, Some(8)));
+            assert_eq!((right.index, right.source_line), (2, Some(13)));
+            assert_eq!(left.positions, [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]);
+        },
+        mismatch => panic!("Expected a normal to differ, found {:?}", mismatch),
+    }
+}
+
+#[test]
+#[should_panic(expected = "OBJs are not approximately equal: texture coordinate of vertex 0 differs between triangle 0 \
+    and triangle 0: none and [0.5, 0, 0]")]
+fn assert_approx_eq() {
+    let a: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.5\nf 1 2 3\n".parse().unwrap();
+    let b: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.5\nf 1/1 2/1 3/1\n".parse().unwrap();
+    assert_obj_approx_eq!(a, b, 0.1);
+}
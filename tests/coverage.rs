@@ -0,0 +1,36 @@
+use wavefront::{Coverage, Obj};
+
+#[test]
+fn coverage() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvn 0 0 1
+o textured
+g with_uvs
+f 1/1/1 2/2/1 3/1/1
+g without_uvs
+f 1//1 2//1 3//1
+o mixed
+f 1/1 2/2 3
+";
+    let obj: Obj = src.parse().unwrap();
+    assert_eq!(obj.uv_coverage(), Coverage::Partial);
+    assert_eq!(obj.normal_coverage(), Coverage::Partial);
+
+    let textured = obj.object("textured").unwrap();
+    assert_eq!(textured.uv_coverage(), Coverage::Partial);
+    assert_eq!(textured.normal_coverage(), Coverage::Full);
+    assert_eq!(textured.group("with_uvs").unwrap().uv_coverage(), Coverage::Full);
+    assert_eq!(textured.group("without_uvs").unwrap().uv_coverage(), Coverage::None);
+
+    let mixed = obj.object("mixed").unwrap();
+    assert_eq!(mixed.uv_coverage(), Coverage::Partial);
+    assert_eq!(mixed.normal_coverage(), Coverage::None);
+
+    // Attributes that no face refers to don't count
+    let unused: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\nf 1 2 3\n".parse().unwrap();
+    assert_eq!(unused.uv_coverage(), Coverage::None);
+    assert_eq!(unused.normal_coverage(), Coverage::None);
+
+    let empty: Obj = "".parse().unwrap();
+    assert_eq!(empty.uv_coverage(), Coverage::None);
+    assert_eq!(Obj::from_file("tests/ship.obj").unwrap().normal_coverage(), Coverage::Full);
+}
@@ -0,0 +1,65 @@
+use wavefront::{DiffBuffer, DiffOptions, Obj, ObjChange};
+
+#[test]
+fn edited() {
+    let src = std::str::from_utf8(include_bytes!("ship.obj")).unwrap();
+    let original: Obj = src.parse().unwrap();
+    assert!(original.diff(&original.clone(), &DiffOptions::new()).is_empty());
+
+    // Nudge the first position, rename the first object, turn the third object into a group of the second, and add a
+    // triangle to the fourth object, which is otherwise empty
+    let first_vertex = src.lines().find(|line| line.starts_with("v ")).unwrap();
+    let mut coords = first_vertex.split_whitespace().skip(1).map(|x| x.parse::<f32>().unwrap());
+    let x = coords.next().unwrap() + 0.0001;
+    let nudged = format!("v {} {} {}", x, coords.next().unwrap(), coords.next().unwrap());
+    let edited = src
+        .replacen(first_vertex, &nudged, 1)
+        .replacen("o SketchUp_ID2.002\n", "o Hull\n", 1)
+        .replacen("o SketchUp.002_ID16.002\n", "g extra\n", 1)
+        + "v 0 0 0\nf -1 -2 -3\n";
+    let edited: Obj = edited.parse().unwrap();
+
+    let diff = original.diff(&edited, &DiffOptions::new());
+    assert_eq!(diff.changes, vec![
+        ObjChange::ObjectRemoved("SketchUp.002_ID16.002".into()),
+        ObjChange::ObjectRemoved("SketchUp_ID2.002".into()),
+        ObjChange::ObjectAdded("Hull".into()),
+        ObjChange::ObjectAdded("SketchUp.003_ID22.001".into()),
+        ObjChange::GroupAdded { object: "SketchUp.001_ID10.002".into(), group: "extra".into() },
+        ObjChange::BufferLength {
+            buffer: DiffBuffer::Positions,
+            before: original.positions().len(),
+            after: original.positions().len() + 1,
+        },
+        ObjChange::AttributesMoved { buffer: DiffBuffer::Positions, count: 1 },
+        ObjChange::BufferLength {
+            buffer: DiffBuffer::Vertices,
+            before: original.vertices().len(),
+            after: original.vertices().len() + 3,
+        },
+        ObjChange::BufferLength { buffer: DiffBuffer::Polygons, before: 165, after: 166 },
+        ObjChange::TriangleCount { object: "SketchUp.001_ID10.002".into(), before: 16, after: 32 },
+    ]);
+
+    // Small movements can be ignored
+    let diff = original.diff(&edited, &DiffOptions::new().epsilon(0.001));
+    assert!(!diff.changes.iter().any(|change| matches!(change, ObjChange::AttributesMoved { .. })));
+
+    let printed = diff.to_string();
+    assert!(printed.starts_with("removed object 'SketchUp.002_ID16.002'\n"));
+    assert!(printed.contains("\nadded group 'extra' to object 'SketchUp.001_ID10.002'\n"));
+    assert!(printed.contains("\nnumber of polygons changed from 165 to 166\n"));
+}
+
+#[test]
+fn materials() {
+    let src = std::str::from_utf8(include_bytes!("materials.obj")).unwrap();
+    let original: Obj = src.parse().unwrap();
+
+    // Make the first red polygon green, and both blue polygons red
+    let edited = src.replacen("usemtl red", "usemtl green", 1).replacen("usemtl blue", "usemtl red", 1);
+    let edited: Obj = edited.parse().unwrap();
+    let diff = original.diff(&edited, &DiffOptions::new());
+    assert_eq!(diff.changes, vec![ObjChange::MaterialsChanged { count: 3 }]);
+    assert_eq!(diff.to_string(), "materials of 3 polygons changed\n");
+}
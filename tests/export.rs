@@ -0,0 +1,277 @@
+use wavefront::{Obj, IndexedMesh, VertexKey, WireframeEdges};
+
+#[test]
+fn indexed_mesh() {
+    let obj = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+
+    let mesh = obj.to_indexed_mesh();
+    assert_eq!(mesh.vertices.len(), 24);
+    assert_eq!(mesh.indices.len(), 36);
+    assert_eq!(&mesh.indices[..6], &[0, 1, 2, 0, 2, 3]);
+    assert!(mesh.indices.iter().all(|i| (*i as usize) < mesh.vertices.len()));
+
+    // Each corner of the resolved triangles must be preserved
+    for (i, [a, b, c]) in obj.triangles().enumerate() {
+        for (j, v) in [a, b, c].iter().enumerate() {
+            assert_eq!(mesh.vertices[mesh.indices[i * 3 + j] as usize], (*v).into());
+        }
+    }
+
+    let mesh = IndexedMesh::from_triangles(obj.triangles(), VertexKey::Position);
+    assert_eq!(mesh.vertices.len(), 8);
+    assert_eq!(mesh.indices.len(), 36);
+
+    // Deterministic
+    assert_eq!(obj.to_indexed_mesh(), obj.object("cube").unwrap().to_indexed_mesh());
+}
+
+#[test]
+fn indexed_mesh_groups() {
+    // Polygons in several groups (or in a group named twice) are only exported once, in file order
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\ng a b\nf 1 2 3\ng b a a\nf 2 4 3\ng c\nf 1 4 2\n";
+    let obj: Obj = src.parse().unwrap();
+    let object = obj.object("").unwrap();
+    let mesh = object.to_indexed_mesh();
+    assert_eq!(mesh.indices, [0, 1, 2, 1, 3, 2, 0, 3, 1]);
+    assert_eq!(mesh, obj.to_indexed_mesh());
+    assert_eq!(object.group("a").unwrap().to_indexed_mesh().indices, [0, 1, 2, 1, 3, 2]);
+}
+
+#[test]
+fn indices_u16() {
+    let obj = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    let (vertices, indices) = obj.to_indexed_mesh_u16().unwrap();
+    assert_eq!(vertices.len(), 24);
+    assert_eq!(indices.iter().map(|i| *i as u32).collect::<Vec<_>>(), obj.to_indexed_mesh().indices);
+
+    // A strip of triangles with one more unique vertex than 16-bit indices can address
+    let count = 65537;
+    let lines = (0..count)
+        .map(|i| format!("v {} {} 0", i / 2, i % 2))
+        .chain((3..=count).map(|i| format!("f {} {} {}", i - 2, i - 1, i)));
+    let obj = Obj::from_lines(lines).unwrap();
+    let err = obj.to_indexed_mesh_u16().unwrap_err();
+    assert_eq!(err.vertices, 65537);
+    assert_eq!(err.limit, 65536);
+}
+
+#[test]
+fn interleaved() {
+    use wavefront::{Attribute, InterleavedLayout};
+
+    let obj = Obj::from_lines([
+        "v 0 0 0", "v 1 0 0", "v 1 1 0", "v 0 1 0",
+        "vt 0.5 0.25",
+        "vn 0 0 1",
+        // A textured triangle followed by an untextured quad
+        "f 1/1/1 2/1/1 3/1/1",
+        "f 1//1 2//1 3//1 4//1",
+    ].iter()).unwrap();
+
+    let layout = [Attribute::Position, Attribute::Normal, Attribute::Uv2];
+    let meta = InterleavedLayout::new(&layout);
+    assert_eq!(meta.stride, 8);
+    assert_eq!(meta.offsets, [0, 3, 6]);
+    assert_eq!(meta.stride_bytes(), 32);
+    assert_eq!(meta.offsets_bytes().collect::<Vec<_>>(), [0, 12, 24]);
+
+    let buf = obj.to_interleaved(&layout);
+    assert_eq!(buf.len(), obj.triangles().count() * 3 * meta.stride);
+    assert_eq!(&buf[..8], &[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.5, 0.25]);
+    // Untextured corners fall back to zero
+    assert_eq!(&buf[24..32], &[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn flat_buffers() {
+    use wavefront::FlatDefaults;
+
+    let obj = Obj::from_lines([
+        "v 0 0 0", "v 1 0 0", "v 1 1 0", "v 0 1 0",
+        "vt 0.5 0.25",
+        "vn 0 0 1",
+        "g textured",
+        "f 1/1/1 2/1/1 3/1/1",
+        "g plain",
+        "f 1 2 3 4",
+    ].iter()).unwrap();
+
+    let defaults = FlatDefaults { normal: [0.0, 1.0, 0.0], uv: [-1.0, -1.0] };
+    let buffers = obj.to_flat_buffers(defaults);
+    assert_eq!(buffers.positions.len(), 9);
+    assert_eq!(buffers.normals.len(), 9);
+    assert_eq!(buffers.uvs.len(), 9);
+    assert_eq!(buffers.normals[0], [0.0, 0.0, 1.0]);
+    assert_eq!(buffers.uvs[0], [0.5, 0.25]);
+    assert_eq!(buffers.normals[3], [0.0, 1.0, 0.0]);
+    assert_eq!(buffers.uvs[3], [-1.0, -1.0]);
+    assert_eq!(buffers.positions[8], [0.0, 1.0, 0.0]);
+
+    let group = obj.object("").unwrap().group("plain").unwrap().to_flat_buffers(FlatDefaults::default());
+    assert_eq!(group.positions.len(), 6);
+    assert!(group.normals.iter().all(|n| *n == [0.0; 3]));
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn packed_vertices() {
+    use core::mem::{offset_of, size_of};
+    use wavefront::{PackedPosition, PackedVertex};
+
+    assert_eq!(size_of::<PackedVertex>(), 32);
+    assert_eq!(offset_of!(PackedVertex, position), 0);
+    assert_eq!(offset_of!(PackedVertex, normal), 12);
+    assert_eq!(offset_of!(PackedVertex, uv), 24);
+    assert_eq!(size_of::<PackedPosition>(), 12);
+
+    let obj = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    let mesh = obj.to_indexed_mesh();
+    let packed = mesh.packed();
+    assert_eq!(packed.len(), 24);
+    assert_eq!(packed[0], PackedVertex { position: [0.0; 3], normal: [0.0, 0.0, -1.0], uv: [0.0, 0.0] });
+
+    let bytes: &[u8] = bytemuck::cast_slice(&packed);
+    assert_eq!(bytes.len(), 24 * 32);
+    assert_eq!(bytemuck::cast_slice::<_, f32>(&obj.to_packed_vertices()), obj.to_interleaved(&[
+        wavefront::Attribute::Position,
+        wavefront::Attribute::Normal,
+        wavefront::Attribute::Uv2,
+    ]).as_slice());
+    assert_eq!(mesh.packed_positions().len(), 24);
+}
+
+#[test]
+fn wireframe() {
+    let obj = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+
+    let edges = obj.wireframe_indices(WireframeEdges::Polygons);
+    assert_eq!(edges.len(), 12);
+    assert_eq!(&edges[..3], &[[0, 1], [0, 2], [0, 4]]);
+    assert!(edges.iter().all(|[a, b]| a < b && (*b as usize) < obj.positions().len()));
+
+    // Each quad gains a diagonal
+    assert_eq!(obj.wireframe_indices(WireframeEdges::Triangles).len(), 18);
+
+    let lines = obj.wireframe(WireframeEdges::Polygons);
+    assert_eq!(lines.len(), 12);
+    assert_eq!(lines[0], [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]]);
+    for [a, b] in lines {
+        let len: f32 = (0..3).map(|i| (a[i] - b[i]).powi(2)).sum();
+        assert_eq!(len, 1.0);
+    }
+
+    // Collapsed edges are skipped
+    let degenerate: Obj = "v 0 0 0\nv 1 0 0\nf 1 1 2\n".parse().unwrap();
+    assert_eq!(degenerate.wireframe_indices(WireframeEdges::Triangles), [[0, 1]]);
+}
+
+#[test]
+fn optimize_vertex_cache() {
+    // A grid of quads, written in a scattered order
+    const N: usize = 24;
+    let mut src = String::new();
+    for y in 0..=N {
+        for x in 0..=N {
+            src += &format!("v {} {} 0\n", x, y);
+        }
+    }
+    let (mut index, quads) = (0, N * N);
+    for _ in 0..quads {
+        // The step is coprime with the number of quads, so this visits each quad once
+        index = (index + 7 * N + 7) % quads;
+        let (x, y) = (index % N, index / N);
+        let corner = |dx, dy| (y + dy) * (N + 1) + x + dx + 1;
+        src += &format!("f {} {} {} {}\n", corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 1));
+    }
+    let obj: Obj = src.parse().unwrap();
+    let original = obj.to_indexed_mesh();
+
+    let triangles = |mesh: &IndexedMesh| {
+        let mut tris = mesh.indices
+            .chunks(3)
+            .map(|tri| tri.iter().map(|i| mesh.vertices[*i as usize].position.map(f32::to_bits)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        tris.sort();
+        tris
+    };
+
+    let mut mesh = original.clone();
+    mesh.optimize_vertex_cache();
+    assert_eq!(mesh.vertices, original.vertices);
+    let mut before = original.indices.chunks(3).collect::<Vec<_>>();
+    let mut after = mesh.indices.chunks(3).collect::<Vec<_>>();
+    before.sort();
+    after.sort();
+    assert_eq!(before, after);
+    for cache_size in [16, 32] {
+        assert!(
+            mesh.acmr(cache_size) < original.acmr(cache_size) * 0.75,
+            "{} >= {}", mesh.acmr(cache_size), original.acmr(cache_size),
+        );
+    }
+
+    // Vertices are reordered by first use, which doesn't affect the cache
+    let acmr = mesh.acmr(16);
+    mesh.optimize_vertex_fetch();
+    assert_eq!(mesh.acmr(16), acmr);
+    assert_eq!(triangles(&mesh), triangles(&original));
+    let mut next = 0;
+    for i in &mesh.indices {
+        assert!(*i <= next);
+        next = next.max(*i + 1);
+    }
+    assert_eq!(next as usize, mesh.vertices.len());
+}
+
+#[test]
+fn acmr() {
+    let mut mesh = IndexedMesh::<f32>::default();
+    assert_eq!(mesh.acmr(16), 0.0);
+    mesh.vertices.resize(5, Default::default());
+    mesh.indices = vec![0, 1, 2, 2, 1, 3, 3, 4, 0];
+    assert_eq!(mesh.acmr(16), 5.0 / 3.0);
+    // With a cache of 2, vertex 0 has been evicted by the time it is used again
+    assert_eq!(mesh.acmr(2), 2.0);
+}
+
+#[test]
+fn split_by_vertex_limit() {
+    // A strip of quads, each sharing an edge with the last
+    let strip = |quads: usize| {
+        let mut src = String::new();
+        for x in 0..=quads {
+            src += &format!("v {} 0 0\nv {} 1 0\n", x, x);
+        }
+        for x in 0..quads {
+            src += &format!("f {} {} {} {}\n", x * 2 + 1, x * 2 + 3, x * 2 + 4, x * 2 + 2);
+        }
+        src.parse::<Obj>().unwrap().to_indexed_mesh()
+    };
+    let triangles = |mesh: &IndexedMesh| {
+        mesh.indices
+            .chunks(3)
+            .map(|tri| tri.iter().map(|i| mesh.vertices[*i as usize].position.map(f32::to_bits)).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+
+    // Just over the limit of 16-bit indices
+    let mesh = strip(32768);
+    assert_eq!(mesh.vertices.len(), 65538);
+    assert!(mesh.indices_u16().is_err());
+    let chunks = mesh.split_by_vertex_limit(65536);
+    assert_eq!(chunks.len(), 2);
+    assert!(chunks.iter().all(|chunk| chunk.indices_u16().is_ok()));
+    // The shared edge is duplicated
+    assert_eq!(chunks.iter().map(|chunk| chunk.vertices.len()).sum::<usize>(), 65540);
+    assert_eq!(chunks.iter().flat_map(triangles).collect::<Vec<_>>(), triangles(&mesh));
+
+    let mesh = strip(100);
+    let chunks = mesh.split_by_vertex_limit(10);
+    assert_eq!(chunks.len(), 25);
+    assert!(chunks.iter().all(|chunk| chunk.vertices.len() <= 10));
+    assert_eq!(chunks.iter().map(|chunk| chunk.indices.len()).sum::<usize>(), mesh.indices.len());
+    assert_eq!(chunks.iter().flat_map(triangles).collect::<Vec<_>>(), triangles(&mesh));
+
+    assert_eq!(mesh.split_by_vertex_limit(1000), vec![mesh.clone()]);
+    assert!(IndexedMesh::<f32>::default().split_by_vertex_limit(3).is_empty());
+}
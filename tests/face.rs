@@ -0,0 +1,90 @@
+use wavefront::{Face, Obj, ObjWriter};
+
+fn count<'a>(faces: impl Iterator<Item = Face<'a>>) -> [usize; 3] {
+    let mut counts = [0; 3];
+    for face in faces {
+        counts[match face {
+            Face::Triangle(_) => 0,
+            Face::Quad(_) => 1,
+            Face::Ngon(_) => 2,
+        }] += 1;
+    }
+    counts
+}
+
+#[test]
+fn faces() {
+    let obj = Obj::from_file("tests/mixed.obj").unwrap();
+    assert_eq!(obj.faces().len(), 10);
+    assert_eq!(count(obj.faces()), [3, 4, 3]);
+    assert_eq!(count(obj.object("Panels").unwrap().faces()), [3, 3, 0]);
+    assert_eq!(count(obj.object("Shapes").unwrap().faces()), [0, 1, 3]);
+    assert_eq!(count(obj.object("Shapes").unwrap().group("ngons").unwrap().faces()), [0, 0, 2]);
+    assert_eq!(count(obj.group("quads").unwrap().faces()), [0, 4, 0]);
+
+    // Vertices are given in order, and other polygons are left as they are
+    for (poly, face) in obj.polygons().zip(obj.faces()) {
+        let positions = match face {
+            Face::Triangle(vertices) => vertices.iter().map(|v| v.position_index()).collect::<Vec<_>>(),
+            Face::Quad(vertices) => vertices.iter().map(|v| v.position_index()).collect(),
+            Face::Ngon(ngon) => {
+                assert_eq!(ngon.index(), poly.index());
+                assert!(!matches!(ngon.vertices().len(), 3 | 4));
+                ngon.vertices().map(|v| v.position_index()).collect()
+            },
+        };
+        assert_eq!(positions, poly.vertices().map(|v| v.position_index()).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn write_quads() {
+    // Written OBJs keep their quads
+    let obj = Obj::from_file("tests/mixed.obj").unwrap();
+    let written: Obj = obj.to_string().parse().unwrap();
+    assert_eq!(count(written.faces()), [3, 4, 3]);
+
+    let mut writer = ObjWriter::new(Vec::new());
+    for position in obj.positions() {
+        writer.position(*position).unwrap();
+    }
+    for face in obj.faces() {
+        if let Face::Quad(vertices) = face {
+            writer.face(&vertices.map(|v| (v.position_index(), None, None))).unwrap();
+        }
+    }
+    let quads: Obj = String::from_utf8(writer.finish().unwrap()).unwrap().parse().unwrap();
+    assert_eq!(count(quads.faces()), [0, 4, 0]);
+}
+
+#[test]
+fn quads() {
+    use wavefront::FaceCounts;
+
+    // A 3x3 grid of quads
+    let plane = Obj::from_file("tests/plane.obj").unwrap();
+    assert_eq!(plane.quads().count(), 3 * 3);
+    assert_eq!(plane.face_counts(), FaceCounts { triangles: 0, quads: 9, ngons: 0 });
+    assert!(plane.is_quad_dominant());
+    for (quad, poly) in plane.quads().zip(plane.polygons()) {
+        assert!(quad.iter().map(|v| v.position_index()).eq(poly.vertices().map(|v| v.position_index())));
+    }
+
+    let obj = Obj::from_file("tests/mixed.obj").unwrap();
+    let counts = obj.face_counts();
+    assert_eq!(counts, FaceCounts { triangles: 3, quads: 4, ngons: 3 });
+    assert_eq!((counts.total(), counts.non_quads()), (10, 6));
+    assert!(!obj.is_quad_dominant());
+    assert_eq!(obj.quads().count(), 4);
+    let panels = obj.object("Panels").unwrap();
+    assert_eq!(panels.quads().count(), 3);
+    assert_eq!(panels.face_counts(), FaceCounts { triangles: 3, quads: 3, ngons: 0 });
+    assert!(!panels.face_counts().is_quad_dominant());
+    assert_eq!(obj.group("quads").unwrap().quads().count(), 4);
+    assert!(obj.group("quads").unwrap().face_counts().is_quad_dominant());
+    assert_eq!(obj.group("tris").unwrap().quads().count(), 0);
+
+    // The iterator and the counts always agree
+    let torus = Obj::from_file("tests/torus.obj").unwrap();
+    assert_eq!(torus.quads().count(), torus.face_counts().quads);
+}
@@ -0,0 +1,73 @@
+use std::{fs, path::PathBuf};
+use wavefront::{Error, FileIndexing, Obj};
+
+fn summary(obj: &Obj) -> impl PartialEq + std::fmt::Debug {
+    let mut groups = obj
+        .objects()
+        .flat_map(|(object, o)| o
+            .groups()
+            .map(move |(group, g)| (object.to_string(), group.clone(), g.polygons().map(|p| p.index()).collect::<Vec<_>>())))
+        .collect::<Vec<_>>();
+    groups.sort();
+    let polygons = obj
+        .polygons()
+        .map(|p| p.vertices().map(|v| (v.position(), v.uv(), v.normal())).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    (obj.positions().to_vec(), obj.uvs().to_vec(), obj.normals().to_vec(), polygons, groups)
+}
+
+fn write(name: &str, src: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("wavefront-files-{}-{}.obj", std::process::id(), name));
+    fs::write(&path, src).unwrap();
+    path
+}
+
+const FIRST: &str = "o a\nv 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2//1 3//1\n";
+// Continuing from `FIRST`, `-3` refers to its last position
+const SECOND: &str = "o b\nv 1 1 0\nv 2 1 0\nf -3 -2 -1\nf 4 5 1\n";
+
+#[test]
+fn continuous() {
+    let paths = [write("continuous-0", FIRST), write("continuous-1", SECOND)];
+    let obj = Obj::from_files(paths.iter().cloned(), FileIndexing::Continuous).unwrap();
+
+    assert_eq!(summary(&obj), summary(&[FIRST, SECOND].concat().parse().unwrap()));
+    let poly = obj.polygon(1).unwrap();
+    assert_eq!(poly.vertices().map(|v| v.position()).collect::<Vec<_>>(), [[0.0, 1.0, 0.0], [1.0, 1.0, 0.0], [2.0, 1.0, 0.0]]);
+}
+
+#[test]
+fn independent() {
+    let second = "o b\nv 1 1 0\nv 2 1 0\nv 1 2 0\nvn 0 0 -1\nf 1//1 -2//1 3//1\n";
+    let paths = [write("independent-0", FIRST), write("independent-1", second)];
+    let obj = Obj::from_files(paths.iter().cloned(), FileIndexing::Independent).unwrap();
+
+    let mut expected: Obj = FIRST.parse().unwrap();
+    expected.merge(&second.parse().unwrap());
+    assert_eq!(summary(&obj), summary(&expected));
+    assert_eq!(obj.polygon(1).unwrap().vertex(2).unwrap().position(), [1.0, 2.0, 0.0]);
+    assert_eq!(obj.polygon(1).unwrap().vertex(0).unwrap().normal(), Some([0.0, 0.0, -1.0]));
+
+    // Indices don't reach into earlier files
+    let paths = [write("independent-2", FIRST), write("independent-3", SECOND)];
+    assert!(matches!(
+        Obj::from_files(paths.iter().cloned(), FileIndexing::Independent),
+        Err(Error::InFile(path, e)) if path == paths[1] && matches!(*e, Error::InvalidIndex(-3)),
+    ));
+}
+
+#[test]
+fn errors() {
+    let paths = [write("errors-0", FIRST), write("errors-1", "v 0 0 0\nf 1 x\n")];
+    for indexing in [FileIndexing::Continuous, FileIndexing::Independent] {
+        let err = Obj::from_files(paths.iter().cloned(), indexing).unwrap_err();
+        assert!(matches!(&err, Error::InFile(path, e) if *path == paths[1] && matches!(**e, Error::ExpectedIdx(2))));
+        assert!(err.to_string().starts_with(&paths[1].display().to_string()));
+    }
+
+    let missing = std::env::temp_dir().join("wavefront-files-missing.obj");
+    assert!(matches!(
+        Obj::from_files(vec![paths[0].clone(), missing.clone()], FileIndexing::Continuous),
+        Err(Error::InFile(path, e)) if path == missing && matches!(*e, Error::Io(_)),
+    ));
+}
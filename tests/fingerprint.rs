@@ -0,0 +1,70 @@
+use wavefront::{Obj, ObjF64, ParseOptions};
+
+fn ship() -> String {
+    String::from_utf8(include_bytes!("ship.obj").to_vec()).unwrap()
+}
+
+#[test]
+fn reformatted() {
+    let src = ship();
+    let obj: Obj = src.parse().unwrap();
+
+    // Extra whitespace, different line endings, moved and added comments, and reformatted numbers
+    let mut reformatted = String::from("# Reformatted\n\n");
+    for line in src.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+        let line = line.split_whitespace().collect::<Vec<_>>().join("   ");
+        let line = line.replace("v   0   ", "v   0.0   ");
+        reformatted.push_str(&format!("\t{}  \r\n", line));
+        if line.starts_with("f ") {
+            reformatted.push_str("# a comment\r\n");
+        }
+    }
+    assert_ne!(src, reformatted);
+    let other: Obj = reformatted.parse().unwrap();
+    assert_eq!(obj.fingerprint(), other.fingerprint());
+
+    // Fingerprints don't depend on parse options that don't affect the contents, or on the precision of attributes
+    let tracked = Obj::from_str_with(&src, ParseOptions::new().track_lines(true).cache_triangles(true)).unwrap();
+    assert_eq!(obj.fingerprint(), tracked.fingerprint());
+    let f64s: ObjF64 = "v 0.5 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+    let f32s: Obj = "v 0.5 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+    assert_eq!(f64s.fingerprint(), f32s.fingerprint());
+
+    // Fingerprints are deterministic, regardless of the order in which objects and groups are stored
+    for _ in 0..4 {
+        assert_eq!(obj.fingerprint(), ship().parse::<Obj>().unwrap().fingerprint());
+    }
+}
+
+#[test]
+fn changed() {
+    let src = ship();
+    let fingerprint = src.parse::<Obj>().unwrap().fingerprint();
+    let first_vertex = src.lines().find(|line| line.starts_with("v ")).unwrap();
+
+    let changes = [
+        // A single moved vertex
+        src.replacen(first_vertex, "v 0 0 0.001", 1),
+        // A renamed object
+        src.replacen("o SketchUp_ID2.002", "o SketchUp_ID2.003", 1),
+        // A face with an extra vertex
+        src.replacen("\nf ", "\nf -1 ", 1),
+        // A face moved into a new group
+        src.replacen("\nf ", "\ng extra\nf ", 1),
+        // A face using a different material
+        src.replacen("usemtl material_0.002\n", "usemtl material_1\n", 1),
+    ];
+    for changed in changes {
+        assert_ne!(changed, src);
+        assert_ne!(changed.parse::<Obj>().unwrap().fingerprint(), fingerprint);
+    }
+
+    // Negative zero is equal to zero, so doesn't change the fingerprint
+    let zero: Obj = "v 0 0 0\n".parse().unwrap();
+    let negative: Obj = "v -0 0 0\n".parse().unwrap();
+    assert_eq!(zero, negative);
+    assert_eq!(zero.fingerprint(), negative.fingerprint());
+}
@@ -0,0 +1,462 @@
+use wavefront::{Obj, ObjF64, ParseOptions, Plane, QuantizeReport};
+
+#[test]
+fn planarity() {
+    let obj = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    for poly in obj.polygons() {
+        assert_eq!(poly.planarity_deviation(), 0.0);
+        assert!(poly.is_planar(0.0));
+    }
+
+    // A unit square with one corner lifted deviates from its best-fit plane equally at every vertex
+    let folded = Obj::from_lines("v 0 0 1\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\nf 1 2 3\nf 1 2\n".lines()).unwrap();
+    let quad = folded.polygon(0).unwrap();
+    assert!((quad.planarity_deviation() - 0.5 / 6.0f32.sqrt()).abs() < 1e-6);
+    assert!(quad.is_planar(0.25));
+    assert!(!quad.is_planar(0.2));
+
+    // Triangles and smaller polygons are trivially planar
+    assert_eq!(folded.polygon(1).unwrap().planarity_deviation(), 0.0);
+    assert_eq!(folded.polygon(2).unwrap().planarity_deviation(), 0.0);
+}
+
+#[test]
+fn face_normals() {
+    // Every face of the cube agrees with its stored normal
+    let obj = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    for poly in obj.polygons() {
+        let normal = poly.normal().unwrap();
+        for v in poly.vertices() {
+            assert_eq!(Some(normal), v.normal());
+        }
+    }
+
+    let src = "
+        v 0 0 0\nv 2 0 0\nv 2 2 0\nv 1 1 0\nv 0 2 0\nv 4 0 0
+        f 1 2 3 4 5
+        f 1 5 4 3 2
+        f 1 2 6
+        f 1 1 2
+    ";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+
+    // A concave pentagon, with the vertices listed counter-clockwise and then clockwise
+    assert_eq!(obj.polygon(0).unwrap().raw_normal(), [0.0, 0.0, 6.0]);
+    assert_eq!(obj.polygon(0).unwrap().normal(), Some([0.0, 0.0, 1.0]));
+    assert_eq!(obj.polygon(1).unwrap().normal(), Some([0.0, 0.0, -1.0]));
+
+    // Faces without area have no normal
+    assert_eq!(obj.polygon(2).unwrap().raw_normal(), [0.0; 3]);
+    assert_eq!(obj.polygon(2).unwrap().normal(), None);
+    assert_eq!(obj.polygon(3).unwrap().normal(), None);
+}
+
+#[test]
+fn area() {
+    let obj = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    assert!(obj.polygons().all(|poly| poly.area() == 1.0 && poly.area_f64() == 1.0));
+    assert_eq!(obj.surface_area(), 6.0);
+
+    let src = "
+        v 0 0 0\nv 2 0 0\nv 2 2 0\nv 1 1 0\nv 0 2 0\nv 4 0 0
+        f 1 2 3 4 5
+        f 1 2 6
+        f 1 1 2
+        f
+    ";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    assert_eq!(obj.polygon(0).unwrap().area(), 3.0);
+    for poly in obj.polygons().skip(1) {
+        assert_eq!(poly.area(), 0.0);
+        assert_eq!(poly.area_f64(), 0.0);
+    }
+    assert_eq!(obj.surface_area(), 3.0);
+
+    // The torus is made of planar quads, so triangulating it shouldn't change its area
+    let torus = Obj::from_reader(include_bytes!("torus.obj") as &[u8]).unwrap();
+    let triangulated = torus
+        .triangles()
+        .map(|tri| {
+            let [a, b, c] = tri.map(|v| v.position());
+            let [x1, y1, z1] = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let [x2, y2, z2] = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let n = [y1 * z2 - z1 * y2, z1 * x2 - x1 * z2, x1 * y2 - y1 * x2];
+            (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() / 2.0
+        })
+        .sum::<f32>();
+    assert!((torus.surface_area() - triangulated).abs() < 1e-3 * triangulated);
+}
+
+#[test]
+fn triangle_areas() {
+    // A unit square split into two triangles, a large triangle, and a sliver
+    let src = "
+        v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 4 0 0\nv 0 4 0\nv 2 0.0001 0
+        f 1 2 3 4
+        f 1 5 6
+        f 1 5 7
+    ";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let areas = obj.triangle_areas().collect::<Vec<_>>();
+    assert_eq!(areas, vec![(0.5, 0), (0.5, 0), (8.0, 1), (0.5 * 4.0 * 0.0001, 2)]);
+    assert_eq!(areas.len(), obj.triangles().count());
+    for poly in obj.polygons() {
+        let sum = areas.iter().filter(|(_, index)| *index == poly.index()).map(|(area, _)| area).sum::<f32>();
+        assert_eq!(sum, poly.area());
+    }
+
+    let stats = obj.area_stats(0.01).unwrap();
+    assert_eq!(stats.count, 4);
+    assert_eq!(stats.min, 0.5 * 4.0 * 0.0001);
+    assert_eq!(stats.max, 8.0);
+    assert_eq!(stats.median, 0.5);
+    assert!((stats.mean - (9.0 + 0.0002) / 4.0).abs() < 1e-6);
+    assert_eq!(stats.below_epsilon, 1);
+    assert_eq!(obj.area_stats(0.0).unwrap().below_epsilon, 0);
+
+    assert!(Obj::from_lines("v 0 0 0\nf 1 1\n".lines()).unwrap().area_stats(1.0).is_none());
+}
+
+#[test]
+fn remove_degenerate_faces() {
+    let src = "
+        v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nv 2 0.000001 0
+        g healthy
+        f 1 2 4 3
+        g slivers
+        f 1 2 5
+        f 1 2 2
+        f 3 3 3
+        g mixed
+        f 1 1 2 2 4 3 1
+        f 1 2 1 2
+    ";
+    let mut obj = Obj::from_str_with(src, ParseOptions::new().track_lines(true).cache_triangles(true)).unwrap();
+    assert_eq!(obj.remove_degenerate_faces(1e-4), 4);
+
+    // The quad with repeated vertices is collapsed into a valid quad rather than being removed
+    let polygons = obj
+        .polygons_with_context()
+        .map(|(_, group, poly)| {
+            let positions = poly.vertices().map(|v| v.position_index()).collect::<Vec<_>>();
+            (group, positions, poly.source_line())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(polygons, vec![
+        ("healthy", vec![0, 1, 3, 2], Some(8)),
+        ("mixed", vec![0, 1, 3, 2], Some(14)),
+    ]);
+    assert!(obj.object("").unwrap().group("slivers").is_none());
+    assert_eq!(obj.triangles().count(), 4);
+    assert_eq!(obj.triangle_indices().unwrap().len(), 4);
+
+    // Nothing more is removed, and a smaller epsilon keeps the sliver
+    assert_eq!(obj.remove_degenerate_faces(1e-4), 0);
+    let mut obj = Obj::from_lines(src.lines()).unwrap();
+    assert_eq!(obj.remove_degenerate_faces(0.0), 3);
+}
+
+#[test]
+fn centroid() {
+    let src = "
+        v 0 0 0\nv 4 0 0\nv 4 1 0\nv 1 1 0\nv 1 4 0\nv 0 4 0
+        f 1 2 3 4 5 6
+        f 1 1 2
+        f
+    ";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+
+    // An L-shape made of a 4x1 bar and a 1x3 bar, whose vertices are unevenly distributed around its area
+    let l = obj.polygon(0).unwrap();
+    let centroid = l.centroid().unwrap();
+    let expected = [(4.0 * 2.0 + 3.0 * 0.5) / 7.0, (4.0 * 0.5 + 3.0 * 2.5) / 7.0, 0.0];
+    assert!((0..3).all(|i| (centroid[i] - expected[i]).abs() < 1e-6));
+    assert_eq!(l.vertex_average(), Some([10.0 / 6.0, 10.0 / 6.0, 0.0]));
+
+    // Degenerate polygons fall back to the vertex average
+    assert_eq!(obj.polygon(1).unwrap().centroid(), Some([4.0 / 3.0, 0.0, 0.0]));
+    assert_eq!(obj.polygon(2).unwrap().centroid(), None);
+    assert_eq!(obj.polygon(2).unwrap().vertex_average(), None);
+
+    let surface = obj.surface_centroid().unwrap();
+    assert!((0..3).all(|i| (surface[i] - expected[i]).abs() < 1e-6));
+
+    let cube = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    assert_eq!(cube.surface_centroid(), Some([0.5; 3]));
+    assert_eq!(Obj::from_lines("v 0 0 0".lines()).unwrap().surface_centroid(), None);
+}
+
+#[test]
+fn quantize() {
+    let src = "v 0.13 1.01 -2.5\nv 0.3 NaN inf\nvt 0.3 0.5\nvn 0 0.70710677 0.70710677\nf 1/1/1 2/1/1 1/1/1\n";
+    let mut obj: ObjF64 = src.parse().unwrap();
+
+    let report = obj.quantize_positions(0.125);
+    assert_eq!(report, QuantizeReport { changed: 3, non_finite: 2 });
+    assert_eq!(obj.positions()[0], [0.125, 1.0, -2.5]);
+    assert_eq!(obj.positions()[1][0], 0.25);
+    assert!(obj.positions()[1][1].is_nan() && obj.positions()[1][2] == f64::INFINITY);
+
+    // Quantizing again changes nothing
+    assert_eq!(obj.quantize_positions(0.125), QuantizeReport { changed: 0, non_finite: 2 });
+
+    assert_eq!(obj.quantize_uvs(0.25).changed, 1);
+    assert_eq!(obj.uvs()[0][..2], [0.25, 0.5]);
+    assert_eq!(obj.quantize_normals(0.5).changed, 2);
+    assert_eq!(obj.normals()[0], [0.0, 0.5, 0.5]);
+
+    // Near-duplicate positions become identical
+    let mut obj: Obj = "v 1.00001 2 3\nv 0.99999 2 3\nf 1 2 1\n".parse().unwrap();
+    obj.quantize_positions(0.0001);
+    assert_eq!(obj.positions()[0], obj.positions()[1]);
+}
+
+#[test]
+fn mirror() {
+    // A unit cube spanning x = 0..1, without its face on the plane x = 0
+    let src = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 1\nv 1 0 1\nv 1 1 1\nv 0 1 1\nvn 1 0 0
+o half
+g side
+f 2//1 3//1 7//1 6//1
+g rest
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 4 8 7 3
+";
+    let half: Obj = src.parse().unwrap();
+    let plane = Plane::new([2.0, 0.0, 0.0], 0.0);
+
+    let mut mirrored = half.clone();
+    mirrored.mirror(plane);
+    assert_eq!(mirrored.positions()[1], [-1.0, 0.0, 0.0]);
+    assert_eq!(mirrored.normals()[0], [-1.0, 0.0, 0.0]);
+    for (a, b) in half.polygons().zip(mirrored.polygons()) {
+        let [x, y, z] = a.normal().unwrap();
+        assert_eq!(b.normal().unwrap(), [-x, y, z]);
+        assert_eq!(a.vertex(0).unwrap().position_index(), b.vertex(0).unwrap().position_index());
+    }
+    assert!(mirrored.manifold_report().is_manifold());
+
+    // Welding the seam closes the mesh
+    let mut full = half.clone();
+    full.mirrored_append(plane, Some(1e-5), "_mirrored");
+    assert_eq!(full.positions().len(), 12);
+    assert_eq!(full.polygons().count(), 10);
+    let report = full.manifold_report();
+    assert!(report.is_closed() && report.is_manifold());
+    assert!(full.object("half_mirrored").unwrap().group("side_mirrored").is_some());
+    assert_eq!(full.surface_area(), 10.0);
+
+    // Without welding, the halves are only touching
+    let mut unwelded = half.clone();
+    unwelded.mirrored_append(plane, None, "");
+    assert_eq!(unwelded.positions().len(), 16);
+    assert!(!unwelded.manifold_report().is_closed());
+    assert_eq!(unwelded.object("half").unwrap().group("rest").unwrap().polygons().len(), 8);
+}
+
+#[test]
+fn map_attributes() {
+    let cube = Obj::from_file("tests/cube.obj").unwrap();
+    let close = |a: [f32; 3], b: [f32; 3]| (0..3).all(|i| (a[i] - b[i]).abs() < 1e-6);
+
+    let mut moved = cube.clone();
+    moved.translate([1.0, 2.0, 3.0]).flip_uv_v();
+    assert!(moved.positions().iter().zip(cube.positions()).all(|(a, b)| *a == [b[0] + 1.0, b[1] + 2.0, b[2] + 3.0]));
+    assert!(moved.uvs().iter().zip(cube.uvs()).all(|(a, b)| *a == [b[0], 1.0 - b[1], b[2]]));
+    assert_eq!(moved.normals(), cube.normals());
+    moved.map_uvs(|[u, v, w]| [u, 1.0 - v, w]);
+    assert_eq!(moved.uvs(), cube.uvs());
+
+    // Normals are renormalised unless asked otherwise
+    let mut doubled = cube.clone();
+    doubled.map_normals(|n| n.map(|x| x * 2.0));
+    assert_eq!(doubled.normals(), cube.normals());
+    doubled.map_normals_raw(|n| n.map(|x| x * 2.0));
+    assert!(doubled.normals().iter().all(|n| n.iter().map(|x| x.abs()).sum::<f32>() == 2.0));
+
+    // Non-uniform scaling keeps normals perpendicular to the surface
+    let mut stretched = cube.clone();
+    stretched.cache_triangles();
+    stretched.scale([2.0, 1.0, 0.5]);
+    assert!((stretched.volume() - 1.0).abs() < 1e-6);
+    for poly in stretched.polygons() {
+        assert!(poly.vertices().all(|v| close(v.normal().unwrap(), poly.normal().unwrap())));
+    }
+    let slope = Obj::from_lines(["v 0 0 0", "v 1 0 0", "v 0 1 1", "vn 0 -1 1", "f 1//1 2//1 3//1"].iter()).unwrap();
+    let mut squashed = slope.clone();
+    squashed.scale([1.0, 1.0, 0.5]);
+    let poly = squashed.polygons().next().unwrap();
+    assert!(close(poly.vertex(0).unwrap().normal().unwrap(), poly.normal().unwrap()));
+
+    // Negative scaling turns the model inside out, so its winding is reversed to compensate
+    let mut flipped = cube.clone();
+    flipped.scale([-1.0, 1.0, 1.0]);
+    assert!((flipped.volume() - 1.0).abs() < 1e-6);
+    assert!(flipped.manifold_report().is_manifold());
+    for poly in flipped.polygons() {
+        assert!(poly.vertices().all(|v| close(v.normal().unwrap(), poly.normal().unwrap())));
+    }
+}
+
+#[test]
+fn flat_shaded() {
+    // Start from shared, smoothed normals so that flat shading has something to undo
+    let src = include_str!("cube.obj")
+        .lines()
+        .filter(|l| !l.starts_with("vn") && !l.starts_with('f'))
+        .map(|l| format!("{}\n", l))
+        .collect::<String>() + "vn 0 0 1\nf 1//1 3//1 4//1 2//1\nf 5//1 6//1 8//1 7//1\nf 1//1 2//1 6//1 5//1\nf 3//1 7//1 8//1 4//1\nf 1//1 5//1 7//1 3//1\nf 2//1 4//1 8//1 6//1\nf 1 1 2\n";
+    let smooth: Obj = src.parse().unwrap();
+    assert_eq!(smooth.to_indexed_mesh().vertices.len(), 10);
+
+    let flat = smooth.flat_shaded();
+    assert_eq!(flat.normals().len(), 6);
+    assert_eq!(flat.positions(), smooth.positions());
+    assert_eq!(flat.to_indexed_mesh().vertices.len(), 26);
+    for poly in flat.polygons().take(6) {
+        assert!(poly.vertices().all(|v| v.normal() == poly.normal()));
+    }
+    assert_eq!(flat.polygon(0).unwrap().vertex(0).unwrap().normal(), Some([0.0, 0.0, -1.0]));
+    // The degenerate polygon is left without normals
+    assert!(flat.polygon(6).unwrap().vertices().all(|v| v.normal().is_none()));
+
+    let reparsed: Obj = flat.to_string().parse().unwrap();
+    assert_eq!(reparsed.normals().len(), 6);
+    assert_eq!(reparsed.to_indexed_mesh().vertices.len(), 26);
+
+    let cube = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap().flat_shaded();
+    assert_eq!(cube.normals().len(), 6);
+    assert_eq!(cube.to_indexed_mesh().vertices.len(), 24);
+}
+
+#[test]
+fn merge_normals() {
+    // A cube exported with one normal per face corner, some of them slightly off
+    let cube = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    let mut src = String::new();
+    for p in cube.positions() {
+        src += &format!("v {} {} {}\n", p[0], p[1], p[2]);
+    }
+    let mut corner = 0;
+    for poly in cube.polygons() {
+        let mut face = String::from("f");
+        for v in poly.vertices() {
+            let [x, y, z] = v.normal().unwrap();
+            corner += 1;
+            let wobble = if corner % 4 == 0 { 0.001 } else { 0.0 };
+            src += &format!("vn {} {} {}\n", x + wobble, y, z);
+            face += &format!(" {}//{}", v.position_index() + 1, corner);
+        }
+        src += &face;
+        src += "\n";
+    }
+    let original: Obj = src.parse().unwrap();
+    assert_eq!(original.normals().len(), 24);
+
+    // Exact duplicates only, so every vertex keeps exactly the same normal
+    let mut exact = original.clone();
+    assert_eq!(exact.merge_normals(0.0), 12);
+    assert_eq!(exact.normals().len(), 12);
+    let normals = |obj: &Obj| obj.vertices().map(|v| v.normal()).collect::<Vec<_>>();
+    assert_eq!(normals(&exact), normals(&original));
+
+    let mut merged = original.clone();
+    assert_eq!(merged.merge_normals(1.0), 18);
+    assert_eq!(merged.normals().len(), 6);
+    for (a, b) in normals(&merged).iter().zip(normals(&cube)) {
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert!((0..3).all(|i| (a[i] - b[i]).abs() <= 0.001), "{:?} != {:?}", a, b);
+    }
+
+    // Unreferenced normals are removed too, and opposite normals are never merged
+    let mut obj: Obj = "v 0 0 0\nvn 0 0 1\nvn 0 0 -1\nvn 1 0 0\nf 1//1 1//2 1//1\n".parse().unwrap();
+    assert_eq!(obj.merge_normals(90.0), 1);
+    assert_eq!(obj.normals(), &[[0.0, 0.0, 1.0], [0.0, 0.0, -1.0]]);
+}
+
+#[test]
+fn sort_faces_spatially() {
+    // A row of triangles written in a scattered order, with two identical triangles and a group split in two
+    let src = "v 0 0 0\nv 1 0 0\nv 2 0 0\nv 3 0 0\nv 0 1 0\nv 1 1 0\nv 2 1 0\nv 3 1 0
+g right
+f 3 4 8
+g left
+f 1 2 6
+g right
+f 3 4 8
+g middle
+f 2 3 7
+g left
+f 1 6 5
+";
+    let options = ParseOptions::new().track_lines(true);
+    let original = Obj::from_str_with(src, options).unwrap();
+    let faces = |obj: &Obj| obj.polygons().map(|poly| poly.to_string()).collect::<Vec<_>>();
+    let lines = |obj: &Obj| obj.polygons().map(|poly| poly.source_line().unwrap()).collect::<Vec<_>>();
+
+    let mut obj = original.clone();
+    obj.cache_triangles();
+    obj.sort_faces_spatially(false);
+    assert_eq!(lines(&obj), vec![12, 16, 10, 14, 18]);
+    // Identical polygons keep their relative order, and nothing is lost
+    let (mut sorted, mut expected) = (faces(&obj), faces(&original));
+    sorted.sort();
+    expected.sort();
+    assert_eq!(sorted, expected);
+    assert_eq!(obj.positions(), original.positions());
+    let positions = |tri: [wavefront::Vertex; 3]| tri.map(|v| v.position());
+    assert_eq!(
+        obj.triangles().map(positions).collect::<Vec<_>>(),
+        obj.polygons().flat_map(|poly| poly.triangles()).map(positions).collect::<Vec<_>>(),
+    );
+    assert_eq!(obj.group("right").unwrap().polygons().map(|poly| poly.source_line().unwrap()).collect::<Vec<_>>(), vec![10, 14]);
+
+    // Sorting again changes nothing
+    let before = faces(&obj);
+    obj.sort_faces_spatially(false);
+    assert_eq!(faces(&obj), before);
+
+    // Groups stay together, in the order they first appear
+    let mut obj = original.clone();
+    obj.sort_faces_spatially(true);
+    assert_eq!(lines(&obj), vec![10, 14, 12, 18, 16]);
+}
+
+#[test]
+fn vertex_normals_or_face() {
+    // Vertex normals are kept wherever they're given
+    let obj = Obj::from_file("tests/cube.obj").unwrap();
+    for poly in obj.polygons() {
+        let normals = poly.vertices().map(|v| v.normal().unwrap()).collect::<Vec<_>>();
+        assert_eq!(poly.vertex_normals_or_face().collect::<Vec<_>>(), normals);
+    }
+
+    let src = "
+        v 0 0 0\nv 2 0 0\nv 2 2 0\nv 0 2 0\nvn 1 0 0\nvn 0 1 0
+        f 1 2 3 4
+        f 4//1 3 2//2 1
+        f 1 2 2
+    ";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let up = [0.0, 0.0, 1.0];
+    let down = [0.0, 0.0, -1.0];
+    let normals = |i| obj.polygon(i).unwrap().vertex_normals_or_face().collect::<Vec<_>>();
+    assert_eq!(normals(0), [up; 4]);
+    // Mixed polygons are handled one vertex at a time
+    assert_eq!(normals(1), [[1.0, 0.0, 0.0], down, [0.0, 1.0, 0.0], down]);
+    // Degenerate polygons have no normal of their own
+    assert_eq!(normals(2), [[0.0; 3]; 3]);
+
+    // Triangles carry the same normals as the polygons they came from
+    let triangles = obj.triangles_with_normals().collect::<Vec<_>>();
+    assert_eq!(triangles.len(), obj.triangles().count());
+    for (tri, plain) in triangles.iter().zip(obj.triangles()) {
+        assert_eq!(tri.map(|(v, _)| v.position()), plain.map(|v| v.position()));
+    }
+    let mixed = obj.polygon(1).unwrap().triangles_with_normals().collect::<Vec<_>>();
+    assert_eq!(mixed.len(), 2);
+    assert_eq!(mixed[0].map(|(_, n)| n), [[1.0, 0.0, 0.0], down, [0.0, 1.0, 0.0]]);
+    assert_eq!(mixed[1].map(|(_, n)| n), [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], down]);
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "flate2")]
+
+use flate2::{write::GzEncoder, Compression};
+use std::{fs, io::Write};
+use wavefront::{Error, Obj};
+
+fn summary(obj: &Obj) -> impl PartialEq + std::fmt::Debug {
+    let mut groups = obj
+        .objects()
+        .flat_map(|(object, o)| o
+            .groups()
+            .map(move |(group, g)| (object.to_string(), group.clone(), g.polygons().map(|p| p.index()).collect::<Vec<_>>())))
+        .collect::<Vec<_>>();
+    groups.sort();
+    let polygons = obj
+        .polygons()
+        .map(|p| p.vertices().map(|v| (v.position(), v.uv(), v.normal())).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    (obj.positions().to_vec(), obj.uvs().to_vec(), obj.normals().to_vec(), polygons, groups)
+}
+
+fn compress(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn matches_plain() {
+    let plain = fs::read("tests/ship.obj").unwrap();
+    let compressed = compress(&plain);
+    let expected = summary(&Obj::from_file("tests/ship.obj").unwrap());
+
+    assert_eq!(summary(&Obj::from_gzip_reader(&compressed[..]).unwrap()), expected);
+
+    // Files are detected by their contents rather than their extension
+    let path = std::env::temp_dir().join(format!("wavefront-gzip-{}.obj", std::process::id()));
+    fs::write(&path, &compressed).unwrap();
+    assert_eq!(summary(&Obj::from_file(&path).unwrap()), expected);
+
+    // Concatenated members are decompressed in turn
+    let (head, tail) = plain.split_at(plain.iter().position(|b| *b == b'\n').unwrap() + 1);
+    let members = [compress(head), compress(tail)].concat();
+    assert_eq!(summary(&Obj::from_gzip_reader(&members[..]).unwrap()), expected);
+}
+
+#[test]
+fn errors() {
+    let compressed = compress(&fs::read("tests/ship.obj").unwrap());
+    assert!(matches!(Obj::from_gzip_reader(&compressed[..compressed.len() / 2]), Err(Error::Io(_))));
+    assert!(matches!(Obj::from_gzip_reader(&b"v 0 0 0\n"[..]), Err(Error::Io(_))));
+    assert!(matches!(Obj::from_gzip_reader(&compress(b"v 0 0 0\nf 1 x\n")[..]), Err(Error::ExpectedIdx(2))));
+}
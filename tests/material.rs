@@ -0,0 +1,53 @@
+use wavefront::Obj;
+
+fn usage<'a>(usage: impl Iterator<Item=(Option<&'a str>, usize)>) -> Vec<(Option<&'a str>, usize)> {
+    usage.collect()
+}
+
+#[test]
+fn material_usage() {
+    let obj = Obj::from_file("tests/materials.obj").unwrap();
+    let materials = obj.polygons().map(|poly| poly.material()).collect::<Vec<_>>();
+    assert_eq!(materials, [None, Some("red"), Some("blue"), Some("blue"), Some("red"), None]);
+
+    let strip = obj.object("strip").unwrap();
+    assert_eq!(usage(strip.group("left").unwrap().material_usage()), [(None, 1), (Some("red"), 1), (Some("blue"), 1)]);
+    assert_eq!(usage(strip.group("right").unwrap().material_usage()), [(Some("blue"), 1), (Some("red"), 1), (None, 1)]);
+    assert_eq!(usage(strip.material_usage()), [(None, 2), (Some("red"), 2), (Some("blue"), 2)]);
+    assert_eq!(usage(obj.material_usage()), usage(strip.material_usage()));
+
+    // Polygons in several groups are counted once per object
+    let shared: Obj = "v 0 0 0\nusemtl red\ng a b\nf 1 1 1\ng a\nf 1 1 1\n".parse().unwrap();
+    assert_eq!(usage(shared.object("").unwrap().material_usage()), [(Some("red"), 2)]);
+    assert_eq!(usage(shared.object("").unwrap().group("b").unwrap().material_usage()), [(Some("red"), 1)]);
+
+    // An OBJ without materials uses none
+    let cube = Obj::from_file("tests/cube.obj").unwrap();
+    assert_eq!(usage(cube.material_usage()), [(None, 6)]);
+    assert!(cube.polygons().all(|poly| poly.material().is_none()));
+}
+
+#[test]
+fn materials_are_kept() {
+    let obj = Obj::from_file("tests/materials.obj").unwrap();
+
+    // Through writing and reading back, although groups may be written in any order
+    let reparsed: Obj = obj.to_string().parse().unwrap();
+    for name in ["left", "right"] {
+        let group = |obj: &Obj| usage(obj.object("strip").unwrap().group(name).unwrap().material_usage())
+            .into_iter()
+            .map(|(material, count)| (material.map(String::from), count))
+            .collect::<Vec<_>>();
+        assert_eq!(group(&reparsed), group(&obj));
+    }
+
+    // Through subdivision, with each of the eight triangles made from a quad taking its material
+    let subdivided = obj.subdivided(1);
+    assert_eq!(usage(subdivided.material_usage()), [(None, 16), (Some("red"), 16), (Some("blue"), 16)]);
+
+    // Through merging, including with an OBJ that has no materials, and one that names them in a different order
+    let mut merged = Obj::from_file("tests/cube.obj").unwrap();
+    merged.merge(&obj);
+    merged.merge(&"v 0 0 0\nusemtl blue\nf 1 1 1\nusemtl green\nf 1 1 1\n".parse().unwrap());
+    assert_eq!(usage(merged.material_usage()), [(None, 8), (Some("red"), 2), (Some("blue"), 3), (Some("green"), 1)]);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "mmap")]
+
+use std::{fs, path::PathBuf};
+use wavefront::{Error, Obj};
+
+fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("wavefront-mmap-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn summary(obj: &Obj) -> impl PartialEq + std::fmt::Debug {
+    let mut groups = obj
+        .objects()
+        .flat_map(|(object, o)| o
+            .groups()
+            .map(move |(group, g)| (object.to_string(), group.clone(), g.polygons().map(|p| p.index()).collect::<Vec<_>>())))
+        .collect::<Vec<_>>();
+    groups.sort();
+    let polygons = obj
+        .polygons()
+        .map(|p| p.vertices().map(|v| (v.position(), v.uv(), v.normal())).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    (obj.positions().to_vec(), obj.uvs().to_vec(), obj.normals().to_vec(), polygons, groups)
+}
+
+#[test]
+fn matches_from_file() {
+    for model in ["ship.obj", "torus.obj", "cube.obj", "cubes.obj", "plane.obj"] {
+        let path = format!("tests/{}", model);
+        let mapped = unsafe { Obj::from_file_mmap(&path) }.unwrap();
+        assert_eq!(summary(&mapped), summary(&Obj::from_file(&path).unwrap()));
+    }
+
+    // Windows line endings and a final line without a newline
+    let path = temp_file("crlf.obj", b"v 0 0 0\r\nv 1 0 0\r\nv 0 1 0\r\nf 1 2 3");
+    let mapped = unsafe { Obj::from_file_mmap(&path) }.unwrap();
+    assert_eq!(mapped.positions(), Obj::from_file(&path).unwrap().positions());
+    assert_eq!(mapped.polygons().len(), 1);
+
+    let path = temp_file("empty.obj", b"");
+    assert_eq!(unsafe { Obj::from_file_mmap(&path) }.unwrap().positions().len(), 0);
+}
+
+#[test]
+fn errors() {
+    let path = temp_file("utf8.obj", b"v 0 0 0\nv \xff 0 0\n");
+    assert!(matches!(unsafe { Obj::from_file_mmap(&path) }, Err(Error::Io(_))));
+    assert!(matches!(Obj::from_file(&path), Err(Error::Io(_))));
+
+    let path = temp_file("index.obj", b"v 0 0 0\n\nf 1 x\n");
+    assert!(matches!(unsafe { Obj::from_file_mmap(&path) }, Err(Error::ExpectedIdx(3))));
+
+    assert!(matches!(unsafe { Obj::from_file_mmap("tests/missing.obj") }, Err(Error::Io(_))));
+}
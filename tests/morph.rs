@@ -0,0 +1,61 @@
+use wavefront::{Obj, TopologyMismatch};
+
+const BASE: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+f 1/1 2/1 3/1 4/1
+";
+
+#[test]
+fn blend() {
+    let base: Obj = BASE.parse().unwrap();
+    // Relative indices resolve to the same vertices as the base's absolute ones
+    let raised: Obj = BASE.replace(" 0\nv", " 2\nv").replace("1 0\nvt", "1 2\nvt").parse().unwrap();
+    let widened: Obj = "v 0 0 0\nv 3 0 0\nv 3 1 0\nv 0 1 0\nvt 0 0\nf -4/1 -3/1 -2/1 -1/1\n".parse().unwrap();
+
+    let raise = Obj::morph_delta(&base, &raised).unwrap();
+    let widen = Obj::morph_delta(&base, &widened).unwrap();
+    assert_eq!(raise, vec![[0.0, 0.0, 2.0]; 4]);
+    assert_eq!(widen, vec![[0.0; 3], [2.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0; 3]]);
+    assert_eq!(Obj::morph_delta(&base, &base).unwrap(), vec![[0.0; 3]; 4]);
+
+    let mut blended = base.clone();
+    blended.apply_morph(&raise, 0.5);
+    blended.apply_morph(&widen, 0.5);
+    assert_eq!(blended.positions(), &[[0.0, 0.0, 1.0], [2.0, 0.0, 1.0], [2.0, 1.0, 1.0], [0.0, 1.0, 1.0]]);
+
+    // A full weight reproduces the target
+    let mut full = base.clone();
+    full.apply_morph(&widen, 1.0);
+    assert_eq!(full.positions(), widened.positions());
+}
+
+#[test]
+fn mismatch() {
+    let base: Obj = BASE.parse().unwrap();
+    let check = |target: &str| Obj::morph_delta(&base, &target.parse().unwrap()).unwrap_err();
+
+    assert_eq!(
+        check(&(BASE.to_string() + "f 1 2 3\n")),
+        TopologyMismatch::PolygonCount { base: 1, target: 2 },
+    );
+    assert_eq!(
+        check(&BASE.replace("4/1\n", "\n")),
+        TopologyMismatch::PolygonLength { polygon: 0, base: 4, target: 3 },
+    );
+    // Vertices are compared by their resolved indices, including texture coordinates
+    assert_eq!(
+        check(&BASE.replace("f 1/1 2/1 3/1 4/1", "f 1/1 2/1 4/1 3/1")),
+        TopologyMismatch::Vertex { polygon: 0, vertex: 2, base: (2, Some(0), None), target: (3, Some(0), None) },
+    );
+    assert_eq!(
+        check(&BASE.replace("3/1 4/1", "3/1 4")),
+        TopologyMismatch::Vertex { polygon: 0, vertex: 3, base: (3, Some(0), None), target: (3, None, None) },
+    );
+    let err = check(&(BASE.to_string() + "v 5 5 5\n"));
+    assert_eq!(err, TopologyMismatch::PositionCount { base: 4, target: 5 });
+    assert_eq!(err.to_string(), "Base has 4 positions but target has 5");
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "rayon")]
+
+use wavefront::{Error, Obj};
+
+fn assert_identical(a: &Obj, b: &Obj) {
+    let bits = |attrs: &[[f32; 3]]| attrs.iter().map(|a| a.map(f32::to_bits)).collect::<Vec<_>>();
+    assert_eq!(bits(a.positions()), bits(b.positions()));
+    assert_eq!(bits(a.uvs()), bits(b.uvs()));
+    assert_eq!(bits(a.normals()), bits(b.normals()));
+    assert_eq!(a.vertices().len(), b.vertices().len());
+    let polygons = |obj: &Obj| obj
+        .polygons()
+        .map(|p| p.vertices().map(|v| (v.position(), v.uv(), v.normal())).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(polygons(a), polygons(b));
+
+    let groups = |obj: &Obj| {
+        let mut groups = obj
+            .objects()
+            .flat_map(|(object, o)| o
+                .groups()
+                .map(move |(group, g)| (object.to_string(), group.clone(), g.polygons().map(|p| p.index()).collect::<Vec<_>>())))
+            .collect::<Vec<_>>();
+        groups.sort();
+        groups
+    };
+    assert_eq!(groups(a), groups(b));
+}
+
+fn models() -> Vec<&'static str> {
+    [
+        include_str!("ship.obj"),
+        include_str!("torus.obj"),
+        include_str!("cube.obj"),
+        include_str!("cubes.obj"),
+        include_str!("plane.obj"),
+    ]
+    .to_vec()
+}
+
+#[test]
+fn matches_serial() {
+    // Small inputs are parsed serially
+    for model in models() {
+        assert_identical(&Obj::from_str_parallel(model).unwrap(), &model.parse().unwrap());
+    }
+
+    // Repeating the models produces an input large enough to be split into many chunks, with faces referring to
+    // attributes in earlier chunks (both absolutely and relatively) and objects and groups spanning several chunks
+    let mut src = String::new();
+    while src.len() < 8 << 20 {
+        for model in models() {
+            src.push_str(model);
+            src.push('\n');
+        }
+    }
+    src.push_str("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -1 -2 -3\nf 1 2 -1");
+    assert_identical(&Obj::from_str_parallel(&src).unwrap(), &src.parse().unwrap());
+}
+
+#[test]
+fn errors_match_serial() {
+    let mut src = String::new();
+    while src.len() < 4 << 20 {
+        src.push_str(include_str!("cube.obj"));
+    }
+    let lines = src.lines().count();
+
+    let serial = |src: &str| src.parse::<Obj>().err().unwrap().to_string();
+    let parallel = |src: &str| Obj::from_str_parallel(src).err().unwrap().to_string();
+
+    // The first error in the input is reported, even if later chunks contain errors too
+    let errors = format!("{}f 1 x\n{}o\n{}f 1/1/1/1 999999999\n", src, src, src);
+    assert_eq!(parallel(&errors), serial(&errors));
+    assert_eq!(parallel(&errors), format!("Expected index on line {}", lines + 1));
+
+    // Objects without a valid name are renamed rather than being errors
+    let errors = format!("{}o\n{}f 1 x\n", src, src);
+    assert_eq!(parallel(&errors), serial(&errors));
+    assert!(matches!(Obj::from_str_parallel(&errors), Err(Error::ExpectedIdx(_))));
+    let renamed = format!("{}o\n{}o -\n", src, src);
+    let (parallel_obj, serial_obj) = (Obj::from_str_parallel(&renamed).unwrap(), renamed.parse::<Obj>().unwrap());
+    assert_eq!(parallel_obj.parse_report(), serial_obj.parse_report());
+    assert_eq!(parallel_obj.parse_report().warnings.len(), 2);
+
+    let errors = format!("{}f 999999999\n{}", src, src);
+    assert_eq!(parallel(&errors), serial(&errors));
+}
+
+#[test]
+fn par_triangles() {
+    use rayon::prelude::*;
+
+    fn area(tri: [wavefront::Vertex; 3]) -> f32 {
+        let [a, b, c] = tri.map(|v| v.position());
+        let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+        let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() / 2.0
+    }
+
+    for model in models() {
+        let mut obj = model.parse::<Obj>().unwrap();
+        for cached in [false, true] {
+            if cached {
+                obj.cache_triangles();
+            }
+            // The areas are produced in the same order, so summing them in that order agrees exactly
+            let serial = obj.triangles().map(area).collect::<Vec<_>>();
+            let parallel = obj.par_triangles().map(area).collect::<Vec<_>>();
+            assert_eq!(parallel, serial);
+            assert_eq!(parallel.iter().sum::<f32>(), obj.triangles().map(area).sum::<f32>());
+            assert_eq!(obj.par_triangles().count(), obj.triangles().count());
+        }
+
+        assert_eq!(obj.par_polygons().len(), obj.polygons().len());
+        let areas = obj.par_polygons().map(|poly| poly.area()).collect::<Vec<_>>();
+        assert_eq!(areas, obj.polygons().map(|poly| poly.area()).collect::<Vec<_>>());
+    }
+}
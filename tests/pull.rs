@@ -0,0 +1,77 @@
+use wavefront::{Error, FaceTerm, GenericObjParser, Obj, ObjEvent, ObjParser};
+
+#[test]
+fn events() {
+    let src = "# Exported by hand
+mtllib a.mtl b.mtl
+
+o thing
+v 1 2 3
+vt 0.5
+vn 0 0 1
+g left right
+usemtl red
+s off
+f 1/1/1 -1//-1 1
+";
+    let events = ObjParser::new(src).collect::<Result<Vec<_>, _>>().unwrap();
+    let lines = events.iter().map(|(line, _)| *line).collect::<Vec<_>>();
+    assert_eq!(lines, vec![1, 2, 4, 5, 6, 7, 8, 9, 10, 11]);
+
+    let mut events = events.into_iter().map(|(_, event)| event);
+    assert!(matches!(events.next(), Some(ObjEvent::Comment(" Exported by hand"))));
+    assert!(matches!(events.next(), Some(ObjEvent::MtlLib(names)) if names == ["a.mtl", "b.mtl"]));
+    assert!(matches!(events.next(), Some(ObjEvent::Object("thing"))));
+    assert!(matches!(events.next(), Some(ObjEvent::Position([1.0, 2.0, 3.0]))));
+    assert!(matches!(events.next(), Some(ObjEvent::Uv([0.5, 0.0, 0.0]))));
+    assert!(matches!(events.next(), Some(ObjEvent::Normal([0.0, 0.0, 1.0]))));
+    assert!(matches!(events.next(), Some(ObjEvent::Group(names)) if names == ["left", "right"]));
+    assert!(matches!(events.next(), Some(ObjEvent::UseMtl("red"))));
+    assert!(matches!(events.next(), Some(ObjEvent::Unknown { keyword: "s", rest: "off" })));
+    match events.next() {
+        Some(ObjEvent::Face(terms)) => assert_eq!(terms.collect::<Result<Vec<_>, _>>().unwrap(), vec![
+            FaceTerm { position: 1, uv: Some(1), normal: Some(1) },
+            FaceTerm { position: -1, uv: None, normal: Some(-1) },
+            FaceTerm { position: 1, uv: None, normal: None },
+        ]),
+        event => panic!("Expected a face, found {:?}", event),
+    }
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn errors() {
+    let mut events = ObjParser::new("o\nv 1 2 3\nf 1 x\n");
+    assert!(matches!(events.next(), Some(Err(Error::ExpectedName(1)))));
+    // Parsing continues after an error
+    assert!(matches!(events.next(), Some(Ok((2, ObjEvent::Position(_))))));
+    match events.next() {
+        Some(Ok((3, ObjEvent::Face(mut terms)))) => {
+            assert!(matches!(terms.next(), Some(Ok(_))));
+            assert!(matches!(terms.next(), Some(Err(Error::ExpectedIdx(3)))));
+        },
+        event => panic!("Expected a face, found {:?}", event),
+    }
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn matches_obj() {
+    let src = std::str::from_utf8(include_bytes!("ship.obj")).unwrap();
+    let obj = Obj::from_lines(src.lines()).unwrap();
+
+    let (mut positions, mut faces, mut vertices) = (Vec::new(), 0, 0);
+    for event in GenericObjParser::<f64>::new(src) {
+        match event.unwrap() {
+            (_, ObjEvent::Position(pos)) => positions.push(pos.map(|x| x as f32)),
+            (_, ObjEvent::Face(terms)) => {
+                faces += 1;
+                vertices += terms.count();
+            },
+            _ => {},
+        }
+    }
+    assert_eq!(positions, obj.positions());
+    assert_eq!(faces, obj.polygons().len());
+    assert_eq!(vertices, obj.vertices().len());
+}
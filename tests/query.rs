@@ -0,0 +1,53 @@
+use wavefront::Obj;
+
+#[test]
+fn raycast() {
+    let obj = Obj::from_reader(include_bytes!("cubes.obj") as &[u8]).unwrap();
+
+    let hit = obj.raycast([-1.0, 0.25, 0.5], [1.0, 0.0, 0.0], false).unwrap();
+    assert_eq!(hit.distance, 1.0);
+    assert_eq!(hit.object, "cube0");
+    assert_eq!(hit.polygon, 4);
+    let point = (0..3)
+        .map(|i| (0..3).map(|v| hit.triangle[v].position()[i] * hit.barycentric[v]).sum::<f32>())
+        .collect::<Vec<_>>();
+    assert_eq!(point, [0.0, 0.25, 0.5]);
+
+    // Both sides of all three cubes
+    let hits = obj.raycast_all([-1.0, 0.25, 0.5], [1.0, 0.0, 0.0], false);
+    assert_eq!(hits.iter().map(|h| h.distance).collect::<Vec<_>>(), [1.0, 2.0, 4.0, 5.0, 7.0, 8.0]);
+    assert_eq!(hits[2].object, "cube1");
+
+    // Only the sides facing the ray
+    let hits = obj.raycast_all([-1.0, 0.25, 0.5], [1.0, 0.0, 0.0], true);
+    assert_eq!(hits.iter().map(|h| h.distance).collect::<Vec<_>>(), [1.0, 4.0, 7.0]);
+
+    assert!(obj.raycast([-1.0, 2.0, 0.5], [1.0, 0.0, 0.0], false).is_none());
+    assert!(obj.raycast([-1.0, 0.25, 0.5], [-1.0, 0.0, 0.0], false).is_none());
+}
+
+#[test]
+fn raycast_shared_edge() {
+    let obj = Obj::from_reader(include_bytes!("cubes.obj") as &[u8]).unwrap();
+
+    // Passes exactly through the diagonal splitting the face into triangles, and through a corner of the far face
+    let hits = obj.raycast_all([-1.0, 0.5, 0.5], [1.0, 0.0, 0.0], false);
+    assert_eq!(hits.len(), 6);
+    let hits = obj.raycast_all([-1.0, 0.0, 0.0], [1.0, 0.0, 0.0], false);
+    assert!(!hits.is_empty());
+    assert_eq!(hits[0].distance, 1.0);
+}
+
+#[test]
+fn raycast_non_finite() {
+    // Lenient parsing accepts NaN positions, which must never be reported as hits
+    let obj: Obj = "v nan 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nv 1 0 1\nv 0 1 1\nf 1 2 3\nf 4 5 6\n".parse().unwrap();
+    let hits = obj.raycast_all([0.25, 0.25, -1.0], [0.0, 0.0, 1.0], false);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].polygon, 1);
+    assert_eq!(hits[0].distance, 2.0);
+    assert_eq!(obj.raycast([0.25, 0.25, -1.0], [0.0, 0.0, 1.0], false).unwrap().polygon, 1);
+
+    let obj: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".parse().unwrap();
+    assert!(obj.raycast_all([f32::NAN, 0.25, -1.0], [0.0, 0.0, 1.0], false).is_empty());
+}
@@ -0,0 +1,74 @@
+use wavefront::{Error, NameCollision, Obj};
+
+const SRC: &str = "v 0 0 0
+o scene01_car
+g scene01_wheel
+f 1 1 1
+f 1 1 1
+g scene01_door
+f 1 1 1
+g wheel
+f 1 1 1
+o scene01_house
+g scene01_door
+f 1 1 1
+o house
+g roof
+f 1 1 1
+";
+
+fn polygons(obj: &Obj, object: &str, group: &str) -> Vec<usize> {
+    obj.object(object).unwrap().group(group).unwrap().polygons().map(|poly| poly.index()).collect()
+}
+
+#[test]
+fn rename_groups() {
+    let mut obj: Obj = SRC.parse().unwrap();
+    let strip = |name: &str| name.strip_prefix("scene01_").map(String::from);
+
+    // `scene01_wheel` collides with `wheel` in the car, so nothing changes
+    assert!(matches!(obj.clone().rename_groups(strip, NameCollision::Error), Err(Error::NameCollision(name)) if name == "wheel"));
+
+    let renamed = obj.rename_groups(strip, NameCollision::Merge).unwrap();
+    assert_eq!(renamed, [
+        ("scene01_door".to_string(), "door".to_string()),
+        ("scene01_wheel".to_string(), "wheel".to_string()),
+    ]);
+    assert_eq!(polygons(&obj, "scene01_car", "wheel"), [0, 1, 3]);
+    assert_eq!(polygons(&obj, "scene01_car", "door"), [2]);
+    assert_eq!(polygons(&obj, "scene01_house", "door"), [4]);
+    assert!(obj.object("scene01_car").unwrap().group("scene01_wheel").is_none());
+    assert!(obj.object("scene01_car").unwrap().group_ignore_case("DOOR").is_some());
+    assert_eq!(obj.object("scene01_car").unwrap().groups().count(), 2);
+
+    // Written output uses the new names
+    let reparsed: Obj = obj.to_string().parse().unwrap();
+    assert_eq!(polygons(&reparsed, "scene01_car", "wheel").len(), 3);
+
+    // Returning `None` or the same name changes nothing
+    assert!(obj.rename_groups(|_| None, NameCollision::Error).unwrap().is_empty());
+    assert!(obj.rename_groups(|name| Some(name.to_string()), NameCollision::Error).unwrap().is_empty());
+}
+
+#[test]
+fn rename_objects() {
+    let mut obj: Obj = SRC.parse().unwrap();
+    let strip = |name: &str| name.strip_prefix("scene01_").map(String::from);
+
+    let mut error = obj.clone();
+    assert!(matches!(error.rename_objects(strip, NameCollision::Error), Err(Error::NameCollision(name)) if name == "house"));
+    assert!(error.object("scene01_house").is_some());
+
+    // Every object is offered to the function exactly once, in sorted order
+    let mut seen = Vec::new();
+    let renamed = obj
+        .rename_objects(|name| { seen.push(name.to_string()); strip(name) }, NameCollision::Merge)
+        .unwrap();
+    assert_eq!(seen, ["house", "scene01_car", "scene01_house"]);
+    assert_eq!(renamed.len(), 2);
+    assert_eq!(obj.objects().count(), 2);
+    assert_eq!(polygons(&obj, "house", "roof"), [5]);
+    assert_eq!(polygons(&obj, "house", "scene01_door"), [4]);
+    assert_eq!(polygons(&obj, "car", "scene01_wheel"), [0, 1]);
+    assert!(obj.object_ignore_case("CAR").is_some());
+}
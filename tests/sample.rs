@@ -0,0 +1,80 @@
+use wavefront::Obj;
+
+// A box with faces of three different areas: 2 (facing along z), 3 (along y) and 6 (along x)
+fn stretched_cube() -> Obj {
+    let src = include_str!("cube.obj")
+        .lines()
+        .map(|line| match line.strip_prefix("v ") {
+            Some(coords) => {
+                let [x, y, z] = [0, 1, 2].map(|i| coords.split(' ').nth(i).unwrap().parse::<f32>().unwrap());
+                format!("v {} {} {}", x, y * 2.0, z * 3.0)
+            },
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    src.parse().unwrap()
+}
+
+#[test]
+fn uniform() {
+    let obj = stretched_cube();
+    let n = 60000;
+    let samples = obj.sample_surface(n, 7);
+    assert_eq!(samples.len(), n);
+
+    let areas = obj.polygons().map(|poly| poly.area() as f64).collect::<Vec<_>>();
+    let total = areas.iter().sum::<f64>();
+    let mut counts = vec![0usize; areas.len()];
+    for sample in &samples {
+        counts[sample.polygon] += 1;
+    }
+    // Pearson's chi-squared test with 5 degrees of freedom, for which 20.5 is the critical value at p = 0.001
+    let chi_squared = counts
+        .iter()
+        .zip(&areas)
+        .map(|(count, area)| {
+            let expected = n as f64 * area / total;
+            (*count as f64 - expected).powi(2) / expected
+        })
+        .sum::<f64>();
+    assert!(chi_squared < 20.5, "counts {:?} don't match areas {:?}", counts, areas);
+
+    // Every sample lies on its polygon, with the polygon's normal and a texture coordinate within the face
+    for sample in &samples {
+        let poly = obj.polygon(sample.polygon).unwrap();
+        let normal = poly.normal().unwrap();
+        assert_eq!(sample.normal, Some(normal));
+        let corner = poly.vertex(0).unwrap().position();
+        let offset = (0..3).map(|i| (sample.position[i] - corner[i]) * normal[i]).sum::<f32>();
+        assert!(offset.abs() < 1e-5);
+        assert!(sample.uv.unwrap().iter().all(|x| (-1e-6..=1.0 + 1e-6).contains(x)));
+    }
+}
+
+#[test]
+fn deterministic() {
+    let obj = stretched_cube();
+    assert_eq!(obj.sample_surface(100, 1), obj.sample_surface(100, 1));
+    assert_ne!(obj.sample_surface(100, 1), obj.sample_surface(100, 2));
+    // Later samples don't depend on how many are taken
+    assert_eq!(obj.sample_surface(10, 1)[..], obj.sample_surface(100, 1)[..10]);
+}
+
+#[test]
+fn degenerate() {
+    // The second and third triangles have no area, and the attributes of the first are partially missing
+    let obj: Obj = "
+        v 0 0 0\nv 1 0 0\nv 0 1 0\nv 2 0 0\nvn 0 0 1
+        f 1//1 2//1 3
+        f 1 2 4
+        f 1 1 3
+    ".parse().unwrap();
+    let samples = obj.sample_surface(1000, 3);
+    assert!(samples.iter().all(|s| s.polygon == 0 && s.normal.is_none() && s.uv.is_none()));
+    assert!(samples.iter().all(|s| s.position[0] + s.position[1] <= 1.0 && s.position[2] == 0.0));
+
+    let flat: Obj = "v 0 0 0\nv 1 0 0\nv 2 0 0\nf 1 2 3\n".parse().unwrap();
+    assert!(flat.sample_surface(10, 0).is_empty());
+    assert!("".parse::<Obj>().unwrap().sample_surface(10, 0).is_empty());
+}
@@ -0,0 +1,99 @@
+use wavefront::Obj;
+
+const SRC: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1
+o car
+g body
+f 1 2 3
+g col_body
+f 1 2 4
+g lod1_body
+f 1 3 4
+o wheel_fl
+g col_wheel
+f 2 3 4 1
+g tyre
+f 1 2 3
+o wheel_fr
+g col_wheel col_tyre
+f 1 2 3
+";
+
+fn indices(obj: &Obj, pattern: &str) -> Vec<usize> {
+    obj.select(pattern).polygons().map(|p| p.index()).collect()
+}
+
+#[test]
+fn select() {
+    let obj: Obj = SRC.parse().unwrap();
+
+    // Patterns can match groups in several objects, and polygons in several matching groups appear once
+    assert_eq!(indices(&obj, "*/col_*"), [1, 3, 5]);
+    assert_eq!(indices(&obj, "wheel_f?/col_*"), [3, 5]);
+    assert_eq!(indices(&obj, "*/*_body"), [1, 2]);
+    assert_eq!(indices(&obj, "wheel_*"), [3, 4, 5]);
+    assert_eq!(indices(&obj, "*/*"), [0, 1, 2, 3, 4, 5]);
+    assert_eq!(indices(&obj, "car/body"), [0]);
+    assert_eq!(indices(&obj, "*/col"), []);
+    assert_eq!(indices(&obj, "truck/*"), []);
+    assert_eq!(indices(&obj, "wheel_f?"), indices(&obj, "wheel_f*"));
+
+    let selection = obj.select("*/col_*");
+    assert_eq!(selection.triangles().count(), 4);
+
+    let extracted = selection.to_obj();
+    assert_eq!(extracted.polygons().count(), 3);
+    assert_eq!(extracted.positions().len(), 4);
+    let mut names = extracted.group_names().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, [("car", "col_body"), ("wheel_fl", "col_wheel"), ("wheel_fr", "col_tyre"), ("wheel_fr", "col_wheel")]);
+
+    let empty = obj.select("*/nothing").to_obj();
+    assert_eq!(empty.polygons().count(), 0);
+    assert_eq!(empty.positions().len(), 0);
+}
+
+#[test]
+fn split() {
+    let obj: Obj = SRC.parse().unwrap();
+    let triangles = |obj: &Obj| {
+        let mut tris = obj
+            .triangles()
+            .map(|tri| tri.map(|v| v.position().map(f32::to_bits)))
+            .collect::<Vec<_>>();
+        tris.sort();
+        tris
+    };
+
+    let objects = obj.split_objects();
+    let names = objects.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["car", "wheel_fl", "wheel_fr"]);
+    let (_, car) = &objects[0];
+    assert_eq!(car.polygons().len(), 3);
+    assert_eq!(car.positions().len(), 4);
+    assert!(car.object("car").unwrap().group("lod1_body").is_some());
+    assert!(car.object("wheel_fl").is_none());
+
+    let groups = obj.split_groups();
+    let names = groups.iter().map(|(object, group, _)| (object.as_str(), group.as_str())).collect::<Vec<_>>();
+    assert_eq!(names, vec![
+        ("car", "body"),
+        ("car", "col_body"),
+        ("car", "lod1_body"),
+        ("wheel_fl", "col_wheel"),
+        ("wheel_fl", "tyre"),
+        ("wheel_fr", "col_tyre"),
+        ("wheel_fr", "col_wheel"),
+    ]);
+    let (_, _, tyre) = &groups[4];
+    assert_eq!(tyre.polygons().len(), 1);
+    assert_eq!(tyre.positions().len(), 3);
+
+    // Merging the pieces back together gives the same triangles
+    let ship = Obj::from_reader(include_bytes!("ship.obj") as &[u8]).unwrap();
+    for original in [&obj, &ship] {
+        let pieces = original.split_objects();
+        let mut merged = pieces[0].1.clone();
+        pieces[1..].iter().for_each(|(_, piece)| merged.merge(piece));
+        assert_eq!(triangles(&merged), triangles(original));
+    }
+}
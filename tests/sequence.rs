@@ -0,0 +1,76 @@
+use std::{fs, path::PathBuf};
+use wavefront::{Error, ObjSequence, TopologyMismatch};
+
+// Write each frame to its own file in a fresh directory
+fn frames(name: &str, frames: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wavefront-sequence-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir(&dir).unwrap();
+    for (name, src) in frames {
+        fs::write(dir.join(name), src).unwrap();
+    }
+    dir
+}
+
+fn triangle(z: f32) -> String {
+    format!("v 0 0 {0}\nv 1 0 {0}\nv 0 1 {0}\nf 1 2 3\n", z)
+}
+
+#[test]
+fn ordered() {
+    let dir = frames("ordered", &[
+        ("frame_10.obj", &triangle(3.0)),
+        ("frame_0002.obj", &triangle(2.0)),
+        ("frame_0001.obj", &triangle(0.0)),
+        ("frame_x.obj", "not a frame"),
+        ("notes.txt", "not a frame"),
+    ]);
+    let seq = ObjSequence::from_pattern(&dir, "frame_####.obj").unwrap();
+    assert_eq!(seq.len(), 3);
+    assert_eq!(seq.path(0).unwrap(), dir.join("frame_0001.obj"));
+    assert_eq!(seq.path(2).unwrap(), dir.join("frame_10.obj"));
+    assert_eq!(seq.frames().map(|frame| frame.positions()[0][2]).collect::<Vec<_>>(), vec![0.0, 2.0, 3.0]);
+
+    assert_eq!(seq.position_deltas(0).unwrap(), vec![[0.0; 3]; 3]);
+    assert_eq!(seq.position_deltas(2).unwrap(), vec![[0.0, 0.0, 3.0]; 3]);
+    assert!(seq.position_deltas(3).is_none());
+
+    // Applying the deltas to the first frame produces the others
+    let mut frame = seq.frame(0).unwrap().clone();
+    frame.apply_morph(&seq.position_deltas(1).unwrap(), 1.0);
+    assert_eq!(frame.positions(), seq.frame(1).unwrap().positions());
+}
+
+#[test]
+fn mismatched() {
+    let dir = frames("mismatched", &[
+        ("f1.obj", &triangle(0.0)),
+        ("f2.obj", &triangle(1.0)),
+        ("f3.obj", "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 3 2\n"),
+    ]);
+    match ObjSequence::from_pattern(&dir, "f#.obj") {
+        Err(Error::InFile(path, e)) => {
+            assert_eq!(path, dir.join("f3.obj"));
+            match *e {
+                Error::TopologyMismatch(e) => assert_eq!(e, TopologyMismatch::Vertex {
+                    polygon: 0,
+                    vertex: 1,
+                    base: (1, None, None),
+                    target: (2, None, None),
+                }),
+                e => panic!("unexpected error: {}", e),
+            }
+        },
+        r => panic!("unexpected result: {:?}", r.map(|seq| seq.len())),
+    }
+
+    // Parse errors name the file too
+    fs::write(dir.join("f2.obj"), "f 1 2 3\n").unwrap();
+    match ObjSequence::from_pattern(&dir, "f#.obj") {
+        Err(Error::InFile(path, _)) => assert_eq!(path, dir.join("f2.obj")),
+        r => panic!("unexpected result: {:?}", r.map(|seq| seq.len())),
+    }
+
+    assert!(matches!(ObjSequence::from_pattern(&dir, "g#.obj"), Err(Error::Io(_))));
+    assert!(matches!(ObjSequence::from_pattern(&dir, "f#_#.obj"), Err(Error::Io(_))));
+}
@@ -0,0 +1,113 @@
+use wavefront::{parse_into, Error, Index, MeshSink, Obj};
+
+type Term = (Index, Option<Index>, Option<Index>);
+
+#[derive(Default)]
+struct Recorder<'a> {
+    positions: Vec<[f32; 3]>,
+    uvs: usize,
+    normals: usize,
+    objects: Vec<&'a str>,
+    groups: Vec<Vec<&'a str>>,
+    faces: Vec<Vec<Term>>,
+}
+
+impl<'a> MeshSink<'a> for Recorder<'a> {
+    type Error = Error;
+
+    fn position(&mut self, position: [f32; 3]) {
+        self.positions.push(position);
+    }
+
+    fn uv(&mut self, _: [f32; 3]) {
+        self.uvs += 1;
+    }
+
+    fn normal(&mut self, _: [f32; 3]) {
+        self.normals += 1;
+    }
+
+    fn begin_object(&mut self, name: &'a str) {
+        self.objects.push(name);
+    }
+
+    fn begin_group(&mut self, names: &[&'a str]) {
+        self.groups.push(names.to_vec());
+    }
+
+    fn face(&mut self, vertices: &[Term]) -> Result<(), Error> {
+        self.faces.push(vertices.to_vec());
+        Ok(())
+    }
+}
+
+#[test]
+fn resolves_indices() {
+    let src = "o a\nv 0 0 0\nv 1 0 0\nvt 0 0\nvn 0 0 1\ng x y\nv 0 1 0\nf 1/1/1 -2/-1 -1//1\nusemtl red\n";
+    let mut sink = Recorder::default();
+    parse_into(src, &mut sink).unwrap();
+    assert_eq!(sink.positions.len(), 3);
+    assert_eq!((sink.uvs, sink.normals), (1, 1));
+    assert_eq!(sink.objects, vec!["a"]);
+    assert_eq!(sink.groups, vec![vec!["x", "y"]]);
+    assert_eq!(sink.faces, vec![vec![(0, Some(0), Some(0)), (1, Some(0), None), (2, None, Some(0))]]);
+}
+
+#[test]
+fn matches_obj() {
+    let src = std::str::from_utf8(include_bytes!("ship.obj")).unwrap();
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let mut sink = Recorder::default();
+    parse_into(src, &mut sink).unwrap();
+
+    assert_eq!(sink.positions, obj.positions());
+    let faces = obj
+        .polygons()
+        .map(|poly| poly.vertices().map(|v| (v.position_index(), v.uv_index(), v.normal_index())).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(sink.faces, faces);
+}
+
+#[derive(Debug)]
+enum SinkError {
+    Parse(Error),
+    NotATriangle,
+}
+
+impl From<Error> for SinkError {
+    fn from(e: Error) -> Self {
+        SinkError::Parse(e)
+    }
+}
+
+struct Triangles(usize);
+
+impl<'a> MeshSink<'a> for Triangles {
+    type Error = SinkError;
+
+    fn position(&mut self, _: [f32; 3]) {}
+
+    fn face(&mut self, vertices: &[Term]) -> Result<(), SinkError> {
+        if vertices.len() != 3 {
+            return Err(SinkError::NotATriangle);
+        }
+        self.0 += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn errors() {
+    let parse = |src: &str| parse_into(src, &mut Triangles(0));
+    assert!(parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").is_ok());
+    // Unlike when building an `Obj`, faces may not refer to attributes that come after them
+    assert!(matches!(parse("v 0 0 0\nv 1 0 0\nf 1 2 3\nv 0 1 0\n"), Err(SinkError::Parse(Error::InvalidIndex(3)))));
+    assert!(matches!(parse("v 0 0 0\nf 1 -2 1\n"), Err(SinkError::Parse(Error::InvalidIndex(-2)))));
+    assert!(matches!(parse("v 0 0 0\nf 1 x 1\n"), Err(SinkError::Parse(Error::ExpectedIdx(2)))));
+    assert!(matches!(parse("v 0 0 0\nf 1 1 1 1\n"), Err(SinkError::NotATriangle)));
+
+    // Parsing stops at the first error
+    let mut sink = Triangles(0);
+    assert!(parse_into("v 0 0 0\nf 1 1 1\nf 1 1\nf 1 1 1\n", &mut sink).is_err());
+    assert_eq!(sink.0, 1);
+}
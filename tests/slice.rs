@@ -0,0 +1,93 @@
+use wavefront::{Obj, Plane, Polyline};
+
+fn load(src: &[u8]) -> Obj {
+    Obj::from_reader(src).unwrap()
+}
+
+/// Twice the signed area enclosed by a closed polyline, projected onto the xy plane.
+fn signed_area_xy(polyline: &Polyline) -> f32 {
+    let n = polyline.points.len();
+    (0..n)
+        .map(|i| {
+            let (p, q) = (polyline.points[i], polyline.points[(i + 1) % n]);
+            p[0] * q[1] - q[0] * p[1]
+        })
+        .sum()
+}
+
+#[test]
+fn cube() {
+    let cube = load(include_bytes!("cube.obj"));
+
+    let sections = cube.slice(Plane::new([0.0, 0.0, 1.0], 0.5), 0.0);
+    assert_eq!(sections.len(), 1);
+    let square = &sections[0];
+    assert!(square.closed);
+    assert_eq!(square.length(), 4.0);
+    assert!(square.points.iter().all(|p| p[2] == 0.5 && (0.0..=1.0).contains(&p[0]) && (0.0..=1.0).contains(&p[1])));
+    for corner in [[0.0, 0.0, 0.5], [1.0, 0.0, 0.5], [1.0, 1.0, 0.5], [0.0, 1.0, 0.5]] {
+        assert!(square.points.contains(&corner));
+    }
+    // Counter-clockwise around the normal of the plane
+    assert_eq!(signed_area_xy(square), 2.0);
+    let flipped = cube.slice(Plane::new([0.0, 0.0, -1.0], -0.5), 0.0);
+    assert_eq!(signed_area_xy(&flipped[0]), -2.0);
+    // Without a positive epsilon ends are chained exactly, and a tiny one doesn't overflow the grid
+    for epsilon in [-1.0, f32::NAN, f32::MIN_POSITIVE] {
+        assert_eq!(cube.slice(Plane::new([0.0, 0.0, 1.0], 0.5), epsilon), sections);
+    }
+
+    // Through a layer of vertices, and missing the cube entirely
+    let sections = cube.slice(Plane::new([0.0, 1.0, 0.0], 1.0), 0.0);
+    assert_eq!(sections.len(), 1);
+    assert!(sections[0].closed);
+    assert_eq!(sections[0].length(), 4.0);
+    assert!(cube.slice(Plane::new([0.0, 1.0, 0.0], 2.0), 0.0).is_empty());
+}
+
+#[test]
+fn loops() {
+    // Slicing through the ring of the torus leaves loops around its inside and outside
+    let torus = load(include_bytes!("torus.obj"));
+    let sections = torus.slice(Plane::new([0.0, 1.0, 0.0], 0.1), 0.0);
+    assert_eq!(sections.len(), 2);
+    assert!(sections.iter().all(|polyline| polyline.closed && polyline.points.len() > 3));
+
+    // An open grid produces an open chain from one edge to the other
+    let plane = load(include_bytes!("plane.obj"));
+    let sections = plane.slice(Plane::new([1.0, 0.0, 0.0], 1.5), 0.0);
+    assert_eq!(sections.len(), 1);
+    assert!(!sections[0].closed);
+    assert_eq!(sections[0].length(), 3.0);
+    let ends = [sections[0].points[0], *sections[0].points.last().unwrap()];
+    assert!(ends.contains(&[1.5, 0.0, 0.0]) && ends.contains(&[1.5, 0.0, 3.0]));
+}
+
+#[test]
+fn junctions() {
+    // Three fins sharing an edge along the z axis
+    let src = "v 0 0 0\nv 0 0 1\nv 1 0 0\nv 1 0 1\nv 0 1 0\nv 0 1 1\nv -1 0 0\nv -1 0 1\n\
+               f 1 3 4 2\nf 1 5 6 2\nf 1 7 8 2\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let sections = obj.slice(Plane::new([0.0, 0.0, 1.0], 0.5), 0.0);
+    assert_eq!(sections.len(), 3);
+    for polyline in &sections {
+        assert!(!polyline.closed);
+        // Quads are split along a diagonal, which may add a point midway
+        let ends = [polyline.points[0], *polyline.points.last().unwrap()];
+        assert!(ends.contains(&[0.0, 0.0, 0.5]));
+        assert_eq!(polyline.length(), 1.0);
+    }
+}
+
+#[test]
+fn epsilon() {
+    // Two triangles that almost share an edge
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 1\nv 1.000001 0 0\nv 0 0 0.000001\nv 1 1 1\nf 1 2 3\nf 4 5 6\n";
+    let obj = Obj::from_lines(src.lines()).unwrap();
+    let plane = Plane::new([0.0, 0.0, 1.0], 0.5);
+    assert_eq!(obj.slice(plane, 0.0).len(), 2);
+    let sections = obj.slice(plane, 1e-3);
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].points.len(), 3);
+}
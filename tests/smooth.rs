@@ -0,0 +1,117 @@
+use std::fmt::Write;
+use wavefront::{Error, Obj};
+
+/// A small xorshift generator producing numbers in `[-1, 1)`.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+/// A unit sphere made by subdividing an octahedron, with each position pushed in or out by up to `noise`.
+fn noisy_sphere(noise: f32) -> Obj {
+    let octahedron = Obj::from_lines([
+        "v 1 0 0", "v -1 0 0", "v 0 1 0", "v 0 -1 0", "v 0 0 1", "v 0 0 -1",
+        "f 1 3 5", "f 3 2 5", "f 2 4 5", "f 4 1 5", "f 3 1 6", "f 2 3 6", "f 4 2 6", "f 1 4 6",
+    ].iter()).unwrap();
+    let sphere = octahedron.subdivided(3);
+
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut src = String::new();
+    for p in sphere.positions() {
+        let len = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+        let r = (1.0 + rng.next() * noise) / len;
+        writeln!(src, "v {} {} {}", p[0] * r, p[1] * r, p[2] * r).unwrap();
+    }
+    writeln!(src, "vt 0 0").unwrap();
+    for [a, b, c] in sphere.triangle_position_indices() {
+        writeln!(src, "f {}/1 {}/1 {}/1", a + 1, b + 1, c + 1).unwrap();
+    }
+    // A stray position that no polygon uses
+    writeln!(src, "v 5 5 5").unwrap();
+    src.parse().unwrap()
+}
+
+/// The average of `1 - cos(θ)` between the normals of neighbouring polygons.
+fn normal_variance(obj: &Obj) -> f32 {
+    let adjacency = obj.adjacency();
+    let (mut sum, mut count) = (0.0, 0);
+    for poly in obj.polygons() {
+        let n = poly.normal().unwrap();
+        for other in adjacency.faces_adjacent_to_face(poly.index()) {
+            let m = obj.polygon(*other).unwrap().normal().unwrap();
+            sum += 1.0 - (n[0] * m[0] + n[1] * m[1] + n[2] * m[2]);
+            count += 1;
+        }
+    }
+    sum / count as f32
+}
+
+#[test]
+fn noisy_sphere_is_smoothed() {
+    let noisy = noisy_sphere(0.1);
+    let before = normal_variance(&noisy);
+
+    let mut smooth = noisy.clone();
+    smooth.smooth_laplacian(5, 0.5, false).unwrap();
+    let after = normal_variance(&smooth);
+    assert!(after < before / 2.0, "{} {}", before, after);
+
+    // Topology, texture coordinates and unused positions are untouched
+    assert_eq!(smooth.polygons().count(), noisy.polygons().count());
+    assert!(smooth.vertices().zip(noisy.vertices()).all(|(a, b)| a.position_index() == b.position_index()));
+    assert_eq!(smooth.uvs(), noisy.uvs());
+    assert_eq!(smooth.positions().last(), Some(&[5.0, 5.0, 5.0]));
+
+    // Smoothing is deterministic
+    let mut again = noisy.clone();
+    again.smooth_laplacian(5, 0.5, false).unwrap();
+    assert_eq!(again.positions(), smooth.positions());
+
+    // Normals can be brought up to date afterwards, and point outwards
+    assert!(smooth.vertices().all(|v| v.normal().is_none()));
+    smooth.recompute_normals();
+    assert_eq!(smooth.normals().len(), smooth.positions().len() - 1);
+    for v in smooth.vertices() {
+        let (p, n) = (v.position(), v.normal().unwrap());
+        assert!(p[0] * n[0] + p[1] * n[1] + p[2] * n[2] > 0.0);
+        assert!(((n[0] * n[0] + n[1] * n[1] + n[2] * n[2]) - 1.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn boundary() {
+    let plane = Obj::from_file("tests/plane.obj").unwrap();
+    let boundary = plane.boundary_edges().into_iter().flatten().collect::<Vec<_>>();
+
+    let mut preserved = plane.clone();
+    preserved.smooth_laplacian(3, 1.0, true).unwrap();
+    let mut shrunk = plane.clone();
+    shrunk.smooth_laplacian(3, 1.0, false).unwrap();
+    for i in 0..plane.positions().len() {
+        if boundary.contains(&i) {
+            assert_eq!(preserved.positions()[i], plane.positions()[i]);
+            assert_ne!(shrunk.positions()[i], plane.positions()[i]);
+        }
+    }
+    // Interior positions of a flat grid with a fixed outline are already at rest
+    assert_eq!(preserved.positions(), plane.positions());
+}
+
+#[test]
+fn invalid_lambda() {
+    let mut obj = noisy_sphere(0.1);
+    let positions = obj.positions().to_vec();
+    for lambda in [0.0, -0.5, 1.5, f32::NAN] {
+        assert!(matches!(obj.smooth_laplacian(1, lambda, false), Err(Error::InvalidParameter(_))));
+    }
+    assert_eq!(obj.positions(), &positions[..]);
+    obj.smooth_laplacian(0, 0.5, false).unwrap();
+    obj.smooth_laplacian(1, 1.0, false).unwrap();
+    assert!(obj.positions() != &positions[..]);
+}
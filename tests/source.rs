@@ -0,0 +1,57 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+};
+use wavefront::{Error, Obj, ObjSource};
+
+struct Memory(HashMap<&'static str, &'static str>, Vec<String>);
+
+impl ObjSource for Memory {
+    fn open(&mut self, name: &str) -> io::Result<Box<dyn Read>> {
+        self.1.push(name.to_string());
+        match self.0.get(name) {
+            Some(src) => Ok(Box::new(src.as_bytes())),
+            None => Err(io::ErrorKind::NotFound.into()),
+        }
+    }
+}
+
+const MTL: &str = "newmtl a\nKd 1 1 1\nmap_Kd textures/a.png\nbump -bm 0.5 textures/a_bump.png\n\nnewmtl b\nmap_Kd textures/a.png\n";
+
+#[test]
+fn load_from_source() {
+    let mut source = Memory(
+        [
+            ("model.obj", "mtllib one.mtl two.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl a\nf 1 2 3\nmtllib one.mtl\n"),
+            ("one.mtl", MTL),
+            ("two.mtl", "# Empty\nnewmtl c\nmap_Ks -o 1 1 1 shiny.png\n"),
+        ].iter().copied().collect(),
+        Vec::new(),
+    );
+
+    let loaded = Obj::load_from_source("model.obj", &mut source).unwrap();
+    assert_eq!(loaded.obj.polygons().count(), 1);
+    assert_eq!(loaded.material_libraries, ["one.mtl", "two.mtl"]);
+    assert_eq!(loaded.textures, ["textures/a.png", "textures/a_bump.png", "shiny.png"]);
+    assert_eq!(source.1, ["model.obj", "one.mtl", "two.mtl"]);
+
+    // Missing libraries and parse errors report the file responsible
+    source.0.remove("two.mtl");
+    assert!(matches!(
+        Obj::load_from_source("model.obj", &mut source),
+        Err(Error::InFile(path, e)) if path.to_str() == Some("two.mtl") && matches!(*e, Error::Io(_)),
+    ));
+    source.0.insert("broken.obj", "v 0 0 0\nf 1 x\n");
+    assert!(matches!(
+        Obj::load_from_source("broken.obj", &mut source),
+        Err(Error::InFile(path, e)) if path.to_str() == Some("broken.obj") && matches!(*e, Error::ExpectedIdx(2)),
+    ));
+}
+
+#[test]
+fn from_file_with_materials() {
+    let loaded = Obj::from_file_with_materials("tests/ship.obj").unwrap();
+    assert_eq!(loaded.material_libraries, ["ship.mtl"]);
+    assert!(loaded.textures.is_empty());
+    assert_eq!(loaded.obj.polygons().count(), Obj::from_file("tests/ship.obj").unwrap().polygons().count());
+}
@@ -0,0 +1,71 @@
+use wavefront::Obj;
+
+fn icosahedron() -> String {
+    let t = (1.0 + 5f32.sqrt()) / 2.0;
+    let positions = [
+        [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+        [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+        [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+    ];
+    let faces = [
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+    let mut src = String::new();
+    for [x, y, z] in positions {
+        let len = (x * x + y * y + z * z).sqrt();
+        src += &format!("v {} {} {}\nvn {} {} {}\n", x, y, z, x / len, y / len, z / len);
+    }
+    src += "g top\n";
+    for (i, [a, b, c]) in faces.iter().enumerate() {
+        if i == 10 {
+            src += "g bottom\n";
+        }
+        src += &format!("f {0}//{0} {1}//{1} {2}//{2}\n", a + 1, b + 1, c + 1);
+    }
+    src
+}
+
+#[test]
+fn icosahedron_counts() {
+    let obj: Obj = icosahedron().parse().unwrap();
+    assert!(obj.manifold_report().is_closed());
+
+    // Each level adds a vertex per edge (V' = V + E), and quadruples the faces and doubles the edges (E' = 2E + 3F)
+    let (mut v, mut e, mut f) = (12, 30, 20);
+    for levels in 1..=3 {
+        let subdivided = obj.subdivided(levels);
+        (v, e, f) = (v + e, 2 * e + 3 * f, 4 * f);
+        assert_eq!(subdivided.positions().len(), v);
+        assert_eq!(subdivided.normals().len(), v);
+        assert_eq!(subdivided.polygons().count(), f);
+        let report = subdivided.manifold_report();
+        assert!(report.is_closed() && report.is_manifold());
+    }
+
+    // Triangles stay in the groups of the polygons they came from
+    let subdivided = obj.subdivided(1);
+    let group = |name| subdivided.object("").unwrap().group(name).unwrap().polygons().count();
+    assert_eq!((group("top"), group("bottom")), (40, 40));
+
+    // Normals are interpolated and renormalised
+    for n in subdivided.normals() {
+        assert!(((n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() - 1.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn polygons() {
+    // A quad with uvs on one triangle's worth of vertices is triangulated before being subdivided
+    let obj: Obj = "v 0 0 0\nv 2 0 0\nv 2 2 0\nv 0 2 0\nvt 0 0\nvt 1 0\nvt 1 1\nf 1/1 2/2 3/3 4\n".parse().unwrap();
+    let subdivided = obj.subdivided(1);
+    assert_eq!(subdivided.polygons().count(), 8);
+    assert_eq!(subdivided.positions().len(), 9);
+    assert_eq!(subdivided.positions()[4], [1.0, 0.0, 0.0]);
+    // Only edges with uvs at both ends produce a new uv
+    assert_eq!(subdivided.uvs().len(), 6);
+    assert_eq!(subdivided.surface_area(), obj.surface_area());
+    assert_eq!(obj.subdivided(0).polygons().count(), 2);
+}
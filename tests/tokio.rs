@@ -0,0 +1,66 @@
+#![cfg(feature = "tokio")]
+
+use std::sync::{atomic::AtomicBool, Arc};
+use wavefront::{Error, Obj, ParseOptions};
+
+fn summary(obj: &Obj) -> impl PartialEq + std::fmt::Debug {
+    let mut groups = obj
+        .objects()
+        .flat_map(|(object, o)| o
+            .groups()
+            .map(move |(group, g)| (object.to_string(), group.clone(), g.polygons().map(|p| p.index()).collect::<Vec<_>>())))
+        .collect::<Vec<_>>();
+    groups.sort();
+    let polygons = obj
+        .polygons()
+        .map(|p| p.vertices().map(|v| (v.position(), v.uv(), v.normal())).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    (obj.positions().to_vec(), obj.uvs().to_vec(), obj.normals().to_vec(), polygons, groups)
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+}
+
+#[test]
+fn matches_from_file() {
+    for model in ["ship.obj", "torus.obj", "cube.obj", "cubes.obj", "plane.obj"] {
+        let path = format!("tests/{}", model);
+        let obj = block_on(async { Obj::from_async_reader(tokio::fs::File::open(&path).await?).await }).unwrap();
+        assert_eq!(summary(&obj), summary(&Obj::from_file(&path).unwrap()));
+    }
+}
+
+#[test]
+fn send() {
+    fn assert_send<F: Send>(_: F) {}
+    assert_send(Obj::from_async_reader(&b""[..]));
+    assert_send(Obj::from_async_reader_with(&b""[..], ParseOptions::new().progress(|_| {})));
+}
+
+#[test]
+fn errors() {
+    let parse = |src: &'static [u8]| block_on(Obj::from_async_reader(src));
+    assert!(matches!(parse(b"v 0 0 0\nv \xff 0 0\n"), Err(Error::Io(_))));
+    assert!(matches!(parse(b"v 0 0 0\n\nf 1 x\n"), Err(Error::ExpectedIdx(3))));
+    assert!(matches!(parse(b"f 1 2 3\n"), Err(Error::InvalidIndex(1))));
+}
+
+#[test]
+fn options() {
+    let parse = |options: ParseOptions| block_on(Obj::from_async_reader_with(&b"v 0 0 0\nx custom\n"[..], options));
+
+    // Callbacks can't be held by the future, so are rejected instead of being silently dropped
+    assert!(matches!(parse(ParseOptions::new().progress(|_| {})), Err(Error::UnsupportedOption("progress"))));
+    let on_unknown = ParseOptions::new().on_unknown(|_, _, _| Ok(()));
+    assert!(matches!(parse(on_unknown), Err(Error::UnsupportedOption("on_unknown"))));
+    assert_eq!(parse(ParseOptions::new().keep_comments(true)).unwrap().positions().len(), 1);
+
+    let src = "v 0 0 0\n".repeat(4096);
+    let parse = |flag: bool| {
+        let options = ParseOptions::new().cancel_flag(Arc::new(AtomicBool::new(flag)));
+        block_on(Obj::from_async_reader_with(src.as_bytes(), options))
+    };
+    assert!(matches!(parse(true), Err(Error::Cancelled)));
+    assert_eq!(parse(false).unwrap().positions().len(), 4096);
+}
@@ -0,0 +1,301 @@
+use wavefront::{BoundaryLoop, CreaseEdge, Obj, OrientReport};
+
+#[test]
+fn torus_is_closed_manifold() {
+    let obj = Obj::from_reader(include_bytes!("torus.obj") as &[u8]).unwrap();
+    let report = obj.manifold_report();
+
+    // The texture seam duplicates uvs, but not positions
+    assert!(report.is_closed(), "{:?}", report);
+    assert!(report.is_manifold(), "{:?}", report);
+}
+
+#[test]
+fn plane_has_boundary() {
+    let obj = Obj::from_reader(include_bytes!("plane.obj") as &[u8]).unwrap();
+    let report = obj.manifold_report();
+
+    assert!(!report.is_closed());
+    assert!(report.is_manifold());
+    assert_eq!(report.boundary_edges.len(), 12);
+}
+
+#[test]
+fn boundary_loops() {
+    let src = include_str!("cube.obj");
+    let cube: Obj = src.parse().unwrap();
+    assert!(cube.boundary_edges().is_empty());
+    assert!(cube.boundary_loops().is_empty());
+
+    // Removing the top face leaves a single hole, traversed as the neighbouring faces traverse its edges
+    let open: Obj = src.replace("f 5/1/2 6/2/2 8/3/2 7/4/2\n", "").parse().unwrap();
+    assert_eq!(open.boundary_edges(), vec![[4, 6], [5, 4], [6, 7], [7, 5]]);
+    let hole = BoundaryLoop { positions: vec![4, 6, 7, 5], closed: true };
+    assert_eq!(open.boundary_loops(), vec![hole.clone()]);
+
+    // Triangulating the cube doesn't introduce any more boundary edges
+    let triangulated = src
+        .lines()
+        .filter(|line| *line != "f 5/1/2 6/2/2 8/3/2 7/4/2")
+        .map(|line| match line.strip_prefix("f ") {
+            Some(terms) => {
+                let t = terms.split(' ').collect::<Vec<_>>();
+                format!("f {} {} {}\nf {} {} {}", t[0], t[1], t[2], t[0], t[2], t[3])
+            },
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let triangulated: Obj = triangulated.parse().unwrap();
+    assert_eq!(triangulated.boundary_loops(), vec![hole]);
+
+    let plane = Obj::from_reader(include_bytes!("plane.obj") as &[u8]).unwrap();
+    let loops = plane.boundary_loops();
+    assert_eq!(loops.len(), 1);
+    assert!(loops[0].closed);
+    assert_eq!(loops[0].positions.len(), 12);
+
+    // Two triangles touching at a single position are split into two open chains at that position
+    let bow_tie = Obj::from_lines(["v 0 0 0", "v 1 0 0", "v 0 1 0", "v -1 0 0", "v 0 -1 0", "f 1 2 3", "f 1 4 5"].iter())
+        .unwrap();
+    assert_eq!(bow_tie.boundary_loops(), vec![
+        BoundaryLoop { positions: vec![0, 1, 2, 0], closed: false },
+        BoundaryLoop { positions: vec![0, 3, 4, 0], closed: false },
+    ]);
+
+    // Boundary edges that do not form a loop (here, because the other edges are doubled) produce an open chain
+    let strip = Obj::from_lines(["v 0 0 0", "v 1 0 0", "v 0 1 0", "v 1 1 0", "f 1 2 3", "f 2 4 3", "f 1 3 2"].iter())
+        .unwrap();
+    assert_eq!(strip.boundary_edges(), vec![[1, 3], [3, 2]]);
+    assert_eq!(strip.boundary_loops(), vec![BoundaryLoop { positions: vec![1, 3, 2], closed: false }]);
+}
+
+#[test]
+fn crease_edges() {
+    let cube = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    let creases = cube.crease_edges(30.0, false);
+    assert_eq!(creases.len(), 12);
+    assert!(creases.iter().all(|c| c.faces.1.is_some() && (c.angle_degrees.unwrap() - 90.0).abs() < 1e-4));
+    assert!(cube.crease_edges(90.0, true).is_empty());
+
+    // Normals in the file are ignored, so flipping one doesn't change anything
+    let src = include_str!("cube.obj").replace("vn 0 0 -1", "vn 0 0 1");
+    assert_eq!(src.parse::<Obj>().unwrap().crease_edges(30.0, false), creases);
+
+    // A finely tessellated sphere has no creases
+    let (rings, segments) = (32, 64);
+    let mut src = String::from("v 0 0 1\nv 0 0 -1\n");
+    for i in 1..rings {
+        let theta = std::f32::consts::PI * i as f32 / rings as f32;
+        for j in 0..segments {
+            let phi = 2.0 * std::f32::consts::PI * j as f32 / segments as f32;
+            src += &format!("v {} {} {}\n", theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos());
+        }
+    }
+    let ring = |i: usize, j: usize| 3 + (i - 1) * segments + j % segments;
+    for j in 0..segments {
+        src += &format!("f 1 {} {}\n", ring(1, j), ring(1, j + 1));
+        src += &format!("f 2 {} {}\n", ring(rings - 1, j + 1), ring(rings - 1, j));
+        for i in 1..rings - 1 {
+            src += &format!("f {} {} {} {}\n", ring(i, j), ring(i + 1, j), ring(i + 1, j + 1), ring(i, j + 1));
+        }
+    }
+    let sphere: Obj = src.parse().unwrap();
+    assert!(sphere.manifold_report().is_closed());
+    assert!(sphere.crease_edges(30.0, true).is_empty());
+    assert!(!sphere.crease_edges(1.0, false).is_empty());
+
+    // Boundary edges are optional
+    let folded = Obj::from_lines(["v 0 0 0", "v 1 0 0", "v 0 1 0", "v 0 0 1", "f 1 2 3", "f 2 1 4"].iter()).unwrap();
+    let fold = CreaseEdge { edge: [0, 1], faces: (0, Some(1)), angle_degrees: Some(90.0) };
+    assert_eq!(folded.crease_edges(45.0, false), vec![fold]);
+    let creases = folded.crease_edges(45.0, true);
+    assert_eq!(creases.len(), 5);
+    assert_eq!(creases[0], fold);
+    assert_eq!(creases[1], CreaseEdge { edge: [0, 2], faces: (0, None), angle_degrees: None });
+}
+
+#[test]
+fn valences() {
+    // The plane is a 3x3 grid of quads, with an unused position added at the end
+    let src = include_str!("plane.obj").to_string() + "v 9 9 9\n";
+    let plane: Obj = src.parse().unwrap();
+    let valences = plane.vertex_valences();
+    assert_eq!(valences, vec![1, 2, 2, 1, 2, 4, 4, 2, 2, 4, 4, 2, 1, 2, 2, 1, 0]);
+    assert_eq!(plane.valence_histogram(), vec![1, 4, 8, 0, 4]);
+    assert!(plane.high_valence_positions(4).is_empty());
+    assert_eq!(plane.high_valence_positions(3), vec![5, 6, 9, 10]);
+
+    // Valences agree with the polygons around each position
+    let adjacency = plane.adjacency();
+    for (pos, valence) in valences.iter().enumerate() {
+        assert_eq!(adjacency.faces_around_vertex(pos).len(), *valence as usize);
+    }
+
+    // A fan of six triangles forms a pole, and a polygon using a position twice counts once
+    let fan = Obj::from_lines([
+        "v 0 0 0", "v 1 0 0", "v 1 1 0", "v 0 1 0", "v -1 1 0", "v -1 0 0", "v 0 -1 0",
+        "f 1 2 3", "f 1 3 4", "f 1 4 5", "f 1 5 6", "f 1 6 7", "f 1 7 2", "f 1 2 1 3",
+    ].iter()).unwrap();
+    assert_eq!(fan.vertex_valences(), vec![7, 3, 3, 2, 2, 2, 2]);
+    assert_eq!(fan.high_valence_positions(4), vec![0]);
+}
+
+#[test]
+fn manifold_problems() {
+    let obj = Obj::from_lines([
+        "v 0 0 0", "v 1 0 0", "v 0 1 0", "v 0 0 1", "v -1 0 0", "v 0 -1 0", "v 1 -1 0",
+        // Three triangles sharing the edge 1-2, one of which is wound the same way as another
+        "f 1 2 3",
+        "f 1 2 4",
+        "f 2 1 5",
+        // A triangle touching the rest only at vertex 1
+        "f 1 6 7",
+    ].iter()).unwrap();
+    let report = obj.manifold_report();
+
+    assert_eq!(report.non_manifold_edges, vec![[0, 1]]);
+    assert!(report.orientation_conflicts.is_empty());
+    assert_eq!(report.non_manifold_vertices, vec![0]);
+    assert!(!report.is_manifold());
+
+    let obj = Obj::from_lines([
+        "v 0 0 0", "v 1 0 0", "v 0 1 0", "v -1 0 0", "v 0 -1 0", "v 0 0 -1",
+        // Two triangles with opposing winding, and a bow-tie meeting at vertex 1
+        "f 1 2 3",
+        "f 1 2 4",
+        "f 1 5 6",
+    ].iter()).unwrap();
+    let report = obj.manifold_report();
+
+    assert_eq!(report.orientation_conflicts, vec![[0, 1]]);
+    assert_eq!(report.non_manifold_vertices, vec![0]);
+}
+
+#[test]
+fn connected_components() {
+    let obj = Obj::from_reader(include_bytes!("cubes.obj") as &[u8]).unwrap();
+    let components = obj.connected_components();
+
+    assert_eq!(components.len(), 3);
+    for (i, component) in components.iter().enumerate() {
+        assert_eq!(component.positions().len(), 8);
+        assert_eq!(component.polygons().len(), 6);
+        assert!(component.manifold_report().is_closed());
+        assert!(component.object(&format!("cube{}", i)).is_some());
+    }
+}
+
+#[test]
+fn connected_components_welded() {
+    // Two triangles touching along an edge, but with their own copies of the shared positions
+    let obj = Obj::from_lines([
+        "v 0 0 0", "v 1 0 0", "v 0 1 0",
+        "v 1 0 0.00001", "v 0 1 0", "v 1 1 0",
+        "g a",
+        "f 1 2 3",
+        "g b",
+        "f 4 6 5",
+    ].iter()).unwrap();
+
+    assert_eq!(obj.connected_components().len(), 2);
+
+    let components = obj.connected_components_welded(0.001);
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].positions().len(), 6);
+    assert!(components[0].object("").unwrap().group("a").is_some());
+    assert!(components[0].object("").unwrap().group("b").is_some());
+
+    // Without a positive epsilon, only the exact copy of `0 1 0` is welded, and tiny or infinite epsilons don't
+    // overflow the grid
+    for epsilon in [0.0, -0.0, -1.0, f32::NAN, f32::MIN_POSITIVE, f32::INFINITY] {
+        assert_eq!(obj.connected_components_welded(epsilon).len(), 1);
+    }
+    let apart = Obj::from_lines(["v 0 0 0", "v 1e30 0 0", "v -1e30 0 0", "f 1 1 1", "f 2 2 2", "f 3 3 3"].iter()).unwrap();
+    assert_eq!(apart.connected_components_welded(f32::MIN_POSITIVE).len(), 3);
+    assert_eq!(apart.connected_components_welded(0.0).len(), 3);
+}
+
+#[test]
+fn adjacency() {
+    // A 3x3 grid of quads over a 4x4 grid of positions
+    let obj = Obj::from_reader(include_bytes!("plane.obj") as &[u8]).unwrap();
+    let adjacency = obj.adjacency();
+
+    // Corner
+    assert_eq!(adjacency.vertex_neighbors(0), &[1, 4]);
+    assert_eq!(adjacency.faces_around_vertex(0), &[0]);
+    // Edge
+    assert_eq!(adjacency.vertex_neighbors(1), &[0, 2, 5]);
+    assert_eq!(adjacency.faces_around_vertex(1), &[0, 1]);
+    // Interior
+    assert_eq!(adjacency.vertex_neighbors(5), &[1, 4, 6, 9]);
+    assert_eq!(adjacency.faces_around_vertex(5), &[0, 1, 3, 4]);
+
+    assert_eq!(adjacency.faces_adjacent_to_face(0), &[1, 3]);
+    assert_eq!(adjacency.faces_adjacent_to_face(4), &[1, 3, 5, 7]);
+    assert!(adjacency.faces_adjacent_to_face(100).is_empty());
+    assert!(adjacency.vertex_neighbors(100).is_empty());
+}
+
+#[test]
+fn adjacency_non_manifold() {
+    let obj = Obj::from_lines([
+        "v 0 0 0", "v 1 0 0", "v 0 1 0", "v 0 0 1", "v 0 -1 0",
+        "f 1 2 3",
+        "f 1 2 4",
+        "f 2 1 5",
+    ].iter()).unwrap();
+    let adjacency = obj.adjacency();
+
+    assert_eq!(adjacency.faces_adjacent_to_face(0), &[1, 2]);
+    assert_eq!(adjacency.faces_around_vertex(1), &[0, 1, 2]);
+    assert_eq!(obj.polygon(2).unwrap().index(), 2);
+}
+
+#[test]
+fn orient_consistently() {
+    let cube = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    let src = String::from_utf8(include_bytes!("cube.obj").to_vec()).unwrap();
+
+    // The top face is wound backwards
+    let mut obj: Obj = src.replace("f 5/1/2 6/2/2 8/3/2 7/4/2", "f 5/1/2 7/4/2 8/3/2 6/2/2").parse().unwrap();
+    assert!(!obj.manifold_report().is_manifold());
+    assert_eq!(obj.orient_consistently(), OrientReport { flipped: vec![1], non_orientable: Vec::new() });
+    assert!(obj.manifold_report().is_manifold());
+    assert_eq!(obj.to_string(), cube.to_string());
+
+    // Every face is wound backwards, so the volume is negative until they're all flipped
+    let mut inside_out: Obj = src
+        .lines()
+        .map(|line| match line.strip_prefix("f ") {
+            Some(face) => format!("f {}", face.split(' ').rev().collect::<Vec<_>>().join(" ")),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .parse()
+        .unwrap();
+    assert_eq!(inside_out.orient_consistently().flipped, vec![0, 1, 2, 3, 4, 5]);
+    assert!(inside_out.manifold_report().is_manifold());
+    assert_eq!(inside_out.orient_consistently(), OrientReport::default());
+}
+
+#[test]
+fn orient_non_orientable() {
+    // A strip of three quads joined end-to-end with a half twist
+    let mut obj = Obj::from_lines([
+        "v 0 0 0", "v 1 0 0", "v 2 0 0", "v 0 1 0", "v 1 1 0", "v 2 1 0",
+        "f 1 2 5 4",
+        "f 2 3 6 5",
+        "f 3 4 1 6",
+        // A separate open patch, mostly wound one way
+        "v 0 0 5", "v 1 0 5", "v 0 1 5", "v 1 1 5", "v 2 0 5",
+        "f 7 8 9",
+        "f 8 10 9",
+        "f 8 10 11",
+    ].iter()).unwrap();
+
+    assert_eq!(obj.orient_consistently(), OrientReport { flipped: vec![5], non_orientable: vec![vec![0, 1, 2]] });
+    assert_eq!(obj.orient_consistently(), OrientReport { flipped: Vec::new(), non_orientable: vec![vec![0, 1, 2]] });
+}
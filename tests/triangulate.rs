@@ -0,0 +1,176 @@
+use wavefront::{IndexedMesh, Obj, ProvokingVertex, QuadSplit, Triangulation, VertexKey};
+
+fn positions(obj: &Obj, options: Triangulation) -> Vec<[[f32; 3]; 3]> {
+    obj.triangles_with(options).map(|tri| tri.map(|v| v.position())).collect()
+}
+
+#[test]
+fn quad_split() {
+    // A unit square with its first corner lifted, folding it along the diagonal between its second and fourth
+    // vertices
+    let folded = Obj::from_lines("v 0 0 1\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n".lines()).unwrap();
+    let [a, b, c, d] = [[0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+
+    let fixed = positions(&folded, Triangulation::default());
+    assert_eq!(fixed, [[a, b, c], [a, c, d]]);
+    assert_eq!(fixed, folded.triangles().map(|tri| tri.map(|v| v.position())).collect::<Vec<_>>());
+
+    let flattest = Triangulation { quad_split: QuadSplit::MinDihedral, ..Default::default() };
+    assert_eq!(positions(&folded, flattest), [[b, c, d], [b, d, a]]);
+    let shortest = Triangulation { quad_split: QuadSplit::ShortestDiagonal, ..Default::default() };
+    assert_eq!(positions(&folded, shortest), [[b, c, d], [b, d, a]]);
+
+    // A planar parallelogram: only the diagonal lengths differ
+    let slanted = Obj::from_lines("v 0 0 0\nv 2 0 0\nv 3 1 0\nv 1 1 0\nf 1 2 3 4\n".lines()).unwrap();
+    assert_eq!(positions(&slanted, flattest), positions(&slanted, Triangulation::default()));
+    assert_eq!(positions(&slanted, shortest)[0][0], [2.0, 0.0, 0.0]);
+
+    let mesh = IndexedMesh::from_triangles(folded.triangles_with(flattest), VertexKey::Attributes);
+    assert_eq!(mesh.indices, [0, 1, 2, 0, 2, 3]);
+    assert_eq!(mesh.vertices[0].position, b);
+}
+
+#[test]
+fn non_quads_unaffected() {
+    let obj = Obj::from_reader(include_bytes!("ship.obj") as &[u8]).unwrap();
+    let options = Triangulation { quad_split: QuadSplit::MinDihedral, ..Default::default() };
+
+    for poly in obj.polygons().filter(|poly| poly.vertices().len() != 4) {
+        let positions = |tri: [wavefront::Vertex; 3]| tri.map(|v| v.position());
+        assert!(poly.triangles().map(positions).eq(poly.triangles_with(options).map(positions)));
+    }
+    assert_eq!(obj.triangles().count(), obj.triangles_with(options).count());
+}
+
+#[test]
+fn cached_triangles() {
+    use wavefront::ParseOptions;
+
+    let src = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 2 0 0\nf 1 2 3 4\nf 1 2\nf 2 5 3\n";
+    let mut obj = Obj::from_lines(src.lines()).unwrap();
+    let triangles = |obj: &Obj| obj.triangles().map(|tri| tri.map(|v| v.position())).collect::<Vec<_>>();
+    let expected = triangles(&obj);
+    assert_eq!(obj.triangle_indices(), None);
+
+    obj.cache_triangles();
+    assert_eq!(obj.triangle_indices(), Some(&[[0, 1, 2], [0, 2, 3], [6, 7, 8]][..]));
+    assert_eq!(triangles(&obj), expected);
+
+    let cached = Obj::from_str_with(src, ParseOptions::new().cache_triangles(true)).unwrap();
+    assert_eq!(cached.triangle_indices(), obj.triangle_indices());
+    assert_eq!(triangles(&cached.cast::<f64>().to_f32()), expected);
+}
+
+const MODES: [ProvokingVertex; 4] =
+    [ProvokingVertex::FanFirst, ProvokingVertex::FanLast, ProvokingVertex::StripFirst, ProvokingVertex::StripLast];
+
+fn cross(tri: [[f32; 3]; 3]) -> [f32; 3] {
+    let [a, b, c] = tri;
+    let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+    [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]]
+}
+
+#[test]
+fn provoking_vertex() {
+    for src in [&include_bytes!("mixed.obj")[..], include_bytes!("cube.obj"), include_bytes!("torus.obj")] {
+        let obj = Obj::from_reader(src).unwrap();
+        for poly in obj.polygons() {
+            let fan = poly.triangles().map(|tri| tri.map(|v| v.position())).collect::<Vec<_>>();
+            let fan_area = fan.iter().map(|&tri| cross(tri)).fold([0.0; 3], |a, n| [a[0] + n[0], a[1] + n[1], a[2] + n[2]]);
+            for mode in MODES {
+                let options = Triangulation { provoking_vertex: mode, ..Default::default() };
+                let tris = poly.triangles_with(options).collect::<Vec<_>>();
+                assert_eq!(tris.len(), fan.len());
+
+                // Every triangle keeps the winding of the polygon (or is degenerate), and together they cover the same area
+                let mut area = [0.0; 3];
+                for tri in &tris {
+                    let n = cross(tri.map(|v| v.position()));
+                    if let Some(normal) = poly.normal() {
+                        assert!(n[0] * normal[0] + n[1] * normal[1] + n[2] * normal[2] >= 0.0, "{:?}", mode);
+                    }
+                    area = [area[0] + n[0], area[1] + n[1], area[2] + n[2]];
+                }
+                for (a, b) in area.iter().zip(fan_area) {
+                    assert!((a - b).abs() < 1e-4, "{:?}", mode);
+                }
+
+                let mut provoking = tris
+                    .iter()
+                    .map(|tri| match mode {
+                        ProvokingVertex::FanFirst | ProvokingVertex::StripFirst => tri[0].position_index(),
+                        ProvokingVertex::FanLast | ProvokingVertex::StripLast => tri[2].position_index(),
+                    })
+                    .collect::<Vec<_>>();
+                provoking.sort_unstable();
+                provoking.dedup();
+                match mode {
+                    ProvokingVertex::FanFirst | ProvokingVertex::FanLast => assert!(provoking.len() <= 1),
+                    ProvokingVertex::StripFirst | ProvokingVertex::StripLast => assert_eq!(provoking.len(), tris.len()),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn provoking_vertex_order() {
+    let hexagon = Obj::from_lines("v 1 0 0\nv 2 0 0\nv 3 1 0\nv 2 2 0\nv 1 2 0\nv 0 1 0\nf 1 2 3 4 5 6\n".lines()).unwrap();
+    let indices = |mode| {
+        let options = Triangulation { provoking_vertex: mode, ..Default::default() };
+        hexagon.triangles_with(options).map(|tri| tri.map(|v| v.position_index())).collect::<Vec<_>>()
+    };
+    assert_eq!(indices(ProvokingVertex::FanFirst), [[0, 1, 2], [0, 2, 3], [0, 3, 4], [0, 4, 5]]);
+    assert_eq!(indices(ProvokingVertex::FanLast), [[1, 2, 0], [2, 3, 0], [3, 4, 0], [4, 5, 0]]);
+    assert_eq!(indices(ProvokingVertex::StripLast), [[1, 2, 0], [0, 2, 3], [0, 3, 5], [5, 3, 4]]);
+    assert_eq!(indices(ProvokingVertex::StripFirst), [[0, 1, 2], [3, 0, 2], [5, 0, 3], [4, 5, 3]]);
+
+    // Quads are split along the same diagonal in every mode
+    let folded = Obj::from_lines("v 0 0 1\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n".lines()).unwrap();
+    for mode in MODES {
+        let options = Triangulation { quad_split: QuadSplit::MinDihedral, provoking_vertex: mode };
+        for tri in folded.triangles_with(options) {
+            assert!(tri.iter().any(|v| v.position_index() == 1) && tri.iter().any(|v| v.position_index() == 3));
+        }
+    }
+
+    let options = Triangulation { provoking_vertex: ProvokingVertex::StripFirst, ..Default::default() };
+    let mesh = hexagon.to_indexed_mesh_with(options);
+    assert_eq!(mesh.indices.len(), 12);
+    assert_eq!(mesh.indices[..3].iter().map(|&i| mesh.vertices[i as usize].position).collect::<Vec<_>>(), [
+        [1.0, 0.0, 0.0],
+        [2.0, 0.0, 0.0],
+        [3.0, 1.0, 0.0]
+    ]);
+}
+
+#[test]
+fn triangle_position_indices() {
+    for (src, count) in [
+        (&include_bytes!("cube.obj")[..], 12),
+        (include_bytes!("plane.obj"), 18),
+        (include_bytes!("mixed.obj"), 3 + 4 * 2 + 3 + 4),
+    ] {
+        let obj = Obj::from_reader(src).unwrap();
+        let positions = obj.positions();
+        let indices = obj.triangle_position_indices().collect::<Vec<_>>();
+        assert_eq!(indices.len(), count);
+        assert!(indices.iter().flatten().all(|&i| i < positions.len()));
+        assert!(indices
+            .iter()
+            .map(|tri| tri.map(|i| positions[i]))
+            .eq(obj.triangles().map(|tri| tri.map(|v| v.position()))));
+
+        let per_group = obj
+            .objects()
+            .flat_map(|(_, object)| object.groups().map(|(_, group)| group.triangle_position_indices().count()).collect::<Vec<_>>())
+            .sum::<usize>();
+        let per_object =
+            obj.objects().map(|(_, object)| object.triangle_position_indices().count()).sum::<usize>();
+        assert_eq!((per_group, per_object), (count, count));
+    }
+
+    let ship = Obj::from_reader(include_bytes!("ship.obj") as &[u8]).unwrap();
+    assert_eq!(ship.triangle_position_indices().count(), ship.triangles().count());
+    assert!(ship.triangle_position_indices().flatten().all(|i| i < ship.positions().len()));
+}
@@ -0,0 +1,90 @@
+use wavefront::{
+    util::{classify_line, Keyword, LineKind},
+    ObjEvent, ObjParser,
+};
+
+const KEYWORDS: [(&str, Keyword); 39] = [
+    // Vertex data
+    ("v", Keyword::Position),
+    ("vt", Keyword::Uv),
+    ("vn", Keyword::Normal),
+    ("vp", Keyword::ParameterVertex),
+    ("cstype", Keyword::CurveSurfaceType),
+    ("deg", Keyword::Degree),
+    ("bmat", Keyword::BasisMatrix),
+    ("step", Keyword::Step),
+    // Elements
+    ("p", Keyword::Point),
+    ("l", Keyword::Line),
+    ("f", Keyword::Face),
+    ("curv", Keyword::Curve),
+    ("curv2", Keyword::Curve2),
+    ("surf", Keyword::Surface),
+    // Free-form curve and surface bodies
+    ("parm", Keyword::Parameter),
+    ("trim", Keyword::Trim),
+    ("hole", Keyword::Hole),
+    ("scrv", Keyword::SpecialCurve),
+    ("sp", Keyword::SpecialPoint),
+    ("end", Keyword::End),
+    ("con", Keyword::Connect),
+    // Grouping
+    ("g", Keyword::Group),
+    ("s", Keyword::SmoothingGroup),
+    ("mg", Keyword::MergingGroup),
+    ("o", Keyword::Object),
+    // Display and render attributes
+    ("bevel", Keyword::Bevel),
+    ("c_interp", Keyword::ColorInterpolation),
+    ("d_interp", Keyword::DissolveInterpolation),
+    ("lod", Keyword::LevelOfDetail),
+    ("usemap", Keyword::UseMap),
+    ("maplib", Keyword::MapLibrary),
+    ("usemtl", Keyword::UseMaterial),
+    ("mtllib", Keyword::MaterialLibrary),
+    ("shadow_obj", Keyword::ShadowObject),
+    ("trace_obj", Keyword::TraceObject),
+    ("ctech", Keyword::CurveTechnique),
+    ("stech", Keyword::SurfaceTechnique),
+    // General statements
+    ("call", Keyword::Call),
+    ("csh", Keyword::Shell),
+];
+
+#[test]
+fn keywords() {
+    for (string, keyword) in KEYWORDS {
+        assert_eq!(Keyword::parse(string), Some(keyword), "{}", string);
+        assert_eq!(keyword.as_str(), string);
+        assert_eq!(keyword.to_string(), string);
+        assert_eq!(Keyword::parse(&string.to_uppercase()), None);
+    }
+    assert_eq!(KEYWORDS.map(|(_, keyword)| keyword), Keyword::ALL);
+    for string in ["", "#", "vx", "face", "usemtl2", "x_collision"] {
+        assert_eq!(Keyword::parse(string), None, "{}", string);
+    }
+}
+
+#[test]
+fn classify() {
+    for (string, keyword) in KEYWORDS {
+        assert_eq!(classify_line(&format!("{} 1 2 3", string)), LineKind::Statement(keyword));
+        assert_eq!(classify_line(&format!("\t{}\r\n", string)), LineKind::Statement(keyword));
+    }
+    assert_eq!(classify_line(""), LineKind::Blank);
+    assert_eq!(classify_line(" \t\r\n"), LineKind::Blank);
+    assert_eq!(classify_line("#"), LineKind::Comment);
+    assert_eq!(classify_line("  #v 1 2 3"), LineKind::Comment);
+    assert_eq!(classify_line("v#1 2 3"), LineKind::Unknown("v#1"));
+    assert_eq!(classify_line("x_collision box 1 2 3"), LineKind::Unknown("x_collision"));
+
+    // Only supported keywords produce their own events from the pull parser
+    for (string, keyword) in KEYWORDS {
+        let line = match keyword {
+            Keyword::Object => "o name".to_string(),
+            _ => format!("{} 1 1 1", string),
+        };
+        let (_, event) = ObjParser::new(&line).next().unwrap().unwrap();
+        assert_eq!(!matches!(event, ObjEvent::Unknown { .. }), keyword.is_supported(), "{}", string);
+    }
+}
@@ -0,0 +1,104 @@
+use wavefront::{Obj, UvSeamOptions};
+
+#[test]
+fn uv_bounds() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.25 0.5\nvt 0.75 -0.5\nvt 0.5 1.5\nvt 9 9
+o a
+g inside
+f 1/1 2/1 3/1
+g outside
+f 1/1 2/2 3/3
+";
+    let obj: Obj = src.parse().unwrap();
+    // The unused texture coordinate is ignored
+    assert_eq!(obj.uv_bounds(), Some(([0.25, -0.5], [0.75, 1.5])));
+
+    let a = obj.object("a").unwrap();
+    assert_eq!(a.uv_bounds(), obj.uv_bounds());
+    assert_eq!(a.group("inside").unwrap().uv_bounds(), Some(([0.25, 0.5], [0.25, 0.5])));
+
+    let untextured: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nf 1 2 3\n".parse().unwrap();
+    assert_eq!(untextured.uv_bounds(), None);
+}
+
+#[test]
+fn uvs_outside() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nvt 1.25 0
+f 1/1 2/2 3/3
+f 1/2 2/4 3/3
+f 1 2 3
+";
+    let obj: Obj = src.parse().unwrap();
+    // The face straddling 1.0 is flagged, but the range is inclusive
+    assert_eq!(obj.polygons_with_uvs_outside([0.0, 0.0], [1.0, 1.0]), vec![1]);
+    assert_eq!(obj.polygons_with_uvs_outside([0.0, 0.0], [2.0, 1.0]), Vec::<usize>::new());
+}
+
+#[test]
+fn wrap_uvs() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0
+vt 2.25 -0.75\nvt 2.75 -0.75\nvt 2.25 -0.25
+vt 0.75 0.25\nvt 1.25 0.25\nvt 0.75 0.75
+vt 0.5 0.5\nvt 7 7
+f 1/1 2/2 3/3
+f 1/4 2/5 3/6
+f 1/7 2/7 3/7
+";
+    let mut obj: Obj = src.parse().unwrap();
+    obj.wrap_uvs();
+    let uvs = obj.uvs();
+
+    // An island away from the texture is moved by whole repeats
+    assert_eq!(&uvs[0..3], &[[0.25, 0.25, 0.0], [0.75, 0.25, 0.0], [0.25, 0.75, 0.0]]);
+    // An island straddling 1.0 is kept whole
+    assert_eq!(&uvs[3..6], &[[0.75, 0.25, 0.0], [1.25, 0.25, 0.0], [0.75, 0.75, 0.0]]);
+    assert_eq!(obj.polygons_with_uvs_outside([0.0, 0.0], [1.0, 1.0]), vec![1]);
+    // Islands already in range and unused texture coordinates are left alone
+    assert_eq!(&uvs[6..], &[[0.5, 0.5, 0.0], [7.0, 7.0, 0.0]]);
+}
+
+#[test]
+fn uv_seams() {
+    // A cylinder of 8 segments and 2 rings, with texture coordinates wrapping around it once
+    let (segments, rings) = (8, 2);
+    let mut src = String::new();
+    for k in 0..=rings {
+        for j in 0..segments {
+            let angle = 2.0 * std::f32::consts::PI * j as f32 / segments as f32;
+            src += &format!("v {} {} {}\n", angle.cos(), angle.sin(), k);
+        }
+        for j in 0..=segments {
+            src += &format!("vt {} {}\n", j as f32 / segments as f32, k as f32 / rings as f32);
+        }
+    }
+    let pos = |j: usize, k: usize| k * segments + j % segments + 1;
+    let uv = |j: usize, k: usize| k * (segments + 1) + j + 1;
+    for k in 0..rings {
+        for j in 0..segments {
+            let corners = [(j, k), (j + 1, k), (j + 1, k + 1), (j, k + 1)];
+            let terms = corners.iter().map(|(j, k)| format!("{}/{}", pos(*j, *k), uv(*j, *k))).collect::<Vec<_>>();
+            src += &format!("f {}\n", terms.join(" "));
+        }
+    }
+    let cylinder: Obj = src.parse().unwrap();
+
+    // The only seam is where the texture wraps around
+    let seams = cylinder.uv_seams(&UvSeamOptions::new());
+    assert_eq!(seams.iter().map(|seam| (seam.edge, seam.faces)).collect::<Vec<_>>(), vec![
+        ([0, 8], [0, 7]),
+        ([8, 16], [8, 15]),
+    ]);
+    assert_eq!(seams[0].uvs, [
+        [Some([0.0, 0.0, 0.0]), Some([0.0, 0.5, 0.0])],
+        [Some([1.0, 0.0, 0.0]), Some([1.0, 0.5, 0.0])],
+    ]);
+    assert_eq!(cylinder.uv_seams(&UvSeamOptions::new().epsilon(0.01)), seams);
+
+    // A cap without texture coordinates is separated from the sides by a seam, unless missing coordinates are ignored
+    let cap = (0..segments).map(|j| pos(j, rings).to_string()).collect::<Vec<_>>();
+    let capped: Obj = (src + &format!("f {}\n", cap.join(" "))).parse().unwrap();
+    let seams = capped.uv_seams(&UvSeamOptions::new());
+    assert_eq!(seams.len(), 2 + segments);
+    assert!(seams.iter().filter(|seam| seam.faces[1] == 16).all(|seam| seam.uvs[1] == [None, None]));
+    assert_eq!(capped.uv_seams(&UvSeamOptions::new().ignore_missing_uvs(true)).len(), 2);
+}
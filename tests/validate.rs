@@ -0,0 +1,89 @@
+use wavefront::{Error, NonFinitePolicy, Obj, ParseOptions, UvSeamOptions};
+
+// Two triangles sharing an edge, with a NaN position, uv or normal referenced only by the second
+const POSITION: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv nan 1 0\nvt 0 0\nvn 0 0 1\ng a\nf 1/1/1 2/1/1 3/1/1\ng b\nf 2/1/1 4/1/1 3/1/1\n";
+const UV: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvt 0 0\nvt 0 inf\nvn 0 0 1\ng a\nf 1/1/1 2/1/1 3/1/1\ng b\nf 2/1/1 4/2/1 3/1/1\n";
+const NORMAL: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvt 0 0\nvn 0 0 1\nvn 0 0 -nan\ng a\nf 1/1/1 2/1/1 3/1/1\ng b\nf 2/1/1 4/1/2 3/1/1\n";
+
+#[test]
+fn validate() {
+    let valid = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    assert!(valid.validate().is_valid());
+    // Seams are counted, but don't make the cube invalid
+    assert_eq!(valid.validate().uv_seams, valid.uv_seams(&UvSeamOptions::new()).len());
+    assert!(valid.validate().uv_seams > 0);
+
+    let report = POSITION.parse::<Obj>().unwrap().validate();
+    assert!(!report.is_valid());
+    assert_eq!(report.non_finite_positions, vec![3]);
+    assert!(report.non_finite_uvs.is_empty() && report.non_finite_normals.is_empty());
+
+    let report = UV.parse::<Obj>().unwrap().validate();
+    assert_eq!(report.non_finite_uvs, vec![1]);
+    assert!(report.non_finite_positions.is_empty() && report.non_finite_normals.is_empty());
+
+    let report = NORMAL.parse::<Obj>().unwrap().validate();
+    assert_eq!(report.non_finite_normals, vec![1]);
+    assert!(report.non_finite_positions.is_empty() && report.non_finite_uvs.is_empty());
+    assert_eq!(
+        report.to_string(),
+        "0 non-finite positions, 0 non-finite texture coordinates and 1 non-finite normals",
+    );
+}
+
+#[test]
+fn repair_non_finite() {
+    for src in [POSITION, UV, NORMAL] {
+        let obj = src.parse::<Obj>().unwrap();
+
+        let mut replaced = obj.clone();
+        assert_eq!(replaced.repair_non_finite(NonFinitePolicy::ReplaceWithZero), Ok(1));
+        assert!(replaced.validate().is_valid());
+        assert_eq!(replaced.polygons().count(), 2);
+        assert!(replaced.polygon(1).unwrap().vertices().any(|v| {
+            v.position() == [0.0; 3] || v.uv() == Some([0.0; 3]) || v.normal() == Some([0.0; 3])
+        }));
+
+        let mut removed = obj.clone();
+        assert_eq!(removed.repair_non_finite(NonFinitePolicy::RemoveFaces), Ok(1));
+        assert!(removed.validate().is_valid());
+        assert_eq!(removed.polygons().count(), 1);
+        assert_eq!(removed.positions().len(), 4);
+        let object = removed.object("").unwrap();
+        assert!(object.group("a").is_some());
+        assert!(object.group("b").is_none());
+
+        let mut untouched = obj.clone();
+        let report = untouched.repair_non_finite(NonFinitePolicy::Error).unwrap_err();
+        assert_eq!(report, obj.validate());
+        assert_eq!(untouched.validate(), report);
+        assert_eq!(untouched.polygons().count(), 2);
+    }
+
+    // Valid OBJs are left alone by every policy
+    let mut cube = Obj::from_reader(include_bytes!("cube.obj") as &[u8]).unwrap();
+    for policy in [NonFinitePolicy::ReplaceWithZero, NonFinitePolicy::RemoveFaces, NonFinitePolicy::Error] {
+        assert_eq!(cube.repair_non_finite(policy), Ok(0));
+    }
+    assert!(cube.validate().is_valid());
+}
+
+#[test]
+fn strict() {
+    let strict = || ParseOptions::new().strict(true);
+    for (src, line) in [(POSITION, 4), (UV, 6), (NORMAL, 7)] {
+        match Obj::from_str_with(src, strict()) {
+            Err(Error::NonFiniteAttribute(l)) => assert_eq!(l, line),
+            other => panic!("expected a non-finite attribute error, found {:?}", other.map(|_| ())),
+        }
+    }
+    assert_eq!(
+        Obj::from_str_with("v inf 0 0\n", strict()).unwrap_err().to_string(),
+        "Non-finite attribute on line 1",
+    );
+
+    // The lenient parser accepts them, as do strict parsers that skip the attributes
+    assert!(Obj::from_str_with(POSITION, ParseOptions::new()).is_ok());
+    assert!(Obj::from_str_with(UV, strict().skip_uvs(true)).is_ok());
+    assert!(Obj::from_file_with("tests/ship.obj", strict()).is_ok());
+}
@@ -0,0 +1,279 @@
+use wavefront::{Error, Obj, ObjParser, ObjWriter};
+
+fn summary(obj: &Obj) -> Vec<String> {
+    let mut summary = vec![format!("{:?}", obj.positions())];
+    summary.extend(obj.polygons_with_context().map(|(o, g, poly)| {
+        format!("{} {} {:?}", o, g, poly.vertices().map(|v| (v.position_index(), v.uv_index(), v.normal_index())).collect::<Vec<_>>())
+    }));
+    summary
+}
+
+#[test]
+fn grid() {
+    const SIZE: usize = 300;
+    let mut writer = ObjWriter::new(Vec::new());
+    writer.object("grid").unwrap();
+    let normal = writer.normal([0.0, 0.0, 1.0]).unwrap();
+    let mut rows = Vec::new();
+    for y in 0..=SIZE {
+        let row = (0..=SIZE)
+            .map(|x| writer.position([x as f32, y as f32, 0.0]).unwrap())
+            .collect::<Vec<_>>();
+        rows.push(row);
+    }
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let corners = [rows[y][x], rows[y][x + 1], rows[y + 1][x + 1], rows[y + 1][x]];
+            writer.face(&corners.map(|p| (p, None, Some(normal)))).unwrap();
+        }
+    }
+
+    let obj: Obj = String::from_utf8(writer.finish().unwrap()).unwrap().parse().unwrap();
+    assert_eq!(obj.positions().len(), (SIZE + 1) * (SIZE + 1));
+    assert_eq!(obj.polygons().len(), SIZE * SIZE);
+    assert_eq!(obj.objects().count(), 1);
+    let last = obj.polygons().last().unwrap();
+    let positions = last.vertices().map(|v| v.position()).collect::<Vec<_>>();
+    let (far, near) = (SIZE as f32, SIZE as f32 - 1.0);
+    assert_eq!(positions, vec![[near, near, 0.0], [far, near, 0.0], [far, far, 0.0], [near, far, 0.0]]);
+    assert!(last.vertices().all(|v| v.normal() == Some([0.0, 0.0, 1.0])));
+}
+
+#[test]
+fn events() {
+    let src = std::str::from_utf8(include_bytes!("ship.obj")).unwrap();
+    let mut writer = ObjWriter::new(Vec::new());
+    for event in ObjParser::new(src) {
+        writer.event(event.unwrap().1).unwrap();
+    }
+    let written = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    let original = Obj::from_lines(src.lines()).unwrap();
+    assert_eq!(summary(&written.parse().unwrap()), summary(&original));
+}
+
+#[test]
+fn material_events() {
+    // Material names are written as they were read, even where they wouldn't be valid object or group names
+    let src = "usemtl two words\nusemtl\n";
+    let mut writer = ObjWriter::<_, f32>::new(Vec::new());
+    for event in ObjParser::new(src) {
+        writer.event(event.unwrap().1).unwrap();
+    }
+    assert_eq!(writer.finish().unwrap(), src.as_bytes());
+}
+
+#[test]
+fn invalid() {
+    let mut writer = ObjWriter::new(Vec::new());
+    let a = writer.position([0.0; 3]).unwrap();
+    assert!(matches!(writer.face(&[(a, None, None), (1, None, None)]), Err(Error::InvalidIndex(2))));
+    assert!(matches!(writer.face(&[(a, Some(0), None)]), Err(Error::InvalidIndex(1))));
+    assert!(matches!(writer.face(&[]), Err(Error::InvalidParameter(_))));
+    assert!(matches!(writer.face(&[(a, None, None), (a, None, None)]), Err(Error::InvalidParameter(_))));
+    assert!(matches!(writer.object("two words"), Err(Error::ExpectedName(2))));
+    assert!(matches!(writer.group(&["ok", ""]), Err(Error::ExpectedName(2))));
+    // Nothing is written for statements that fail
+    assert_eq!(writer.finish().unwrap(), b"v 0 0 0\n");
+}
+
+#[test]
+fn sanitize_names() {
+    use wavefront::{util, WriteOptions};
+
+    assert_eq!(util::sanitize_name("Wheel_FL.001"), "Wheel_FL.001");
+    assert!(matches!(util::sanitize_name("Wheel_FL.001"), std::borrow::Cow::Borrowed(_)));
+    assert_eq!(util::sanitize_name("front left/wheel"), "front_left_wheel");
+    assert_eq!(util::sanitize_name("Räder\t#1"), "R_der__1");
+    assert!(util::name_is_valid(&util::sanitize_name("o g f # \u{1F697}")));
+
+    let mut sanitizer = util::NameSanitizer::new();
+    assert_eq!(sanitizer.sanitize("a b"), "a_b");
+    assert_eq!(sanitizer.sanitize("a_b"), "a_b_2");
+    assert_eq!(sanitizer.sanitize("a_b_2"), "a_b_2_2");
+    assert_eq!(sanitizer.sanitize("a b"), "a_b");
+    assert_eq!(sanitizer.get("c"), None);
+    let mut renamed = sanitizer.renamed().collect::<Vec<_>>();
+    renamed.sort();
+    assert_eq!(renamed, [("a b", "a_b"), ("a_b", "a_b_2"), ("a_b_2", "a_b_2_2")]);
+
+    // Names that are already valid are written as they are
+    let obj = Obj::from_file("tests/ship.obj").unwrap();
+    let mut out = Vec::new();
+    assert!(obj.write_with(&mut out, WriteOptions::new().sanitize_names(true)).unwrap().is_empty());
+    assert_eq!(String::from_utf8(out).unwrap(), obj.to_string());
+}
+
+#[test]
+fn unnamed_object_and_group() {
+    use wavefront::{WriteOptions, WriteOrdering};
+
+    // Faces outside of any object can't be selected again after an `o`, so they're written first
+    let src = "v 0 0 0\nf 1 1 1\no b\ng y\nf 1 1 1\no a\ng x\nf 1 1 1\ng\nf 1 1 1\n";
+    let obj: Obj = src.parse().unwrap();
+    let write = |ordering| {
+        let mut out = Vec::new();
+        obj.write_with(&mut out, WriteOptions::new().ordering(ordering)).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    assert_eq!(write(WriteOrdering::SortNames), "v 0 0 0\nf 1 1 1\no a\ng x\nf 1 1 1\ng\nf 1 1 1\no b\ng y\nf 1 1 1\n");
+
+    // Otherwise, faces are written in the order they're stored, selecting the default group again with an empty `g`
+    let preserved = write(WriteOrdering::Preserve);
+    assert_eq!(preserved, src);
+    let reparsed: Obj = preserved.parse().unwrap();
+    assert_eq!(reparsed.object("").unwrap().polygons().len(), 1);
+    assert_eq!(reparsed.object("a").unwrap().group("").unwrap().polygons().len(), 1);
+}
+
+#[test]
+fn round_trip_memberships() {
+    use wavefront::{CompareOptions, WriteOptions, WriteOrdering};
+
+    // A face in several groups is written once, after a single `g` naming all of them, and an object named more than
+    // once is written wherever its faces are
+    for src in [
+        "v 0 0 0\ng a b\nf 1 1 1\n",
+        "v 0 0 0\no a\ng x\nf 1 1 1\no b\nf 1 1 1\no a\ng x y\nf 1 1 1\ng\nf 1 1 1\n",
+    ] {
+        let obj: Obj = src.parse().unwrap();
+        assert_eq!(obj.to_string(), src);
+        for ordering in [WriteOrdering::Preserve, WriteOrdering::SortNames, WriteOrdering::Canonical] {
+            let mut out = Vec::new();
+            obj.write_with(&mut out, WriteOptions::new().ordering(ordering)).unwrap();
+            let reparsed: Obj = String::from_utf8(out).unwrap().parse().unwrap();
+            assert_eq!(reparsed.polygons().len(), obj.polygons().len());
+            // Sorting by object name moves the faces of `b` after those of `a`
+            assert!(reparsed.structurally_equal(&obj, &CompareOptions::new().ignore_order(true)));
+            if ordering == WriteOrdering::Preserve {
+                assert_eq!(reparsed, obj);
+            }
+        }
+    }
+}
+
+#[test]
+fn ordering() {
+    use std::fmt::Write;
+    use wavefront::{WriteOptions, WriteOrdering};
+
+    let write = |obj: &Obj, ordering| {
+        let mut out = Vec::new();
+        obj.write_with(&mut out, WriteOptions::new().ordering(ordering)).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    // The same model, with its attributes stored in reverse, objects and groups defined in reverse (other than the
+    // unnamed ones), and attributes that nothing uses
+    let reversed = |obj: &Obj| {
+        let mut src = String::new();
+        for (keyword, attributes) in [("v", obj.positions()), ("vt", obj.uvs()), ("vn", obj.normals())] {
+            for [x, y, z] in attributes.iter().rev().chain([[9.0; 3]].iter()) {
+                writeln!(src, "{} {} {} {}", keyword, x, y, z).unwrap();
+            }
+        }
+        let (positions, uvs, normals) = (obj.positions().len(), obj.uvs().len(), obj.normals().len());
+        let mut objects = obj.objects().collect::<Vec<_>>();
+        objects.sort_by_key(|(name, _)| (!name.is_empty(), std::cmp::Reverse(*name)));
+        for (name, object) in objects {
+            if !name.is_empty() {
+                writeln!(src, "o {}", name).unwrap();
+            }
+            let mut groups = object.groups().collect::<Vec<_>>();
+            groups.sort_by_key(|(name, _)| (!name.is_empty(), std::cmp::Reverse(name.as_str())));
+            for (name, group) in groups {
+                writeln!(src, "g {}", name).unwrap();
+                for poly in group.polygons() {
+                    writeln!(src, "usemtl {}", poly.material().unwrap_or("")).unwrap();
+                    write!(src, "f").unwrap();
+                    for v in poly.vertices() {
+                        write!(src, " {}", positions - v.position_index()).unwrap();
+                        match (v.uv_index(), v.normal_index()) {
+                            (uv, Some(norm)) => write!(src, "/{}/{}", uv.map_or(String::new(), |uv| (uvs - uv).to_string()), normals - norm),
+                            (Some(uv), None) => write!(src, "/{}", uvs - uv),
+                            (None, None) => Ok(()),
+                        }.unwrap();
+                    }
+                    writeln!(src).unwrap();
+                }
+            }
+        }
+        src.parse::<Obj>().unwrap()
+    };
+
+    let grouped: Obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2//1 3//1\no b\ng y\nf 3 2 1\ng x\nusemtl red\nf 2 3 1\no a\nf 1 3 2\n"
+        .parse()
+        .unwrap();
+    for obj in [Obj::from_file("tests/ship.obj").unwrap(), grouped] {
+        let other = reversed(&obj);
+        assert_ne!(write(&other, WriteOrdering::SortNames), write(&obj, WriteOrdering::SortNames));
+        let canonical = write(&obj, WriteOrdering::Canonical);
+        assert_eq!(write(&other, WriteOrdering::Canonical), canonical);
+
+        // Canonical output is its own normal form, and only leaves out unused attributes
+        let reparsed: Obj = canonical.parse().unwrap();
+        assert_eq!(write(&reparsed, WriteOrdering::Canonical), canonical);
+        assert_eq!(reparsed.polygons().len(), obj.polygons().len());
+        let used = obj.vertices().map(|v| v.position_index()).collect::<std::collections::HashSet<_>>();
+        assert_eq!(reparsed.positions().len(), used.len());
+        assert_eq!(other.positions().len(), obj.positions().len() + 1);
+
+        // Sorting names writes objects in order
+        let sorted = write(&obj, WriteOrdering::SortNames);
+        let objects = sorted.lines().filter(|line| line.starts_with("o ")).collect::<Vec<_>>();
+        assert!(objects.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(sorted.lines().filter(|line| line.starts_with("v ")).count(), obj.positions().len());
+        // Otherwise, the same lines are written, although materials may need to be selected a different number of times
+        let lines = |src: &str| src.lines().filter(|line| !line.starts_with("usemtl")).map(String::from).collect::<Vec<_>>();
+        let (mut preserved, mut sorted) = (lines(&write(&obj, WriteOrdering::Preserve)), lines(&sorted));
+        preserved.sort();
+        sorted.sort();
+        assert_eq!(preserved, sorted);
+    }
+}
+
+#[test]
+fn shared_attributes() {
+    use wavefront::WriteOptions;
+
+    let count = |src: &str, keyword: &str| src.lines().filter(|line| line.split_whitespace().next() == Some(keyword)).count();
+    let write = |obj: &Obj, options| {
+        let mut out = Vec::new();
+        obj.write_with(&mut out, options).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    let sorted = |obj: &Obj| {
+        let mut summary = summary(obj);
+        summary.sort();
+        summary
+    };
+
+    // Attributes are written once, however many vertices refer to them, and are referred to by the same indices
+    let src = std::fs::read_to_string("tests/ship.obj").unwrap();
+    let obj: Obj = src.parse().unwrap();
+    let written = write(&obj, WriteOptions::new());
+    for keyword in ["v", "vt", "vn"] {
+        assert_eq!(count(&written, keyword), count(&src, keyword));
+    }
+    assert!(count(&written, "v") < obj.vertices().len());
+    assert_eq!(sorted(&written.parse().unwrap()), sorted(&obj));
+
+    // Unless every corner is asked to have its own
+    let expanded = write(&obj, WriteOptions::new().expand_corners(true));
+    let with = |f: fn(&wavefront::Vertex) -> bool| obj.vertices().filter(f).count();
+    assert_eq!(count(&expanded, "v"), obj.vertices().len());
+    assert_eq!(count(&expanded, "vt"), with(|v| v.uv_index().is_some()));
+    assert_eq!(count(&expanded, "vn"), with(|v| v.normal_index().is_some()));
+    let reparsed: Obj = expanded.parse().unwrap();
+    let attributes = |obj: &Obj| {
+        let mut attributes = obj
+            .polygons_with_context()
+            .map(|(o, g, poly)| format!("{} {} {:?}", o, g, poly.vertices().map(|v| (v.position(), v.uv(), v.normal())).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        attributes.sort();
+        attributes
+    };
+    assert_eq!(attributes(&reparsed), attributes(&obj));
+    let positions = reparsed.vertices().map(|v| v.position_index()).collect::<std::collections::HashSet<_>>();
+    assert_eq!(positions.len(), reparsed.vertices().len());
+}